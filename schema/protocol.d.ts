@@ -0,0 +1,1883 @@
+// Generated by `cargo run --features schema --bin export-schema`.
+
+/** A round length that grows with the word: a base, more for each
+character, and more the rarer the word is. */
+export type AdaptiveDuration = {
+  base: number;
+  commonFrequency: number;
+  max: number;
+  min: number;
+  perCharacterMs?: number;
+  perRarityStepMs?: number;
+};
+
+export type AnswerInfo = {
+  answer: string;
+  answerTime: number;
+  id: string;
+  isCorrect: boolean;
+  kind?: AnswerKind;
+  sequence?: number | null;
+};
+
+/** Whether an answer was submitted or filled in when the round ran out. */
+export type AnswerKind = "answered" | "timed_out";
+
+/** How quick and how accurate a bot is. */
+export type BotDifficulty = "easy" | "normal" | "hard";
+
+/** Optional protocol features a client opts into in its hello. Messages
+behind one are only sent to clients that asked for it. */
+export type Capability =
+  | "reactions"
+  | "streaks"
+  | "assets";
+
+export type ClientInfo = {
+  handicap?: Handicap | null;
+  id: string;
+  isAdmin: boolean;
+  isBot?: boolean;
+  isMuted?: boolean;
+  isReady?: boolean;
+  isSpectator?: boolean;
+  key: string;
+  name: string;
+  playerId?: string;
+};
+
+/** What a client is in its room. An admin is one whether or not it plays. */
+export type ClientRole = "player" | "spectator" | "admin";
+
+export type DisconnectReason = "connection_lost" | "left";
+
+/** The limits and features a server runs with, as far as clients are
+concerned. Built once at launch; passwords, keys and paths stay out. */
+export type EffectiveConfig = {
+  announcementRateLimit: RateLimitInfo;
+  capabilities: Array<Capability>;
+  chatMaxLength: number;
+  chatRateLimit: RateLimitInfo;
+  encodings: Array<Encoding>;
+  features: ServerFeatures;
+  lobbyChatRateLimit: RateLimitInfo;
+  maxBinaryFrames: number;
+  maxConnectionsPerIp?: number | null;
+  maxMessageSize: number;
+  maxNameLength: number;
+  maxRoomClients?: number | null;
+  protocolVersion: number;
+  serverVersion: string;
+  sessionTokenTtlSeconds: number;
+  signatureAlgorithms: Array<SignatureAlgorithm>;
+};
+
+/** Frame encoding a client asks for in its hello; text JSON unless stated. */
+export type Encoding = "json" | "msgpack";
+
+/** Why something a client asked for or was asked for can't be done, for
+clients to act on rather than show. */
+export type ErrorCode =
+  | "render_failed"
+  | "no_words_match_filters"
+  | "timed_out"
+  | "other";
+
+export type GameReplay = {
+  gameSettings: GameSettings;
+  rounds: Array<RoundRecord>;
+  seed?: number | null;
+  stopReason?: GameStopReason | null;
+  version: number;
+};
+
+export type GameSettings = {
+  acceptNearMiss?: number | null;
+  adaptiveDuration?: AdaptiveDuration | null;
+  adminPlays?: boolean;
+  allowAnswerChange?: boolean;
+  allowRepeatWords?: boolean;
+  cutRoundAtTimeLimit?: boolean;
+  dictionaryName?: string | null;
+  endRoundWhenAllAnswered?: boolean;
+  enforceFrequency?: boolean;
+  firstFontName?: string | null;
+  fontsCount: number;
+  gameTimeLimit?: number | null;
+  liveAnswerStats?: boolean;
+  lockRoomDuringGame?: boolean;
+  maxFrequency: number;
+  minFrequency: number;
+  practiceMode?: boolean;
+  questionTimeout?: number;
+  questionType?: QuestionType;
+  questionTypeWeights?: Record<string, number>;
+  requireAllReady?: boolean;
+  roundDuration: number;
+  roundsCount: number;
+  seed?: number | null;
+  shareNearMisses?: boolean;
+  spectatorDelayMs?: number;
+  startCountdown?: number;
+  streakBonus?: number | null;
+  tier?: string | null;
+  usingMaxFrequency: boolean;
+  wordPart?: string | null;
+  wordPartReading?: string | null;
+};
+
+/** A handful of `GameSettings` fields to change without resending the rest. */
+export type GameSettingsOverride = {
+  maxFrequency?: number | null;
+  minFrequency?: number | null;
+  roundDuration?: number | null;
+  roundsCount?: number | null;
+  seed?: number | null;
+  startCountdown?: number | null;
+};
+
+/** Any subset of `GameSettings` fields. A field left out keeps its current
+value; for the optional ones an explicit `null` clears it. */
+export type GameSettingsPatch = {
+  acceptNearMiss?: number | null;
+  adaptiveDuration?: AdaptiveDuration | null;
+  adminPlays?: boolean | null;
+  allowAnswerChange?: boolean | null;
+  allowRepeatWords?: boolean | null;
+  cutRoundAtTimeLimit?: boolean | null;
+  dictionaryName?: string | null;
+  endRoundWhenAllAnswered?: boolean | null;
+  enforceFrequency?: boolean | null;
+  firstFontName?: string | null;
+  fontsCount?: number | null;
+  gameTimeLimit?: number | null;
+  liveAnswerStats?: boolean | null;
+  lockRoomDuringGame?: boolean | null;
+  maxFrequency?: number | null;
+  minFrequency?: number | null;
+  practiceMode?: boolean | null;
+  questionTimeout?: number | null;
+  questionType?: QuestionType | null;
+  questionTypeWeights?: Record<string, number> | null;
+  requireAllReady?: boolean | null;
+  roundDuration?: number | null;
+  roundsCount?: number | null;
+  seed?: number | null;
+  shareNearMisses?: boolean | null;
+  spectatorDelayMs?: number | null;
+  startCountdown?: number | null;
+  streakBonus?: number | null;
+  tier?: string | null;
+  usingMaxFrequency?: boolean | null;
+  wordPart?: string | null;
+  wordPartReading?: string | null;
+};
+
+export type GameStopReason =
+  | "completed" | "stopped_by_admin" | "aborted_no_admin" | "aborted_empty_room"
+  | "abandoned"
+  | "no_questions"
+  | "time_limit_reached";
+
+/** Evens out a game for a weaker player: more time to answer and more
+points per correct answer. */
+export type Handicap = {
+  extraTimeMs: number;
+  scoreMultiplier: number;
+};
+
+/** Puts a server-side bot player in the room. */
+export type InReqAddBot = {
+  difficulty?: BotDifficulty;
+};
+
+export type InReqAddBotEnvelope = {
+  correlationId: string;
+  payload: InReqAddBot;
+  seq?: number | null;
+};
+
+export type InReqClientList = Record<string, unknown>;
+
+export type InReqClientListEnvelope = {
+  correlationId: string;
+  payload: InReqClientList;
+  seq?: number | null;
+};
+
+/** Starts or stops recording a client's connection to the server's
+capture directory. For operators, when captures are configured. */
+export type InReqDebugCapture = {
+  clientId: string;
+  enabled: boolean;
+};
+
+export type InReqDebugCaptureEnvelope = {
+  correlationId: string;
+  payload: InReqDebugCapture;
+  seq?: number | null;
+};
+
+export type InReqDebugOutbox = {
+  clientId: string;
+};
+
+export type InReqDebugOutboxEnvelope = {
+  correlationId: string;
+  payload: InReqDebugOutbox;
+  seq?: number | null;
+};
+
+export type InReqExportGame = {
+  gameIndex: number;
+};
+
+export type InReqExportGameEnvelope = {
+  correlationId: string;
+  payload: InReqExportGame;
+  seq?: number | null;
+};
+
+export type InReqExtendRound = {
+  extraSeconds: number;
+};
+
+export type InReqExtendRoundEnvelope = {
+  correlationId: string;
+  payload: InReqExtendRound;
+  seq?: number | null;
+};
+
+/** Asks for a question image announced by its `assetId`. Only the current
+and the previous round's are kept. */
+export type InReqFetchAsset = {
+  assetId: string;
+};
+
+export type InReqFetchAssetEnvelope = {
+  correlationId: string;
+  payload: InReqFetchAsset;
+  seq?: number | null;
+};
+
+/** Looks a registered player up by player id or by name, matched the way
+name uniqueness is. */
+export type InReqFindPlayer = {
+  playerIdOrName: string;
+};
+
+export type InReqFindPlayerEnvelope = {
+  correlationId: string;
+  payload: InReqFindPlayer;
+  seq?: number | null;
+};
+
+export type InReqGameReplay = Record<string, unknown>;
+
+export type InReqGameReplayEnvelope = {
+  correlationId: string;
+  payload: InReqGameReplay;
+  seq?: number | null;
+};
+
+export type InReqLeaveRoom = Record<string, unknown>;
+
+export type InReqLeaveRoomEnvelope = {
+  correlationId: string;
+  payload: InReqLeaveRoom;
+  seq?: number | null;
+};
+
+export type InReqMakeAdmin = {
+  adminPassword: string;
+  clientId: string;
+  revokeExisting?: boolean;
+};
+
+export type InReqMakeAdminEnvelope = {
+  correlationId: string;
+  payload: InReqMakeAdmin;
+  seq?: number | null;
+};
+
+export type InReqMuteClient = {
+  clientId: string;
+  muted: boolean;
+};
+
+export type InReqMuteClientEnvelope = {
+  correlationId: string;
+  payload: InReqMuteClient;
+  seq?: number | null;
+};
+
+export type InReqPatchGameSettings = {
+  gameSettings: GameSettingsPatch;
+};
+
+export type InReqPatchGameSettingsEnvelope = {
+  correlationId: string;
+  payload: InReqPatchGameSettings;
+  seq?: number | null;
+};
+
+export type InReqRegisterClient = {
+  name: string;
+  password?: Password | null;
+  spectator?: boolean;
+};
+
+export type InReqRegisterClientEnvelope = {
+  correlationId: string;
+  payload: InReqRegisterClient;
+  seq?: number | null;
+};
+
+export type InReqRematch = {
+  shuffleSettings?: GameSettingsOverride | null;
+};
+
+export type InReqRematchEnvelope = {
+  correlationId: string;
+  payload: InReqRematch;
+  seq?: number | null;
+};
+
+export type InReqRemoveBot = {
+  clientId: string;
+};
+
+export type InReqRemoveBotEnvelope = {
+  correlationId: string;
+  payload: InReqRemoveBot;
+  seq?: number | null;
+};
+
+export type InReqRenameClient = {
+  name: string;
+};
+
+export type InReqRenameClientEnvelope = {
+  correlationId: string;
+  payload: InReqRenameClient;
+  seq?: number | null;
+};
+
+export type InReqResumeGame = Record<string, unknown>;
+
+export type InReqResumeGameEnvelope = {
+  correlationId: string;
+  payload: InReqResumeGame;
+  seq?: number | null;
+};
+
+/** Stands in for the key/signature exchange with a token from an earlier
+`OUT_NOTIF_sessionToken`. */
+export type InReqResumeSession = {
+  capabilities?: Array<string>;
+  encoding?: Encoding;
+  token: string;
+};
+
+export type InReqResumeSessionEnvelope = {
+  correlationId: string;
+  payload: InReqResumeSession;
+  seq?: number | null;
+};
+
+export type InReqRoomEvents = Record<string, unknown>;
+
+export type InReqRoomEventsEnvelope = {
+  correlationId: string;
+  payload: InReqRoomEvents;
+  seq?: number | null;
+};
+
+export type InReqRoomInfo = Record<string, unknown>;
+
+export type InReqRoomInfoEnvelope = {
+  correlationId: string;
+  payload: InReqRoomInfo;
+  seq?: number | null;
+};
+
+export type InReqRoundState = Record<string, unknown>;
+
+export type InReqRoundStateEnvelope = {
+  correlationId: string;
+  payload: InReqRoundState;
+  seq?: number | null;
+};
+
+export type InReqSendAnnouncement = {
+  message: string;
+};
+
+export type InReqSendAnnouncementEnvelope = {
+  correlationId: string;
+  payload: InReqSendAnnouncement;
+  seq?: number | null;
+};
+
+export type InReqSendAnswer = {
+  answer: string;
+  gameId?: string | null;
+  questionId?: string | null;
+};
+
+export type InReqSendAnswerEnvelope = {
+  correlationId: string;
+  payload: InReqSendAnswer;
+  seq?: number | null;
+};
+
+export type InReqSendChat = {
+  message: string;
+};
+
+export type InReqSendChatEnvelope = {
+  correlationId: string;
+  payload: InReqSendChat;
+  seq?: number | null;
+};
+
+export type InReqSendGameSettings = {
+  gameSettings: GameSettings;
+};
+
+export type InReqSendGameSettingsEnvelope = {
+  correlationId: string;
+  payload: InReqSendGameSettings;
+  seq?: number | null;
+};
+
+export type InReqSendLobbyChat = {
+  message: string;
+};
+
+export type InReqSendLobbyChatEnvelope = {
+  correlationId: string;
+  payload: InReqSendLobbyChat;
+  seq?: number | null;
+};
+
+export type InReqSendPublicKey = {
+  algorithm?: SignatureAlgorithm;
+  capabilities?: Array<string>;
+  encoding?: Encoding;
+  key: string;
+};
+
+export type InReqSendPublicKeyEnvelope = {
+  correlationId: string;
+  payload: InReqSendPublicKey;
+  seq?: number | null;
+};
+
+export type InReqSendReaction = {
+  emoji: string;
+  target: ReactionTarget;
+};
+
+export type InReqSendReactionEnvelope = {
+  correlationId: string;
+  payload: InReqSendReaction;
+  seq?: number | null;
+};
+
+export type InReqSendWhisper = {
+  message: string;
+  targetId: string;
+};
+
+export type InReqSendWhisperEnvelope = {
+  correlationId: string;
+  payload: InReqSendWhisper;
+  seq?: number | null;
+};
+
+/** Asks for the server's [`EffectiveConfig`]; allowed before the handshake. */
+export type InReqServerConfig = Record<string, unknown>;
+
+export type InReqServerConfigEnvelope = {
+  correlationId: string;
+  payload: InReqServerConfig;
+  seq?: number | null;
+};
+
+export type InReqServerStats = Record<string, unknown>;
+
+export type InReqServerStatsEnvelope = {
+  correlationId: string;
+  payload: InReqServerStats;
+  seq?: number | null;
+};
+
+/** Names who becomes admin when the last admin leaves, ahead of whoever
+joined first. `null` clears it. */
+export type InReqSetAdminSuccessor = {
+  clientId?: string | null;
+};
+
+export type InReqSetAdminSuccessorEnvelope = {
+  correlationId: string;
+  payload: InReqSetAdminSuccessor;
+  seq?: number | null;
+};
+
+/** Sets a player's handicap; no extra time and a multiplier of 1 clear it. */
+export type InReqSetHandicap = {
+  clientId: string;
+  extraTimeMs: number;
+  scoreMultiplier: number;
+};
+
+export type InReqSetHandicapEnvelope = {
+  correlationId: string;
+  payload: InReqSetHandicap;
+  seq?: number | null;
+};
+
+export type InReqSetModerationSettings = {
+  action: ModerationAction;
+};
+
+export type InReqSetModerationSettingsEnvelope = {
+  correlationId: string;
+  payload: InReqSetModerationSettings;
+  seq?: number | null;
+};
+
+/** Whether others can find this client with `IN_REQ_findPlayer`. Visible
+until changed, for the rest of the session. */
+export type InReqSetPresenceVisibility = {
+  visible: boolean;
+};
+
+export type InReqSetPresenceVisibilityEnvelope = {
+  correlationId: string;
+  payload: InReqSetPresenceVisibility;
+  seq?: number | null;
+};
+
+/** Makes one of the admins the one asked for questions. */
+export type InReqSetQuestionProvider = {
+  clientId: string;
+};
+
+export type InReqSetQuestionProviderEnvelope = {
+  correlationId: string;
+  payload: InReqSetQuestionProvider;
+  seq?: number | null;
+};
+
+export type InReqSetReady = {
+  ready: boolean;
+};
+
+export type InReqSetReadyEnvelope = {
+  correlationId: string;
+  payload: InReqSetReady;
+  seq?: number | null;
+};
+
+export type InReqSetRoomName = {
+  name: string;
+};
+
+export type InReqSetRoomNameEnvelope = {
+  correlationId: string;
+  payload: InReqSetRoomName;
+  seq?: number | null;
+};
+
+export type InReqSetRoomPassword = {
+  password?: Password | null;
+};
+
+export type InReqSetRoomPasswordEnvelope = {
+  correlationId: string;
+  payload: InReqSetRoomPassword;
+  seq?: number | null;
+};
+
+/** Replaces the [`Subscription`] categories the room sends this client;
+the names are those of `Subscription`. */
+export type InReqSetSubscriptions = {
+  categories: Array<string>;
+};
+
+export type InReqSetSubscriptionsEnvelope = {
+  correlationId: string;
+  payload: InReqSetSubscriptions;
+  seq?: number | null;
+};
+
+export type InReqStartGame = {
+  gameSettings: GameSettings;
+};
+
+export type InReqStartGameEnvelope = {
+  correlationId: string;
+  payload: InReqStartGame;
+  seq?: number | null;
+};
+
+export type InReqStopGame = Record<string, unknown>;
+
+export type InReqStopGameEnvelope = {
+  correlationId: string;
+  payload: InReqStopGame;
+  seq?: number | null;
+};
+
+/** Starts `OUT_NOTIF_roomListChanged` updates, beginning with every room
+as it is now. */
+export type InReqSubscribeRoomList = Record<string, unknown>;
+
+export type InReqSubscribeRoomListEnvelope = {
+  correlationId: string;
+  payload: InReqSubscribeRoomList;
+  seq?: number | null;
+};
+
+export type InReqUnsubscribeRoomList = Record<string, unknown>;
+
+export type InReqUnsubscribeRoomListEnvelope = {
+  correlationId: string;
+  payload: InReqUnsubscribeRoomList;
+  seq?: number | null;
+};
+
+export type InReqVerifySignature = {
+  signature: string;
+};
+
+export type InReqVerifySignatureEnvelope = {
+  correlationId: string;
+  payload: InReqVerifySignature;
+  seq?: number | null;
+};
+
+export type InReqWordPartList = Record<string, unknown>;
+
+export type InReqWordPartListEnvelope = {
+  correlationId: string;
+  payload: InReqWordPartList;
+  seq?: number | null;
+};
+
+export type InRespQuestion = {
+  question: QuestionInfo;
+  questionSvg: string;
+};
+
+export type InRespQuestionEnvelope = {
+  correlationId: string;
+  payload: InRespQuestion;
+  seq?: number | null;
+};
+
+/** Sent instead of a question when the admin's client can't make one. */
+export type InRespQuestionError = {
+  errorCode: ErrorCode;
+  message?: string;
+};
+
+export type InRespQuestionErrorEnvelope = {
+  correlationId: string;
+  payload: InRespQuestionError;
+  seq?: number | null;
+};
+
+export type IpConnections = {
+  connections: number;
+  ip: string;
+};
+
+/** Why a room turned a registering client away, sent as the `code` of
+`OUT_RESP_error`. The registration can be retried once it's resolved. */
+export type JoinRejection =
+  | "wrong_password" | "room_full"
+  | "game_in_progress"
+  | "unavailable";
+
+/** Latencies in microseconds. `buckets` counts them against the upper
+bounds 1, 5, 10, 25, 50, 100, 250, 500, 1000 and 2500 ms, with a last
+bucket for anything slower. */
+export type LatencyHistogram = {
+  buckets: Array<number>;
+  count: number;
+  maxUs: number;
+  totalUs: number;
+};
+
+/** What happens to a chat message or name containing a blocked word. */
+export type ModerationAction =
+  | "reject" | "mask"
+  | "warn";
+
+/** How far a player's wrong answer was from the closest accepted reading. */
+export type NearMiss = {
+  closestReading: string;
+  distance: number;
+  id: string;
+};
+
+export type OutNotifAdminMade = {
+  id: string;
+};
+
+export type OutNotifAdminMadeEnvelope = {
+  correlationId: string;
+  payload: OutNotifAdminMade;
+  seq?: number | null;
+};
+
+export type OutNotifAdminRevoked = {
+  id: string;
+};
+
+export type OutNotifAdminRevokedEnvelope = {
+  correlationId: string;
+  payload: OutNotifAdminRevoked;
+  seq?: number | null;
+};
+
+export type OutNotifAnnouncement = {
+  id: string;
+  message: string;
+  messageId: string;
+  reactions?: Record<string, number>;
+  sentAt: number;
+};
+
+export type OutNotifAnnouncementEnvelope = {
+  correlationId: string;
+  payload: OutNotifAnnouncement;
+  seq?: number | null;
+};
+
+/** Sent privately to a player whose answer to a reading question was
+wrong, as the round ends. */
+export type OutNotifAnswerFeedback = {
+  answer: string;
+  closestReading: string;
+  distance: number;
+};
+
+export type OutNotifAnswerFeedbackEnvelope = {
+  correlationId: string;
+  payload: OutNotifAnswerFeedback;
+  seq?: number | null;
+};
+
+export type OutNotifChatSent = {
+  id: string;
+  message: string;
+  messageId: string;
+  reactions?: Record<string, number>;
+};
+
+export type OutNotifChatSentEnvelope = {
+  correlationId: string;
+  payload: OutNotifChatSent;
+  seq?: number | null;
+};
+
+export type OutNotifClientAnswered = {
+  answeredCount?: number | null;
+  correctCount?: number | null;
+  gameId?: string | null;
+  id: string;
+  totalPlayers?: number | null;
+};
+
+export type OutNotifClientAnsweredEnvelope = {
+  correlationId: string;
+  payload: OutNotifClientAnswered;
+  seq?: number | null;
+};
+
+export type OutNotifClientDisconnected = {
+  id: string;
+  reason?: DisconnectReason;
+};
+
+export type OutNotifClientDisconnectedEnvelope = {
+  correlationId: string;
+  payload: OutNotifClientDisconnected;
+  seq?: number | null;
+};
+
+export type OutNotifClientMuted = {
+  id: string;
+  muted: boolean;
+};
+
+export type OutNotifClientMutedEnvelope = {
+  correlationId: string;
+  payload: OutNotifClientMuted;
+  seq?: number | null;
+};
+
+export type OutNotifClientRegistered = {
+  client: ClientInfo;
+};
+
+export type OutNotifClientRegisteredEnvelope = {
+  correlationId: string;
+  payload: OutNotifClientRegistered;
+  seq?: number | null;
+};
+
+export type OutNotifClientRenamed = {
+  id: string;
+  name: string;
+};
+
+export type OutNotifClientRenamedEnvelope = {
+  correlationId: string;
+  payload: OutNotifClientRenamed;
+  seq?: number | null;
+};
+
+export type OutNotifCountdown = {
+  secondsRemaining: number;
+};
+
+export type OutNotifCountdownEnvelope = {
+  correlationId: string;
+  payload: OutNotifCountdown;
+  seq?: number | null;
+};
+
+/** A question outside the game's frequency bounds was let through. */
+export type OutNotifFrequencyWarning = {
+  frequency: number;
+  maxFrequency?: number | null;
+  minFrequency: number;
+};
+
+export type OutNotifFrequencyWarningEnvelope = {
+  correlationId: string;
+  payload: OutNotifFrequencyWarning;
+  seq?: number | null;
+};
+
+export type OutNotifGameSettingsChanged = {
+  gameSettings: GameSettings;
+};
+
+export type OutNotifGameSettingsChangedEnvelope = {
+  correlationId: string;
+  payload: OutNotifGameSettingsChanged;
+  seq?: number | null;
+};
+
+export type OutNotifGameStarted = {
+  deadline?: number | null;
+  gameId?: string | null;
+  gameIndex?: number;
+  gameSettings: GameSettings;
+  rematch?: boolean;
+};
+
+export type OutNotifGameStartedEnvelope = {
+  correlationId: string;
+  payload: OutNotifGameStarted;
+  seq?: number | null;
+};
+
+export type OutNotifGameStopped = {
+  acceptedReadings?: Array<string>;
+  answers: Array<AnswerInfo>;
+  elapsedMs?: number;
+  finalScores?: Array<PlayerScore>;
+  gameId?: string | null;
+  playerSummaries?: Array<PlayerSummary>;
+  primaryReading?: string;
+  question: QuestionInfo;
+  reason?: GameStopReason;
+  tierSummaries?: Array<TierSummary>;
+  winnerId?: string | null;
+};
+
+export type OutNotifGameStoppedEnvelope = {
+  correlationId: string;
+  payload: OutNotifGameStopped;
+  seq?: number | null;
+};
+
+export type OutNotifHandicapChanged = {
+  handicap?: Handicap | null;
+  id: string;
+};
+
+export type OutNotifHandicapChangedEnvelope = {
+  correlationId: string;
+  payload: OutNotifHandicapChanged;
+  seq?: number | null;
+};
+
+/** The game is about to be stopped as abandoned: another round without an
+answer ends it. */
+export type OutNotifIdleWarning = {
+  emptyRounds: number;
+  idleMs: number;
+  roundsLeft: number;
+};
+
+export type OutNotifIdleWarningEnvelope = {
+  correlationId: string;
+  payload: OutNotifIdleWarning;
+  seq?: number | null;
+};
+
+/** Server-wide chat, seen by every registered client whatever room it is
+in. Room chat arrives as `OUT_NOTIF_chatSent` instead. */
+export type OutNotifLobbyChat = {
+  id: string;
+  message: string;
+  name: string;
+  sentAt: number;
+};
+
+export type OutNotifLobbyChatEnvelope = {
+  correlationId: string;
+  payload: OutNotifLobbyChat;
+  seq?: number | null;
+};
+
+/** `active` says whether the server has a word list at all; without one
+the action changes nothing. */
+export type OutNotifModerationSettingsChanged = {
+  action: ModerationAction;
+  active: boolean;
+};
+
+export type OutNotifModerationSettingsChangedEnvelope = {
+  correlationId: string;
+  payload: OutNotifModerationSettingsChanged;
+  seq?: number | null;
+};
+
+export type OutNotifQuestion = {
+  assetId?: string | null;
+  assetSize?: number | null;
+  gameId?: string | null;
+  questionId: string;
+  questionSvg?: string;
+  remainingMs?: number | null;
+  roundDurationMs?: number | null;
+};
+
+export type OutNotifQuestionEnvelope = {
+  correlationId: string;
+  payload: OutNotifQuestion;
+  seq?: number | null;
+};
+
+/** The admin's client couldn't make a question. With `relaxed` it is asked
+again without the frequency bounds; a game that can't go on stops. */
+export type OutNotifQuestionFailed = {
+  errorCode: ErrorCode;
+  message: string;
+  relaxed?: boolean;
+};
+
+export type OutNotifQuestionFailedEnvelope = {
+  correlationId: string;
+  payload: OutNotifQuestionFailed;
+  seq?: number | null;
+};
+
+/** The admin the room asks for questions from now on. */
+export type OutNotifQuestionProviderChanged = {
+  id: string;
+};
+
+export type OutNotifQuestionProviderChangedEnvelope = {
+  correlationId: string;
+  payload: OutNotifQuestionProviderChanged;
+  seq?: number | null;
+};
+
+export type OutNotifReaction = {
+  emoji: string;
+  fromId: string;
+  target: ReactionTarget;
+};
+
+export type OutNotifReactionEnvelope = {
+  correlationId: string;
+  payload: OutNotifReaction;
+  seq?: number | null;
+};
+
+export type OutNotifReadyChanged = {
+  id: string;
+  ready: boolean;
+};
+
+export type OutNotifReadyChangedEnvelope = {
+  correlationId: string;
+  payload: OutNotifReadyChanged;
+  seq?: number | null;
+};
+
+export type OutNotifRoomListChanged = {
+  change: RoomListChange;
+};
+
+export type OutNotifRoomListChangedEnvelope = {
+  correlationId: string;
+  payload: OutNotifRoomListChanged;
+  seq?: number | null;
+};
+
+export type OutNotifRoomPasswordChanged = {
+  hasPassword: boolean;
+};
+
+export type OutNotifRoomPasswordChangedEnvelope = {
+  correlationId: string;
+  payload: OutNotifRoomPasswordChanged;
+  seq?: number | null;
+};
+
+export type OutNotifRoomRenamed = {
+  name: string;
+};
+
+export type OutNotifRoomRenamedEnvelope = {
+  correlationId: string;
+  payload: OutNotifRoomRenamed;
+  seq?: number | null;
+};
+
+export type OutNotifRoundEnded = {
+  acceptedReadings?: Array<string>;
+  answers: Array<AnswerInfo>;
+  gameId?: string | null;
+  nearMisses?: Array<NearMiss>;
+  primaryReading?: string;
+  question: QuestionInfo;
+  streaks?: Array<PlayerStreak>;
+};
+
+export type OutNotifRoundEndedEnvelope = {
+  correlationId: string;
+  payload: OutNotifRoundEnded;
+  seq?: number | null;
+};
+
+export type OutNotifRoundExtended = {
+  gameId?: string | null;
+  newRemainingMs: number;
+};
+
+export type OutNotifRoundExtendedEnvelope = {
+  correlationId: string;
+  payload: OutNotifRoundExtended;
+  seq?: number | null;
+};
+
+/** Sent at the server's checkpoints before a round ends, and with zero
+remaining once it does. */
+export type OutNotifRoundTimeWarning = {
+  gameId?: string | null;
+  remainingMs: number;
+};
+
+export type OutNotifRoundTimeWarningEnvelope = {
+  correlationId: string;
+  payload: OutNotifRoundTimeWarning;
+  seq?: number | null;
+};
+
+/** Something broke on the server side. The room the client was in is gone;
+either it is about to be put back into a fresh one or the connection
+closes. */
+export type OutNotifServerError = {
+  message: string;
+};
+
+export type OutNotifServerErrorEnvelope = {
+  correlationId: string;
+  payload: OutNotifServerError;
+  seq?: number | null;
+};
+
+/** Sent after every successful verification. */
+export type OutNotifSessionToken = {
+  expiresAt: number;
+  token: string;
+};
+
+export type OutNotifSessionTokenEnvelope = {
+  correlationId: string;
+  payload: OutNotifSessionToken;
+  seq?: number | null;
+};
+
+export type OutNotifStreak = {
+  id: string;
+  length: number;
+};
+
+export type OutNotifStreakEnvelope = {
+  correlationId: string;
+  payload: OutNotifStreak;
+  seq?: number | null;
+};
+
+export type OutNotifWhisperReceived = {
+  fromId: string;
+  message: string;
+};
+
+export type OutNotifWhisperReceivedEnvelope = {
+  correlationId: string;
+  payload: OutNotifWhisperReceived;
+  seq?: number | null;
+};
+
+export type OutReqQuestion = {
+  fontIndex?: number | null;
+  fontName?: string | null;
+  maxFrequency?: number | null;
+  minFrequency?: number;
+  questionType?: QuestionType;
+  roundSeed?: number | null;
+  usedWordCount?: number;
+};
+
+export type OutReqQuestionEnvelope = {
+  correlationId: string;
+  payload: OutReqQuestion;
+  seq?: number | null;
+};
+
+export type OutRespAsset = {
+  assetId: string;
+  content: string;
+};
+
+export type OutRespAssetEnvelope = {
+  correlationId: string;
+  payload: OutRespAsset;
+  seq?: number | null;
+};
+
+export type OutRespClientList = {
+  clients: Array<ClientInfo>;
+};
+
+export type OutRespClientListEnvelope = {
+  correlationId: string;
+  payload: OutRespClientList;
+  seq?: number | null;
+};
+
+export type OutRespClientRegistered = {
+  capabilities?: Array<Capability>;
+  /** @deprecated */
+  gameSettings: GameSettings;
+  id: string;
+  role: ClientRole;
+  room: RoomInfo;
+};
+
+export type OutRespClientRegisteredEnvelope = {
+  correlationId: string;
+  payload: OutRespClientRegistered;
+  seq?: number | null;
+};
+
+/** Oldest first. */
+export type OutRespDebugOutbox = {
+  clientId: string;
+  entries: Array<OutboxEntry>;
+};
+
+export type OutRespDebugOutboxEnvelope = {
+  correlationId: string;
+  payload: OutRespDebugOutbox;
+  seq?: number | null;
+};
+
+export type OutRespError = {
+  code?: JoinRejection | null;
+  error: string;
+};
+
+export type OutRespErrorEnvelope = {
+  correlationId: string;
+  payload: OutRespError;
+  seq?: number | null;
+};
+
+/** The archive document is passed as text, so its format (see `export`) is
+versioned apart from the protocol. */
+export type OutRespGameExport = {
+  document: string;
+  fileName: string;
+};
+
+export type OutRespGameExportEnvelope = {
+  correlationId: string;
+  payload: OutRespGameExport;
+  seq?: number | null;
+};
+
+export type OutRespGameReplay = {
+  replay: GameReplay;
+};
+
+export type OutRespGameReplayEnvelope = {
+  correlationId: string;
+  payload: OutRespGameReplay;
+  seq?: number | null;
+};
+
+/** A hidden player is reported the same as one that isn't there. */
+export type OutRespPlayerLocation = {
+  found: boolean;
+  inGame: boolean;
+  roomName?: string | null;
+};
+
+export type OutRespPlayerLocationEnvelope = {
+  correlationId: string;
+  payload: OutRespPlayerLocation;
+  seq?: number | null;
+};
+
+/** Oldest first. */
+export type OutRespRoomEvents = {
+  events: Array<RoomEvent>;
+};
+
+export type OutRespRoomEventsEnvelope = {
+  correlationId: string;
+  payload: OutRespRoomEvents;
+  seq?: number | null;
+};
+
+export type OutRespRoomInfo = {
+  room: RoomInfo;
+  subscriptions?: Array<Subscription>;
+};
+
+export type OutRespRoomInfoEnvelope = {
+  correlationId: string;
+  payload: OutRespRoomInfo;
+  seq?: number | null;
+};
+
+export type OutRespRoundState = {
+  active: boolean;
+  answeredCount: number;
+  hasAnswered: boolean;
+  nextRoundMs?: number | null;
+  remainingMs?: number | null;
+  roundNumber: number;
+};
+
+export type OutRespRoundStateEnvelope = {
+  correlationId: string;
+  payload: OutRespRoundState;
+  seq?: number | null;
+};
+
+export type OutRespServerConfig = {
+  config: EffectiveConfig;
+};
+
+export type OutRespServerConfigEnvelope = {
+  correlationId: string;
+  payload: OutRespServerConfig;
+  seq?: number | null;
+};
+
+export type OutRespServerStats = {
+  stats: ServerStats;
+};
+
+export type OutRespServerStatsEnvelope = {
+  correlationId: string;
+  payload: OutRespServerStats;
+  seq?: number | null;
+};
+
+export type OutRespSignMessage = {
+  capabilities?: Array<Capability>;
+  message: string;
+};
+
+export type OutRespSignMessageEnvelope = {
+  correlationId: string;
+  payload: OutRespSignMessage;
+  seq?: number | null;
+};
+
+export type OutRespStatus = {
+  sequence?: number | null;
+  status: string;
+};
+
+export type OutRespStatusEnvelope = {
+  correlationId: string;
+  payload: OutRespStatus;
+  seq?: number | null;
+};
+
+/** Most used first. Empty when the server has no word part index, in which
+case any `wordPart` is accepted. */
+export type OutRespWordPartList = {
+  wordParts: Array<WordPartEntry>;
+};
+
+export type OutRespWordPartListEnvelope = {
+  correlationId: string;
+  payload: OutRespWordPartList;
+  seq?: number | null;
+};
+
+/** A message the server sent to one client, as kept for debugging. */
+export type OutboxEntry = {
+  correlationId?: string | null;
+  delivered: boolean;
+  messageType: string;
+  sentAt: number;
+  size: number;
+};
+
+/** Never printed, so passwords don't leak through message logging. */
+export type Password = string;
+
+export type PlayerScore = {
+  id: string;
+  score: number;
+};
+
+export type PlayerStreak = {
+  id: string;
+  streak: number;
+};
+
+export type PlayerSummary = {
+  accuracy: number;
+  averageCorrectTime?: number | null;
+  correctAnswers: number;
+  disconnected: boolean;
+  id: string;
+  longestStreak: number;
+  rank: number;
+  roundsPlayed: number;
+};
+
+export type QuestionInfo = {
+  fontName: string;
+  frequency?: number | null;
+  questionType?: QuestionType;
+  wordInfo: WordInfo;
+};
+
+/** What the prompt shows and what the player has to type back. */
+export type QuestionType =
+  | "reading_of_word"
+  | "word_from_reading"
+  | "meaning_of_word";
+
+/** At most `count` messages in any `window_ms`. */
+export type RateLimitInfo = {
+  count: number;
+  windowMs: number;
+};
+
+/** A chat message by its `messageId`, or a round by its `questionId`. */
+export type ReactionTarget = {
+  id: string;
+  type: ReactionTargetKind;
+};
+
+export type ReactionTargetKind = "chat" | "round";
+
+export type ReadingWithParts = {
+  parts: Array<WordPartInfo>;
+  reading: string;
+};
+
+/** One entry of a room's audit log. */
+export type RoomEvent = {
+  at: number;
+  kind: RoomEventKind;
+};
+
+/** Client ids are the room's uuids; `by` is whoever made the change. */
+export type RoomEventKind =
+  | {
+    id: string;
+    name: string;
+    type: "client_joined";
+  }
+  | {
+    id: string;
+    reason: DisconnectReason;
+    type: "client_left";
+  }
+  | {
+    id: string;
+    type: "admin_made";
+  }
+  | {
+    id: string;
+    type: "admin_revoked";
+  }
+  | {
+    by: string;
+    type: "settings_changed";
+  }
+  | {
+    by: string;
+    type: "game_started";
+  }
+  | {
+    reason: GameStopReason;
+    type: "game_stopped";
+  };
+
+export type RoomInfo = {
+  chatMaxLength?: number;
+  clientCount: number;
+  currentRound: number;
+  gameSettings: GameSettings;
+  isGameRunning: boolean;
+  name: string;
+  spectatorCount: number;
+  spectatorDelayMs?: number;
+};
+
+export type RoomListChange =
+  | {
+    room: RoomListEntry;
+    type: "opened";
+  }
+  | {
+    room: RoomListEntry;
+    type: "updated";
+  }
+  | {
+    id: string;
+    type: "closed";
+  };
+
+/** What a room looks like from the lobby. */
+export type RoomListEntry = {
+  hasPassword: boolean;
+  id: string;
+  isGameRunning: boolean;
+  maxClients?: number | null;
+  name: string;
+  playerCount: number;
+  spectatorCount: number;
+};
+
+export type RoomStats = {
+  clients: number;
+  gamesStarted?: number;
+  isGameRunning: boolean;
+  name: string;
+  players?: number;
+  roundsPlayed?: number;
+  spectators?: number;
+};
+
+export type RoundRecord = {
+  aborted?: boolean;
+  answers: Array<AnswerInfo>;
+  question: QuestionInfo;
+  questionId?: string | null;
+  reactions?: Record<string, number>;
+  tier?: string | null;
+};
+
+/** Optional parts of the server that are switched on. */
+export type ServerFeatures = {
+  capture: boolean;
+  debugOutbox: boolean;
+  emptyRoomBots: number;
+  persistence: boolean;
+  tls: boolean;
+  wordFilter: boolean;
+  wordParts: boolean;
+};
+
+export type ServerStats = {
+  binaryFrames?: number;
+  connectedClients?: number;
+  connectionsAccepted?: number;
+  connectionsPerIp?: Array<IpConnections>;
+  gamesCompleted?: number;
+  gamesStarted?: number;
+  pendingClients: number;
+  registeredClients: number;
+  registrations?: number;
+  rejectedOrigins?: number;
+  requestLatency?: Record<string, LatencyHistogram>;
+  rooms: Array<RoomStats>;
+  uptimeSeconds?: number;
+};
+
+/** Signature scheme of the public key a client sends in its hello. */
+export type SignatureAlgorithm =
+  | "ed25519"
+  | "ecdsa-p256"
+  | "unknown";
+
+/** Room notifications a client can opt out of with
+`IN_REQ_setSubscriptions`. Whatever has no category, such as the game
+starting or stopping, is always sent. */
+export type Subscription =
+  | "questions"
+  | "chat"
+  | "answers"
+  | "presence";
+
+/** How the room did on the questions of one difficulty tier. */
+export type TierSummary = {
+  accuracy: number;
+  correctAnswers: number;
+  rounds: number;
+  tier: string;
+};
+
+export type WordInfo = {
+  meanings: Array<Array<Array<string>>>;
+  readings: Array<ReadingWithParts>;
+  word: string;
+};
+
+/** One reading of a word part in the server's index, with how many words
+use the part with that reading. */
+export type WordPartEntry = {
+  count: number;
+  wordPart: string;
+  wordPartReading: string;
+};
+
+export type WordPartExample = {
+  frequency?: number | null;
+  reading: string;
+  word: string;
+};
+
+export type WordPartInfo = {
+  examples: Array<WordPartExample>;
+  wordPart: string;
+  wordPartReading: string;
+};
+
+export type TransportMsg =
+  | InReqSendPublicKeyEnvelope & {
+    messageType: "IN_REQ_sendPublicKey";
+  }
+  | InReqVerifySignatureEnvelope & {
+    messageType: "IN_REQ_verifysignature";
+  }
+  | InReqRegisterClientEnvelope & {
+    messageType: "IN_REQ_registerClient";
+  }
+  | InReqSendChatEnvelope & {
+    messageType: "IN_REQ_sendChat";
+  }
+  | InReqMakeAdminEnvelope & {
+    messageType: "IN_REQ_makeAdmin";
+  }
+  | InReqClientListEnvelope & {
+    messageType: "IN_REQ_clientList";
+  }
+  | InReqStartGameEnvelope & {
+    messageType: "IN_REQ_startGame";
+  }
+  | InReqStopGameEnvelope & {
+    messageType: "IN_REQ_stopGame";
+  }
+  | InReqSendAnswerEnvelope & {
+    messageType: "IN_REQ_sendAnswer";
+  }
+  | InReqSendGameSettingsEnvelope & {
+    messageType: "IN_REQ_sendGameSettings";
+  }
+  | InReqSendWhisperEnvelope & {
+    messageType: "IN_REQ_sendWhisper";
+  }
+  | InReqRenameClientEnvelope & {
+    messageType: "IN_REQ_renameClient";
+  }
+  | InReqSetReadyEnvelope & {
+    messageType: "IN_REQ_setReady";
+  }
+  | InReqServerStatsEnvelope & {
+    messageType: "IN_REQ_serverStats";
+  }
+  | InReqSetRoomPasswordEnvelope & {
+    messageType: "IN_REQ_setRoomPassword";
+  }
+  | InReqMuteClientEnvelope & {
+    messageType: "IN_REQ_muteClient";
+  }
+  | InReqRoomInfoEnvelope & {
+    messageType: "IN_REQ_roomInfo";
+  }
+  | InReqSetRoomNameEnvelope & {
+    messageType: "IN_REQ_setRoomName";
+  }
+  | InReqGameReplayEnvelope & {
+    messageType: "IN_REQ_gameReplay";
+  }
+  | InReqExtendRoundEnvelope & {
+    messageType: "IN_REQ_extendRound";
+  }
+  | InReqSendAnnouncementEnvelope & {
+    messageType: "IN_REQ_sendAnnouncement";
+  }
+  | InReqRematchEnvelope & {
+    messageType: "IN_REQ_rematch";
+  }
+  | InReqLeaveRoomEnvelope & {
+    messageType: "IN_REQ_leaveRoom";
+  }
+  | InReqResumeGameEnvelope & {
+    messageType: "IN_REQ_resumeGame";
+  }
+  | InReqSendReactionEnvelope & {
+    messageType: "IN_REQ_sendReaction";
+  }
+  | InReqRoundStateEnvelope & {
+    messageType: "IN_REQ_roundState";
+  }
+  | InReqResumeSessionEnvelope & {
+    messageType: "IN_REQ_resumeSession";
+  }
+  | InReqSendLobbyChatEnvelope & {
+    messageType: "IN_REQ_sendLobbyChat";
+  }
+  | InReqPatchGameSettingsEnvelope & {
+    messageType: "IN_REQ_patchGameSettings";
+  }
+  | InReqExportGameEnvelope & {
+    messageType: "IN_REQ_exportGame";
+  }
+  | InReqSetHandicapEnvelope & {
+    messageType: "IN_REQ_setHandicap";
+  }
+  | InReqRoomEventsEnvelope & {
+    messageType: "IN_REQ_roomEvents";
+  }
+  | InReqSubscribeRoomListEnvelope & {
+    messageType: "IN_REQ_subscribeRoomList";
+  }
+  | InReqUnsubscribeRoomListEnvelope & {
+    messageType: "IN_REQ_unsubscribeRoomList";
+  }
+  | InReqSetModerationSettingsEnvelope & {
+    messageType: "IN_REQ_setModerationSettings";
+  }
+  | InReqDebugOutboxEnvelope & {
+    messageType: "IN_REQ_debugOutbox";
+  }
+  | InReqWordPartListEnvelope & {
+    messageType: "IN_REQ_wordPartList";
+  }
+  | InReqSetQuestionProviderEnvelope & {
+    messageType: "IN_REQ_setQuestionProvider";
+  }
+  | InReqAddBotEnvelope & {
+    messageType: "IN_REQ_addBot";
+  }
+  | InReqRemoveBotEnvelope & {
+    messageType: "IN_REQ_removeBot";
+  }
+  | InReqFetchAssetEnvelope & {
+    messageType: "IN_REQ_fetchAsset";
+  }
+  | InReqFindPlayerEnvelope & {
+    messageType: "IN_REQ_findPlayer";
+  }
+  | InReqSetPresenceVisibilityEnvelope & {
+    messageType: "IN_REQ_setPresenceVisibility";
+  }
+  | InReqSetAdminSuccessorEnvelope & {
+    messageType: "IN_REQ_setAdminSuccessor";
+  }
+  | InReqServerConfigEnvelope & {
+    messageType: "IN_REQ_serverConfig";
+  }
+  | InReqSetSubscriptionsEnvelope & {
+    messageType: "IN_REQ_setSubscriptions";
+  }
+  | InReqDebugCaptureEnvelope & {
+    messageType: "IN_REQ_debugCapture";
+  }
+  | OutRespClientRegisteredEnvelope & {
+    messageType: "OUT_RESP_clientRegistered";
+  }
+  | OutRespStatusEnvelope & {
+    messageType: "OUT_RESP_status";
+  }
+  | OutRespClientListEnvelope & {
+    messageType: "OUT_RESP_clientList";
+  }
+  | OutRespSignMessageEnvelope & {
+    messageType: "OUT_RESP_signMessage";
+  }
+  | OutRespServerStatsEnvelope & {
+    messageType: "OUT_RESP_serverStats";
+  }
+  | OutRespErrorEnvelope & {
+    messageType: "OUT_RESP_error";
+  }
+  | OutRespRoomInfoEnvelope & {
+    messageType: "OUT_RESP_roomInfo";
+  }
+  | OutRespGameReplayEnvelope & {
+    messageType: "OUT_RESP_gameReplay";
+  }
+  | OutRespRoundStateEnvelope & {
+    messageType: "OUT_RESP_roundState";
+  }
+  | OutRespGameExportEnvelope & {
+    messageType: "OUT_RESP_gameExport";
+  }
+  | OutRespRoomEventsEnvelope & {
+    messageType: "OUT_RESP_roomEvents";
+  }
+  | OutRespDebugOutboxEnvelope & {
+    messageType: "OUT_RESP_debugOutbox";
+  }
+  | OutRespWordPartListEnvelope & {
+    messageType: "OUT_RESP_wordPartList";
+  }
+  | OutRespAssetEnvelope & {
+    messageType: "OUT_RESP_asset";
+  }
+  | OutRespPlayerLocationEnvelope & {
+    messageType: "OUT_RESP_playerLocation";
+  }
+  | OutRespServerConfigEnvelope & {
+    messageType: "OUT_RESP_serverConfig";
+  }
+  | OutReqQuestionEnvelope & {
+    messageType: "OUT_REQ_question";
+  }
+  | InRespQuestionEnvelope & {
+    messageType: "IN_RESP_question";
+  }
+  | InRespQuestionErrorEnvelope & {
+    messageType: "IN_RESP_questionError";
+  }
+  | OutNotifClientRegisteredEnvelope & {
+    messageType: "OUT_NOTIF_clientRegistered";
+  }
+  | OutNotifClientDisconnectedEnvelope & {
+    messageType: "OUT_NOTIF_clientDisconnected";
+  }
+  | OutNotifChatSentEnvelope & {
+    messageType: "OUT_NOTIF_chatSent";
+  }
+  | OutNotifAdminMadeEnvelope & {
+    messageType: "OUT_NOTIF_adminMade";
+  }
+  | OutNotifGameStartedEnvelope & {
+    messageType: "OUT_NOTIF_gameStarted";
+  }
+  | OutNotifGameStoppedEnvelope & {
+    messageType: "OUT_NOTIF_gameStopped";
+  }
+  | OutNotifQuestionEnvelope & {
+    messageType: "OUT_NOTIF_question";
+  }
+  | OutNotifClientAnsweredEnvelope & {
+    messageType: "OUT_NOTIF_clientAnswered";
+  }
+  | OutNotifRoundEndedEnvelope & {
+    messageType: "OUT_NOTIF_roundEnded";
+  }
+  | OutNotifGameSettingsChangedEnvelope & {
+    messageType: "OUT_NOTIF_gameSettingsChanged";
+  }
+  | OutNotifWhisperReceivedEnvelope & {
+    messageType: "OUT_NOTIF_whisperReceived";
+  }
+  | OutNotifClientRenamedEnvelope & {
+    messageType: "OUT_NOTIF_clientRenamed";
+  }
+  | OutNotifReadyChangedEnvelope & {
+    messageType: "OUT_NOTIF_readyChanged";
+  }
+  | OutNotifRoomPasswordChangedEnvelope & {
+    messageType: "OUT_NOTIF_roomPasswordChanged";
+  }
+  | OutNotifClientMutedEnvelope & {
+    messageType: "OUT_NOTIF_clientMuted";
+  }
+  | OutNotifRoomRenamedEnvelope & {
+    messageType: "OUT_NOTIF_roomRenamed";
+  }
+  | OutNotifCountdownEnvelope & {
+    messageType: "OUT_NOTIF_countdown";
+  }
+  | OutNotifRoundExtendedEnvelope & {
+    messageType: "OUT_NOTIF_roundExtended";
+  }
+  | OutNotifStreakEnvelope & {
+    messageType: "OUT_NOTIF_streak";
+  }
+  | OutNotifAnnouncementEnvelope & {
+    messageType: "OUT_NOTIF_announcement";
+  }
+  | OutNotifReactionEnvelope & {
+    messageType: "OUT_NOTIF_reaction";
+  }
+  | OutNotifFrequencyWarningEnvelope & {
+    messageType: "OUT_NOTIF_frequencyWarning";
+  }
+  | OutNotifSessionTokenEnvelope & {
+    messageType: "OUT_NOTIF_sessionToken";
+  }
+  | OutNotifLobbyChatEnvelope & {
+    messageType: "OUT_NOTIF_lobbyChat";
+  }
+  | OutNotifServerErrorEnvelope & {
+    messageType: "OUT_NOTIF_serverError";
+  }
+  | OutNotifHandicapChangedEnvelope & {
+    messageType: "OUT_NOTIF_handicapChanged";
+  }
+  | OutNotifRoomListChangedEnvelope & {
+    messageType: "OUT_NOTIF_roomListChanged";
+  }
+  | OutNotifRoundTimeWarningEnvelope & {
+    messageType: "OUT_NOTIF_roundTimeWarning";
+  }
+  | OutNotifModerationSettingsChangedEnvelope & {
+    messageType: "OUT_NOTIF_moderationSettingsChanged";
+  }
+  | OutNotifAnswerFeedbackEnvelope & {
+    messageType: "OUT_NOTIF_answerFeedback";
+  }
+  | OutNotifIdleWarningEnvelope & {
+    messageType: "OUT_NOTIF_idleWarning";
+  }
+  | OutNotifAdminRevokedEnvelope & {
+    messageType: "OUT_NOTIF_adminRevoked";
+  }
+  | OutNotifQuestionProviderChangedEnvelope & {
+    messageType: "OUT_NOTIF_questionProviderChanged";
+  }
+  | OutNotifQuestionFailedEnvelope & {
+    messageType: "OUT_NOTIF_questionFailed";
+  };
@@ -0,0 +1,45 @@
+//! Writes the wire protocol as JSON Schema, and optionally as TypeScript.
+//!
+//! Usage: `export-schema [SCHEMA_PATH] [--ts TS_PATH]`. Without a path the
+//! schema goes to stdout.
+
+use std::{env, fs, process};
+
+use kanjilab_server::schema::{protocol_schema, protocol_schema_pretty, typescript_definitions};
+
+fn main() {
+    let mut schema_path = None;
+    let mut ts_path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ts" => match args.next() {
+                Some(path) => ts_path = Some(path),
+                None => fail("--ts needs a path"),
+            },
+            _ if schema_path.is_none() => schema_path = Some(arg),
+            _ => fail(&format!("unexpected argument {arg}")),
+        }
+    }
+
+    let schema = protocol_schema_pretty();
+    match schema_path {
+        Some(path) => write(&path, &schema),
+        None => print!("{schema}"),
+    }
+    if let Some(path) = ts_path {
+        write(&path, &typescript_definitions(&protocol_schema()));
+    }
+}
+
+fn write(path: &str, contents: &str) {
+    if let Err(e) = fs::write(path, contents) {
+        fail(&format!("failed to write {path}: {e}"));
+    }
+}
+
+fn fail(msg: &str) -> ! {
+    eprintln!("export-schema: {msg}");
+    process::exit(1);
+}
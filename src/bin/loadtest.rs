@@ -0,0 +1,333 @@
+//! Drives a running server with many clients and reports how it held up.
+//!
+//! Usage: `loadtest [URL] [--clients N] [--duration SECS] [--chat-every SECS]
+//! [--answer-within SECS] [--max-p95 MS] [--max-p99 MS] [--max-dropped N]`.
+//! The URL defaults to `ws://127.0.0.1:8080`. The first client hosts: it
+//! starts a game and serves the questions, the rest chat and answer. Exits
+//! with 1 when a `--max-*` threshold is exceeded, so it can gate CI.
+//!
+//! All clients connect from one address, so `max_connections_per_ip` has
+//! to allow them.
+
+use std::{collections::HashMap, env, process, time::Duration};
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, envelope},
+    tools::random_bytes,
+};
+use tokio::{
+    task::JoinSet,
+    time::{Instant, sleep},
+};
+use uuid::Uuid;
+
+struct Options {
+    url: String,
+    clients: usize,
+    duration: Duration,
+    chat_every: Duration,
+    answer_within: Duration,
+    max_p95: Option<Duration>,
+    max_p99: Option<Duration>,
+    max_dropped: Option<usize>,
+}
+
+/// What one client saw.
+#[derive(Default)]
+struct Outcome {
+    connected: bool,
+    dropped: bool,
+    round_trips: Vec<Duration>,
+}
+
+#[tokio::main]
+async fn main() {
+    let options = parse_args();
+
+    let mut host = match join(&options.url, "load-host").await {
+        Ok(client) => client,
+        Err(e) => fail(&format!("host can't join: {e}")),
+    };
+    if let Err(e) = start_game(&mut host).await {
+        fail(&format!("host can't start a game: {e}"));
+    }
+
+    let deadline = Instant::now() + options.duration;
+    let mut clients = JoinSet::new();
+    clients.spawn(play(Ok(host), true, deadline, Pace::of(&options)));
+    for i in 1..options.clients {
+        let url = options.url.clone();
+        let pace = Pace::of(&options);
+        clients.spawn(async move {
+            let client = join(&url, &format!("load-{i}")).await;
+            play(client, false, deadline, pace).await
+        });
+    }
+
+    let mut outcomes = Vec::new();
+    while let Some(outcome) = clients.join_next().await {
+        match outcome {
+            Ok(outcome) => outcomes.push(outcome),
+            Err(e) => fail(&format!("client task failed: {e}")),
+        }
+    }
+
+    if !report(&options, &outcomes) {
+        process::exit(1);
+    }
+}
+
+async fn join(url: &str, name: &str) -> Result<TestClient, String> {
+    let mut client = TestClient::connect(url).await?;
+    client.handshake().await?;
+    client.register(name).await?;
+    Ok(client)
+}
+
+async fn start_game(host: &mut TestClient) -> Result<(), String> {
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings {
+            rounds_count: 1000,
+            start_countdown: 0,
+            allow_repeat_words: true,
+            ..Default::default()
+        },
+    }));
+    let correlation_id = host.send(start).await?;
+    match host.status(correlation_id, DEFAULT_TIMEOUT).await?.as_str() {
+        "success" => Ok(()),
+        status => Err(status.to_string()),
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Pace {
+    chat_every: Duration,
+    answer_within: Duration,
+}
+
+impl Pace {
+    fn of(options: &Options) -> Self {
+        Self {
+            chat_every: options.chat_every,
+            answer_within: options.answer_within,
+        }
+    }
+}
+
+/// Chats, answers and, for the host, keeps the game going until the
+/// deadline. Every request is timed until its response arrives.
+async fn play(
+    client: Result<TestClient, String>,
+    is_host: bool,
+    deadline: Instant,
+    pace: Pace,
+) -> Outcome {
+    let mut outcome = Outcome::default();
+    let mut client = match client {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("loadtest: can't join: {e}");
+            return outcome;
+        }
+    };
+    outcome.connected = true;
+
+    let mut in_flight: HashMap<Uuid, Instant> = HashMap::new();
+    let mut answers: Vec<(Instant, InReqSendAnswer)> = Vec::new();
+    let mut next_chat = Instant::now() + pace.chat_every.mul_f64(random_unit());
+
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+
+        let mut to_send = Vec::new();
+        if now >= next_chat {
+            to_send.push(TransportMsg::InReqSendChat(envelope(InReqSendChat {
+                message: "load".into(),
+            })));
+            next_chat += pace.chat_every;
+        }
+        let (due, later) = answers.into_iter().partition(|(at, _)| *at <= now);
+        answers = later;
+        for (_, answer) in due {
+            to_send.push(TransportMsg::InReqSendAnswer(envelope(answer)));
+        }
+        for msg in to_send {
+            match client.send(msg).await {
+                Ok(correlation_id) => {
+                    in_flight.insert(correlation_id, Instant::now());
+                }
+                Err(_) => {
+                    outcome.dropped = true;
+                    return outcome;
+                }
+            }
+        }
+
+        let wake = answers
+            .iter()
+            .map(|(at, _)| *at)
+            .chain([next_chat, deadline])
+            .min()
+            .unwrap_or(deadline);
+        let msg = match client.next(wake.saturating_duration_since(now)).await {
+            Ok(Some(msg)) => msg,
+            Ok(None) => continue,
+            Err(_) => {
+                outcome.dropped = true;
+                return outcome;
+            }
+        };
+
+        if let Some(sent) = response_id(&msg).and_then(|id| in_flight.remove(&id)) {
+            outcome.round_trips.push(sent.elapsed());
+        }
+        match msg {
+            TransportMsg::OutNotifQuestion(env) => {
+                let at = Instant::now() + pace.answer_within.mul_f64(random_unit());
+                let answer = InReqSendAnswer {
+                    answer: "load".into(),
+                    question_id: Some(env.payload.question_id),
+                    game_id: env.payload.game_id,
+                };
+                answers.push((at, answer));
+            }
+            TransportMsg::OutReqQuestion(env) if is_host => {
+                let question = TransportMsg::InRespQuestion(TransportEnvelope {
+                    correlation_id: env.correlation_id,
+                    seq: None,
+                    payload: InRespQuestion {
+                        question: QuestionInfo::default(),
+                        question_svg: "<svg/>".into(),
+                    },
+                });
+                if client.send(question).await.is_err() {
+                    outcome.dropped = true;
+                    return outcome;
+                }
+            }
+            TransportMsg::OutNotifGameStopped(_) if is_host => {
+                sleep(Duration::from_secs(1)).await;
+                if start_game(&mut client).await.is_err() {
+                    eprintln!("loadtest: host can't start the next game");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    client.close().await;
+    outcome
+}
+
+/// Prints the summary; `false` if a threshold was exceeded.
+fn report(options: &Options, outcomes: &[Outcome]) -> bool {
+    let connected = outcomes.iter().filter(|o| o.connected).count();
+    let dropped = outcomes.iter().filter(|o| o.dropped).count();
+    let mut round_trips: Vec<Duration> = outcomes
+        .iter()
+        .flat_map(|o| o.round_trips.iter().copied())
+        .collect();
+    round_trips.sort();
+
+    println!(
+        "connected {connected}/{} ({:.1}%)",
+        options.clients,
+        100.0 * connected as f64 / options.clients as f64
+    );
+    println!("dropped   {dropped}");
+    println!("requests  {}", round_trips.len());
+    let p95 = percentile(&round_trips, 0.95);
+    let p99 = percentile(&round_trips, 0.99);
+    for (name, value) in [
+        ("p50", percentile(&round_trips, 0.50)),
+        ("p95", p95),
+        ("p99", p99),
+    ] {
+        match value {
+            Some(d) => println!("{name}       {:.1} ms", d.as_secs_f64() * 1000.0),
+            None => println!("{name}       -"),
+        }
+    }
+
+    let mut ok = true;
+    for (name, value, max) in [("p95", p95, options.max_p95), ("p99", p99, options.max_p99)] {
+        if let (Some(value), Some(max)) = (value, max)
+            && value > max
+        {
+            eprintln!("loadtest: {name} {value:?} is over {max:?}");
+            ok = false;
+        }
+    }
+    if let Some(max) = options.max_dropped
+        && dropped + (options.clients - connected) > max
+    {
+        eprintln!(
+            "loadtest: {dropped} dropped and {} never connected, allowed {max}",
+            options.clients - connected
+        );
+        ok = false;
+    }
+    ok
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[Duration], p: f64) -> Option<Duration> {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.saturating_sub(1)).copied()
+}
+
+fn random_unit() -> f64 {
+    let bytes = random_bytes();
+    f64::from(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])) / f64::from(u32::MAX)
+}
+
+fn parse_args() -> Options {
+    let mut options = Options {
+        url: "ws://127.0.0.1:8080".into(),
+        clients: 10,
+        duration: Duration::from_secs(60),
+        chat_every: Duration::from_secs(5),
+        answer_within: Duration::from_secs(3),
+        max_p95: None,
+        max_p99: None,
+        max_dropped: None,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| -> u64 {
+            let Some(value) = args.next() else {
+                fail(&format!("{name} needs a value"));
+            };
+            value
+                .parse()
+                .unwrap_or_else(|_| fail(&format!("{name} takes a number, not {value}")))
+        };
+        match arg.as_str() {
+            "--clients" => options.clients = value("--clients").max(1) as usize,
+            "--duration" => options.duration = Duration::from_secs(value("--duration")),
+            "--chat-every" => {
+                options.chat_every = Duration::from_secs(value("--chat-every").max(1))
+            }
+            "--answer-within" => {
+                options.answer_within = Duration::from_secs(value("--answer-within"))
+            }
+            "--max-p95" => options.max_p95 = Some(Duration::from_millis(value("--max-p95"))),
+            "--max-p99" => options.max_p99 = Some(Duration::from_millis(value("--max-p99"))),
+            "--max-dropped" => options.max_dropped = Some(value("--max-dropped") as usize),
+            _ if !arg.starts_with("--") => options.url = arg,
+            _ => fail(&format!("unexpected argument {arg}")),
+        }
+    }
+    options
+}
+
+fn fail(msg: &str) -> ! {
+    eprintln!("loadtest: {msg}");
+    process::exit(1);
+}
@@ -0,0 +1,144 @@
+//! Sends a session recorded with `ServerConfig::capture` to a running
+//! server again, to reproduce a bug report.
+//!
+//! Usage: `replay FILE [URL] [--fast]`. The URL defaults to
+//! `ws://127.0.0.1:8080`. The recorded frames go out with the gaps they
+//! had, or back to back with `--fast`. The replay signs in with a key of
+//! its own, so the recorded key exchange and session resumes are skipped.
+//!
+//! Each response is checked against the recorded one: its type, and the
+//! status of an `OUT_RESP_status`. Notifications depend on who else was
+//! connected and are not compared. Exits with 1 on any mismatch.
+
+use std::{collections::HashMap, env, path::PathBuf, process, time::Duration};
+
+use kanjilab_server::{
+    capture::{CaptureEntry, Direction, outcome, read_capture},
+    testkit::{DEFAULT_TIMEOUT, TestClient},
+};
+use serde_json::Value;
+use tokio::time::{Instant, sleep_until};
+use uuid::Uuid;
+
+/// Requests replaced by the replay's own sign-in.
+const SIGN_IN: [&str; 3] = [
+    "IN_REQ_sendPublicKey",
+    "IN_REQ_verifysignature",
+    "IN_REQ_resumeSession",
+];
+
+struct Options {
+    path: PathBuf,
+    url: String,
+    fast: bool,
+}
+
+#[tokio::main]
+async fn main() {
+    let options = parse_args();
+    let entries = read_capture(&options.path).unwrap_or_else(|e| fail(&e));
+    let expected = recorded_responses(&entries);
+
+    let mut client = match TestClient::connect(options.url.as_str()).await {
+        Ok(client) => client,
+        Err(e) => fail(&format!("can't connect: {e}")),
+    };
+    if let Err(e) = client.handshake().await {
+        fail(&format!("handshake failed: {e}"));
+    }
+
+    let requests: Vec<_> = entries
+        .iter()
+        .filter(|e| e.direction == Direction::In)
+        .filter(|e| !SIGN_IN.contains(&message_type(&e.frame)))
+        .collect();
+    let first_at = requests.first().map_or(0, |e| e.at);
+    let started = Instant::now();
+    let (mut compared, mut mismatches) = (0, 0);
+
+    for entry in &requests {
+        if !options.fast {
+            sleep_until(started + Duration::from_millis(entry.at.saturating_sub(first_at))).await;
+        }
+        let frame = match &entry.frame {
+            Value::String(text) => text.clone(),
+            frame => frame.to_string(),
+        };
+        if let Err(e) = client.send_raw(frame).await {
+            fail(&format!("connection lost: {e}"));
+        }
+
+        let Some((correlation_id, expected)) =
+            correlation_id(&entry.frame).and_then(|id| Some((id, expected.get(&id)?)))
+        else {
+            continue;
+        };
+        let got = match client.response(correlation_id, DEFAULT_TIMEOUT).await {
+            Ok(msg) => outcome(&serde_json::to_value(&msg).unwrap_or_default()),
+            Err(e) => format!("nothing ({e})"),
+        };
+        compared += 1;
+        if got != *expected {
+            mismatches += 1;
+            println!(
+                "{} {correlation_id}: expected {expected}, got {got}",
+                message_type(&entry.frame)
+            );
+        }
+    }
+
+    println!(
+        "replayed {} frames, {} of {compared} responses matched",
+        requests.len(),
+        compared - mismatches
+    );
+    if mismatches > 0 {
+        process::exit(1);
+    }
+}
+
+/// The first recorded response to each request.
+fn recorded_responses(entries: &[CaptureEntry]) -> HashMap<Uuid, String> {
+    let mut responses = HashMap::new();
+    for entry in entries {
+        if entry.direction == Direction::Out
+            && message_type(&entry.frame).starts_with("OUT_RESP_")
+            && let Some(id) = correlation_id(&entry.frame)
+        {
+            responses.entry(id).or_insert_with(|| outcome(&entry.frame));
+        }
+    }
+    responses
+}
+
+fn message_type(frame: &Value) -> &str {
+    frame["messageType"].as_str().unwrap_or_default()
+}
+
+fn correlation_id(frame: &Value) -> Option<Uuid> {
+    frame["correlationId"].as_str()?.parse().ok()
+}
+
+fn parse_args() -> Options {
+    let mut path = None;
+    let mut options = Options {
+        path: PathBuf::new(),
+        url: "ws://127.0.0.1:8080".into(),
+        fast: false,
+    };
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--fast" => options.fast = true,
+            _ if arg.starts_with("--") => fail(&format!("unexpected argument {arg}")),
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => options.url = arg,
+        }
+    }
+    options.path = path.unwrap_or_else(|| fail("usage: replay FILE [URL] [--fast]"));
+    options
+}
+
+fn fail(msg: &str) -> ! {
+    eprintln!("replay: {msg}");
+    process::exit(1);
+}
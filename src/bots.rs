@@ -0,0 +1,277 @@
+// #region IMPORTS
+use crate::{
+    config::BotProfile, data_types::*, game_actor::*, room_actor::*, session_client_actor::*,
+    tools::*, websocket_client_actor::*,
+};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use ed25519_dalek::{Signer, SigningKey};
+use kameo::{
+    Actor,
+    actor::{ActorID, ActorRef, WeakActorRef},
+    error::{ActorStopReason, Infallible},
+    message::{Context, Message},
+};
+use std::{
+    ops::ControlFlow,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tracing::{debug, warn};
+use uuid::Uuid;
+// #endregion
+
+/// Numbers bot names across the process.
+static NEXT_BOT: AtomicU64 = AtomicU64::new(1);
+
+/// What a bot sends when it gets a question wrong.
+const WRONG_ANSWER: &str = "わからない";
+
+// #region ACTOR
+/// A player run by the server. It speaks the client protocol to a session
+/// of its own, with no socket in between, and answers questions after a
+/// delay and with an accuracy set by its profile.
+pub struct BotClientActor {
+    session: ActorRef<SessionClientActor>,
+    profile: BotProfile,
+    key: SigningKey,
+    name: String,
+    id: Option<String>,
+    verify_id: Option<Uuid>,
+    register_id: Option<Uuid>,
+}
+
+impl Actor for BotClientActor {
+    type Args = (WeakActorRef<GameActor>, BotProfile);
+    type Error = Infallible;
+
+    async fn on_start(
+        (game, profile): Self::Args,
+        ar: ActorRef<Self>,
+    ) -> Result<Self, Self::Error> {
+        let session =
+            SessionClientActor::spawn_link(&ar, SessionClientActor::bot(game.clone())).await;
+        session
+            .tell(SetTransport(ar.clone().recipient::<ToTransport>()))
+            .await
+            .ok();
+        if let Some(game) = game.upgrade() {
+            game.tell(AttachSession(session.clone())).await.ok();
+        }
+
+        let bot = Self {
+            session,
+            profile,
+            key: SigningKey::from_bytes(&random_bytes()),
+            name: next_name(),
+            id: None,
+            verify_id: None,
+            register_id: None,
+        };
+        bot.send(TransportMsg::InReqSendPublicKey(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: InReqSendPublicKey {
+                key: BASE64_STANDARD.encode(bot.key.verifying_key().as_bytes()),
+                algorithm: SignatureAlgorithm::Ed25519,
+                encoding: Encoding::Json,
+                capabilities: Vec::new(),
+            },
+        }))
+        .await;
+        Ok(bot)
+    }
+
+    async fn on_link_died(
+        &mut self,
+        _ar: WeakActorRef<Self>,
+        id: ActorID,
+        reason: ActorStopReason,
+    ) -> Result<ControlFlow<ActorStopReason>, Self::Error> {
+        if id == self.session.id() {
+            debug!("session of {} ended: {reason:?}", self.name);
+            return Ok(ControlFlow::Break(ActorStopReason::Normal));
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+fn next_name() -> String {
+    format!("Bot {}", NEXT_BOT.fetch_add(1, Ordering::Relaxed))
+}
+
+/// A number from 0 to 1.
+fn random_unit() -> f64 {
+    let bytes = random_bytes();
+    f64::from(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])) / f64::from(u32::MAX)
+}
+
+impl BotClientActor {
+    async fn send(&self, msg: TransportMsg) {
+        self.session.tell(msg).await.ok();
+    }
+
+    async fn request<P>(&self, payload: P, wrap: fn(TransportEnvelope<P>) -> TransportMsg) -> Uuid {
+        let correlation_id = Uuid::new_v4();
+        self.send(wrap(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload,
+        }))
+        .await;
+        correlation_id
+    }
+
+    /// Ends the session, which takes the bot down with it.
+    async fn quit(&self) {
+        self.session.stop_gracefully().await.ok();
+    }
+
+    async fn register(&mut self) {
+        let payload = InReqRegisterClient {
+            name: self.name.clone(),
+            spectator: false,
+            password: None,
+        };
+        self.register_id = Some(
+            self.request(payload, TransportMsg::InReqRegisterClient)
+                .await,
+        );
+    }
+
+    async fn set_ready(&self) {
+        self.request(InReqSetReady { ready: true }, TransportMsg::InReqSetReady)
+            .await;
+    }
+
+    async fn on_message(&mut self, msg: TransportMsg, ctx: &mut Context<Self, ()>) {
+        match msg {
+            TransportMsg::OutRespSignMessage(env) => {
+                let signature = self.key.sign(env.payload.message.as_bytes());
+                let payload = InReqVerifySignature {
+                    signature: BASE64_STANDARD.encode(signature.to_bytes()),
+                };
+                self.verify_id = Some(
+                    self.request(payload, TransportMsg::InReqVerifySignature)
+                        .await,
+                );
+            }
+
+            TransportMsg::OutRespStatus(env) if Some(env.correlation_id) == self.verify_id => {
+                if env.payload.status == "success" {
+                    self.register().await;
+                } else {
+                    warn!("{} not verified: {}", self.name, env.payload.status);
+                    self.quit().await;
+                }
+            }
+
+            TransportMsg::OutRespStatus(env) if Some(env.correlation_id) == self.register_id => {
+                if env.payload.status == "name taken" {
+                    self.name = next_name();
+                    self.register().await;
+                } else {
+                    warn!("{} not registered: {}", self.name, env.payload.status);
+                    self.quit().await;
+                }
+            }
+
+            TransportMsg::OutRespError(env) if Some(env.correlation_id) == self.register_id => {
+                warn!("{} turned away: {}", self.name, env.payload.error);
+                self.quit().await;
+            }
+
+            TransportMsg::OutRespClientRegistered(env) => {
+                debug!("{} registered as {}", self.name, env.payload.id);
+                self.id = Some(env.payload.id);
+                self.set_ready().await;
+            }
+
+            // readiness is reset with the settings; a bot is always ready
+            TransportMsg::OutNotifReadyChanged(env)
+                if !env.payload.ready && Some(&env.payload.id) == self.id.as_ref() =>
+            {
+                self.set_ready().await;
+            }
+
+            TransportMsg::OutNotifQuestion(env) => {
+                let delay = self.profile.answer_delay.mul_f64(0.5 + random_unit());
+                let bot = ctx.actor_ref().downgrade();
+                let question_id = env.payload.question_id;
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    if let Some(bot) = bot.upgrade() {
+                        bot.tell(Answer(question_id)).await.ok();
+                    }
+                });
+            }
+
+            TransportMsg::OutNotifGameStopped(env)
+                if env.payload.reason == GameStopReason::Completed =>
+            {
+                let payload = InReqSendChat {
+                    message: "gg".into(),
+                };
+                self.request(payload, TransportMsg::InReqSendChat).await;
+            }
+
+            _ => {}
+        }
+    }
+
+    async fn answer(&self, question_id: Uuid) {
+        let room = match self.session.ask(GetRoom).await {
+            Ok(Some(room)) => room.upgrade(),
+            _ => None,
+        };
+        let Some(room) = room else {
+            return;
+        };
+        // a round that is already over has no key
+        let Ok(Some(key)) = room.ask(AnswerKey(question_id)).await else {
+            return;
+        };
+        let answer = if random_unit() < self.profile.accuracy {
+            key
+        } else {
+            WRONG_ANSWER.into()
+        };
+        let payload = InReqSendAnswer {
+            answer,
+            question_id: Some(question_id),
+            game_id: None,
+        };
+        self.request(payload, TransportMsg::InReqSendAnswer).await;
+    }
+}
+// #endregion
+
+// #region MESSAGES
+impl Message<ToTransport> for BotClientActor {
+    type Reply = ();
+
+    async fn handle(&mut self, msg: ToTransport, ctx: &mut Context<Self, Self::Reply>) {
+        let msg = match msg {
+            ToTransport::Raw(text) => match parse(&text) {
+                Ok(msg) => msg,
+                Err(e) => {
+                    warn!("{} can't read {text}: {e}", self.name);
+                    return;
+                }
+            },
+            ToTransport::TransportMsg(msg) | ToTransport::Response(msg, _) => *msg,
+            ToTransport::SetEncoding(_) => return,
+        };
+        self.on_message(msg, ctx).await;
+    }
+}
+
+/// Time is up for the bot to answer a question.
+struct Answer(Uuid);
+
+impl Message<Answer> for BotClientActor {
+    type Reply = ();
+
+    async fn handle(&mut self, Answer(question_id): Answer, _ctx: &mut Context<Self, ()>) {
+        self.answer(question_id).await;
+    }
+}
+// #endregion
@@ -0,0 +1,171 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{config::CaptureConfig, data_types::TransportMsg, tools::unix_millis};
+
+/// Stands in for a redacted value.
+pub const REDACTED: &str = "<redacted>";
+
+/// Fields blanked out of captured frames: public keys, signatures, session
+/// tokens and passwords, wherever they are nested.
+const SECRET_FIELDS: [&str; 5] = ["key", "signature", "token", "password", "adminPassword"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    In,
+    Out,
+}
+
+/// One line of a capture file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureEntry {
+    /// Unix time in milliseconds.
+    pub at: u64,
+    pub direction: Direction,
+    /// The frame as JSON, whatever encoding it went over the wire in.
+    /// Text that isn't JSON is kept as a string.
+    pub frame: Value,
+}
+
+struct Recorder {
+    file: File,
+    path: PathBuf,
+    written: u64,
+    max_bytes: u64,
+    redact: bool,
+}
+
+/// Records one connection's frames to a file, shared between the game,
+/// which switches it on and off, and the transport writing to it. Does
+/// nothing while stopped.
+#[derive(Clone, Default)]
+pub struct Capture {
+    recorder: Arc<Mutex<Option<Recorder>>>,
+}
+
+impl Capture {
+    /// Starts writing to a new file in the configured directory, named
+    /// after `label`. Returns the file, also when already recording.
+    pub fn start(&self, config: &CaptureConfig, label: &str) -> Result<PathBuf, String> {
+        let mut recorder = self.recorder.lock().unwrap();
+        if let Some(recorder) = &*recorder {
+            return Ok(recorder.path.clone());
+        }
+        fs::create_dir_all(&config.dir).map_err(|e| format!("{}: {e}", config.dir.display()))?;
+        let path = config
+            .dir
+            .join(format!("capture-{}-{label}.ndjson", unix_millis()));
+        let file = File::create(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        *recorder = Some(Recorder {
+            file,
+            path: path.clone(),
+            written: 0,
+            max_bytes: config.max_bytes,
+            redact: config.redact,
+        });
+        Ok(path)
+    }
+
+    /// Returns the file that was being written.
+    pub fn stop(&self) -> Option<PathBuf> {
+        self.recorder.lock().unwrap().take().map(|r| r.path)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.recorder.lock().unwrap().is_some()
+    }
+
+    pub fn record_text(&self, direction: Direction, text: &str) {
+        if self.is_active() {
+            let frame = serde_json::from_str(text).unwrap_or_else(|_| Value::from(text));
+            self.record(direction, frame);
+        }
+    }
+
+    pub fn record_msg(&self, direction: Direction, msg: &TransportMsg) {
+        if self.is_active() {
+            self.record(direction, serde_json::to_value(msg).unwrap_or_default());
+        }
+    }
+
+    fn record(&self, direction: Direction, mut frame: Value) {
+        let mut guard = self.recorder.lock().unwrap();
+        let Some(recorder) = guard.as_mut() else {
+            return;
+        };
+        if recorder.redact {
+            redact(&mut frame);
+        }
+        let entry = CaptureEntry {
+            at: unix_millis(),
+            direction,
+            frame,
+        };
+        let mut line = serde_json::to_vec(&entry).unwrap_or_default();
+        line.push(b'\n');
+
+        if recorder.written + line.len() as u64 > recorder.max_bytes {
+            warn!(
+                "capture {} reached {} bytes, stopped",
+                recorder.path.display(),
+                recorder.max_bytes
+            );
+            *guard = None;
+            return;
+        }
+        if let Err(e) = recorder.file.write_all(&line) {
+            warn!("capture {}: {e}, stopped", recorder.path.display());
+            *guard = None;
+            return;
+        }
+        recorder.written += line.len() as u64;
+    }
+}
+
+/// Blanks out the values of [`SECRET_FIELDS`] in `value`.
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                if SECRET_FIELDS.contains(&name.as_str()) && !field.is_null() {
+                    *field = Value::from(REDACTED);
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+pub fn read_capture(path: &Path) -> Result<Vec<CaptureEntry>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| format!("{}:{}: {e}", path.display(), i + 1))
+        })
+        .collect()
+}
+
+/// What a response frame says that doesn't change from run to run: its
+/// type, and for `OUT_RESP_status` the status too. Replays compare these.
+pub fn outcome(frame: &Value) -> String {
+    let message_type = frame["messageType"].as_str().unwrap_or_default();
+    match frame["payload"]["status"].as_str() {
+        Some(status) if message_type == "OUT_RESP_status" => format!("{message_type} {status}"),
+        _ => message_type.to_string(),
+    }
+}
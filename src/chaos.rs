@@ -0,0 +1,68 @@
+//! Deliberate network trouble for development builds. Every frame a
+//! connection sends or receives can be held back or lost, which brings out
+//! races that sub-millisecond localhost round trips never show.
+
+use std::{ops::RangeInclusive, time::Duration};
+
+use uuid::Uuid;
+
+use crate::replay::round_seed;
+
+#[derive(Debug, Clone)]
+pub struct ChaosConfig {
+    /// Delay added to each frame, picked uniformly from the range.
+    pub jitter: RangeInclusive<Duration>,
+    /// Chance of a frame being lost, from 0 to 1.
+    pub drop_probability: f64,
+    /// Gives every connection the same run of delays and drops so a failure
+    /// can be repeated. Without it each connection picks its own.
+    pub seed: Option<u64>,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            jitter: Duration::ZERO..=Duration::ZERO,
+            drop_probability: 0.0,
+            seed: None,
+        }
+    }
+}
+
+/// Decides the fate of one connection's frames.
+#[derive(Debug)]
+pub struct Chaos {
+    config: ChaosConfig,
+    seed: u64,
+    draws: u64,
+}
+
+impl Chaos {
+    pub fn new(config: ChaosConfig) -> Self {
+        let seed = config
+            .seed
+            .unwrap_or_else(|| Uuid::new_v4().as_u64_pair().0);
+        Self {
+            config,
+            seed,
+            draws: 0,
+        }
+    }
+
+    /// `None` if the next frame is lost, otherwise how long to hold it.
+    pub fn next_frame(&mut self) -> Option<Duration> {
+        if self.next_f64() < self.config.drop_probability {
+            return None;
+        }
+        let (min, max) = (*self.config.jitter.start(), *self.config.jitter.end());
+        let span = max.saturating_sub(min).as_secs_f64();
+        Some(min + Duration::from_secs_f64(span * self.next_f64()))
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        let bits = round_seed(self.seed, self.draws);
+        self.draws += 1;
+        (bits >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
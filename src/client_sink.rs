@@ -0,0 +1,20 @@
+use futures_util::future::BoxFuture;
+use kameo::actor::ActorRef;
+
+use crate::{
+    data_types::TransportMsg,
+    session_client_actor::{SendWs, SessionClientActor},
+};
+
+/// Where a room delivers messages meant for one client. Sessions are the
+/// real sink; tests can record into a buffer instead.
+pub trait ClientSink: Send + Sync + 'static {
+    /// Returns whether the message was handed over.
+    fn send(&self, msg: TransportMsg) -> BoxFuture<'_, bool>;
+}
+
+impl ClientSink for ActorRef<SessionClientActor> {
+    fn send(&self, msg: TransportMsg) -> BoxFuture<'_, bool> {
+        Box::pin(async move { self.tell(SendWs(msg)).await.is_ok() })
+    }
+}
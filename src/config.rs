@@ -0,0 +1,311 @@
+use std::{path::PathBuf, time::Duration};
+
+#[cfg(feature = "chaos")]
+use crate::chaos::ChaosConfig;
+use crate::{
+    data_types::{
+        BotDifficulty, Capability, EffectiveConfig, Encoding, ModerationAction, PROTOCOL_VERSION,
+        Password, RateLimitInfo, ServerFeatures, SignatureAlgorithm,
+    },
+    game_actor::{LOBBY_CHAT_RATE_LIMIT, LOBBY_CHAT_RATE_WINDOW},
+    room_actor::{
+        ANNOUNCEMENT_RATE_LIMIT, ANNOUNCEMENT_RATE_WINDOW, CHAT_RATE_LIMIT, CHAT_RATE_WINDOW,
+    },
+    tools::MAX_NAME_LENGTH,
+};
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub port: String,
+    pub chat_idle_purge: Duration,
+    pub max_clients: Option<usize>,
+    pub max_message_size: usize,
+    pub tls: Option<TlsConfig>,
+    /// Browser origins allowed to open a socket. Empty or `*` allows any
+    /// origin; clients that send no `Origin` header are always accepted.
+    pub allowed_origins: Vec<String>,
+    /// Binary frames tolerated before the connection is closed.
+    pub max_binary_frames: u32,
+    /// Whether registering with a key that is already connected replaces
+    /// the old session instead of being rejected.
+    pub replace_duplicate_sessions: bool,
+    /// Longest chat message accepted, in characters after sanitizing.
+    pub chat_max_length: usize,
+    /// Concurrent connections allowed from one IP address.
+    pub max_connections_per_ip: Option<usize>,
+    /// Consecutive correct answers that trigger a streak notification.
+    pub streak_milestones: Vec<u32>,
+    /// Time left in a round at which clients are warned. Empty turns the
+    /// warnings off, including the one when the round ends.
+    pub round_time_warnings: Vec<Duration>,
+    /// Rounds in a row that may end without a single answer before the game
+    /// is stopped as abandoned. 0 never stops it.
+    pub abandoned_after_rounds: u32,
+    /// Public keys allowed to request server stats and make the debug
+    /// requests. Empty lets any verified client read the stats, without
+    /// the connections per address, and nobody make debug requests.
+    pub operator_keys: Vec<String>,
+    /// Requests whose response takes longer than this to be written are
+    /// logged. Zero logs none.
+    pub slow_request_threshold: Duration,
+    /// How long a single socket write may block before the client is
+    /// considered stuck and disconnected.
+    pub write_timeout: Duration,
+    /// How long a session token can stand in for the key/signature
+    /// exchange.
+    pub session_token_ttl: Duration,
+    /// Word list for chat and names, one word per line.
+    pub blocked_words_path: Option<PathBuf>,
+    /// Word parts games may be narrowed to, one `part<TAB>reading<TAB>count`
+    /// per line. Without it any word part is accepted.
+    pub word_parts_path: Option<PathBuf>,
+    /// What happens to a blocked word until the admin changes it.
+    pub moderation_action: ModerationAction,
+    /// Tiers a game's `tier` setting can name. A question falls into the
+    /// first one whose range holds its frequency.
+    pub difficulty_tiers: Vec<DifficultyTier>,
+    /// Messages kept per connection for `IN_REQ_debugOutbox`; 0 keeps none.
+    pub outbox_size: usize,
+    /// Whether operators may read a client's outbox over the wire.
+    pub debug_outbox: bool,
+    /// Where connections are recorded to. `None`, the default, records
+    /// nothing and turns `IN_REQ_debugCapture` down.
+    pub capture: Option<CaptureConfig>,
+    /// Request correlation ids remembered per connection, to turn away a
+    /// request that reuses one. 0 remembers none.
+    pub duplicate_id_history: usize,
+    /// How long a correlation id is remembered.
+    pub duplicate_id_window: Duration,
+    pub bots: BotConfig,
+    /// File the room is saved to on shutdown and restored from on start.
+    #[cfg(feature = "persistence")]
+    pub state_path: Option<PathBuf>,
+    /// Delays and drops injected into every connection's frames.
+    #[cfg(feature = "chaos")]
+    pub chaos: Option<ChaosConfig>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: "8080".into(),
+            chat_idle_purge: Duration::from_secs(10 * 60),
+            max_clients: None,
+            max_message_size: 1024 * 1024,
+            tls: None,
+            allowed_origins: Vec::new(),
+            max_binary_frames: 3,
+            replace_duplicate_sessions: false,
+            chat_max_length: 500,
+            max_connections_per_ip: None,
+            streak_milestones: vec![3, 5, 10],
+            round_time_warnings: vec![Duration::from_secs(10), Duration::from_secs(3)],
+            abandoned_after_rounds: 3,
+            operator_keys: Vec::new(),
+            slow_request_threshold: Duration::from_millis(500),
+            write_timeout: Duration::from_secs(10),
+            session_token_ttl: Duration::from_secs(15 * 60),
+            blocked_words_path: None,
+            word_parts_path: None,
+            moderation_action: ModerationAction::default(),
+            difficulty_tiers: default_difficulty_tiers(),
+            outbox_size: 100,
+            debug_outbox: false,
+            capture: None,
+            duplicate_id_history: 32,
+            duplicate_id_window: Duration::from_secs(60),
+            bots: BotConfig::default(),
+            #[cfg(feature = "persistence")]
+            state_path: None,
+            #[cfg(feature = "chaos")]
+            chaos: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn is_origin_allowed(&self, origin: Option<&str>) -> bool {
+        let Some(origin) = origin else {
+            return true;
+        };
+        self.allowed_origins.is_empty()
+            || self
+                .allowed_origins
+                .iter()
+                .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+    }
+}
+
+/// A named frequency range players pick instead of raw bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DifficultyTier {
+    pub name: String,
+    pub min_frequency: u64,
+    /// `None` leaves the range open at the top.
+    pub max_frequency: Option<u64>,
+}
+
+impl DifficultyTier {
+    pub fn new(name: &str, min_frequency: u64, max_frequency: Option<u64>) -> Self {
+        Self {
+            name: name.into(),
+            min_frequency,
+            max_frequency,
+        }
+    }
+
+    pub fn contains(&self, frequency: f64) -> bool {
+        frequency >= self.min_frequency as f64
+            && self.max_frequency.is_none_or(|max| frequency <= max as f64)
+    }
+}
+
+/// Common words are easy, rare ones hard.
+pub fn default_difficulty_tiers() -> Vec<DifficultyTier> {
+    vec![
+        DifficultyTier::new("easy", 10_000, None),
+        DifficultyTier::new("medium", 1_000, Some(9_999)),
+        DifficultyTier::new("hard", 0, Some(999)),
+    ]
+}
+
+/// How a bot of one difficulty plays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BotProfile {
+    /// Typical time to answer; each answer takes between half and one and
+    /// a half times as long.
+    pub answer_delay: Duration,
+    /// Share of questions answered correctly, from 0 to 1.
+    pub accuracy: f64,
+}
+
+/// Recording of connections' frames, for bug reports. See `capture`.
+#[derive(Debug, Clone)]
+pub struct CaptureConfig {
+    /// Directory the `.ndjson` files are written to.
+    pub dir: PathBuf,
+    /// Records every connection from its first frame. Otherwise only the
+    /// clients operators pick with `IN_REQ_debugCapture` are.
+    pub record_all: bool,
+    /// A recording stops before its file grows past this.
+    pub max_bytes: u64,
+    /// Blanks out public keys, signatures, session tokens and passwords.
+    pub redact: bool,
+}
+
+impl CaptureConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            record_all: false,
+            max_bytes: 10 * 1024 * 1024,
+            redact: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BotConfig {
+    /// Bots put in a newly created room.
+    pub empty_room_bots: usize,
+    pub empty_room_difficulty: BotDifficulty,
+    pub easy: BotProfile,
+    pub normal: BotProfile,
+    pub hard: BotProfile,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            empty_room_bots: 0,
+            empty_room_difficulty: BotDifficulty::Normal,
+            easy: BotProfile {
+                answer_delay: Duration::from_secs(8),
+                accuracy: 0.4,
+            },
+            normal: BotProfile {
+                answer_delay: Duration::from_secs(5),
+                accuracy: 0.7,
+            },
+            hard: BotProfile {
+                answer_delay: Duration::from_secs(2),
+                accuracy: 0.95,
+            },
+        }
+    }
+}
+
+impl BotConfig {
+    pub fn profile(&self, difficulty: BotDifficulty) -> BotProfile {
+        match difficulty {
+            BotDifficulty::Easy => self.easy,
+            BotDifficulty::Normal => self.normal,
+            BotDifficulty::Hard => self.hard,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RoomConfig {
+    pub max_clients: Option<usize>,
+    pub join_password: Option<Password>,
+}
+
+impl RoomConfig {
+    pub fn from_server(config: &ServerConfig) -> Self {
+        Self {
+            max_clients: config.max_clients,
+            join_password: None,
+        }
+    }
+}
+
+impl EffectiveConfig {
+    /// What `config` and the built-in limits add up to. Optional data files
+    /// count as on when configured; the game actor corrects that once it
+    /// has tried to load them.
+    pub fn from_server(config: &ServerConfig) -> Self {
+        let rate_limit = |count: usize, window: Duration| RateLimitInfo {
+            count: count as u64,
+            window_ms: window.as_millis() as u64,
+        };
+        #[cfg(feature = "persistence")]
+        let persistence = config.state_path.is_some();
+        #[cfg(not(feature = "persistence"))]
+        let persistence = false;
+        Self {
+            server_version: env!("CARGO_PKG_VERSION").into(),
+            protocol_version: PROTOCOL_VERSION,
+            max_message_size: config.max_message_size as u64,
+            max_binary_frames: config.max_binary_frames,
+            max_name_length: MAX_NAME_LENGTH as u64,
+            chat_max_length: config.chat_max_length as u64,
+            chat_rate_limit: rate_limit(CHAT_RATE_LIMIT, CHAT_RATE_WINDOW),
+            announcement_rate_limit: rate_limit(ANNOUNCEMENT_RATE_LIMIT, ANNOUNCEMENT_RATE_WINDOW),
+            lobby_chat_rate_limit: rate_limit(LOBBY_CHAT_RATE_LIMIT, LOBBY_CHAT_RATE_WINDOW),
+            max_room_clients: RoomConfig::from_server(config)
+                .max_clients
+                .map(|max| max as u64),
+            max_connections_per_ip: config.max_connections_per_ip.map(|max| max as u64),
+            session_token_ttl_seconds: config.session_token_ttl.as_secs(),
+            capabilities: Capability::ALL.to_vec(),
+            encodings: Encoding::ALL.to_vec(),
+            signature_algorithms: SignatureAlgorithm::SUPPORTED.to_vec(),
+            features: ServerFeatures {
+                persistence,
+                tls: config.tls.is_some(),
+                word_filter: config.blocked_words_path.is_some(),
+                word_parts: config.word_parts_path.is_some(),
+                empty_room_bots: config.bots.empty_room_bots as u64,
+                debug_outbox: config.debug_outbox,
+                capture: config.capture.is_some(),
+            },
+        }
+    }
+}
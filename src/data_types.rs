@@ -1,14 +1,29 @@
-use serde::{Deserialize, Serialize};
+use kameo::Reply;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "{T}Envelope"))]
 #[serde(rename_all = "camelCase")]
 pub struct TransportEnvelope<T> {
     pub correlation_id: Uuid,
+    /// Set on `OUT_NOTIF_*` messages a room sends to everyone in it. Counts
+    /// up per room and never goes back, across games and admin changes, so
+    /// clients can order these notifications and drop repeats. Numbers may
+    /// be skipped, e.g. for notifications behind a capability. Those held
+    /// back from spectators are numbered when they are sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
     pub payload: T,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "messageType")]
 #[serde(rename_all = "camelCase")]
 pub enum TransportMsg {
@@ -42,6 +57,117 @@ pub enum TransportMsg {
 
     #[serde(rename = "IN_REQ_sendGameSettings")]
     InReqSendGameSettings(TransportEnvelope<InReqSendGameSettings>),
+
+    #[serde(rename = "IN_REQ_sendWhisper")]
+    InReqSendWhisper(TransportEnvelope<InReqSendWhisper>),
+
+    #[serde(rename = "IN_REQ_renameClient")]
+    InReqRenameClient(TransportEnvelope<InReqRenameClient>),
+
+    #[serde(rename = "IN_REQ_setReady")]
+    InReqSetReady(TransportEnvelope<InReqSetReady>),
+
+    #[serde(rename = "IN_REQ_serverStats")]
+    InReqServerStats(TransportEnvelope<InReqServerStats>),
+
+    #[serde(rename = "IN_REQ_setRoomPassword")]
+    InReqSetRoomPassword(TransportEnvelope<InReqSetRoomPassword>),
+
+    #[serde(rename = "IN_REQ_muteClient")]
+    InReqMuteClient(TransportEnvelope<InReqMuteClient>),
+
+    #[serde(rename = "IN_REQ_roomInfo")]
+    InReqRoomInfo(TransportEnvelope<InReqRoomInfo>),
+
+    #[serde(rename = "IN_REQ_setRoomName")]
+    InReqSetRoomName(TransportEnvelope<InReqSetRoomName>),
+
+    #[serde(rename = "IN_REQ_gameReplay")]
+    InReqGameReplay(TransportEnvelope<InReqGameReplay>),
+
+    #[serde(rename = "IN_REQ_extendRound")]
+    InReqExtendRound(TransportEnvelope<InReqExtendRound>),
+
+    #[serde(rename = "IN_REQ_sendAnnouncement")]
+    InReqSendAnnouncement(TransportEnvelope<InReqSendAnnouncement>),
+
+    #[serde(rename = "IN_REQ_rematch")]
+    InReqRematch(TransportEnvelope<InReqRematch>),
+
+    #[serde(rename = "IN_REQ_leaveRoom")]
+    InReqLeaveRoom(TransportEnvelope<InReqLeaveRoom>),
+
+    #[serde(rename = "IN_REQ_resumeGame")]
+    InReqResumeGame(TransportEnvelope<InReqResumeGame>),
+
+    #[serde(rename = "IN_REQ_sendReaction")]
+    InReqSendReaction(TransportEnvelope<InReqSendReaction>),
+
+    #[serde(rename = "IN_REQ_roundState")]
+    InReqRoundState(TransportEnvelope<InReqRoundState>),
+
+    #[serde(rename = "IN_REQ_resumeSession")]
+    InReqResumeSession(TransportEnvelope<InReqResumeSession>),
+
+    #[serde(rename = "IN_REQ_sendLobbyChat")]
+    InReqSendLobbyChat(TransportEnvelope<InReqSendLobbyChat>),
+
+    #[serde(rename = "IN_REQ_patchGameSettings")]
+    InReqPatchGameSettings(TransportEnvelope<InReqPatchGameSettings>),
+
+    #[serde(rename = "IN_REQ_exportGame")]
+    InReqExportGame(TransportEnvelope<InReqExportGame>),
+
+    #[serde(rename = "IN_REQ_setHandicap")]
+    InReqSetHandicap(TransportEnvelope<InReqSetHandicap>),
+
+    #[serde(rename = "IN_REQ_roomEvents")]
+    InReqRoomEvents(TransportEnvelope<InReqRoomEvents>),
+
+    #[serde(rename = "IN_REQ_subscribeRoomList")]
+    InReqSubscribeRoomList(TransportEnvelope<InReqSubscribeRoomList>),
+
+    #[serde(rename = "IN_REQ_unsubscribeRoomList")]
+    InReqUnsubscribeRoomList(TransportEnvelope<InReqUnsubscribeRoomList>),
+
+    #[serde(rename = "IN_REQ_setModerationSettings")]
+    InReqSetModerationSettings(TransportEnvelope<InReqSetModerationSettings>),
+
+    #[serde(rename = "IN_REQ_debugOutbox")]
+    InReqDebugOutbox(TransportEnvelope<InReqDebugOutbox>),
+
+    #[serde(rename = "IN_REQ_wordPartList")]
+    InReqWordPartList(TransportEnvelope<InReqWordPartList>),
+
+    #[serde(rename = "IN_REQ_setQuestionProvider")]
+    InReqSetQuestionProvider(TransportEnvelope<InReqSetQuestionProvider>),
+
+    #[serde(rename = "IN_REQ_addBot")]
+    InReqAddBot(TransportEnvelope<InReqAddBot>),
+
+    #[serde(rename = "IN_REQ_removeBot")]
+    InReqRemoveBot(TransportEnvelope<InReqRemoveBot>),
+
+    #[serde(rename = "IN_REQ_fetchAsset")]
+    InReqFetchAsset(TransportEnvelope<InReqFetchAsset>),
+
+    #[serde(rename = "IN_REQ_findPlayer")]
+    InReqFindPlayer(TransportEnvelope<InReqFindPlayer>),
+
+    #[serde(rename = "IN_REQ_setPresenceVisibility")]
+    InReqSetPresenceVisibility(TransportEnvelope<InReqSetPresenceVisibility>),
+
+    #[serde(rename = "IN_REQ_setAdminSuccessor")]
+    InReqSetAdminSuccessor(TransportEnvelope<InReqSetAdminSuccessor>),
+
+    #[serde(rename = "IN_REQ_serverConfig")]
+    InReqServerConfig(TransportEnvelope<InReqServerConfig>),
+
+    #[serde(rename = "IN_REQ_setSubscriptions")]
+    InReqSetSubscriptions(TransportEnvelope<InReqSetSubscriptions>),
+
+    #[serde(rename = "IN_REQ_debugCapture")]
+    InReqDebugCapture(TransportEnvelope<InReqDebugCapture>),
     // #endregion
 
     // #region OUT_RESP
@@ -56,6 +182,42 @@ pub enum TransportMsg {
 
     #[serde(rename = "OUT_RESP_signMessage")]
     OutRespSignMessage(TransportEnvelope<OutRespSignMessage>),
+
+    #[serde(rename = "OUT_RESP_serverStats")]
+    OutRespServerStats(TransportEnvelope<OutRespServerStats>),
+
+    #[serde(rename = "OUT_RESP_error")]
+    OutRespError(TransportEnvelope<OutRespError>),
+
+    #[serde(rename = "OUT_RESP_roomInfo")]
+    OutRespRoomInfo(TransportEnvelope<OutRespRoomInfo>),
+
+    #[serde(rename = "OUT_RESP_gameReplay")]
+    OutRespGameReplay(TransportEnvelope<OutRespGameReplay>),
+
+    #[serde(rename = "OUT_RESP_roundState")]
+    OutRespRoundState(TransportEnvelope<OutRespRoundState>),
+
+    #[serde(rename = "OUT_RESP_gameExport")]
+    OutRespGameExport(TransportEnvelope<OutRespGameExport>),
+
+    #[serde(rename = "OUT_RESP_roomEvents")]
+    OutRespRoomEvents(TransportEnvelope<OutRespRoomEvents>),
+
+    #[serde(rename = "OUT_RESP_debugOutbox")]
+    OutRespDebugOutbox(TransportEnvelope<OutRespDebugOutbox>),
+
+    #[serde(rename = "OUT_RESP_wordPartList")]
+    OutRespWordPartList(TransportEnvelope<OutRespWordPartList>),
+
+    #[serde(rename = "OUT_RESP_asset")]
+    OutRespAsset(TransportEnvelope<OutRespAsset>),
+
+    #[serde(rename = "OUT_RESP_playerLocation")]
+    OutRespPlayerLocation(TransportEnvelope<OutRespPlayerLocation>),
+
+    #[serde(rename = "OUT_RESP_serverConfig")]
+    OutRespServerConfig(TransportEnvelope<OutRespServerConfig>),
     // #endregion
 
     // #region OUT_REQ
@@ -66,6 +228,9 @@ pub enum TransportMsg {
     // #region IN_RESP
     #[serde(rename = "IN_RESP_question")]
     InRespQuestion(TransportEnvelope<InRespQuestion>),
+
+    #[serde(rename = "IN_RESP_questionError")]
+    InRespQuestionError(TransportEnvelope<InRespQuestionError>),
     // #endregion
 
     // #region OUT_NOTIF
@@ -98,6 +263,78 @@ pub enum TransportMsg {
 
     #[serde(rename = "OUT_NOTIF_gameSettingsChanged")]
     OutNotifGameSettingsChanged(TransportEnvelope<OutNotifGameSettingsChanged>),
+
+    #[serde(rename = "OUT_NOTIF_whisperReceived")]
+    OutNotifWhisperReceived(TransportEnvelope<OutNotifWhisperReceived>),
+
+    #[serde(rename = "OUT_NOTIF_clientRenamed")]
+    OutNotifClientRenamed(TransportEnvelope<OutNotifClientRenamed>),
+
+    #[serde(rename = "OUT_NOTIF_readyChanged")]
+    OutNotifReadyChanged(TransportEnvelope<OutNotifReadyChanged>),
+
+    #[serde(rename = "OUT_NOTIF_roomPasswordChanged")]
+    OutNotifRoomPasswordChanged(TransportEnvelope<OutNotifRoomPasswordChanged>),
+
+    #[serde(rename = "OUT_NOTIF_clientMuted")]
+    OutNotifClientMuted(TransportEnvelope<OutNotifClientMuted>),
+
+    #[serde(rename = "OUT_NOTIF_roomRenamed")]
+    OutNotifRoomRenamed(TransportEnvelope<OutNotifRoomRenamed>),
+
+    #[serde(rename = "OUT_NOTIF_countdown")]
+    OutNotifCountdown(TransportEnvelope<OutNotifCountdown>),
+
+    #[serde(rename = "OUT_NOTIF_roundExtended")]
+    OutNotifRoundExtended(TransportEnvelope<OutNotifRoundExtended>),
+
+    #[serde(rename = "OUT_NOTIF_streak")]
+    OutNotifStreak(TransportEnvelope<OutNotifStreak>),
+
+    #[serde(rename = "OUT_NOTIF_announcement")]
+    OutNotifAnnouncement(TransportEnvelope<OutNotifAnnouncement>),
+
+    #[serde(rename = "OUT_NOTIF_reaction")]
+    OutNotifReaction(TransportEnvelope<OutNotifReaction>),
+
+    #[serde(rename = "OUT_NOTIF_frequencyWarning")]
+    OutNotifFrequencyWarning(TransportEnvelope<OutNotifFrequencyWarning>),
+
+    #[serde(rename = "OUT_NOTIF_sessionToken")]
+    OutNotifSessionToken(TransportEnvelope<OutNotifSessionToken>),
+
+    #[serde(rename = "OUT_NOTIF_lobbyChat")]
+    OutNotifLobbyChat(TransportEnvelope<OutNotifLobbyChat>),
+
+    #[serde(rename = "OUT_NOTIF_serverError")]
+    OutNotifServerError(TransportEnvelope<OutNotifServerError>),
+
+    #[serde(rename = "OUT_NOTIF_handicapChanged")]
+    OutNotifHandicapChanged(TransportEnvelope<OutNotifHandicapChanged>),
+
+    #[serde(rename = "OUT_NOTIF_roomListChanged")]
+    OutNotifRoomListChanged(TransportEnvelope<OutNotifRoomListChanged>),
+
+    #[serde(rename = "OUT_NOTIF_roundTimeWarning")]
+    OutNotifRoundTimeWarning(TransportEnvelope<OutNotifRoundTimeWarning>),
+
+    #[serde(rename = "OUT_NOTIF_moderationSettingsChanged")]
+    OutNotifModerationSettingsChanged(TransportEnvelope<OutNotifModerationSettingsChanged>),
+
+    #[serde(rename = "OUT_NOTIF_answerFeedback")]
+    OutNotifAnswerFeedback(TransportEnvelope<OutNotifAnswerFeedback>),
+
+    #[serde(rename = "OUT_NOTIF_idleWarning")]
+    OutNotifIdleWarning(TransportEnvelope<OutNotifIdleWarning>),
+
+    #[serde(rename = "OUT_NOTIF_adminRevoked")]
+    OutNotifAdminRevoked(TransportEnvelope<OutNotifAdminRevoked>),
+
+    #[serde(rename = "OUT_NOTIF_questionProviderChanged")]
+    OutNotifQuestionProviderChanged(TransportEnvelope<OutNotifQuestionProviderChanged>),
+
+    #[serde(rename = "OUT_NOTIF_questionFailed")]
+    OutNotifQuestionFailed(TransportEnvelope<OutNotifQuestionFailed>),
     // #endregion
 }
 
@@ -109,24 +346,238 @@ pub fn serialize(msg: &TransportMsg) -> serde_json::Result<String> {
     serde_json::to_string(msg)
 }
 
+/// Msgpack keeps the JSON shape (named fields, string ids), so clients can
+/// share one set of types for both encodings.
+pub fn parse_msgpack(data: &[u8]) -> Result<TransportMsg, rmp_serde::decode::Error> {
+    let mut de = rmp_serde::Deserializer::from_read_ref(data).with_human_readable();
+    TransportMsg::deserialize(&mut de)
+}
+
+pub fn serialize_msgpack(msg: &TransportMsg) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    let mut data = Vec::new();
+    let mut ser = rmp_serde::Serializer::new(&mut data)
+        .with_struct_map()
+        .with_human_readable();
+    msg.serialize(&mut ser)?;
+    Ok(data)
+}
+
+pub fn correlation_id(msg: &TransportMsg) -> Option<Uuid> {
+    let value = serde_json::to_value(msg).ok()?;
+    value.get("correlationId")?.as_str()?.parse().ok()
+}
+
+/// `messageType` and correlation id of a request, without serializing it;
+/// `None` for anything else.
+pub fn request_head(msg: &TransportMsg) -> Option<(&'static str, Uuid)> {
+    let head = match msg {
+        TransportMsg::InReqSendPublicKey(env) => ("IN_REQ_sendPublicKey", env.correlation_id),
+        TransportMsg::InReqVerifySignature(env) => ("IN_REQ_verifysignature", env.correlation_id),
+        TransportMsg::InReqRegisterClient(env) => ("IN_REQ_registerClient", env.correlation_id),
+        TransportMsg::InReqSendChat(env) => ("IN_REQ_sendChat", env.correlation_id),
+        TransportMsg::InReqMakeAdmin(env) => ("IN_REQ_makeAdmin", env.correlation_id),
+        TransportMsg::InReqClientList(env) => ("IN_REQ_clientList", env.correlation_id),
+        TransportMsg::InReqStartGame(env) => ("IN_REQ_startGame", env.correlation_id),
+        TransportMsg::InReqStopGame(env) => ("IN_REQ_stopGame", env.correlation_id),
+        TransportMsg::InReqSendAnswer(env) => ("IN_REQ_sendAnswer", env.correlation_id),
+        TransportMsg::InReqSendGameSettings(env) => ("IN_REQ_sendGameSettings", env.correlation_id),
+        TransportMsg::InReqSendWhisper(env) => ("IN_REQ_sendWhisper", env.correlation_id),
+        TransportMsg::InReqRenameClient(env) => ("IN_REQ_renameClient", env.correlation_id),
+        TransportMsg::InReqSetReady(env) => ("IN_REQ_setReady", env.correlation_id),
+        TransportMsg::InReqServerStats(env) => ("IN_REQ_serverStats", env.correlation_id),
+        TransportMsg::InReqSetRoomPassword(env) => ("IN_REQ_setRoomPassword", env.correlation_id),
+        TransportMsg::InReqMuteClient(env) => ("IN_REQ_muteClient", env.correlation_id),
+        TransportMsg::InReqRoomInfo(env) => ("IN_REQ_roomInfo", env.correlation_id),
+        TransportMsg::InReqSetRoomName(env) => ("IN_REQ_setRoomName", env.correlation_id),
+        TransportMsg::InReqGameReplay(env) => ("IN_REQ_gameReplay", env.correlation_id),
+        TransportMsg::InReqExtendRound(env) => ("IN_REQ_extendRound", env.correlation_id),
+        TransportMsg::InReqSendAnnouncement(env) => ("IN_REQ_sendAnnouncement", env.correlation_id),
+        TransportMsg::InReqRematch(env) => ("IN_REQ_rematch", env.correlation_id),
+        TransportMsg::InReqLeaveRoom(env) => ("IN_REQ_leaveRoom", env.correlation_id),
+        TransportMsg::InReqResumeGame(env) => ("IN_REQ_resumeGame", env.correlation_id),
+        TransportMsg::InReqSendReaction(env) => ("IN_REQ_sendReaction", env.correlation_id),
+        TransportMsg::InReqRoundState(env) => ("IN_REQ_roundState", env.correlation_id),
+        TransportMsg::InReqResumeSession(env) => ("IN_REQ_resumeSession", env.correlation_id),
+        TransportMsg::InReqSendLobbyChat(env) => ("IN_REQ_sendLobbyChat", env.correlation_id),
+        TransportMsg::InReqPatchGameSettings(env) => {
+            ("IN_REQ_patchGameSettings", env.correlation_id)
+        }
+        TransportMsg::InReqExportGame(env) => ("IN_REQ_exportGame", env.correlation_id),
+        TransportMsg::InReqSetHandicap(env) => ("IN_REQ_setHandicap", env.correlation_id),
+        TransportMsg::InReqRoomEvents(env) => ("IN_REQ_roomEvents", env.correlation_id),
+        TransportMsg::InReqSubscribeRoomList(env) => {
+            ("IN_REQ_subscribeRoomList", env.correlation_id)
+        }
+        TransportMsg::InReqUnsubscribeRoomList(env) => {
+            ("IN_REQ_unsubscribeRoomList", env.correlation_id)
+        }
+        TransportMsg::InReqSetModerationSettings(env) => {
+            ("IN_REQ_setModerationSettings", env.correlation_id)
+        }
+        TransportMsg::InReqDebugOutbox(env) => ("IN_REQ_debugOutbox", env.correlation_id),
+        TransportMsg::InReqWordPartList(env) => ("IN_REQ_wordPartList", env.correlation_id),
+        TransportMsg::InReqSetQuestionProvider(env) => {
+            ("IN_REQ_setQuestionProvider", env.correlation_id)
+        }
+        TransportMsg::InReqAddBot(env) => ("IN_REQ_addBot", env.correlation_id),
+        TransportMsg::InReqRemoveBot(env) => ("IN_REQ_removeBot", env.correlation_id),
+        TransportMsg::InReqFetchAsset(env) => ("IN_REQ_fetchAsset", env.correlation_id),
+        TransportMsg::InReqFindPlayer(env) => ("IN_REQ_findPlayer", env.correlation_id),
+        TransportMsg::InReqSetPresenceVisibility(env) => {
+            ("IN_REQ_setPresenceVisibility", env.correlation_id)
+        }
+        TransportMsg::InReqSetAdminSuccessor(env) => {
+            ("IN_REQ_setAdminSuccessor", env.correlation_id)
+        }
+        TransportMsg::InReqServerConfig(env) => ("IN_REQ_serverConfig", env.correlation_id),
+        TransportMsg::InReqSetSubscriptions(env) => ("IN_REQ_setSubscriptions", env.correlation_id),
+        TransportMsg::InReqDebugCapture(env) => ("IN_REQ_debugCapture", env.correlation_id),
+        _ => return None,
+    };
+    Some(head)
+}
+
+/// The correlation id of a response, without serializing it. Notifications
+/// and server requests have none.
+pub fn response_id(msg: &TransportMsg) -> Option<Uuid> {
+    match msg {
+        TransportMsg::OutRespClientRegistered(env) => Some(env.correlation_id),
+        TransportMsg::OutRespStatus(env) => Some(env.correlation_id),
+        TransportMsg::OutRespClientList(env) => Some(env.correlation_id),
+        TransportMsg::OutRespSignMessage(env) => Some(env.correlation_id),
+        TransportMsg::OutRespServerStats(env) => Some(env.correlation_id),
+        TransportMsg::OutRespError(env) => Some(env.correlation_id),
+        TransportMsg::OutRespRoomInfo(env) => Some(env.correlation_id),
+        TransportMsg::OutRespGameReplay(env) => Some(env.correlation_id),
+        TransportMsg::OutRespRoundState(env) => Some(env.correlation_id),
+        TransportMsg::OutRespGameExport(env) => Some(env.correlation_id),
+        TransportMsg::OutRespRoomEvents(env) => Some(env.correlation_id),
+        TransportMsg::OutRespDebugOutbox(env) => Some(env.correlation_id),
+        TransportMsg::OutRespWordPartList(env) => Some(env.correlation_id),
+        TransportMsg::OutRespAsset(env) => Some(env.correlation_id),
+        TransportMsg::OutRespPlayerLocation(env) => Some(env.correlation_id),
+        TransportMsg::OutRespServerConfig(env) => Some(env.correlation_id),
+        _ => None,
+    }
+}
+
+/// Numbers a notification, see [`TransportEnvelope::seq`]. Anything else is
+/// left as it is.
+pub fn set_seq(msg: &mut TransportMsg, seq: u64) {
+    match msg {
+        TransportMsg::OutNotifClientRegistered(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifClientDisconnected(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifChatSent(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifAdminMade(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifGameStarted(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifGameStopped(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifQuestion(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifClientAnswered(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifRoundEnded(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifGameSettingsChanged(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifWhisperReceived(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifClientRenamed(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifReadyChanged(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifRoomPasswordChanged(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifClientMuted(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifRoomRenamed(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifCountdown(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifRoundExtended(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifStreak(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifAnnouncement(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifReaction(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifFrequencyWarning(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifSessionToken(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifLobbyChat(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifServerError(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifHandicapChanged(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifRoomListChanged(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifRoundTimeWarning(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifModerationSettingsChanged(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifAnswerFeedback(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifIdleWarning(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifAdminRevoked(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifQuestionProviderChanged(env) => env.seq = Some(seq),
+        TransportMsg::OutNotifQuestionFailed(env) => env.seq = Some(seq),
+        _ => {}
+    }
+}
+
 // #region OTHER
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ClientInfo {
     pub id: String,
+    #[serde(default)]
+    pub player_id: String,
     pub key: String,
     pub name: String,
     pub is_admin: bool,
+    #[serde(default)]
+    pub is_spectator: bool,
+    #[serde(default)]
+    pub is_ready: bool,
+    #[serde(default)]
+    pub is_muted: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handicap: Option<Handicap>,
+    /// A player run by the server.
+    #[serde(default)]
+    pub is_bot: bool,
+}
+
+/// What a client is in its room. An admin is one whether or not it plays.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ClientRole {
+    #[default]
+    Player,
+    Spectator,
+    Admin,
+}
+
+/// Evens out a game for a weaker player: more time to answer and more
+/// points per correct answer.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct Handicap {
+    pub extra_time_ms: u64,
+    pub score_multiplier: f64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct QuestionInfo {
     pub word_info: WordInfo,
     pub font_name: String,
+    #[serde(default)]
+    pub question_type: QuestionType,
+    /// How common the word is, on the same scale as the frequency bounds
+    /// in `GameSettings`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<f64>,
+}
+
+/// What the prompt shows and what the player has to type back.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionType {
+    /// Shown the word, answer with a reading.
+    #[default]
+    ReadingOfWord,
+    /// Shown a reading, answer with the word.
+    WordFromReading,
+    /// Shown the word, answer with one of its meanings.
+    MeaningOfWord,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WordPartExample {
     pub word: String,
@@ -135,6 +586,7 @@ pub struct WordPartExample {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WordPartInfo {
     pub word_part: String,
@@ -142,7 +594,19 @@ pub struct WordPartInfo {
     pub examples: Vec<WordPartExample>,
 }
 
+/// One reading of a word part in the server's index, with how many words
+/// use the part with that reading.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct WordPartEntry {
+    pub word_part: String,
+    pub word_part_reading: String,
+    pub count: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ReadingWithParts {
     pub reading: String,
@@ -150,6 +614,7 @@ pub struct ReadingWithParts {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct WordInfo {
     pub word: String,
@@ -158,15 +623,49 @@ pub struct WordInfo {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct AnswerInfo {
     pub id: String,
     pub answer: String,
     pub is_correct: bool,
     pub answer_time: u64,
+    #[serde(default)]
+    pub kind: AnswerKind,
+    /// Counts up from 1 through a round's accepted answers; a changed
+    /// answer gets a new one. Unset for players who didn't answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+/// How far a player's wrong answer was from the closest accepted reading.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NearMiss {
+    pub id: String,
+    pub closest_reading: String,
+    pub distance: u32,
+}
+
+/// Whether an answer was submitted or filled in when the round ran out.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerKind {
+    #[default]
+    Answered,
+    TimedOut,
+}
+
+/// Raised whenever a change to the messages would break existing clients.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+pub const DEFAULT_QUESTION_TIMEOUT: u64 = 5;
+pub const DEFAULT_START_COUNTDOWN: u64 = 3;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct GameSettings {
     pub min_frequency: u64,
@@ -179,172 +678,1921 @@ pub struct GameSettings {
     pub fonts_count: u64,
     pub first_font_name: Option<String>,
     pub dictionary_name: Option<String>,
+    #[serde(default)]
+    pub live_answer_stats: bool,
+    #[serde(default)]
+    pub require_all_ready: bool,
+    #[serde(default)]
+    pub allow_answer_change: bool,
+    #[serde(default = "default_question_timeout")]
+    pub question_timeout: u64,
+    #[serde(default = "default_start_countdown")]
+    pub start_countdown: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Solo play: no chat or answer notifications, and `rounds_count` 0
+    /// runs until the game is stopped.
+    #[serde(default)]
+    pub practice_mode: bool,
+    #[serde(default = "default_end_round_when_all_answered")]
+    pub end_round_when_all_answered: bool,
+    /// Points for a correct answer while the player is on a streak of at
+    /// least the first streak milestone. Plain answers score 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streak_bonus: Option<u32>,
+    /// Rejects joins while a game is running instead of letting players
+    /// drop into the current round.
+    #[serde(default)]
+    pub lock_room_during_game: bool,
+    #[serde(default)]
+    pub question_type: QuestionType,
+    /// Relative weights for picking a question type each round. Empty
+    /// means every round uses `question_type`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub question_type_weights: BTreeMap<QuestionType, u32>,
+    /// Rejects questions outside the frequency bounds instead of only
+    /// warning the room about them.
+    #[serde(default)]
+    pub enforce_frequency: bool,
+    /// Whether the admin, who supplies the question and so knows its
+    /// answer, may also answer it.
+    #[serde(default = "default_admin_plays")]
+    pub admin_plays: bool,
+    /// A difficulty tier whose frequency range replaces the bounds above.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tier: Option<String>,
+    /// Counts reading answers at most this many edits from an accepted
+    /// reading as correct.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accept_near_miss: Option<u32>,
+    /// Shows everyone how close the wrong answers were at the end of a
+    /// round, not only the players who gave them.
+    #[serde(default)]
+    pub share_near_misses: bool,
+    /// Works each round's length out from its word, in place of
+    /// `round_duration`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adaptive_duration: Option<AdaptiveDuration>,
+    /// Lets a word come up more than once in a game.
+    #[serde(default)]
+    pub allow_repeat_words: bool,
+    /// Seconds the whole game may last, however many rounds are left. The
+    /// round under way when it runs out is played to the end.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_time_limit: Option<u64>,
+    /// Ends the round under way as soon as `game_time_limit` runs out.
+    #[serde(default)]
+    pub cut_round_at_time_limit: bool,
+    /// Milliseconds spectators get questions and answers after the players,
+    /// so a streamed game can't be relayed back to them in time. Round and
+    /// game results go out to everyone at once.
+    #[serde(default)]
+    pub spectator_delay_ms: u64,
 }
 
-// #endregion
-
-// #region IN_REQ
+/// A round length that grows with the word: a base, more for each
+/// character, and more the rarer the word is.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct InReqSendPublicKey {
-    pub key: String,
+pub struct AdaptiveDuration {
+    /// Seconds.
+    pub base: u64,
+    #[serde(default)]
+    pub per_character_ms: u64,
+    /// Added for each power of ten the word's frequency falls below
+    /// `common_frequency`. Words without a frequency get none.
+    #[serde(default)]
+    pub per_rarity_step_ms: u64,
+    pub common_frequency: u64,
+    /// Seconds the result is kept within.
+    pub min: u64,
+    pub max: u64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+impl AdaptiveDuration {
+    pub fn round_duration(&self, question: &QuestionInfo) -> Duration {
+        let characters = question.word_info.word.chars().count() as u64;
+        let rarity_steps = question.frequency.map_or(0.0, |f| {
+            (self.common_frequency as f64 / f.max(1.0)).log10().max(0.0)
+        });
+        let ms = self.base * 1000
+            + characters * self.per_character_ms
+            + (rarity_steps * self.per_rarity_step_ms as f64) as u64;
+        Duration::from_millis(ms.clamp(self.min * 1000, self.max * 1000))
+    }
+}
+
+/// A handful of `GameSettings` fields to change without resending the rest.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct InReqVerifySignature {
-    pub signature: String,
+pub struct GameSettingsOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_frequency: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_frequency: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub round_duration: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rounds_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_countdown: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+impl GameSettingsOverride {
+    pub fn apply(&self, settings: &mut GameSettings) {
+        if let Some(v) = self.min_frequency {
+            settings.min_frequency = v;
+        }
+        if let Some(v) = self.max_frequency {
+            settings.max_frequency = v;
+        }
+        if let Some(v) = self.round_duration {
+            settings.round_duration = v;
+        }
+        if let Some(v) = self.rounds_count {
+            settings.rounds_count = v;
+        }
+        if let Some(v) = self.start_countdown {
+            settings.start_countdown = v;
+        }
+        if let Some(v) = self.seed {
+            settings.seed = Some(v);
+        }
+    }
+}
+
+/// Any subset of `GameSettings` fields. A field left out keeps its current
+/// value; for the optional ones an explicit `null` clears it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct InReqRegisterClient {
-    pub name: String,
+pub struct GameSettingsPatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_frequency: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_frequency: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub using_max_frequency: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub round_duration: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rounds_count: Option<u64>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub word_part: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub word_part_reading: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fonts_count: Option<u64>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub first_font_name: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub dictionary_name: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live_answer_stats: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_all_ready: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_answer_change: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question_timeout: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_countdown: Option<u64>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub seed: Option<Option<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub practice_mode: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_round_when_all_answered: Option<bool>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub streak_bonus: Option<Option<u32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_room_during_game: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question_type: Option<QuestionType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question_type_weights: Option<BTreeMap<QuestionType, u32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforce_frequency: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_plays: Option<bool>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub tier: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub accept_near_miss: Option<Option<u32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share_near_misses: Option<bool>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub adaptive_duration: Option<Option<AdaptiveDuration>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_repeat_words: Option<bool>,
+    #[serde(
+        default,
+        deserialize_with = "present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub game_time_limit: Option<Option<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cut_round_at_time_limit: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spectator_delay_ms: Option<u64>,
 }
 
+impl GameSettingsPatch {
+    pub fn apply(&self, settings: &mut GameSettings) {
+        macro_rules! merge {
+            ($($field:ident),* $(,)?) => {
+                // destructured so a new settings field can't be forgotten here
+                let Self { $($field),* } = self;
+                $(
+                    if let Some(v) = $field {
+                        settings.$field = v.clone();
+                    }
+                )*
+            };
+        }
+        merge!(
+            min_frequency,
+            max_frequency,
+            using_max_frequency,
+            round_duration,
+            rounds_count,
+            word_part,
+            word_part_reading,
+            fonts_count,
+            first_font_name,
+            dictionary_name,
+            live_answer_stats,
+            require_all_ready,
+            allow_answer_change,
+            question_timeout,
+            start_countdown,
+            seed,
+            practice_mode,
+            end_round_when_all_answered,
+            streak_bonus,
+            lock_room_during_game,
+            question_type,
+            question_type_weights,
+            enforce_frequency,
+            admin_plays,
+            tier,
+            accept_near_miss,
+            share_near_misses,
+            adaptive_duration,
+            allow_repeat_words,
+            game_time_limit,
+            cut_round_at_time_limit,
+            spectator_delay_ms,
+        );
+    }
+}
+
+/// Reads a field that is present, even as `null`, into `Some`, so that a
+/// missing field (the serde default) stays distinguishable as `None`.
+fn present<'de, T, D>(de: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(de).map(Some)
+}
+
+fn default_question_timeout() -> u64 {
+    DEFAULT_QUESTION_TIMEOUT
+}
+
+fn default_start_countdown() -> u64 {
+    DEFAULT_START_COUNTDOWN
+}
+
+fn default_end_round_when_all_answered() -> bool {
+    true
+}
+
+fn default_admin_plays() -> bool {
+    true
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            min_frequency: 0,
+            max_frequency: 0,
+            using_max_frequency: false,
+            round_duration: 0,
+            rounds_count: 0,
+            word_part: None,
+            word_part_reading: None,
+            fonts_count: 0,
+            first_font_name: None,
+            dictionary_name: None,
+            live_answer_stats: false,
+            require_all_ready: false,
+            allow_answer_change: false,
+            question_timeout: DEFAULT_QUESTION_TIMEOUT,
+            start_countdown: DEFAULT_START_COUNTDOWN,
+            seed: None,
+            practice_mode: false,
+            end_round_when_all_answered: true,
+            streak_bonus: None,
+            lock_room_during_game: false,
+            question_type: QuestionType::default(),
+            question_type_weights: BTreeMap::new(),
+            enforce_frequency: false,
+            admin_plays: true,
+            tier: None,
+            accept_near_miss: None,
+            share_near_misses: false,
+            adaptive_duration: None,
+            allow_repeat_words: false,
+            game_time_limit: None,
+            cut_round_at_time_limit: false,
+            spectator_delay_ms: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum GameStopReason {
+    #[default]
+    Completed,
+    StoppedByAdmin,
+    AbortedNoAdmin,
+    AbortedEmptyRoom,
+    /// Nobody answered for several rounds in a row.
+    Abandoned,
+    /// The admin's client ran out of questions for the filters.
+    NoQuestions,
+    /// `game_time_limit` ran out.
+    TimeLimitReached,
+}
+
+/// Why something a client asked for or was asked for can't be done, for
+/// clients to act on rather than show.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// No word in the dictionary meets the game's filters.
+    NoWordsMatchFilters,
+    RenderFailed,
+    /// No question came within the game's `question_timeout`.
+    TimedOut,
+    /// Any code this server doesn't know.
+    #[default]
+    #[serde(other)]
+    Other,
+}
+
+/// Why a room turned a registering client away, sent as the `code` of
+/// `OUT_RESP_error`. The registration can be retried once it's resolved.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum JoinRejection {
+    WrongPassword,
+    RoomFull,
+    /// The room is locked while its game runs.
+    GameInProgress,
+    /// The room couldn't be reached.
+    Unavailable,
+}
+
+impl JoinRejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JoinRejection::WrongPassword => "wrong password",
+            JoinRejection::RoomFull => "room full",
+            JoinRejection::GameInProgress => "game in progress",
+            JoinRejection::Unavailable => "room unavailable",
+        }
+    }
+}
+
+/// How quick and how accurate a bot is.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BotDifficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+/// What happens to a chat message or name containing a blocked word.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    Reject,
+    #[default]
+    Mask,
+    /// Logged and let through.
+    Warn,
+}
+
+/// Frame encoding a client asks for in its hello; text JSON unless stated.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    #[default]
+    Json,
+    Msgpack,
+}
+
+impl Encoding {
+    pub const ALL: &[Encoding] = &[Encoding::Json, Encoding::Msgpack];
+}
+
+/// Signature scheme of the public key a client sends in its hello.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureAlgorithm {
+    #[default]
+    Ed25519,
+    /// SEC1-encoded P-256 key, DER or fixed-size signature. Only verified
+    /// when the server is built with the `ecdsa` feature.
+    EcdsaP256,
+    /// Anything this server doesn't know; the hello is turned away.
+    #[serde(other)]
+    Unknown,
+}
+
+impl SignatureAlgorithm {
+    pub const SUPPORTED: &[SignatureAlgorithm] = &[
+        SignatureAlgorithm::Ed25519,
+        #[cfg(feature = "ecdsa")]
+        SignatureAlgorithm::EcdsaP256,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SignatureAlgorithm::Ed25519 => "ed25519",
+            SignatureAlgorithm::EcdsaP256 => "ecdsa-p256",
+            SignatureAlgorithm::Unknown => "unknown",
+        }
+    }
+
+    pub fn is_supported(self) -> bool {
+        Self::SUPPORTED.contains(&self)
+    }
+}
+
+/// Optional protocol features a client opts into in its hello. Messages
+/// behind one are only sent to clients that asked for it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// `OUT_NOTIF_reaction`
+    Reactions,
+    /// `OUT_NOTIF_streak`
+    Streaks,
+    /// `OUT_NOTIF_question` names its image for `IN_REQ_fetchAsset`
+    /// rather than carrying it.
+    Assets,
+}
+
+impl Capability {
+    pub const ALL: &[Capability] = &[
+        Capability::Reactions,
+        Capability::Streaks,
+        Capability::Assets,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Capability::Reactions => "reactions",
+            Capability::Streaks => "streaks",
+            Capability::Assets => "assets",
+        }
+    }
+
+    /// The requested capabilities the server knows; anything else is
+    /// ignored so newer clients still connect.
+    pub fn negotiate(requested: &[String]) -> BTreeSet<Capability> {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(|c| requested.iter().any(|r| r == c.as_str()))
+            .collect()
+    }
+}
+
+/// Room notifications a client can opt out of with
+/// `IN_REQ_setSubscriptions`. Whatever has no category, such as the game
+/// starting or stopping, is always sent.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Subscription {
+    /// `OUT_NOTIF_question` with its image, `OUT_NOTIF_frequencyWarning`
+    Questions,
+    /// `OUT_NOTIF_chatSent`, `OUT_NOTIF_announcement`, `OUT_NOTIF_reaction`
+    Chat,
+    /// `OUT_NOTIF_clientAnswered`, `OUT_NOTIF_streak`
+    Answers,
+    /// Clients coming, going, renaming and getting ready
+    Presence,
+}
+
+impl Subscription {
+    pub const ALL: &[Subscription] = &[
+        Subscription::Questions,
+        Subscription::Chat,
+        Subscription::Answers,
+        Subscription::Presence,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Subscription::Questions => "questions",
+            Subscription::Chat => "chat",
+            Subscription::Answers => "answers",
+            Subscription::Presence => "presence",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Subscription> {
+        Self::ALL.iter().copied().find(|s| s.as_str() == name)
+    }
+
+    /// The category `msg` falls under, if it can be opted out of.
+    pub fn of(msg: &TransportMsg) -> Option<Subscription> {
+        match msg {
+            TransportMsg::OutNotifQuestion(_) | TransportMsg::OutNotifFrequencyWarning(_) => {
+                Some(Subscription::Questions)
+            }
+            TransportMsg::OutNotifChatSent(_)
+            | TransportMsg::OutNotifAnnouncement(_)
+            | TransportMsg::OutNotifReaction(_) => Some(Subscription::Chat),
+            TransportMsg::OutNotifClientAnswered(_) | TransportMsg::OutNotifStreak(_) => {
+                Some(Subscription::Answers)
+            }
+            TransportMsg::OutNotifClientRegistered(_)
+            | TransportMsg::OutNotifClientDisconnected(_)
+            | TransportMsg::OutNotifClientRenamed(_)
+            | TransportMsg::OutNotifReadyChanged(_) => Some(Subscription::Presence),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectReason {
+    #[default]
+    ConnectionLost,
+    Left,
+}
+
+/// One entry of a room's audit log.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct InReqSendChat {
-    pub message: String,
+pub struct RoomEvent {
+    /// Unix time in milliseconds.
+    pub at: u64,
+    pub kind: RoomEventKind,
 }
 
+/// Client ids are the room's uuids; `by` is whoever made the change.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[serde(rename_all = "camelCase")]
-pub struct InReqMakeAdmin {
-    pub admin_password: String,
-    pub client_id: String,
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RoomEventKind {
+    ClientJoined {
+        id: String,
+        name: String,
+    },
+    ClientLeft {
+        id: String,
+        reason: DisconnectReason,
+    },
+    AdminMade {
+        id: String,
+    },
+    AdminRevoked {
+        id: String,
+    },
+    SettingsChanged {
+        by: String,
+    },
+    GameStarted {
+        by: String,
+    },
+    GameStopped {
+        reason: GameStopReason,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct InReqClientList {}
+pub struct RoundRecord {
+    pub question: QuestionInfo,
+    pub answers: Vec<AnswerInfo>,
+    /// Set for a round the server shut down in the middle of.
+    #[serde(default)]
+    pub aborted: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question_id: Option<Uuid>,
+    /// Reaction counts by emoji.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub reactions: BTreeMap<String, u32>,
+    /// Difficulty tier the question's frequency fell into.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tier: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct InReqStartGame {
+pub struct GameRecord {
+    /// Saved games from before ids existed get a fresh one on load.
+    #[serde(default = "Uuid::new_v4")]
+    pub game_id: Uuid,
     pub game_settings: GameSettings,
+    pub rounds: Vec<RoundRecord>,
+    pub stop_reason: Option<GameStopReason>,
+    /// Counts the room's games from 0, so it stays put as old games drop
+    /// out of the history.
+    #[serde(default)]
+    pub game_index: u64,
+    /// Unix time in milliseconds.
+    #[serde(default)]
+    pub started_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<u64>,
+    /// Names of the players with answers in the game, by id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub player_names: BTreeMap<String, String>,
+    /// Handicaps the players had in their last answered round, by id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub handicaps: BTreeMap<String, Handicap>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[serde(rename_all = "camelCase")]
-pub struct InReqStopGame {}
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionTargetKind {
+    Chat,
+    Round,
+}
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A chat message by its `messageId`, or a round by its `questionId`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct InReqSendAnswer {
-    pub answer: String,
+pub struct ReactionTarget {
+    #[serde(rename = "type")]
+    pub kind: ReactionTargetKind,
+    pub id: Uuid,
+}
+
+/// Never printed, so passwords don't leak through message logging.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct Password(pub String);
+
+impl std::fmt::Debug for Password {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Password(***)")
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct InReqSendGameSettings {
-    pub game_settings: GameSettings,
+pub struct PlayerScore {
+    pub id: String,
+    pub score: u64,
 }
-// #endregion
 
-// #region OUT_RESP
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutRespClientRegistered {
+pub struct PlayerStreak {
     pub id: String,
-    pub game_settings: GameSettings, // TODO delete
+    pub streak: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutRespStatus {
-    pub status: String,
+pub struct PlayerSummary {
+    pub id: String,
+    pub rounds_played: u64,
+    pub correct_answers: u64,
+    pub accuracy: f64,
+    pub average_correct_time: Option<u64>,
+    pub longest_streak: u64,
+    pub rank: u64,
+    pub disconnected: bool,
 }
 
+/// How the room did on the questions of one difficulty tier.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutRespClientList {
-    pub clients: Vec<ClientInfo>,
+pub struct TierSummary {
+    pub tier: String,
+    pub rounds: u64,
+    pub correct_answers: u64,
+    /// Percent of submitted answers that were correct.
+    pub accuracy: f64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Reply)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutRespSignMessage {
-    pub message: String,
+pub struct RoomInfo {
+    pub name: String,
+    pub client_count: u64,
+    pub spectator_count: u64,
+    pub is_game_running: bool,
+    pub current_round: u64,
+    pub game_settings: GameSettings,
+    #[serde(default)]
+    pub chat_max_length: u64,
+    /// How far behind the players spectators are, see
+    /// `GameSettings::spectator_delay_ms`.
+    #[serde(default)]
+    pub spectator_delay_ms: u64,
 }
-// #endregion
 
-// #region OUT_REQ
+/// What a room looks like from the lobby.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutReqQuestion {}
-// #endregion
+pub struct RoomListEntry {
+    pub id: String,
+    pub name: String,
+    pub player_count: u64,
+    pub spectator_count: u64,
+    pub max_clients: Option<u64>,
+    pub is_game_running: bool,
+    pub has_password: bool,
+}
 
-// #region IN_RESP
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RoomListChange {
+    Opened { room: RoomListEntry },
+    Updated { room: RoomListEntry },
+    Closed { id: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Reply)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct InRespQuestion {
-    pub question: QuestionInfo,
-    pub question_svg: String,
+pub struct RoomStats {
+    pub name: String,
+    pub clients: u64,
+    pub is_game_running: bool,
+    #[serde(default)]
+    pub players: u64,
+    #[serde(default)]
+    pub spectators: u64,
+    #[serde(default)]
+    pub rounds_played: u64,
+    #[serde(default)]
+    pub games_started: u64,
 }
-// #endregion
 
-// #region OUT_NOTIF
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutNotifClientRegistered {
-    pub client: ClientInfo,
+pub struct ServerStats {
+    pub pending_clients: u64,
+    pub registered_clients: u64,
+    pub rooms: Vec<RoomStats>,
+    #[serde(default)]
+    pub rejected_origins: u64,
+    #[serde(default)]
+    pub binary_frames: u64,
+    /// Left empty over the wire unless the client is an operator.
+    #[serde(default)]
+    pub connections_per_ip: Vec<IpConnections>,
+    #[serde(default)]
+    pub uptime_seconds: u64,
+    #[serde(default)]
+    pub connections_accepted: u64,
+    #[serde(default)]
+    pub connected_clients: u64,
+    #[serde(default)]
+    pub registrations: u64,
+    #[serde(default)]
+    pub games_started: u64,
+    #[serde(default)]
+    pub games_completed: u64,
+    /// Time from a request arriving to its response being written, by
+    /// request type. Socket clients only.
+    #[serde(default)]
+    pub request_latency: BTreeMap<String, LatencyHistogram>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// The limits and features a server runs with, as far as clients are
+/// concerned. Built once at launch; passwords, keys and paths stay out.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Reply)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutNotifClientDisconnected {
-    pub id: String,
+pub struct EffectiveConfig {
+    pub server_version: String,
+    pub protocol_version: u32,
+    /// Bytes in one websocket message.
+    pub max_message_size: u64,
+    /// Binary frames tolerated from a JSON client before it is dropped.
+    pub max_binary_frames: u32,
+    pub max_name_length: u64,
+    pub chat_max_length: u64,
+    pub chat_rate_limit: RateLimitInfo,
+    pub announcement_rate_limit: RateLimitInfo,
+    pub lobby_chat_rate_limit: RateLimitInfo,
+    /// Clients in one room, spectators included.
+    pub max_room_clients: Option<u64>,
+    pub max_connections_per_ip: Option<u64>,
+    pub session_token_ttl_seconds: u64,
+    pub capabilities: Vec<Capability>,
+    pub encodings: Vec<Encoding>,
+    pub signature_algorithms: Vec<SignatureAlgorithm>,
+    pub features: ServerFeatures,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// At most `count` messages in any `window_ms`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutNotifChatSent {
-    pub id: String,
-    pub message: String,
+pub struct RateLimitInfo {
+    pub count: u64,
+    pub window_ms: u64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// Optional parts of the server that are switched on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutNotifAdminMade {
-    pub id: String,
+pub struct ServerFeatures {
+    /// Rooms are saved and restored across restarts.
+    pub persistence: bool,
+    pub tls: bool,
+    /// Chat and names are checked against a blocked word list.
+    pub word_filter: bool,
+    /// `IN_REQ_wordPartList` has word parts to offer.
+    pub word_parts: bool,
+    /// Bots that keep an empty room company.
+    pub empty_room_bots: u64,
+    /// Operators can read client outboxes with `IN_REQ_debugOutbox`.
+    pub debug_outbox: bool,
+    /// Operators can record connections with `IN_REQ_debugCapture`.
+    pub capture: bool,
 }
 
+/// Latencies in microseconds. `buckets` counts them against the upper
+/// bounds 1, 5, 10, 25, 50, 100, 250, 500, 1000 and 2500 ms, with a last
+/// bucket for anything slower.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutNotifGameStarted {
-    pub game_settings: GameSettings,
+pub struct LatencyHistogram {
+    pub count: u64,
+    pub total_us: u64,
+    pub max_us: u64,
+    pub buckets: Vec<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutNotifGameStopped {
-    pub question: QuestionInfo,
-    pub answers: Vec<AnswerInfo>,
+pub struct IpConnections {
+    pub ip: String,
+    pub connections: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutNotifQuestion {
-    pub question_svg: String,
+pub struct GameReplay {
+    pub version: u32,
+    pub seed: Option<u64>,
+    pub game_settings: GameSettings,
+    pub rounds: Vec<RoundRecord>,
+    pub stop_reason: Option<GameStopReason>,
 }
 
+/// A message the server sent to one client, as kept for debugging.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutNotifClientAnswered {
-    pub id: String,
+pub struct OutboxEntry {
+    /// `messageType` of the message; `raw` and `close` for frames that are
+    /// not one.
+    pub message_type: String,
+    pub correlation_id: Option<Uuid>,
+    /// Encoded size in bytes.
+    pub size: u64,
+    /// Unix time in milliseconds.
+    pub sent_at: u64,
+    /// Whether the write went through; `false` for writes that failed,
+    /// timed out or were never tried on a stuck connection.
+    pub delivered: bool,
 }
 
+// #endregion
+
+// #region IN_REQ
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
-pub struct OutNotifRoundEnded {
-    pub question: QuestionInfo,
-    pub answers: Vec<AnswerInfo>,
+pub struct InReqSendPublicKey {
+    pub key: String,
+    #[serde(default)]
+    pub algorithm: SignatureAlgorithm,
+    #[serde(default)]
+    pub encoding: Encoding,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqVerifySignature {
+    pub signature: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqRegisterClient {
+    pub name: String,
+    #[serde(default)]
+    pub spectator: bool,
+    #[serde(default)]
+    pub password: Option<Password>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSendChat {
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqMakeAdmin {
+    pub admin_password: String,
+    pub client_id: String,
+    /// Takes admin rights from everyone else, handing them over instead of
+    /// adding a co-admin.
+    #[serde(default)]
+    pub revoke_existing: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqClientList {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqStartGame {
+    pub game_settings: GameSettings,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqStopGame {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSendAnswer {
+    pub answer: String,
+    /// Optional until every client sends it; a missing id means the
+    /// current round.
+    #[serde(default)]
+    pub question_id: Option<Uuid>,
+    /// Like `question_id`; an answer for an earlier game is turned away.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSendGameSettings {
+    pub game_settings: GameSettings,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqPatchGameSettings {
+    pub game_settings: GameSettingsPatch,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSendWhisper {
+    pub target_id: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqRenameClient {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSetReady {
+    pub ready: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqServerStats {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSetRoomPassword {
+    pub password: Option<Password>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqMuteClient {
+    pub client_id: String,
+    pub muted: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqRoomInfo {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSetRoomName {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqGameReplay {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqExportGame {
+    pub game_index: u64,
+}
+
+/// Sets a player's handicap; no extra time and a multiplier of 1 clear it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSetHandicap {
+    pub client_id: String,
+    pub extra_time_ms: u64,
+    pub score_multiplier: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqRoomEvents {}
+
+/// Starts `OUT_NOTIF_roomListChanged` updates, beginning with every room
+/// as it is now.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSubscribeRoomList {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqUnsubscribeRoomList {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSetModerationSettings {
+    pub action: ModerationAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqExtendRound {
+    pub extra_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSendAnnouncement {
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqRematch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shuffle_settings: Option<GameSettingsOverride>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqLeaveRoom {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqResumeGame {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSendReaction {
+    pub target: ReactionTarget,
+    pub emoji: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqRoundState {}
+
+/// Stands in for the key/signature exchange with a token from an earlier
+/// `OUT_NOTIF_sessionToken`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqResumeSession {
+    pub token: String,
+    #[serde(default)]
+    pub encoding: Encoding,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSendLobbyChat {
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqDebugOutbox {
+    pub client_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqWordPartList {}
+
+/// Makes one of the admins the one asked for questions.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSetQuestionProvider {
+    pub client_id: String,
+}
+
+/// Puts a server-side bot player in the room.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqAddBot {
+    #[serde(default)]
+    pub difficulty: BotDifficulty,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqRemoveBot {
+    pub client_id: String,
+}
+
+/// Asks for a question image announced by its `assetId`. Only the current
+/// and the previous round's are kept.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqFetchAsset {
+    pub asset_id: String,
+}
+
+/// Looks a registered player up by player id or by name, matched the way
+/// name uniqueness is.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqFindPlayer {
+    pub player_id_or_name: String,
+}
+
+/// Whether others can find this client with `IN_REQ_findPlayer`. Visible
+/// until changed, for the rest of the session.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSetPresenceVisibility {
+    pub visible: bool,
+}
+
+/// Names who becomes admin when the last admin leaves, ahead of whoever
+/// joined first. `null` clears it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSetAdminSuccessor {
+    #[serde(default)]
+    pub client_id: Option<String>,
+}
+
+/// Asks for the server's [`EffectiveConfig`]; allowed before the handshake.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqServerConfig {}
+
+/// Replaces the [`Subscription`] categories the room sends this client;
+/// the names are those of `Subscription`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqSetSubscriptions {
+    pub categories: Vec<String>,
+}
+
+/// Starts or stops recording a client's connection to the server's
+/// capture directory. For operators, when captures are configured.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InReqDebugCapture {
+    pub client_id: String,
+    pub enabled: bool,
+}
+// #endregion
+
+// #region OUT_RESP
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespClientRegistered {
+    pub id: String,
+    /// The room's current settings, the same as `room.gameSettings`. Kept
+    /// for clients that still read it.
+    #[deprecated(note = "read `room.game_settings` instead")]
+    pub game_settings: GameSettings,
+    /// The room as `IN_REQ_roomInfo` would describe it.
+    pub room: Box<RoomInfo>,
+    pub role: ClientRole,
+    /// The capabilities agreed on in the hello.
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespStatus {
+    pub status: String,
+    /// Set on an accepted answer: its place among the round's answers, as
+    /// found in `AnswerInfo.sequence`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespClientList {
+    pub clients: Vec<ClientInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespSignMessage {
+    pub message: String,
+    /// The requested capabilities the server supports.
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespServerStats {
+    pub stats: ServerStats,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespError {
+    pub error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<JoinRejection>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespRoomInfo {
+    pub room: RoomInfo,
+    /// What the asking client is subscribed to.
+    #[serde(default)]
+    pub subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespGameReplay {
+    pub replay: GameReplay,
+}
+
+/// The archive document is passed as text, so its format (see `export`) is
+/// versioned apart from the protocol.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespGameExport {
+    pub file_name: String,
+    pub document: String,
+}
+
+/// Oldest first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespRoomEvents {
+    pub events: Vec<RoomEvent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespRoundState {
+    /// Whether a question is on screen right now.
+    pub active: bool,
+    /// 1-based number of the current or upcoming round, 0 outside a game.
+    pub round_number: u64,
+    /// Time left in the active round, extensions included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining_ms: Option<u64>,
+    /// Time until the countdown before the next round runs out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_round_ms: Option<u64>,
+    pub answered_count: u64,
+    pub has_answered: bool,
+}
+
+/// Oldest first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespDebugOutbox {
+    pub client_id: String,
+    pub entries: Vec<OutboxEntry>,
+}
+
+/// Most used first. Empty when the server has no word part index, in which
+/// case any `wordPart` is accepted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespWordPartList {
+    pub word_parts: Vec<WordPartEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespAsset {
+    pub asset_id: String,
+    pub content: String,
+}
+
+/// A hidden player is reported the same as one that isn't there.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespPlayerLocation {
+    pub found: bool,
+    /// `None` while the player is in the lobby.
+    pub room_name: Option<String>,
+    pub in_game: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutRespServerConfig {
+    pub config: EffectiveConfig,
+}
+// #endregion
+
+// #region OUT_REQ
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutReqQuestion {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub round_seed: Option<u64>,
+    /// Position in the font rotation, absent when `fonts_count` is 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_index: Option<u64>,
+    /// Font expected at `font_index`, once the server knows it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_name: Option<String>,
+    #[serde(default)]
+    pub question_type: QuestionType,
+    /// Frequency bounds the question should fall in, with any tier
+    /// already resolved.
+    #[serde(default)]
+    pub min_frequency: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_frequency: Option<u64>,
+    /// Words already asked this game, none of which may come again unless
+    /// `allow_repeat_words` is set.
+    #[serde(default)]
+    pub used_word_count: u64,
+}
+// #endregion
+
+// #region IN_RESP
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InRespQuestion {
+    pub question: QuestionInfo,
+    pub question_svg: String,
+}
+
+/// Sent instead of a question when the admin's client can't make one.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct InRespQuestionError {
+    pub error_code: ErrorCode,
+    #[serde(default)]
+    pub message: String,
+}
+// #endregion
+
+// #region OUT_NOTIF
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifClientRegistered {
+    pub client: ClientInfo,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifClientDisconnected {
+    pub id: String,
+    #[serde(default)]
+    pub reason: DisconnectReason,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifChatSent {
+    pub id: String,
+    pub message: String,
+    pub message_id: Uuid,
+    /// Reaction counts by emoji, filled in when the message is replayed.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub reactions: BTreeMap<String, u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifAdminMade {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifGameStarted {
+    pub game_settings: GameSettings,
+    #[serde(default)]
+    pub rematch: bool,
+    /// Refers to this game in `IN_REQ_exportGame`.
+    #[serde(default)]
+    pub game_index: u64,
+    /// The game this belongs to. Optional on the wire for one release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<Uuid>,
+    /// When `game_time_limit` runs out, in Unix milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifGameStopped {
+    pub question: QuestionInfo,
+    pub answers: Vec<AnswerInfo>,
+    #[serde(default)]
+    pub reason: GameStopReason,
+    #[serde(default)]
+    pub final_scores: Vec<PlayerScore>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub winner_id: Option<String>,
+    #[serde(default)]
+    pub player_summaries: Vec<PlayerSummary>,
+    #[serde(default)]
+    pub accepted_readings: Vec<String>,
+    #[serde(default)]
+    pub primary_reading: String,
+    #[serde(default)]
+    pub tier_summaries: Vec<TierSummary>,
+    /// The game this belongs to. Optional on the wire for one release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<Uuid>,
+    /// Milliseconds since the game started.
+    #[serde(default)]
+    pub elapsed_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifQuestion {
+    /// Empty for clients with the `assets` capability, which fetch it by
+    /// `asset_id` instead.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub question_svg: String,
+    pub question_id: Uuid,
+    /// Only set for clients joining mid-round.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining_ms: Option<u64>,
+    /// The game this belongs to. Optional on the wire for one release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<Uuid>,
+    /// Length of the round, which differs between rounds with
+    /// `adaptive_duration`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub round_duration_ms: Option<u64>,
+    /// Names the question image for `IN_REQ_fetchAsset`; the same image
+    /// always gets the same id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_id: Option<String>,
+    /// Size of the image in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifClientAnswered {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answered_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correct_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_players: Option<u64>,
+    /// The game this belongs to. Optional on the wire for one release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifRoundEnded {
+    pub question: QuestionInfo,
+    pub answers: Vec<AnswerInfo>,
+    #[serde(default)]
+    pub accepted_readings: Vec<String>,
+    #[serde(default)]
+    pub primary_reading: String,
+    #[serde(default)]
+    pub streaks: Vec<PlayerStreak>,
+    /// Only with `share_near_misses`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub near_misses: Vec<NearMiss>,
+    /// The game this belongs to. Optional on the wire for one release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct OutNotifGameSettingsChanged {
     pub game_settings: GameSettings,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifWhisperReceived {
+    pub from_id: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifClientRenamed {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifReadyChanged {
+    pub id: String,
+    pub ready: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifRoomPasswordChanged {
+    pub has_password: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifClientMuted {
+    pub id: String,
+    pub muted: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifRoomRenamed {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifCountdown {
+    pub seconds_remaining: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifRoundExtended {
+    pub new_remaining_ms: u64,
+    /// The game this belongs to. Optional on the wire for one release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<Uuid>,
+}
+
+/// Sent at the server's checkpoints before a round ends, and with zero
+/// remaining once it does.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifRoundTimeWarning {
+    pub remaining_ms: u64,
+    /// The game this belongs to. Optional on the wire for one release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<Uuid>,
+}
+
+/// `active` says whether the server has a word list at all; without one
+/// the action changes nothing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifModerationSettingsChanged {
+    pub action: ModerationAction,
+    pub active: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifStreak {
+    pub id: String,
+    pub length: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifAnnouncement {
+    pub id: String,
+    pub message: String,
+    /// Unix time in milliseconds.
+    pub sent_at: u64,
+    pub message_id: Uuid,
+    /// Reaction counts by emoji, filled in when the message is replayed.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub reactions: BTreeMap<String, u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifReaction {
+    pub from_id: String,
+    pub target: ReactionTarget,
+    pub emoji: String,
+}
+
+/// Sent after every successful verification.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Reply)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifSessionToken {
+    pub token: String,
+    /// Unix time in milliseconds.
+    pub expires_at: u64,
+}
+
+/// Server-wide chat, seen by every registered client whatever room it is
+/// in. Room chat arrives as `OUT_NOTIF_chatSent` instead.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifLobbyChat {
+    pub id: String,
+    pub name: String,
+    pub message: String,
+    /// Unix time in milliseconds.
+    pub sent_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifHandicapChanged {
+    pub id: String,
+    pub handicap: Option<Handicap>,
+}
+
+/// Something broke on the server side. The room the client was in is gone;
+/// either it is about to be put back into a fresh one or the connection
+/// closes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifServerError {
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifRoomListChanged {
+    pub change: RoomListChange,
+}
+
+/// The admin's client couldn't make a question. With `relaxed` it is asked
+/// again without the frequency bounds; a game that can't go on stops.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifQuestionFailed {
+    pub error_code: ErrorCode,
+    pub message: String,
+    #[serde(default)]
+    pub relaxed: bool,
+}
+
+/// A question outside the game's frequency bounds was let through.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifFrequencyWarning {
+    pub frequency: f64,
+    pub min_frequency: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_frequency: Option<u64>,
+}
+
+/// Sent privately to a player whose answer to a reading question was
+/// wrong, as the round ends.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifAnswerFeedback {
+    pub answer: String,
+    pub closest_reading: String,
+    /// Edits between the answer and `closest_reading`, after both are
+    /// normalized.
+    pub distance: u32,
+}
+
+/// The game is about to be stopped as abandoned: another round without an
+/// answer ends it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifIdleWarning {
+    pub empty_rounds: u32,
+    pub rounds_left: u32,
+    /// Time since the last answer, or since the game started.
+    pub idle_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifAdminRevoked {
+    pub id: String,
+}
+
+/// The admin the room asks for questions from now on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OutNotifQuestionProviderChanged {
+    pub id: String,
+}
 // #endregion
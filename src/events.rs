@@ -0,0 +1,41 @@
+use tokio::sync::broadcast;
+
+use crate::data_types::{
+    AnswerInfo, ClientInfo, GameSettings, GameStopReason, PlayerScore, QuestionInfo,
+};
+
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Game events mirrored to the embedding application. They are emitted at
+/// the same points as the matching `OUT_NOTIF_*` broadcasts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerEvent {
+    ClientRegistered {
+        client: ClientInfo,
+    },
+    ClientDisconnected {
+        id: String,
+    },
+    GameStarted {
+        game_settings: GameSettings,
+    },
+    RoundEnded {
+        question: QuestionInfo,
+        answers: Vec<AnswerInfo>,
+    },
+    ChatMessage {
+        id: String,
+        message: String,
+    },
+    GameStopped {
+        reason: GameStopReason,
+        final_scores: Vec<PlayerScore>,
+        winner_id: Option<String>,
+    },
+}
+
+pub type EventSender = broadcast::Sender<ServerEvent>;
+
+pub fn event_channel() -> EventSender {
+    broadcast::channel(EVENT_CHANNEL_CAPACITY).0
+}
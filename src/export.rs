@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    data_types::{AnswerKind, GameRecord, GameSettings, GameStopReason, Handicap, QuestionType},
+    summary::player_summaries,
+    tools::normalize_answer,
+};
+
+/// Names the document, so an archived file says what it is without the
+/// server around.
+pub const EXPORT_FORMAT: &str = "kanjilab-game-export";
+
+/// Bumped whenever the export document changes.
+pub const EXPORT_VERSION: u32 = 2;
+
+/// An archived game: who played, every round with its answers, and the
+/// settings it was played with.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GameExport {
+    pub format: String,
+    pub version: u32,
+    pub server_version: String,
+    pub room_name: String,
+    pub game_id: Uuid,
+    pub game_index: u64,
+    /// Unix time in milliseconds.
+    pub started_at: u64,
+    pub ended_at: Option<u64>,
+    pub stop_reason: Option<GameStopReason>,
+    pub game_settings: GameSettings,
+    pub players: Vec<ExportPlayer>,
+    pub rounds: Vec<ExportRound>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPlayer {
+    pub id: String,
+    pub name: Option<String>,
+    pub rank: u64,
+    pub rounds_played: u64,
+    pub correct_answers: u64,
+    pub accuracy: f64,
+    pub average_correct_time: Option<u64>,
+    pub longest_streak: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handicap: Option<Handicap>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRound {
+    pub number: u64,
+    pub word: String,
+    pub question_type: QuestionType,
+    pub font_name: String,
+    pub aborted: bool,
+    pub answers: Vec<ExportAnswer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportAnswer {
+    pub player_id: String,
+    pub answer: String,
+    /// The answer as it was compared against the accepted ones.
+    pub normalized: String,
+    pub is_correct: bool,
+    /// Milliseconds from the question going out.
+    pub answer_time: u64,
+    pub kind: AnswerKind,
+}
+
+impl GameExport {
+    pub fn new(room_name: &str, game: &GameRecord) -> Self {
+        let players = player_summaries(&game.rounds, |_| true)
+            .into_iter()
+            .map(|s| ExportPlayer {
+                name: game.player_names.get(&s.id).cloned(),
+                handicap: game.handicaps.get(&s.id).copied(),
+                id: s.id,
+                rank: s.rank,
+                rounds_played: s.rounds_played,
+                correct_answers: s.correct_answers,
+                accuracy: s.accuracy,
+                average_correct_time: s.average_correct_time,
+                longest_streak: s.longest_streak,
+            })
+            .collect();
+
+        let rounds = game
+            .rounds
+            .iter()
+            .zip(1..)
+            .map(|(round, number)| {
+                let question_type = round.question.question_type;
+                ExportRound {
+                    number,
+                    word: round.question.word_info.word.clone(),
+                    question_type,
+                    font_name: round.question.font_name.clone(),
+                    aborted: round.aborted,
+                    answers: round
+                        .answers
+                        .iter()
+                        .map(|a| ExportAnswer {
+                            player_id: a.id.clone(),
+                            answer: a.answer.clone(),
+                            normalized: normalize_answer(&a.answer, question_type),
+                            is_correct: a.is_correct,
+                            answer_time: a.answer_time,
+                            kind: a.kind,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        GameExport {
+            format: EXPORT_FORMAT.into(),
+            version: EXPORT_VERSION,
+            server_version: env!("CARGO_PKG_VERSION").into(),
+            room_name: room_name.into(),
+            game_id: game.game_id,
+            game_index: game.game_index,
+            started_at: game.started_at,
+            ended_at: game.ended_at,
+            stop_reason: game.stop_reason,
+            game_settings: game.game_settings.clone(),
+            players,
+            rounds,
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        let room: String = self
+            .room_name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("kanjilab-{room}-game-{}.json", self.game_index)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
@@ -0,0 +1,174 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    path::Path,
+};
+
+use tracing::{error, info, warn};
+
+use crate::{config::ServerConfig, data_types::ModerationAction};
+
+/// Blocked words, matched anywhere in a text after case folding and undoing
+/// common look-alike substitutions. Built once into an Aho-Corasick
+/// automaton so a check is a single pass over the text.
+#[derive(Debug, Default)]
+pub struct WordFilter {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    next: HashMap<char, usize>,
+    fail: usize,
+    /// Lengths of the words that end here, including through `fail` links.
+    matches: Vec<usize>,
+}
+
+impl WordFilter {
+    pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> Self {
+        let mut nodes = vec![Node::default()];
+        for word in words {
+            let word: Vec<char> = normalize(word.as_ref().trim()).map(|(c, _)| c).collect();
+            if word.is_empty() {
+                continue;
+            }
+            let mut state = 0;
+            for &c in &word {
+                state = match nodes[state].next.get(&c) {
+                    Some(&next) => next,
+                    None => {
+                        nodes.push(Node::default());
+                        let next = nodes.len() - 1;
+                        nodes[state].next.insert(c, next);
+                        next
+                    }
+                };
+            }
+            nodes[state].matches.push(word.len());
+        }
+
+        // breadth first, so a node's fail target is finished before it
+        let mut queue: VecDeque<usize> = nodes[0].next.values().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            let edges: Vec<(char, usize)> =
+                nodes[state].next.iter().map(|(&c, &n)| (c, n)).collect();
+            for (c, next) in edges {
+                let mut fail = nodes[state].fail;
+                while fail != 0 && !nodes[fail].next.contains_key(&c) {
+                    fail = nodes[fail].fail;
+                }
+                nodes[next].fail = nodes[fail].next.get(&c).copied().unwrap_or(0);
+                let inherited = nodes[nodes[next].fail].matches.clone();
+                nodes[next].matches.extend(inherited);
+                queue.push_back(next);
+            }
+        }
+        Self { nodes }
+    }
+
+    /// Reads one word per line; blank lines and lines starting with `#` are
+    /// skipped.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let words = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        Ok(Self::new(words))
+    }
+
+    /// The filter for the configured word list. A list that can't be read
+    /// is logged and leaves filtering off rather than stopping the server.
+    pub fn from_config(config: &ServerConfig) -> Self {
+        let Some(path) = &config.blocked_words_path else {
+            return Self::default();
+        };
+        match Self::load(path) {
+            Ok(filter) => {
+                info!("loaded blocked words from {}", path.display());
+                filter
+            }
+            Err(e) => {
+                error!("can't load blocked words from {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() <= 1
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.matched_chars(text).iter().any(|&hit| hit)
+    }
+
+    /// Replaces every character of a blocked word with `*`.
+    pub fn mask(&self, text: &str) -> String {
+        text.chars()
+            .zip(self.matched_chars(text))
+            .map(|(c, hit)| if hit { '*' } else { c })
+            .collect()
+    }
+
+    /// What `action` makes of `text`: unchanged when it is clean or only
+    /// warned about, masked, or refused.
+    pub fn moderate(&self, action: ModerationAction, text: &str) -> Result<String, &'static str> {
+        if !self.is_match(text) {
+            return Ok(text.to_string());
+        }
+        match action {
+            ModerationAction::Reject => Err("blocked word"),
+            ModerationAction::Mask => Ok(self.mask(text)),
+            ModerationAction::Warn => {
+                warn!("blocked word let through: {text:?}");
+                Ok(text.to_string())
+            }
+        }
+    }
+
+    /// For each character of `text`, whether it is part of a blocked word.
+    fn matched_chars(&self, text: &str) -> Vec<bool> {
+        let mut hits = vec![false; text.chars().count()];
+        if self.is_empty() {
+            return hits;
+        }
+        let normalized: Vec<(char, usize)> = normalize(text).collect();
+        let mut state = 0;
+        for (i, &(c, _)) in normalized.iter().enumerate() {
+            while state != 0 && !self.nodes[state].next.contains_key(&c) {
+                state = self.nodes[state].fail;
+            }
+            state = self.nodes[state].next.get(&c).copied().unwrap_or(0);
+            for &len in &self.nodes[state].matches {
+                for &(_, original) in &normalized[i + 1 - len..=i] {
+                    hits[original] = true;
+                }
+            }
+        }
+        hits
+    }
+}
+
+/// Folds `text` to the form words are compared in, with each character's
+/// position in the original.
+fn normalize(text: &str) -> impl Iterator<Item = (char, usize)> + '_ {
+    text.chars()
+        .enumerate()
+        .flat_map(|(i, c)| c.to_lowercase().map(move |c| (unleet(c), i)))
+}
+
+fn unleet(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' | '!' => 'i',
+        '|' => 'l',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        '8' => 'b',
+        '9' => 'g',
+        _ => c,
+    }
+}
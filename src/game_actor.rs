@@ -1,49 +1,188 @@
 // #region IMPORTS
-use crate::{data_types::*, room_actor::*, session_client_actor::*, websocket_client_actor::*};
+#[cfg(feature = "persistence")]
+use crate::persistence::*;
+use crate::{
+    bots::*, capture::*, config::*, data_types::*, events::*, filter::*, health::*, metrics::*,
+    question_provider::*, room_actor::*, session_client_actor::*, tools::*,
+    websocket_client_actor::*, word_parts::*,
+};
 use futures_util::{StreamExt, future};
 use kameo::{
-    Actor,
+    Actor, Reply,
     actor::{ActorID, ActorRef, WeakActorRef},
-    error::{ActorStopReason, Infallible},
+    error::{ActorStopReason, Infallible, SendError},
     message::{Context, Message},
 };
-use std::{collections::HashMap, ops::ControlFlow};
-use tokio::net::TcpStream;
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    net::{IpAddr, SocketAddr},
+    ops::ControlFlow,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
 use tokio_tungstenite::{
-    accept_async,
-    tungstenite::{Error as WsErr, Message as WsMsg},
+    accept_hdr_async_with_config,
+    tungstenite::{
+        Error as WsErr, Message as WsMsg,
+        handshake::server::{ErrorResponse, Request, Response},
+        http::{StatusCode, header::ORIGIN},
+        protocol::WebSocketConfig,
+    },
 };
-use tracing::{error, warn};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 // #endregion
 
+pub const LOBBY_CHAT_RATE_LIMIT: usize = 5;
+pub const LOBBY_CHAT_RATE_WINDOW: Duration = Duration::from_secs(10);
+const LOBBY_HISTORY_LIMIT: usize = 50;
+
 // #region ACTOR
 pub struct GameActor {
     pending_clients: HashMap<Uuid, ActorRef<SessionClientActor>>,
     registered_clients: HashMap<Uuid, RegisteredClient>,
     room: ActorRef<RoomActor>,
+    config: Arc<ServerConfig>,
+    /// What clients are told about `config`, see `IN_REQ_serverConfig`.
+    effective_config: EffectiveConfig,
+    events: EventSender,
+    word_filter: Arc<WordFilter>,
+    word_parts: Arc<WordPartIndex>,
+    /// Handed to the room, see [`QuestionProvider`].
+    question_provider: Option<Arc<dyn QuestionProvider>>,
+    /// The room's current action, which applies to names as well.
+    moderation_action: ModerationAction,
+    rejected_origins: u64,
+    binary_frames: Arc<AtomicU64>,
+    latency: LatencyMetrics,
+    connections_per_ip: HashMap<IpAddr, usize>,
+    session_ips: HashMap<ActorID, IpAddr>,
+    /// What each socket session has sent lately. A reconnect is a new
+    /// session and starts with an empty one.
+    outboxes: HashMap<ActorID, Outbox>,
+    /// Each socket session's recorder, idle unless capturing.
+    captures: HashMap<ActorID, Capture>,
+    started_at: Instant,
+    connections_accepted: u64,
+    registrations: u64,
+    games_started: u64,
+    games_completed: u64,
+    /// Signs session tokens. Regenerated on every start, so a restart
+    /// invalidates all tokens.
+    token_secret: [u8; 32],
+    /// Recent lobby chat, replayed to clients as they register.
+    lobby_history: VecDeque<OutNotifLobbyChat>,
+    /// The room as last reported, for room list subscribers.
+    room_entry: Option<RoomListEntry>,
+    /// Clients of a restored room that have not reconnected yet, by key.
+    #[cfg(feature = "persistence")]
+    saved_clients: HashMap<String, SavedClient>,
 }
 
 impl Actor for GameActor {
-    type Args = ();
+    type Args = (
+        Arc<ServerConfig>,
+        EffectiveConfig,
+        EventSender,
+        Option<Arc<dyn QuestionProvider>>,
+    );
     type Error = Infallible;
 
-    async fn on_start(_: Self::Args, ar: ActorRef<Self>) -> Result<Self, Self::Error> {
-        let room = RoomActor::spawn_link(&ar, ("default".into(), ar.downgrade())).await;
+    async fn on_start(
+        (config, mut effective_config, events, question_provider): Self::Args,
+        ar: ActorRef<Self>,
+    ) -> Result<Self, Self::Error> {
+        let word_filter = Arc::new(WordFilter::from_config(&config));
+        let word_parts = Arc::new(WordPartIndex::from_config(&config));
+        // a list that failed to load is as good as none
+        effective_config.features.word_filter &= !word_filter.is_empty();
+        effective_config.features.word_parts &= !word_parts.is_empty();
+        let room = RoomActor::spawn_link(
+            &ar,
+            (
+                "default".into(),
+                ar.downgrade(),
+                config.clone(),
+                events.clone(),
+                word_filter.clone(),
+                word_parts.clone(),
+                question_provider.clone(),
+            ),
+        )
+        .await;
+        #[cfg(feature = "persistence")]
+        let saved_clients = restore_room(&config, &room).await;
+        let bot_profile = config.bots.profile(config.bots.empty_room_difficulty);
+        for _ in 0..config.bots.empty_room_bots {
+            BotClientActor::spawn((ar.downgrade(), bot_profile));
+        }
 
         Ok(Self {
             pending_clients: HashMap::new(),
             registered_clients: HashMap::new(),
             room,
+            moderation_action: config.moderation_action,
+            config,
+            effective_config,
+            events,
+            word_filter,
+            word_parts,
+            question_provider,
+            rejected_origins: 0,
+            binary_frames: Arc::new(AtomicU64::new(0)),
+            latency: LatencyMetrics::default(),
+            connections_per_ip: HashMap::new(),
+            session_ips: HashMap::new(),
+            outboxes: HashMap::new(),
+            captures: HashMap::new(),
+            started_at: Instant::now(),
+            connections_accepted: 0,
+            registrations: 0,
+            games_started: 0,
+            games_completed: 0,
+            token_secret: random_bytes(),
+            lobby_history: VecDeque::new(),
+            room_entry: None,
+            #[cfg(feature = "persistence")]
+            saved_clients,
         })
     }
 
     async fn on_link_died(
         &mut self,
-        _ar: WeakActorRef<Self>,
+        ar: WeakActorRef<Self>,
         id: ActorID,
         reason: ActorStopReason,
     ) -> Result<ControlFlow<ActorStopReason>, Self::Error> {
+        if id == self.room.id()
+            && !matches!(reason, ActorStopReason::Normal)
+            && let Some(ar) = ar.upgrade()
+        {
+            error!("room died: {reason:?}, starting a new one");
+            if let Some(entry) = self.room_entry.take() {
+                self.notif_room_list(RoomListChange::Closed { id: entry.id })
+                    .await;
+            }
+            self.respawn_room(&ar).await;
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        self.outboxes.remove(&id);
+        if let Some(capture) = self.captures.remove(&id) {
+            capture.stop();
+        }
+        if let Some(ip) = self.session_ips.remove(&id)
+            && let Some(count) = self.connections_per_ip.get_mut(&ip)
+        {
+            *count -= 1;
+            if *count == 0 {
+                self.connections_per_ip.remove(&ip);
+            }
+        }
+
         let mut uuid_to_remove: Option<Uuid> = None;
         for (uuid, session) in &self.pending_clients {
             if session.id() == id {
@@ -77,24 +216,328 @@ impl Actor for GameActor {
 }
 
 impl GameActor {
+    fn outbox_of(&self, client_id: &str) -> Result<Vec<OutboxEntry>, &'static str> {
+        let session = client_id
+            .parse()
+            .ok()
+            .and_then(|uuid| self.registered_clients.get(&uuid))
+            .map(|c| &c.session)
+            .ok_or("no such client")?;
+        self.outboxes
+            .get(&session.id())
+            .map(Outbox::entries)
+            .ok_or("no outbox")
+    }
+
+    async fn reply_status(
+        &self,
+        session: &ActorRef<SessionClientActor>,
+        correlation_id: Uuid,
+        status: &str,
+    ) {
+        session
+            .tell(SendWs(TransportMsg::OutRespStatus(TransportEnvelope {
+                correlation_id,
+                seq: None,
+                payload: OutRespStatus {
+                    status: status.to_string(),
+                    sequence: None,
+                },
+            })))
+            .await
+            .ok();
+    }
+
+    async fn reply_rejected(
+        &self,
+        session: &ActorRef<SessionClientActor>,
+        correlation_id: Uuid,
+        rejection: JoinRejection,
+    ) {
+        warn!("registration rejected by the room: {rejection:?}");
+        session
+            .tell(SendWs(TransportMsg::OutRespError(TransportEnvelope {
+                correlation_id,
+                seq: None,
+                payload: OutRespError {
+                    error: rejection.as_str().to_string(),
+                    code: Some(rejection),
+                },
+            })))
+            .await
+            .ok();
+    }
+
+    async fn reply_registered(
+        &self,
+        session: &ActorRef<SessionClientActor>,
+        correlation_id: Uuid,
+        uuid: Uuid,
+    ) {
+        let Registration { room, role } = match self.room.ask(GetRegistration(uuid)).await {
+            Ok(registration) => registration,
+            Err(e) => {
+                error!("can't describe the room: {e}");
+                self.reply_status(session, correlation_id, "error").await;
+                return;
+            }
+        };
+        let capabilities = self
+            .registered_clients
+            .get(&uuid)
+            .map(|c| c.info.capabilities.iter().copied().collect())
+            .unwrap_or_default();
+        #[allow(deprecated)]
+        let resp = TransportMsg::OutRespClientRegistered(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload: OutRespClientRegistered {
+                id: uuid.to_string(),
+                game_settings: room.game_settings.clone(),
+                room: Box::new(room),
+                role,
+                capabilities,
+            },
+        });
+        session.tell(SendWs(resp)).await.ok();
+    }
+
+    async fn notif_room_list(&self, change: RoomListChange) {
+        let subscribers = self
+            .registered_clients
+            .values()
+            .filter(|c| c.room_list_subscribed);
+        for client in subscribers {
+            let notif = TransportMsg::OutNotifRoomListChanged(TransportEnvelope {
+                correlation_id: Uuid::new_v4(),
+                seq: None,
+                payload: OutNotifRoomListChanged {
+                    change: change.clone(),
+                },
+            });
+            client.session.tell(SendWs(notif)).await.ok();
+        }
+        debug!("OUT_NOTIF_roomListChanged");
+    }
+
+    async fn rename(&mut self, uuid: Uuid, name: &str) -> Result<(), &'static str> {
+        let name = self.check_name(name, Some(uuid))?;
+
+        let Some(client) = self.registered_clients.get_mut(&uuid) else {
+            return Err("not registered");
+        };
+        if client.info.name == name {
+            return Ok(());
+        }
+        client.info.name = name.clone();
+
+        self.room.tell(ClientRenamed { uuid, name }).await.ok();
+        Ok(())
+    }
+
+    /// Adds the client to the room and points its session at it.
+    async fn join_room(
+        &mut self,
+        session: &ActorRef<SessionClientActor>,
+        info: GameClientInfo,
+        spectator: bool,
+        password: Option<Password>,
+    ) -> Result<(), JoinRejection> {
+        let added = self
+            .room
+            .ask(AddClient {
+                uuid: info.id,
+                session: session.clone(),
+                sink: Box::new(session.clone()),
+                info,
+                is_spectator: spectator,
+                password,
+            })
+            .await;
+        match added {
+            Ok(()) => {}
+            Err(SendError::HandlerError(rejection)) => return Err(rejection),
+            Err(e) => {
+                error!("can't add client to room: {e}");
+                return Err(JoinRejection::Unavailable);
+            }
+        }
+
+        session.tell(SetRoom(self.room.downgrade())).await.ok();
+        Ok(())
+    }
+
+    /// Replaces a crashed room with an empty one and puts the clients that
+    /// were in it back in. Room state such as settings, history and the
+    /// join password starts over.
+    async fn respawn_room(&mut self, ar: &ActorRef<Self>) {
+        self.room = RoomActor::spawn_link(
+            ar,
+            (
+                "default".into(),
+                ar.downgrade(),
+                self.config.clone(),
+                self.events.clone(),
+                self.word_filter.clone(),
+                self.word_parts.clone(),
+                self.question_provider.clone(),
+            ),
+        )
+        .await;
+        self.moderation_action = self.config.moderation_action;
+
+        let members: Vec<_> = self
+            .registered_clients
+            .iter()
+            .filter(|(_, c)| c.in_room)
+            .map(|(uuid, c)| (*uuid, c.session.clone(), c.info.clone(), c.spectator))
+            .collect();
+        for (uuid, session, info, spectator) in members {
+            let notif = TransportMsg::OutNotifServerError(TransportEnvelope {
+                correlation_id: Uuid::new_v4(),
+                seq: None,
+                payload: OutNotifServerError {
+                    message: "room restarted".into(),
+                },
+            });
+            session.tell(SendWs(notif)).await.ok();
+
+            if let Err(rejection) = self.join_room(&session, info, spectator, None).await {
+                warn!("can't put {uuid} back into the room: {rejection:?}");
+                if let Some(client) = self.registered_clients.get_mut(&uuid) {
+                    client.in_room = false;
+                }
+            }
+        }
+    }
+
+    /// Disconnects a registered client. It goes back to the pending list so
+    /// its link death is still recognised as a client leaving.
+    fn drop_registered(&mut self, uuid: Uuid) {
+        if let Some(client) = self.registered_clients.remove(&uuid) {
+            debug!("replacing session of {}", client.info.player_id);
+            client.session.kill();
+            self.pending_clients.insert(uuid, client.session);
+        }
+    }
+
+    fn health_report(&self) -> HealthReport {
+        HealthReport {
+            status: "ok".into(),
+            version: env!("CARGO_PKG_VERSION").into(),
+            uptime_seconds: self.started_at.elapsed().as_secs(),
+            clients: self.registered_clients.len() as u64,
+        }
+    }
+
+    /// Normalizes and moderates a name, then checks nobody else has it.
+    fn check_name(&self, name: &str, except: Option<Uuid>) -> Result<String, &'static str> {
+        let name = normalize_name(name)?;
+        let name = self.word_filter.moderate(self.moderation_action, &name)?;
+        if self.is_name_taken(&name, except) {
+            return Err("name taken");
+        }
+        Ok(name)
+    }
+
+    fn is_name_taken(&self, name: &str, except: Option<Uuid>) -> bool {
+        let name = name_key(name);
+        self.registered_clients
+            .iter()
+            .any(|(uuid, c)| Some(*uuid) != except && name_key(&c.info.name) == name)
+    }
+
     async fn spawn_client(
         &mut self,
-        stream: TcpStream,
+        stream: ClientStream,
+        peer_addr: SocketAddr,
         ctx: &mut Context<Self, ()>,
     ) -> Result<(), String> {
-        let ws_stream = accept_async(stream).await.map_err(|e| e.to_string())?;
+        // monitors probe the same port over plain HTTP
+        let stream = read_head(stream).await.map_err(|e| e.to_string())?;
+        if let Some(path) = probe_path(stream.head()) {
+            debug!("{path} probe from {peer_addr}");
+            return respond(stream, &self.health_report())
+                .await
+                .map_err(|e| e.to_string());
+        }
+        let stream: ClientStream = Box::new(stream);
+
+        let ip = peer_addr.ip();
+        let over_limit = self
+            .config
+            .max_connections_per_ip
+            .is_some_and(|max| self.connections_per_ip.get(&ip).copied().unwrap_or(0) >= max);
+
+        // the transport answers oversized messages itself, so tungstenite only
+        // has to stop the ones that would not fit in memory
+        let hard_limit = self.config.max_message_size.saturating_mul(2);
+        let ws_config = WebSocketConfig::default()
+            .max_message_size(Some(hard_limit))
+            .max_frame_size(Some(hard_limit));
+        let mut rejected = false;
+        // the callback signature is fixed by tungstenite
+        #[allow(clippy::result_large_err)]
+        let check_request = |req: &Request, resp: Response| {
+            if over_limit {
+                warn!("too many connections from {ip}");
+                return Err(error_response(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "too many connections",
+                ));
+            }
+            let origin = req.headers().get(ORIGIN).map(|o| o.to_str().unwrap_or(""));
+            if self.config.is_origin_allowed(origin) {
+                return Ok(resp);
+            }
+            warn!("rejected origin {origin:?} from {peer_addr}");
+            rejected = true;
+            Err(error_response(StatusCode::FORBIDDEN, "origin not allowed"))
+        };
+        let handshake = accept_hdr_async_with_config(stream, check_request, Some(ws_config)).await;
+        if rejected {
+            self.rejected_origins += 1;
+        }
+        let ws_stream = handshake.map_err(|e| e.to_string())?;
         let (write, read) = ws_stream.split();
 
         let game_ref = ctx.actor_ref();
         let session_ref = SessionClientActor::spawn_link(
             &game_ref,
-            SessionClientActor::new(game_ref.downgrade()),
+            SessionClientActor::new(game_ref.downgrade(), Some(peer_addr)).with_recent_ids(
+                RecentIds::new(
+                    self.config.duplicate_id_history,
+                    self.config.duplicate_id_window,
+                ),
+            ),
         )
         .await;
+        info!("client connected from {peer_addr}");
+        *self.connections_per_ip.entry(ip).or_default() += 1;
+        self.session_ips.insert(session_ref.id(), ip);
+        let outbox = Outbox::new(self.config.outbox_size);
+        self.outboxes.insert(session_ref.id(), outbox.clone());
+        let client_uuid = Uuid::new_v4();
+        let capture = Capture::default();
+        if let Some(config) = self.config.capture.as_ref().filter(|c| c.record_all) {
+            match capture.start(config, &client_uuid.to_string()) {
+                Ok(path) => debug!("recording {peer_addr} to {}", path.display()),
+                Err(e) => warn!("can't record {peer_addr}: {e}"),
+            }
+        }
+        self.captures.insert(session_ref.id(), capture.clone());
 
         let transport_ref = WebSocketClientActor::spawn_link(
             &session_ref,
-            WebSocketClientActor::new(write, session_ref.downgrade()),
+            WebSocketClientActor::new(
+                write,
+                session_ref.downgrade(),
+                self.config.clone(),
+                self.binary_frames.clone(),
+                outbox,
+                capture,
+                self.latency.clone(),
+            ),
         )
         .await;
 
@@ -103,44 +546,131 @@ impl GameActor {
 
         let raw_stream = read.filter_map(|r: Result<WsMsg, WsErr>| {
             future::ready(match r {
-                Ok(WsMsg::Text(txt)) => Some(Ok(txt.to_string())),
-                Ok(_) => None,
+                Ok(msg) => Frame::from_ws(msg).map(Ok),
                 Err(e) => Some(Err(e.to_string())),
             })
         });
-        transport_ref.attach_stream(raw_stream, (), ());
+        let reader = transport_ref.attach_stream(raw_stream, (), ());
+        transport_ref
+            .tell(SetReader(reader.abort_handle()))
+            .await
+            .ok();
 
-        let client_uuid = Uuid::new_v4();
         self.pending_clients.insert(client_uuid, session_ref);
+        self.connections_accepted += 1;
         Ok(())
     }
 }
+
+/// Loads the saved room, if any, and removes the file so a later crash does
+/// not bring back a stale game. Returns the clients that can reclaim their
+/// old identities.
+#[cfg(feature = "persistence")]
+async fn restore_room(
+    config: &ServerConfig,
+    room: &ActorRef<RoomActor>,
+) -> HashMap<String, SavedClient> {
+    let Some(path) = &config.state_path else {
+        return HashMap::new();
+    };
+    let snapshot = match load_snapshot(path) {
+        Ok(Some(snapshot)) => snapshot,
+        Ok(None) => return HashMap::new(),
+        Err(e) => {
+            error!("can't load saved state: {e}");
+            return HashMap::new();
+        }
+    };
+    if let Err(e) = std::fs::remove_file(path) {
+        warn!("can't remove saved state: {e}");
+    }
+
+    info!(
+        "restoring room {} with {} clients",
+        snapshot.room_name,
+        snapshot.clients.len()
+    );
+    let saved_clients = snapshot
+        .clients
+        .iter()
+        .map(|c| (c.key.clone(), c.clone()))
+        .collect();
+    room.tell(RestoreSnapshot(snapshot)).await.ok();
+    saved_clients
+}
+
+pub async fn collect_server_stats(game: &ActorRef<GameActor>) -> Result<ServerStats, String> {
+    let GameStats { rooms, stats } = game.ask(GetServerStats).await.map_err(|e| e.to_string())?;
+
+    let mut stats = ServerStats {
+        rooms: Vec::with_capacity(rooms.len()),
+        ..stats
+    };
+    for room in rooms {
+        match room.ask(GetRoomStats).await {
+            Ok(room_stats) => stats.rooms.push(room_stats),
+            Err(e) => warn!("room stats unavailable: {e}"),
+        }
+    }
+    Ok(stats)
+}
+/// Names that compare equal here are the same name, for uniqueness and
+/// for looking players up.
+fn name_key(name: &str) -> String {
+    name.to_lowercase()
+}
+
+fn error_response(status: StatusCode, reason: &str) -> ErrorResponse {
+    let mut resp = ErrorResponse::new(Some(reason.to_string()));
+    *resp.status_mut() = status;
+    resp
+}
 // #endregion
 
 // #region TYPES
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct GameClientInfo {
     pub id: Uuid,
+    pub player_id: Uuid,
     pub key: String,
     pub name: String,
+    pub capabilities: BTreeSet<Capability>,
+    pub is_bot: bool,
 }
 
 struct RegisteredClient {
     session: ActorRef<SessionClientActor>,
     info: GameClientInfo,
+    in_room: bool,
+    spectator: bool,
+    lobby_limiter: RateLimiter,
+    room_list_subscribed: bool,
+    /// Whether `FindPlayerRequest` can turn this client up.
+    discoverable: bool,
+}
+
+/// Game-level counters, with the rooms still to be asked for theirs.
+#[derive(Reply)]
+pub struct GameStats {
+    pub rooms: Vec<ActorRef<RoomActor>>,
+    pub stats: ServerStats,
 }
 // #endregion
 
 // #region MESSAGES
-pub struct NewClient(pub TcpStream);
+pub struct NewClient(pub ClientStream, pub SocketAddr);
 
 impl Message<NewClient> for GameActor {
     type Reply = ();
 
-    async fn handle(&mut self, NewClient(stream): NewClient, ctx: &mut Context<Self, Self::Reply>) {
-        if let Err(e) = self.spawn_client(stream, ctx).await {
-            error!("can't start client: {e}");
+    async fn handle(
+        &mut self,
+        NewClient(stream, peer_addr): NewClient,
+        ctx: &mut Context<Self, Self::Reply>,
+    ) {
+        if let Err(e) = self.spawn_client(stream, peer_addr, ctx).await {
+            error!("can't start client from {peer_addr}: {e}");
         }
     }
 }
@@ -148,7 +678,11 @@ impl Message<NewClient> for GameActor {
 pub struct RegisterClientRequest {
     pub session: ActorRef<SessionClientActor>,
     pub name: String,
+    pub spectator: bool,
+    pub password: Option<Password>,
     pub pub_key: String,
+    pub capabilities: BTreeSet<Capability>,
+    pub is_bot: bool,
     pub correlation_id: Uuid,
 }
 
@@ -159,10 +693,44 @@ impl Message<RegisterClientRequest> for GameActor {
         let RegisterClientRequest {
             session,
             name,
+            spectator,
+            password,
             pub_key,
+            capabilities,
+            is_bot,
             correlation_id,
         } = msg;
 
+        let registered_uuid = self
+            .registered_clients
+            .iter()
+            .find(|(_, c)| c.session.id() == session.id())
+            .map(|(uuid, _)| *uuid);
+
+        if let Some(uuid) = registered_uuid {
+            debug!("repeated registration for {uuid}");
+            if let Err(status) = self.rename(uuid, &name).await {
+                self.reply_status(&session, correlation_id, status).await;
+                return;
+            }
+            // a client that left the room joins it again without re-authenticating
+            let client = &self.registered_clients[&uuid];
+            if !client.in_room {
+                let info = client.info.clone();
+                if let Err(rejection) = self.join_room(&session, info, spectator, password).await {
+                    self.reply_rejected(&session, correlation_id, rejection)
+                        .await;
+                    return;
+                }
+                if let Some(client) = self.registered_clients.get_mut(&uuid) {
+                    client.in_room = true;
+                    client.spectator = spectator;
+                }
+            }
+            self.reply_registered(&session, correlation_id, uuid).await;
+            return;
+        }
+
         let client_uuid = self
             .pending_clients
             .iter()
@@ -174,69 +742,656 @@ impl Message<RegisterClientRequest> for GameActor {
             return;
         };
 
-        let running = self.room.ask(IsGameRunning).await.unwrap_or(true);
-        if running {
-            let resp = TransportMsg::OutRespStatus(TransportEnvelope {
-                correlation_id,
-                payload: OutRespStatus {
-                    status: "game already running".into(),
-                },
-            });
-            session.tell(SendWs(resp)).await.ok();
+        let player_id = player_id(&pub_key);
+        let connected = self
+            .registered_clients
+            .iter()
+            .find(|(_, c)| c.info.player_id == player_id)
+            .map(|(uuid, _)| *uuid);
+        if let Some(old_uuid) = connected {
+            if !self.config.replace_duplicate_sessions {
+                warn!("registration rejected: {player_id} already connected");
+                self.reply_status(&session, correlation_id, "already connected")
+                    .await;
+                return;
+            }
+            self.drop_registered(old_uuid);
+        }
+
+        // a client of a restored room gets its old id and, if still free, name
+        #[cfg(feature = "persistence")]
+        let (id, name) = match self.saved_clients.get(&pub_key) {
+            Some(saved) if !self.is_name_taken(&saved.name, None) => (saved.id, saved.name.clone()),
+            Some(saved) => (saved.id, name),
+            None => (uuid, name),
+        };
+        #[cfg(not(feature = "persistence"))]
+        let id = uuid;
+
+        let name = match self.check_name(&name, None) {
+            Ok(name) => name,
+            Err(status) => {
+                warn!("registration rejected: {status}");
+                self.reply_status(&session, correlation_id, status).await;
+                return;
+            }
+        };
+
+        let info = GameClientInfo {
+            id,
+            player_id,
+            key: pub_key,
+            name,
+            capabilities,
+            is_bot,
+        };
 
-            session.tell(Shutdown).await.ok();
+        // not registered until the room takes it, so a rejected client is
+        // still pending and can simply try again
+        if let Err(rejection) = self
+            .join_room(&session, info.clone(), spectator, password)
+            .await
+        {
+            self.reply_rejected(&session, correlation_id, rejection)
+                .await;
             return;
         }
 
         let session_ref = self.pending_clients.remove(&uuid).unwrap();
+        self.registrations += 1;
+        #[cfg(feature = "persistence")]
+        self.saved_clients.remove(&info.key);
         self.registered_clients.insert(
-            uuid,
+            id,
             RegisteredClient {
                 session: session_ref.clone(),
-                info: GameClientInfo {
-                    id: uuid,
-                    key: pub_key.clone(),
-                    name: name.clone(),
-                },
+                info,
+                in_room: true,
+                spectator,
+                lobby_limiter: RateLimiter::new(LOBBY_CHAT_RATE_LIMIT, LOBBY_CHAT_RATE_WINDOW),
+                room_list_subscribed: false,
+                discoverable: true,
             },
         );
 
-        self.room
-            .tell(AddClient {
-                uuid,
-                session: session_ref.clone(),
-            })
+        self.reply_registered(&session_ref, correlation_id, id)
+            .await;
+        for notif in &self.lobby_history {
+            session_ref
+                .tell(SendWs(TransportMsg::OutNotifLobbyChat(TransportEnvelope {
+                    correlation_id: Uuid::new_v4(),
+                    seq: None,
+                    payload: notif.clone(),
+                })))
+                .await
+                .ok();
+        }
+    }
+}
+
+pub struct RenameClientRequest {
+    pub session: ActorRef<SessionClientActor>,
+    pub name: String,
+    pub correlation_id: Uuid,
+}
+
+impl Message<RenameClientRequest> for GameActor {
+    type Reply = ();
+
+    async fn handle(&mut self, msg: RenameClientRequest, _ctx: &mut Context<Self, ()>) {
+        let RenameClientRequest {
+            session,
+            name,
+            correlation_id,
+        } = msg;
+
+        let client_uuid = self
+            .registered_clients
+            .iter()
+            .find(|(_, c)| c.session.id() == session.id())
+            .map(|(uuid, _)| *uuid);
+
+        let Some(uuid) = client_uuid else {
+            self.reply_status(&session, correlation_id, "not registered")
+                .await;
+            return;
+        };
+
+        let status = match self.rename(uuid, &name).await {
+            Ok(()) => "success",
+            Err(status) => status,
+        };
+        self.reply_status(&session, correlation_id, status).await;
+    }
+}
+
+pub struct SendLobbyChatRequest {
+    pub session: ActorRef<SessionClientActor>,
+    pub message: String,
+    pub correlation_id: Uuid,
+}
+
+impl Message<SendLobbyChatRequest> for GameActor {
+    type Reply = ();
+
+    async fn handle(&mut self, msg: SendLobbyChatRequest, _ctx: &mut Context<Self, ()>) {
+        let SendLobbyChatRequest {
+            session,
+            message,
+            correlation_id,
+        } = msg;
+
+        let max_length = self.config.chat_max_length;
+        let Some(client) = self
+            .registered_clients
+            .values_mut()
+            .find(|c| c.session.id() == session.id())
+        else {
+            self.reply_status(&session, correlation_id, "not registered")
+                .await;
+            return;
+        };
+
+        let message = match sanitize_message(&message, max_length) {
+            Ok(message) => message,
+            Err(status) => {
+                self.reply_status(&session, correlation_id, status).await;
+                return;
+            }
+        };
+        if !client.lobby_limiter.check() {
+            warn!("lobby chat rate limited for {}", client.info.id);
+            self.reply_status(&session, correlation_id, "rate limited")
+                .await;
+            return;
+        }
+
+        let notif = OutNotifLobbyChat {
+            id: client.info.id.to_string(),
+            name: client.info.name.clone(),
+            message,
+            sent_at: unix_millis(),
+        };
+        self.reply_status(&session, correlation_id, "success").await;
+
+        for client in self.registered_clients.values() {
+            client
+                .session
+                .tell(SendWs(TransportMsg::OutNotifLobbyChat(TransportEnvelope {
+                    correlation_id: Uuid::new_v4(),
+                    seq: None,
+                    payload: notif.clone(),
+                })))
+                .await
+                .ok();
+        }
+        debug!("OUT_NOTIF_lobbyChat");
+
+        self.lobby_history.push_back(notif);
+        if self.lobby_history.len() > LOBBY_HISTORY_LIMIT {
+            self.lobby_history.pop_front();
+        }
+    }
+}
+
+pub struct SubscribeRoomListRequest {
+    pub session: ActorRef<SessionClientActor>,
+    pub subscribe: bool,
+    pub correlation_id: Uuid,
+}
+
+impl Message<SubscribeRoomListRequest> for GameActor {
+    type Reply = ();
+
+    async fn handle(&mut self, msg: SubscribeRoomListRequest, _ctx: &mut Context<Self, ()>) {
+        let SubscribeRoomListRequest {
+            session,
+            subscribe,
+            correlation_id,
+        } = msg;
+
+        let Some(client) = self
+            .registered_clients
+            .values_mut()
+            .find(|c| c.session.id() == session.id())
+        else {
+            self.reply_status(&session, correlation_id, "not registered")
+                .await;
+            return;
+        };
+        let newly_subscribed = subscribe && !client.room_list_subscribed;
+        client.room_list_subscribed = subscribe;
+        self.reply_status(&session, correlation_id, "success").await;
+
+        if newly_subscribed && let Some(entry) = self.room_entry.clone() {
+            let notif = TransportMsg::OutNotifRoomListChanged(TransportEnvelope {
+                correlation_id: Uuid::new_v4(),
+                seq: None,
+                payload: OutNotifRoomListChanged {
+                    change: RoomListChange::Opened { room: entry },
+                },
+            });
+            session.tell(SendWs(notif)).await.ok();
+        }
+    }
+}
+
+pub struct FindPlayerRequest {
+    pub session: ActorRef<SessionClientActor>,
+    pub player_id_or_name: String,
+    pub correlation_id: Uuid,
+}
+
+impl Message<FindPlayerRequest> for GameActor {
+    type Reply = ();
+
+    async fn handle(&mut self, msg: FindPlayerRequest, _ctx: &mut Context<Self, ()>) {
+        let FindPlayerRequest {
+            session,
+            player_id_or_name,
+            correlation_id,
+        } = msg;
+
+        let player_id = Uuid::parse_str(player_id_or_name.trim()).ok();
+        let name = normalize_name(&player_id_or_name)
+            .ok()
+            .map(|name| name_key(&name));
+        let found = self.registered_clients.values().find(|c| {
+            c.discoverable
+                && (Some(c.info.player_id) == player_id || Some(name_key(&c.info.name)) == name)
+        });
+
+        let location = match found {
+            Some(client) => {
+                let room = self.room_entry.as_ref().filter(|_| client.in_room);
+                OutRespPlayerLocation {
+                    found: true,
+                    room_name: room.map(|r| r.name.clone()),
+                    in_game: room.is_some_and(|r| r.is_game_running),
+                }
+            }
+            None => OutRespPlayerLocation::default(),
+        };
+        session
+            .tell(SendWs(TransportMsg::OutRespPlayerLocation(
+                TransportEnvelope {
+                    correlation_id,
+                    seq: None,
+                    payload: location,
+                },
+            )))
             .await
             .ok();
+    }
+}
+
+pub struct SetPresenceVisibilityRequest {
+    pub session: ActorRef<SessionClientActor>,
+    pub visible: bool,
+    pub correlation_id: Uuid,
+}
 
-        session_ref.tell(SetRoom(self.room.downgrade())).await.ok();
+impl Message<SetPresenceVisibilityRequest> for GameActor {
+    type Reply = ();
 
-        let resp = TransportMsg::OutRespClientRegistered(TransportEnvelope {
+    async fn handle(&mut self, msg: SetPresenceVisibilityRequest, _ctx: &mut Context<Self, ()>) {
+        let SetPresenceVisibilityRequest {
+            session,
+            visible,
             correlation_id,
-            payload: OutRespClientRegistered {
-                id: uuid.to_string(),
-                game_settings: GameSettings::default(),
+        } = msg;
+
+        let Some(client) = self
+            .registered_clients
+            .values_mut()
+            .find(|c| c.session.id() == session.id())
+        else {
+            self.reply_status(&session, correlation_id, "not registered")
+                .await;
+            return;
+        };
+        client.discoverable = visible;
+        self.reply_status(&session, correlation_id, "success").await;
+    }
+}
+
+/// Registers a session that was spawned without a websocket, for bots and
+/// for tests that drive the protocol in-process.
+pub struct AttachSession(pub ActorRef<SessionClientActor>);
+
+impl Message<AttachSession> for GameActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        AttachSession(session): AttachSession,
+        ctx: &mut Context<Self, Self::Reply>,
+    ) {
+        session.link(&ctx.actor_ref()).await;
+        self.pending_clients.insert(Uuid::new_v4(), session);
+    }
+}
+
+pub struct GetServerStats;
+
+impl Message<GetServerStats> for GameActor {
+    type Reply = GameStats;
+
+    async fn handle(
+        &mut self,
+        _: GetServerStats,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> GameStats {
+        let pending_clients = self.pending_clients.len() as u64;
+        let registered_clients = self.registered_clients.len() as u64;
+        GameStats {
+            rooms: vec![self.room.clone()],
+            stats: ServerStats {
+                pending_clients,
+                registered_clients,
+                rooms: Vec::new(),
+                rejected_origins: self.rejected_origins,
+                binary_frames: self.binary_frames.load(Ordering::Relaxed),
+                connections_per_ip: self
+                    .connections_per_ip
+                    .iter()
+                    .map(|(ip, count)| IpConnections {
+                        ip: ip.to_string(),
+                        connections: *count as u64,
+                    })
+                    .collect(),
+                uptime_seconds: self.started_at.elapsed().as_secs(),
+                connections_accepted: self.connections_accepted,
+                connected_clients: pending_clients + registered_clients,
+                registrations: self.registrations,
+                games_started: self.games_started,
+                games_completed: self.games_completed,
+                request_latency: self.latency.snapshot(),
             },
-        });
-        session_ref.tell(SendWs(resp)).await.ok();
+        }
+    }
+}
+
+/// Whether `key` is one of the configured operator keys; without any,
+/// nobody is an operator.
+pub struct IsOperator(pub String);
+
+impl Message<IsOperator> for GameActor {
+    type Reply = bool;
+
+    async fn handle(
+        &mut self,
+        IsOperator(key): IsOperator,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> bool {
+        self.config.operator_keys.contains(&key)
+    }
+}
+
+/// Whether `key` may read the server stats: any key when no operator keys
+/// are configured.
+pub struct MayReadStats(pub String);
+
+impl Message<MayReadStats> for GameActor {
+    type Reply = bool;
+
+    async fn handle(
+        &mut self,
+        MayReadStats(key): MayReadStats,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> bool {
+        self.config.operator_keys.is_empty() || self.config.operator_keys.contains(&key)
+    }
+}
+
+/// The messages last sent to a registered client, oldest first. Clients
+/// without a socket have no outbox.
+pub struct DumpOutbox(pub String);
+
+impl Message<DumpOutbox> for GameActor {
+    type Reply = Result<Vec<OutboxEntry>, &'static str>;
+
+    async fn handle(
+        &mut self,
+        DumpOutbox(client_id): DumpOutbox,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        self.outbox_of(&client_id)
+    }
+}
+
+/// `DumpOutbox` for `IN_REQ_debugOutbox`, refused unless the config allows
+/// it. The operator check is left to the session.
+pub struct DebugOutbox(pub String);
+
+impl Message<DebugOutbox> for GameActor {
+    type Reply = Result<Vec<OutboxEntry>, &'static str>;
+
+    async fn handle(
+        &mut self,
+        DebugOutbox(client_id): DebugOutbox,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        if !self.config.debug_outbox {
+            return Err("outbox debugging disabled");
+        }
+        self.outbox_of(&client_id)
     }
 }
 
-pub struct GetClientsInfo {
-    pub ids: Vec<Uuid>,
+/// Starts or stops recording a registered client's connection, for
+/// `IN_REQ_debugCapture`. The operator check is left to the session.
+pub struct DebugCapture {
+    pub client_id: String,
+    pub enabled: bool,
 }
 
-impl Message<GetClientsInfo> for GameActor {
-    type Reply = Vec<GameClientInfo>;
+impl Message<DebugCapture> for GameActor {
+    type Reply = Result<(), &'static str>;
+
+    async fn handle(
+        &mut self,
+        DebugCapture { client_id, enabled }: DebugCapture,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        let Some(config) = &self.config.capture else {
+            return Err("capture disabled");
+        };
+        let capture = client_id
+            .parse()
+            .ok()
+            .and_then(|uuid| self.registered_clients.get(&uuid))
+            .and_then(|c| self.captures.get(&c.session.id()))
+            .ok_or("no such client")?;
+
+        if !enabled {
+            if let Some(path) = capture.stop() {
+                info!("stopped recording {client_id} to {}", path.display());
+            }
+            return Ok(());
+        }
+        match capture.start(config, &client_id) {
+            Ok(path) => {
+                info!("recording {client_id} to {}", path.display());
+                Ok(())
+            }
+            Err(e) => {
+                error!("can't record {client_id}: {e}");
+                Err("capture failed")
+            }
+        }
+    }
+}
+
+/// Every entry of the word part index, for `IN_REQ_wordPartList`.
+pub struct WordPartList;
+
+impl Message<WordPartList> for GameActor {
+    type Reply = Vec<WordPartEntry>;
+
+    async fn handle(
+        &mut self,
+        _: WordPartList,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        self.word_parts.entries().to_vec()
+    }
+}
+
+pub struct GetEffectiveConfig;
+
+impl Message<GetEffectiveConfig> for GameActor {
+    type Reply = EffectiveConfig;
 
     async fn handle(
         &mut self,
-        GetClientsInfo { ids }: GetClientsInfo,
+        _: GetEffectiveConfig,
         _ctx: &mut Context<Self, Self::Reply>,
-    ) -> Vec<GameClientInfo> {
-        ids.into_iter()
-            .filter_map(|id| self.registered_clients.get(&id).map(|c| c.info.clone()))
-            .collect()
+    ) -> Self::Reply {
+        self.effective_config.clone()
+    }
+}
+
+/// Issues a session token for a verified key.
+pub struct IssueSessionToken(pub String);
+
+impl Message<IssueSessionToken> for GameActor {
+    type Reply = OutNotifSessionToken;
+
+    async fn handle(
+        &mut self,
+        IssueSessionToken(key): IssueSessionToken,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> OutNotifSessionToken {
+        let expires_at = unix_millis() + self.config.session_token_ttl.as_millis() as u64;
+        OutNotifSessionToken {
+            token: issue_session_token(&self.token_secret, &key, expires_at),
+            expires_at,
+        }
+    }
+}
+
+/// Returns the key a session token was issued for.
+pub struct CheckSessionToken(pub String);
+
+impl Message<CheckSessionToken> for GameActor {
+    type Reply = Result<String, &'static str>;
+
+    async fn handle(
+        &mut self,
+        CheckSessionToken(token): CheckSessionToken,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        check_session_token(&self.token_secret, &token, unix_millis())
+    }
+}
+
+/// Sent by rooms so the game can keep server-wide counters.
+pub enum RoomActivity {
+    GameStarted,
+    GameStopped(GameStopReason),
+}
+
+impl Message<RoomActivity> for GameActor {
+    type Reply = ();
+
+    async fn handle(&mut self, activity: RoomActivity, _ctx: &mut Context<Self, Self::Reply>) {
+        match activity {
+            RoomActivity::GameStarted => self.games_started += 1,
+            RoomActivity::GameStopped(GameStopReason::Completed) => self.games_completed += 1,
+            RoomActivity::GameStopped(_) => {}
+        }
+    }
+}
+
+/// Sent by the room when its admin changes how blocked words are handled.
+pub struct ModerationChanged(pub ModerationAction);
+
+impl Message<ModerationChanged> for GameActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        ModerationChanged(action): ModerationChanged,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) {
+        self.moderation_action = action;
+    }
+}
+
+/// Sent by a room whenever what the lobby sees of it changes.
+pub struct RoomStatusChanged(pub RoomListEntry);
+
+impl Message<RoomStatusChanged> for GameActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        RoomStatusChanged(entry): RoomStatusChanged,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) {
+        let change = match &self.room_entry {
+            Some(old) if old.id == entry.id => RoomListChange::Updated {
+                room: entry.clone(),
+            },
+            _ => RoomListChange::Opened {
+                room: entry.clone(),
+            },
+        };
+        self.room_entry = Some(entry);
+        self.notif_room_list(change).await;
+    }
+}
+
+/// Saves the room to the configured state file, together with restored
+/// clients that never came back.
+#[cfg(feature = "persistence")]
+pub struct SaveState;
+
+#[cfg(feature = "persistence")]
+impl Message<SaveState> for GameActor {
+    type Reply = Result<(), String>;
+
+    async fn handle(
+        &mut self,
+        _: SaveState,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Result<(), String> {
+        let Some(path) = self.config.state_path.clone() else {
+            return Ok(());
+        };
+
+        let mut snapshot = self
+            .room
+            .ask(TakeSnapshot)
+            .await
+            .map_err(|e| e.to_string())?;
+        for saved in self.saved_clients.values() {
+            if !snapshot.clients.iter().any(|c| c.id == saved.id) {
+                snapshot.clients.push(saved.clone());
+            }
+        }
+
+        save_snapshot(&path, &snapshot)?;
+        info!("room state saved to {}", path.display());
+        Ok(())
+    }
+}
+
+/// Sent by the room when a client leaves it but keeps its connection.
+pub struct ClientLeftRoom(pub Uuid);
+
+impl Message<ClientLeftRoom> for GameActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        ClientLeftRoom(uuid): ClientLeftRoom,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) {
+        if let Some(client) = self.registered_clients.get_mut(&uuid) {
+            client.in_room = false;
+        }
     }
 }
 // #endregion
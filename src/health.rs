@@ -0,0 +1,116 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+use crate::websocket_client_actor::ClientStream;
+
+/// Longest request head read before giving the connection to tungstenite,
+/// which has its own limits for whatever is left.
+const MAX_HEAD: usize = 8 * 1024;
+
+/// Plain HTTP paths answered without a websocket upgrade.
+pub const PROBE_PATHS: [&str; 2] = ["/healthz", "/info"];
+
+/// Body of a probe response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub status: String,
+    pub version: String,
+    pub uptime_seconds: u64,
+    pub clients: u64,
+}
+
+/// Reads the HTTP request head off a fresh connection. The bytes are kept,
+/// so the stream can still be handed to the websocket handshake.
+pub async fn read_head(mut stream: ClientStream) -> io::Result<Prefixed> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_HEAD {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(Prefixed {
+        prefix: head,
+        pos: 0,
+        inner: stream,
+    })
+}
+
+/// The probe path a request head asks for, if it is one.
+pub fn probe_path(head: &[u8]) -> Option<&'static str> {
+    let line = head.split(|&b| b == b'\r').next()?;
+    let mut parts = line.split(|&b| b == b' ');
+    if parts.next()? != b"GET" {
+        return None;
+    }
+    let target = parts.next()?;
+    let path = target.split(|&b| b == b'?').next()?;
+    PROBE_PATHS.into_iter().find(|p| p.as_bytes() == path)
+}
+
+/// Writes `report` as a complete HTTP response and closes the connection.
+pub async fn respond(mut stream: Prefixed, report: &HealthReport) -> io::Result<()> {
+    let body = serde_json::to_string(report).map_err(io::Error::other)?;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// A stream that replays bytes already read from it before reading on.
+pub struct Prefixed {
+    prefix: Vec<u8>,
+    pos: usize,
+    inner: ClientStream,
+}
+
+impl Prefixed {
+    pub fn head(&self) -> &[u8] {
+        &self.prefix
+    }
+}
+
+impl AsyncRead for Prefixed {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.pos < self.prefix.len() {
+            let n = buf.remaining().min(self.prefix.len() - self.pos);
+            buf.put_slice(&self.prefix[self.pos..self.pos + n]);
+            self.pos += n;
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Prefixed {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
@@ -1,10 +1,40 @@
+pub mod bots;
+pub mod capture;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod client_sink;
+pub mod config;
 pub mod data_types;
+pub mod events;
+pub mod export;
+pub mod filter;
 pub mod game_actor;
+pub mod health;
+pub mod metrics;
 pub mod pending_tracker;
+#[cfg(feature = "persistence")]
+pub mod persistence;
+pub mod question_provider;
+pub mod replay;
 pub mod room_actor;
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod server;
 pub mod session_client_actor;
+pub mod summary;
+#[cfg(feature = "testkit")]
+pub mod testkit;
+pub mod tls;
 pub mod tools;
 pub mod websocket_client_actor;
+pub mod word_parts;
 
-pub use server::{call_launch_server, call_stop_server};
+pub use config::{CaptureConfig, ServerConfig, TlsConfig};
+pub use events::ServerEvent;
+pub use question_provider::{ProvidedQuestion, ProviderError, QuestionProvider};
+pub use server::{
+    Listener, ServerId, call_launch_server, call_launch_server_with_config,
+    call_launch_server_with_provider, call_reload_tls, call_stop_server, call_stop_sole_server,
+    get_client_outbox, get_effective_config, get_server_addr, get_server_stats, serve,
+    subscribe_events,
+};
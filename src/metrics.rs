@@ -0,0 +1,52 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use crate::data_types::LatencyHistogram;
+
+/// Upper bounds of the latency buckets, in milliseconds. Anything slower
+/// lands in one more bucket at the end.
+pub const LATENCY_BUCKETS_MS: [u64; 10] = [1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500];
+
+/// When a request arrived, carried along until its response is written.
+#[derive(Debug, Clone)]
+pub struct RequestTiming {
+    /// `messageType` of the request.
+    pub message_type: String,
+    pub received_at: Instant,
+}
+
+/// Request handling latency per message type, shared by every connection.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyMetrics(Arc<Mutex<BTreeMap<String, LatencyHistogram>>>);
+
+impl LatencyMetrics {
+    pub fn record(&self, message_type: &str, latency: Duration) {
+        let mut histograms = self.0.lock().unwrap();
+        let histogram = histograms
+            .entry(message_type.to_string())
+            .or_insert_with(|| LatencyHistogram {
+                count: 0,
+                total_us: 0,
+                max_us: 0,
+                buckets: vec![0; LATENCY_BUCKETS_MS.len() + 1],
+            });
+        let us = latency.as_micros() as u64;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&ms| us <= ms * 1000)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        histogram.count += 1;
+        histogram.total_us += us;
+        histogram.max_us = histogram.max_us.max(us);
+        histogram.buckets[bucket] += 1;
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, LatencyHistogram> {
+        self.0.lock().unwrap().clone()
+    }
+}
@@ -1,10 +1,9 @@
 use kameo::{Actor, message::Message};
-use std::{
-    collections::HashMap,
-    marker::PhantomData,
-    time::{Duration, Instant},
+use std::{collections::HashMap, marker::PhantomData, time::Duration};
+use tokio::{
+    task::AbortHandle,
+    time::{self, Instant},
 };
-use tokio::time;
 use uuid::Uuid;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -36,10 +35,21 @@ impl<K> From<Uuid> for Ticket<K> {
 
 pub struct Timeout(pub Uuid);
 
+/// How long past its deadline an entry is still accepted, to cover a
+/// `Timeout` that is queued but not yet handled.
+pub const DEFAULT_SLACK: Duration = Duration::from_secs(5);
+
 pub struct PendingMeta<K> {
     pub kind: K,
     pub sent: Instant,
     pub t_out: Duration,
+    timer: AbortHandle,
+}
+
+impl<K> PendingMeta<K> {
+    fn is_expired(&self, now: Instant, slack: Duration) -> bool {
+        now > self.sent + self.t_out + slack
+    }
 }
 
 pub struct PendingTracker<A, K>
@@ -49,6 +59,7 @@ where
 {
     map: HashMap<Uuid, PendingMeta<K>>,
     actor: kameo::actor::WeakActorRef<A>,
+    slack: Duration,
 }
 
 impl<A, K> PendingTracker<A, K>
@@ -60,10 +71,17 @@ where
         Self {
             map: HashMap::new(),
             actor,
+            slack: DEFAULT_SLACK,
         }
     }
 
+    pub fn with_slack(mut self, slack: Duration) -> Self {
+        self.slack = slack;
+        self
+    }
+
     pub fn add(&mut self, kind: K, dur: Duration) -> Ticket<K> {
+        self.purge_expired();
         let id = Uuid::new_v4();
         self.map.insert(
             id,
@@ -71,25 +89,81 @@ where
                 kind,
                 sent: Instant::now(),
                 t_out: dur,
+                timer: spawn_timer(self.actor.clone(), id, dur),
             },
         );
 
-        let a = self.actor.clone();
-        tokio::spawn(async move {
-            time::sleep(dur).await;
-            if let Some(r) = a.upgrade() {
-                r.tell(Timeout(id)).await.ok();
-            }
-        });
-
         Ticket::new(id)
     }
 
+    /// Pushes the deadline out by `extra` and returns the time now left.
+    pub fn extend(&mut self, ticket: Ticket<K>, extra: Duration) -> Option<Duration> {
+        let meta = self.map.get_mut(&ticket.id)?;
+        meta.t_out += extra;
+        let remaining = (meta.sent + meta.t_out).saturating_duration_since(Instant::now());
+
+        meta.timer.abort();
+        meta.timer = spawn_timer(self.actor.clone(), ticket.id, remaining);
+        Some(remaining)
+    }
+
+    /// Looks up a live entry; one past its deadline plus the slack is
+    /// treated as gone.
+    pub fn get(&self, ticket: Ticket<K>) -> Option<&PendingMeta<K>> {
+        self.map
+            .get(&ticket.id)
+            .filter(|meta| !meta.is_expired(Instant::now(), self.slack))
+    }
+
+    /// Removes the entry, refusing one whose deadline has passed by more
+    /// than the slack.
     pub fn take(&mut self, ticket: Ticket<K>) -> Option<PendingMeta<K>> {
-        self.map.remove(&ticket.id)
+        let meta = self.map.remove(&ticket.id)?;
+        meta.timer.abort();
+        (!meta.is_expired(Instant::now(), self.slack)).then_some(meta)
     }
 
+    /// Drops the entry and returns whether it was still live.
     pub fn cancel(&mut self, ticket: Ticket<K>) -> bool {
-        self.map.remove(&ticket.id).is_some()
+        self.take(ticket).is_some()
+    }
+
+    /// Drops entries whose `Timeout` never arrived and returns how many.
+    pub fn purge_expired(&mut self) -> usize {
+        let now = Instant::now();
+        let before = self.map.len();
+        self.map.retain(|_, meta| {
+            let expired = meta.is_expired(now, self.slack);
+            if expired {
+                meta.timer.abort();
+            }
+            !expired
+        });
+        before - self.map.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Ticket<K>, &PendingMeta<K>)> {
+        self.map.iter().map(|(id, meta)| (Ticket::new(*id), meta))
+    }
+}
+
+fn spawn_timer<A>(actor: kameo::actor::WeakActorRef<A>, id: Uuid, dur: Duration) -> AbortHandle
+where
+    A: Actor + Message<Timeout, Reply = ()>,
+{
+    tokio::spawn(async move {
+        time::sleep(dur).await;
+        if let Some(r) = actor.upgrade() {
+            r.tell(Timeout(id)).await.ok();
+        }
+    })
+    .abort_handle()
 }
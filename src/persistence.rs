@@ -0,0 +1,71 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use kameo::Reply;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::data_types::{GameRecord, GameSettings};
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// What a room needs to carry a game across a server restart.
+#[derive(Reply, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomSnapshot {
+    pub version: u32,
+    pub room_name: String,
+    pub clients: Vec<SavedClient>,
+    pub game_settings: GameSettings,
+    pub game_history: Vec<GameRecord>,
+    pub rounds_played: u64,
+    pub current_font_index: u64,
+    pub font_names: HashMap<u64, String>,
+    pub streaks: HashMap<Uuid, u32>,
+    pub streak_points: HashMap<String, u64>,
+    /// Whether a game was in progress, so the room comes back suspended.
+    pub suspended: bool,
+    /// What was left of the game's `game_time_limit`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_time_left_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedClient {
+    pub id: Uuid,
+    pub key: String,
+    pub name: String,
+}
+
+/// Writes next to `path` first so a crash mid-write never leaves a
+/// truncated snapshot behind.
+pub fn save_snapshot(path: &Path, snapshot: &RoomSnapshot) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(snapshot).map_err(|e| e.to_string())?;
+    let tmp = tmp_path(path);
+    fs::write(&tmp, json).map_err(|e| format!("{}: {e}", tmp.display()))?;
+    fs::rename(&tmp, path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Returns `None` when nothing was saved.
+pub fn load_snapshot(path: &Path) -> Result<Option<RoomSnapshot>, String> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("{}: {e}", path.display())),
+    };
+    let snapshot: RoomSnapshot = serde_json::from_slice(&data).map_err(|e| e.to_string())?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(format!("unsupported snapshot version {}", snapshot.version));
+    }
+    Ok(Some(snapshot))
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
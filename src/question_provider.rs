@@ -0,0 +1,30 @@
+use futures_util::future::BoxFuture;
+
+use crate::data_types::{ErrorCode, GameSettings, OutReqQuestion, QuestionInfo};
+
+/// A question made by a [`QuestionProvider`], as an admin's client would
+/// send it in `IN_RESP_question`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProvidedQuestion {
+    pub question: QuestionInfo,
+    pub question_svg: String,
+}
+
+/// Why a [`QuestionProvider`] has no question. Handled as an admin's
+/// `IN_RESP_questionError`: the room is told and asks again or gives up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderError {
+    pub error_code: ErrorCode,
+    pub message: String,
+}
+
+/// Makes questions inside the process embedding the server, in place of
+/// the admin's client. The request is what `OUT_REQ_question` would carry;
+/// questions are checked, timed out and retried all the same.
+pub trait QuestionProvider: Send + Sync + 'static {
+    fn next_question<'a>(
+        &'a self,
+        settings: &'a GameSettings,
+        request: &'a OutReqQuestion,
+    ) -> BoxFuture<'a, Result<ProvidedQuestion, ProviderError>>;
+}
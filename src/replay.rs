@@ -0,0 +1,66 @@
+use serde_json::Value;
+
+use crate::data_types::{GameRecord, GameReplay};
+
+/// Bumped whenever the replay schema changes. `parse_replay` keeps
+/// accepting every earlier version.
+pub const REPLAY_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Malformed(serde_json::Error),
+    UnsupportedVersion(u64),
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Malformed(e) => write!(f, "malformed replay: {e}"),
+            ReplayError::UnsupportedVersion(v) => write!(f, "unsupported replay version {v}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<serde_json::Error> for ReplayError {
+    fn from(e: serde_json::Error) -> Self {
+        ReplayError::Malformed(e)
+    }
+}
+
+impl From<&GameRecord> for GameReplay {
+    fn from(game: &GameRecord) -> Self {
+        GameReplay {
+            version: REPLAY_VERSION,
+            seed: game.game_settings.seed,
+            game_settings: game.game_settings.clone(),
+            rounds: game.rounds.clone(),
+            stop_reason: game.stop_reason,
+        }
+    }
+}
+
+/// Derives the seed for one round from the game seed (splitmix64), so
+/// clients can pick words for any round without replaying earlier ones.
+pub fn round_seed(game_seed: u64, round_index: u64) -> u64 {
+    let mut z =
+        game_seed.wrapping_add(round_index.wrapping_add(1).wrapping_mul(0x9E3779B97F4A7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+pub fn serialize_replay(replay: &GameReplay) -> Result<String, ReplayError> {
+    Ok(serde_json::to_string_pretty(replay)?)
+}
+
+pub fn parse_replay(json: &str) -> Result<GameReplay, ReplayError> {
+    let value: Value = serde_json::from_str(json)?;
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(1);
+
+    match version {
+        1 => Ok(serde_json::from_value(value)?),
+        v => Err(ReplayError::UnsupportedVersion(v)),
+    }
+}
@@ -1,96 +1,463 @@
 // #region IMPORTS
-use crate::{data_types::*, game_actor::*, pending_tracker::*, session_client_actor::*};
+#[cfg(feature = "persistence")]
+use crate::persistence::*;
+use crate::{
+    bots::*, client_sink::*, config::*, data_types::*, events::*, export::*, filter::*,
+    game_actor::*, pending_tracker::*, question_provider::*, replay::*, session_client_actor::*,
+    summary::*, tools::*, word_parts::*,
+};
 use kameo::{
-    Actor,
+    Actor, Reply,
     actor::{ActorID, ActorRef, WeakActorRef},
     error::{ActorStopReason, Infallible},
     message::{Context, Message},
 };
+use sha2::{Digest, Sha256};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    hash::{DefaultHasher, Hash, Hasher},
     ops::ControlFlow,
-    time::{Duration, Instant},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
 };
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 // #endregion
 
 // #region ACTOR
 
+pub const CHAT_RATE_LIMIT: usize = 5;
+pub const CHAT_RATE_WINDOW: Duration = Duration::from_secs(5);
+const CHAT_HISTORY_LIMIT: usize = 50;
+pub const ANNOUNCEMENT_RATE_LIMIT: usize = 1;
+pub const ANNOUNCEMENT_RATE_WINDOW: Duration = Duration::from_secs(10);
+const GAME_HISTORY_LIMIT: usize = 10;
+/// Question images kept for `IN_REQ_fetchAsset`: the current round's and
+/// the one before.
+const ASSET_LIMIT: usize = 2;
+const ROOM_EVENT_LIMIT: usize = 500;
+const QUESTION_TIMEOUT_RANGE: std::ops::RangeInclusive<u64> = 2..=60;
+const MAX_START_COUNTDOWN: u64 = 30;
+/// Seconds an adaptive round may be held to at either end.
+const ADAPTIVE_DURATION_RANGE: std::ops::RangeInclusive<u64> = 1..=600;
+const MAX_ADAPTIVE_STEP_MS: u64 = 60_000;
+const MAX_ROUND_EXTENSION: u64 = 120;
+const GAME_TIME_LIMIT_RANGE: std::ops::RangeInclusive<u64> = 1..=24 * 60 * 60;
+const MAX_STREAK_BONUS: u32 = 10;
+const MAX_QUESTION_RETRIES: u32 = 3;
+const REACTION_EMOJI: &[&str] = &["👏", "😭", "😂", "😮", "🔥", "👍"];
+const MAX_HANDICAP_EXTRA_MS: u64 = 30_000;
+const HANDICAP_MULTIPLIER_RANGE: std::ops::RangeInclusive<f64> = 0.5..=3.0;
+const MAX_SPECTATOR_DELAY_MS: u64 = 5 * 60_000;
+
 #[derive(Copy, Clone, PartialEq)]
 enum RoomPending {
-    Question { uuid: Uuid },
+    /// `uuid` is the admin asked, `None` for the embedded provider.
+    Question {
+        uuid: Option<Uuid>,
+        question_type: QuestionType,
+    },
+    PrefetchQuestion {
+        uuid: Option<Uuid>,
+        question_type: QuestionType,
+    },
+    Countdown {
+        remaining: u64,
+    },
     Round,
+    /// Extra time for handicapped players still to answer once the round
+    /// itself is over.
+    RoundGrace,
+    RoundWarning,
+    ChatPurge,
+    /// `game_time_limit` is up.
+    GameClock,
+    /// Spectators are due what was held back from them.
+    SpectatorDelay,
+}
+
+/// Where a room's game stands. Every lifecycle request is checked against
+/// it and moves it on in the same handler, so two requests racing through
+/// the mailbox can't both act on the same phase.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GamePhase {
+    Idle,
+    /// Counting down to the first question.
+    Countdown,
+    /// Waiting on the provider for the question to ask next.
+    AwaitingQuestion,
+    /// The round's clock is running.
+    RoundActive,
+    /// The round's own time is up, handicapped players still have theirs.
+    Intermission,
+    /// A game restored from disk that waits for the admin to resume it.
+    Paused,
+}
+
+/// A lifecycle request, for [`GamePhase::check`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GameAction {
+    Start,
+    Stop,
+    Resume,
+    Answer,
+    ExtendRound,
+}
+
+impl GamePhase {
+    /// Whether a game is under way; a paused one is not until resumed.
+    pub fn is_running(self) -> bool {
+        !matches!(self, GamePhase::Idle | GamePhase::Paused)
+    }
+
+    /// The status a request gets when this phase turns it down.
+    pub fn check(self, action: GameAction) -> Result<(), &'static str> {
+        use {GameAction::*, GamePhase::*};
+        match (action, self) {
+            (Start, Idle | Paused)
+            | (Stop, Countdown | AwaitingQuestion | RoundActive | Intermission)
+            | (Resume, Paused)
+            | (Answer, RoundActive | Intermission)
+            | (ExtendRound, RoundActive) => Ok(()),
+            (Start, _) => Err("already running"),
+            (Resume, _) => Err("no suspended game"),
+            (Stop, Idle) => Err("not running"),
+            (_, Paused) => Err("game suspended"),
+            (_, Countdown) => Err("countdown running"),
+            (ExtendRound, Intermission) => Err("time is up"),
+            _ => Err("no active round"),
+        }
+    }
 }
 
 pub struct RoomActor {
-    #[allow(dead_code)]
+    /// Identifies the room in the room list.
+    id: Uuid,
     name: String,
     clients: HashMap<Uuid, RoomClient>,
     game_settings: GameSettings,
+    room_config: RoomConfig,
     game: WeakActorRef<GameActor>,
+    config: Arc<ServerConfig>,
+    events: EventSender,
+    word_filter: Arc<WordFilter>,
+    word_parts: Arc<WordPartIndex>,
+    /// Asked for questions instead of the admin's client when the
+    /// embedding app registered one.
+    embedded_provider: Option<Arc<dyn QuestionProvider>>,
+    /// The room itself, for provider tasks to answer to.
+    myself: WeakActorRef<Self>,
+    moderation_action: ModerationAction,
 
     current_question: Option<QuestionInfo>,
+    current_question_id: Option<Uuid>,
+    current_question_svg: Option<String>,
+    /// Recent question images by asset id, oldest first.
+    assets: VecDeque<(String, String)>,
     current_answers: Vec<AnswerInfo>,
+    /// Last sequence number given to an answer this round.
+    answer_sequence: u32,
+    /// Players the current round waits on, taken when its question went out.
+    expected_answers: HashSet<Uuid>,
+    /// The admin asked for questions. Unset while there is a single admin,
+    /// who is then the provider.
+    question_provider: Option<Uuid>,
+    /// The admin who supplied the current question, when they sit it out.
+    sitting_out: Option<Uuid>,
+    /// Who takes over when the last admin leaves, if still here; otherwise
+    /// the player who joined first does.
+    admin_successor: Option<Uuid>,
+    current_reactions: BTreeMap<String, u32>,
+    next_question: Option<NextQuestion>,
+    /// Hashes of the words asked so far this game.
+    used_words: HashSet<u64>,
 
-    is_game_running: bool,
+    phase: GamePhase,
+    question_ticket: Option<Ticket<RoomPending>>,
+    prefetch_ticket: Option<Ticket<RoomPending>>,
+    /// Questions turned away for their frequency or word part, for the
+    /// current and the prefetched round.
+    question_retries: u32,
+    prefetch_retries: u32,
+    /// The admin's client found no words within the frequency bounds, so
+    /// the rest of the game is asked for without them.
+    relax_frequency: bool,
+    countdown_ticket: Option<Ticket<RoomPending>>,
+    game_clock_ticket: Option<Ticket<RoomPending>>,
+    /// When `game_time_limit` runs out.
+    game_deadline: Option<tokio::time::Instant>,
+    /// What was left of `game_time_limit` when a suspended game was saved.
+    game_time_left: Option<Duration>,
+    /// The game ends when the round under way does.
+    time_up: bool,
     round_ticket: Option<Ticket<RoomPending>>,
+    grace_ticket: Option<Ticket<RoomPending>>,
+    warning_tickets: Vec<Ticket<RoomPending>>,
+    /// Notifications held back from spectators, by the ticket that
+    /// releases them.
+    delayed_sends: HashMap<Uuid, HeldBack>,
     pending: PendingTracker<Self, RoomPending>,
-    round_start: Option<Instant>,
+    round_start: Option<tokio::time::Instant>,
+    round_limit: Duration,
     rounds_played: u64,
+    /// Rounds in a row that ended without an answer.
+    empty_rounds: u32,
+    /// When a player last answered, or the game started.
+    last_activity: tokio::time::Instant,
+    current_font_index: u64,
+    font_names: HashMap<u64, String>,
+    streaks: HashMap<Uuid, u32>,
+    streak_points: HashMap<String, u64>,
+    games_started: u64,
+    game_history: VecDeque<GameRecord>,
+
+    chat_history: VecDeque<ChatEntry>,
+    chat_purge_ticket: Option<Ticket<RoomPending>>,
+    room_events: VecDeque<RoomEvent>,
+    reported_entry: Option<RoomListEntry>,
+    /// The last `seq` given to a broadcast. Atomic only so broadcasting
+    /// can stay `&self`.
+    notif_seq: AtomicU64,
 }
 
 impl Actor for RoomActor {
-    type Args = (String, WeakActorRef<GameActor>);
+    type Args = (
+        String,
+        WeakActorRef<GameActor>,
+        Arc<ServerConfig>,
+        EventSender,
+        Arc<WordFilter>,
+        Arc<WordPartIndex>,
+        Option<Arc<dyn QuestionProvider>>,
+    );
     type Error = Infallible;
 
-    async fn on_start((name, game): Self::Args, ar: ActorRef<Self>) -> Result<Self, Self::Error> {
-        Ok(Self {
+    async fn on_start(
+        (name, game, config, events, word_filter, word_parts, embedded_provider): Self::Args,
+        ar: ActorRef<Self>,
+    ) -> Result<Self, Self::Error> {
+        let mut room = Self {
+            id: Uuid::new_v4(),
             name,
             clients: HashMap::new(),
             game_settings: GameSettings::default(),
+            room_config: RoomConfig::from_server(&config),
             game,
+            moderation_action: config.moderation_action,
+            config,
+            events,
+            word_filter,
+            word_parts,
+            embedded_provider,
+            myself: ar.downgrade(),
             current_question: None,
+            current_question_id: None,
+            current_question_svg: None,
+            assets: VecDeque::new(),
             current_answers: Vec::new(),
-            is_game_running: false,
+            answer_sequence: 0,
+            expected_answers: HashSet::new(),
+            question_provider: None,
+            sitting_out: None,
+            admin_successor: None,
+            current_reactions: BTreeMap::new(),
+            next_question: None,
+            used_words: HashSet::new(),
+            phase: GamePhase::Idle,
+            question_ticket: None,
+            prefetch_ticket: None,
+            question_retries: 0,
+            prefetch_retries: 0,
+            relax_frequency: false,
+            countdown_ticket: None,
+            game_clock_ticket: None,
+            game_deadline: None,
+            game_time_left: None,
+            time_up: false,
             round_ticket: None,
+            grace_ticket: None,
+            warning_tickets: Vec::new(),
+            delayed_sends: HashMap::new(),
             pending: PendingTracker::new(ar.downgrade()),
             round_start: None,
+            round_limit: Duration::ZERO,
             rounds_played: 0,
-        })
+            empty_rounds: 0,
+            last_activity: tokio::time::Instant::now(),
+            current_font_index: 0,
+            font_names: HashMap::new(),
+            streaks: HashMap::new(),
+            streak_points: HashMap::new(),
+            games_started: 0,
+            game_history: VecDeque::new(),
+            chat_history: VecDeque::new(),
+            room_events: VecDeque::new(),
+            chat_purge_ticket: None,
+            reported_entry: None,
+            notif_seq: AtomicU64::new(0),
+        };
+        room.report_status().await;
+        Ok(room)
     }
 
     async fn on_link_died(
         &mut self,
         _ar: WeakActorRef<Self>,
         id: ActorID,
-        _reason: ActorStopReason,
+        reason: ActorStopReason,
     ) -> Result<ControlFlow<ActorStopReason>, Self::Error> {
+        // without the game actor nobody can register or rejoin, so the room
+        // stops and its links take the sessions down with it
+        if id == self.game.id() {
+            if !matches!(reason, ActorStopReason::Normal) {
+                error!("game actor died: {reason:?}");
+                self.notif_server_error("internal error").await;
+            }
+            return Ok(ControlFlow::Break(ActorStopReason::LinkDied {
+                id,
+                reason: Box::new(reason),
+            }));
+        }
+
         if let Some(uuid) = self
             .clients
             .iter()
             .find(|(_, c)| c.session.id() == id)
             .map(|(u, _)| *u)
         {
-            self.clients.remove(&uuid);
-            self.notif_client_disconnected(uuid).await;
-
-            if let Some((&new_admin_uuid, _)) = self.clients.iter().next() {
-                if !self.clients[&new_admin_uuid].room_info.is_admin {
-                    self.clients
-                        .get_mut(&new_admin_uuid)
-                        .unwrap()
-                        .room_info
-                        .is_admin = true;
-                    self.notif_admin_made(new_admin_uuid).await;
-                }
-            }
+            self.remove_client(uuid, DisconnectReason::ConnectionLost)
+                .await;
         }
         Ok(ControlFlow::Continue(()))
     }
 }
 
 impl RoomActor {
+    /// The designated successor, or else the player who has been here the
+    /// longest. Spectators and bots never inherit the room.
+    fn next_admin(&self) -> Option<Uuid> {
+        let eligible = |c: &RoomClient| !c.room_info.is_spectator && !c.info.is_bot;
+        self.admin_successor
+            .filter(|uuid| self.clients.get(uuid).is_some_and(eligible))
+            .or_else(|| {
+                self.clients
+                    .iter()
+                    .filter(|(_, c)| eligible(c))
+                    .min_by_key(|(_, c)| c.joined_at)
+                    .map(|(&uuid, _)| uuid)
+            })
+    }
+
+    /// Drops a client from the room and repairs whatever depended on it:
+    /// the game, the admin seat and the questions asked of it.
+    async fn remove_client(&mut self, uuid: Uuid, reason: DisconnectReason) {
+        self.clients.remove(&uuid);
+        self.notif_client_disconnected(uuid, reason).await;
+        self.log_event(RoomEventKind::ClientLeft {
+            id: uuid.to_string(),
+            reason,
+        });
+
+        if self.clients.is_empty() {
+            self.room_config.join_password = None;
+        }
+
+        // bots play on only while someone is there to watch them
+        let humans_left = self
+            .clients
+            .values()
+            .any(|c| !c.room_info.is_spectator && !c.info.is_bot);
+        if self.phase.is_running() && !humans_left {
+            warn!("no players left – aborting game");
+            self.end_game(GameStopReason::AbortedEmptyRoom).await;
+        }
+
+        // co-admins carry on; only the last admin leaving promotes someone
+        if self.admin_successor == Some(uuid) {
+            self.admin_successor = None;
+        }
+        let has_admin = self.clients.values().any(|c| c.room_info.is_admin);
+        if !has_admin && let Some(new_admin_uuid) = self.next_admin() {
+            self.admin_successor = None;
+            if let Some(client) = self.clients.get_mut(&new_admin_uuid) {
+                client.room_info.is_admin = true;
+            }
+            self.notif_admin_made(new_admin_uuid).await;
+        }
+        if self.question_provider == Some(uuid) {
+            self.question_provider = None;
+            // a lone admin, promoted or not, is the provider without saying
+            if has_admin {
+                self.replace_provider().await;
+            }
+        }
+        self.reassign_questions(uuid).await;
+
+        // the round may have been waiting on nobody but this player
+        self.cancel_delayed_sends(Some(uuid));
+        if self.expected_answers.remove(&uuid) && self.can_end_round_early() {
+            self.end_round_early().await;
+        }
+
+        self.report_status().await;
+    }
+
+    /// Asks the current provider again for the questions `uuid` was asked
+    /// for, once it left or stopped being the provider.
+    async fn reassign_questions(&mut self, uuid: Uuid) {
+        let prefetch_lost = self.prefetch_ticket.is_some_and(|ticket| {
+            self.pending.get(ticket).is_some_and(
+                |m| matches!(m.kind, RoomPending::PrefetchQuestion { uuid: u, .. } if u == Some(uuid)),
+            )
+        });
+        if prefetch_lost {
+            self.cancel_prefetch();
+            if self.phase.is_running() && self.current_question.is_some() {
+                self.request_prefetch().await;
+            }
+        }
+
+        // between rounds the game waits on this question, so ask the new
+        // admin right away instead of letting the request time out
+        let question_lost = self.question_ticket.is_some_and(|ticket| {
+            self.pending.get(ticket).is_some_and(
+                |m| matches!(m.kind, RoomPending::Question { uuid: u, .. } if u == Some(uuid)),
+            )
+        });
+        if question_lost {
+            if let Some(ticket) = self.question_ticket.take() {
+                self.pending.cancel(ticket);
+            }
+            if self.phase.is_running() {
+                self.request_question().await;
+            }
+        }
+    }
+
+    /// The admin questions are asked of: the designated one, or any admin
+    /// when that one is gone.
+    fn provider(&self) -> Option<Uuid> {
+        let is_admin = |uuid: &Uuid| self.clients.get(uuid).is_some_and(|c| c.room_info.is_admin);
+        self.question_provider.filter(is_admin).or_else(|| {
+            self.clients
+                .iter()
+                .find(|(_, c)| c.room_info.is_admin)
+                .map(|(&uuid, _)| uuid)
+        })
+    }
+
+    /// Hands the questions to another admin once the designated provider
+    /// is gone, and tells the room.
+    async fn replace_provider(&mut self) {
+        self.question_provider = self.provider();
+        if let Some(uuid) = self.question_provider {
+            self.notif_provider_changed(uuid).await;
+        }
+    }
+
     fn find_client(
         &self,
         session_id: ActorID,
@@ -101,10 +468,210 @@ impl RoomActor {
             .map(|(&uuid, c)| (uuid, c.room_info, c.session.clone()))
     }
 
-    async fn broadcast(&self, ws: TransportMsg) {
-        for RoomClient { session, .. } in self.clients.values() {
-            session.tell(SendWs(ws.clone())).await.ok();
+    /// Sends to every client subscribed to the message's category.
+    async fn broadcast(&self, mut ws: TransportMsg) {
+        set_seq(&mut ws, self.next_seq());
+        for client in self.clients.values().filter(|c| c.wants(&ws)) {
+            client.sink.send(ws.clone()).await;
+        }
+    }
+
+    /// Broadcasts only to clients that opted into `capability`.
+    async fn broadcast_capable(&self, capability: Capability, mut ws: TransportMsg) {
+        set_seq(&mut ws, self.next_seq());
+        let capable = self
+            .clients
+            .values()
+            .filter(|c| c.info.capabilities.contains(&capability) && c.wants(&ws));
+        for RoomClient { sink, .. } in capable {
+            sink.send(ws.clone()).await;
+        }
+    }
+
+    /// Broadcasts to the players now and to spectators once
+    /// `spectator_delay_ms` has passed.
+    async fn broadcast_ahead_of_spectators(&mut self, mut ws: TransportMsg) {
+        set_seq(&mut ws, self.next_seq());
+        let mut held = Vec::new();
+        for (&uuid, client) in self.clients.iter().filter(|(_, c)| c.wants(&ws)) {
+            if self.is_delayed(client) {
+                held.push((uuid, ws.clone()));
+            } else {
+                client.sink.send(ws.clone()).await;
+            }
+        }
+        self.hold_back(held, true);
+    }
+
+    fn is_delayed(&self, client: &RoomClient) -> bool {
+        client.room_info.is_spectator && self.game_settings.spectator_delay_ms > 0
+    }
+
+    /// Holds back copies of one notification, `numbered` when it is a
+    /// broadcast that gets a `seq`.
+    fn hold_back(&mut self, sends: Vec<(Uuid, TransportMsg)>, numbered: bool) {
+        if sends.is_empty() {
+            return;
+        }
+        let delay = Duration::from_millis(self.game_settings.spectator_delay_ms);
+        let ticket = self.pending.add(RoomPending::SpectatorDelay, delay);
+        self.delayed_sends
+            .insert(ticket.into(), HeldBack { numbered, sends });
+    }
+
+    fn next_seq(&self) -> u64 {
+        self.notif_seq.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Sends through the client's sink, or straight to the session when it
+    /// is not in the room.
+    async fn send_to(&self, session: &ActorRef<SessionClientActor>, ws: TransportMsg) {
+        match self
+            .clients
+            .values()
+            .find(|c| c.session.id() == session.id())
+        {
+            Some(client) => {
+                client.sink.send(ws).await;
+            }
+            None => {
+                session.tell(SendWs(ws)).await.ok();
+            }
+        }
+    }
+
+    fn client_info(&self, uuid: Uuid) -> Option<ClientInfo> {
+        let client = self.clients.get(&uuid)?;
+        Some(ClientInfo {
+            id: uuid.to_string(),
+            player_id: client.info.player_id.to_string(),
+            key: client.info.key.clone(),
+            name: client.info.name.clone(),
+            is_admin: client.room_info.is_admin,
+            is_spectator: client.room_info.is_spectator,
+            is_ready: client.room_info.is_ready,
+            is_muted: client.room_info.is_muted,
+            handicap: client.handicap,
+            is_bot: client.info.is_bot,
+        })
+    }
+
+    fn player_count(&self) -> usize {
+        self.clients
+            .values()
+            .filter(|c| !c.room_info.is_spectator)
+            .count()
+    }
+
+    /// Returns the sanitized message to broadcast.
+    fn check_chat(&mut self, uuid: Uuid, message: &str) -> Result<String, &'static str> {
+        let Some(client) = self.clients.get_mut(&uuid) else {
+            return Err("client not found");
+        };
+        if client.room_info.is_muted {
+            return Err("muted");
+        }
+        if self.phase.is_running() && self.game_settings.practice_mode {
+            return Err("practice mode");
         }
+        let message = sanitize_message(message, self.config.chat_max_length)?;
+        let message = self
+            .word_filter
+            .moderate(self.moderation_action, &message)?;
+        if !client.chat_limiter.check() {
+            return Err("rate limited");
+        }
+        Ok(message)
+    }
+
+    /// Returns the sanitized announcement to broadcast.
+    fn check_announcement(&mut self, uuid: Uuid, message: &str) -> Result<String, &'static str> {
+        let Some(client) = self.clients.get_mut(&uuid) else {
+            return Err("client not found");
+        };
+        if !client.room_info.is_admin {
+            return Err("not admin");
+        }
+        let message = sanitize_message(message, self.config.chat_max_length)?;
+        if !client.announcement_limiter.check() {
+            return Err("rate limited");
+        }
+        Ok(message)
+    }
+
+    /// Counts a reaction on a chat message or round still in history.
+    fn add_reaction(
+        &mut self,
+        uuid: Uuid,
+        target: ReactionTarget,
+        emoji: &str,
+    ) -> Result<(), &'static str> {
+        let Some(client) = self.clients.get(&uuid) else {
+            return Err("client not found");
+        };
+        if client.room_info.is_muted {
+            return Err("muted");
+        }
+        if !REACTION_EMOJI.contains(&emoji) {
+            return Err("emoji not allowed");
+        }
+
+        let reactions = match target.kind {
+            ReactionTargetKind::Chat => self
+                .chat_history
+                .iter_mut()
+                .find(|e| e.message_id == target.id)
+                .map(|e| &mut e.reactions),
+            ReactionTargetKind::Round if self.current_question_id == Some(target.id) => {
+                Some(&mut self.current_reactions)
+            }
+            ReactionTargetKind::Round => self
+                .game_history
+                .iter_mut()
+                .flat_map(|g| g.rounds.iter_mut())
+                .find(|r| r.question_id == Some(target.id))
+                .map(|r| &mut r.reactions),
+        };
+        let Some(reactions) = reactions else {
+            return Err("unknown target");
+        };
+
+        let Some(client) = self.clients.get_mut(&uuid) else {
+            return Err("client not found");
+        };
+        if !client.reaction_limiter.check() {
+            return Err("rate limited");
+        }
+        *reactions.entry(emoji.to_string()).or_default() += 1;
+        Ok(())
+    }
+
+    /// Appends to the audit log, dropping the oldest entry past the limit,
+    /// and mirrors it to the tracing log.
+    fn log_event(&mut self, kind: RoomEventKind) {
+        info!(room = %self.name, event = ?kind, "room event");
+        self.room_events.push_back(RoomEvent {
+            at: unix_millis(),
+            kind,
+        });
+        if self.room_events.len() > ROOM_EVENT_LIMIT {
+            self.room_events.pop_front();
+        }
+    }
+
+    fn push_chat_history(&mut self, entry: ChatEntry) {
+        self.chat_history.push_back(entry);
+        if self.chat_history.len() > CHAT_HISTORY_LIMIT {
+            self.chat_history.pop_front();
+        }
+
+        if let Some(ticket) = self.chat_purge_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+        self.chat_purge_ticket = Some(
+            self.pending
+                .add(RoomPending::ChatPurge, self.config.chat_idle_purge),
+        );
     }
 
     async fn reply_status(
@@ -113,41 +680,129 @@ impl RoomActor {
         correlation_id: Uuid,
         status: &str,
     ) {
-        session
-            .tell(SendWs(TransportMsg::OutRespStatus(TransportEnvelope {
-                correlation_id,
-                payload: OutRespStatus {
-                    status: status.to_string(),
-                },
-            })))
-            .await
-            .ok();
+        let ws = TransportMsg::OutRespStatus(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload: OutRespStatus {
+                status: status.to_string(),
+                sequence: None,
+            },
+        });
+        self.send_to(session, ws).await;
+    }
+
+    /// Numbers an accepted answer and tells the player which number it got.
+    async fn reply_accepted(
+        &mut self,
+        session: &ActorRef<SessionClientActor>,
+        correlation_id: Uuid,
+    ) -> u32 {
+        self.answer_sequence += 1;
+        let ws = TransportMsg::OutRespStatus(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload: OutRespStatus {
+                status: "success".into(),
+                sequence: Some(self.answer_sequence),
+            },
+        });
+        self.send_to(session, ws).await;
+        self.answer_sequence
+    }
+
+    fn list_entry(&self) -> RoomListEntry {
+        let spectators = self
+            .clients
+            .values()
+            .filter(|c| c.room_info.is_spectator)
+            .count();
+        RoomListEntry {
+            id: self.id.to_string(),
+            name: self.name.clone(),
+            player_count: (self.clients.len() - spectators) as u64,
+            spectator_count: spectators as u64,
+            max_clients: self.room_config.max_clients.map(|max| max as u64),
+            is_game_running: self.phase.is_running(),
+            has_password: self.room_config.join_password.is_some(),
+        }
+    }
+
+    /// Tells the game how the room looks from the lobby, if that changed
+    /// since the last report.
+    async fn report_status(&mut self) {
+        let entry = self.list_entry();
+        if self.reported_entry.as_ref() == Some(&entry) {
+            return;
+        }
+        self.reported_entry = Some(entry.clone());
+        if let Some(game) = self.game.upgrade() {
+            game.tell(RoomStatusChanged(entry)).await.ok();
+        }
+    }
+
+    async fn notify_game(&self, activity: RoomActivity) {
+        match self.game.upgrade() {
+            Some(game) => {
+                game.tell(activity).await.ok();
+            }
+            None => warn!("game actor gone, dropping room activity"),
+        }
+    }
+
+    async fn notif_server_error(&self, message: &str) {
+        let notif = TransportMsg::OutNotifServerError(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifServerError {
+                message: message.to_string(),
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_serverError");
+    }
+
+    fn emit(&self, event: ServerEvent) {
+        // no receivers is fine, the host may not be listening
+        let _ = self.events.send(event);
     }
 
     async fn notif_client_registered(&self, client_info: ClientInfo) {
         let ws = TransportMsg::OutNotifClientRegistered(TransportEnvelope {
             correlation_id: Uuid::new_v4(),
+            seq: None,
             payload: OutNotifClientRegistered {
-                client: client_info,
+                client: client_info.clone(),
             },
         });
         self.broadcast(ws).await;
         debug!("OUT_NOTIF_clientRegistered");
+        self.emit(ServerEvent::ClientRegistered {
+            client: client_info,
+        });
     }
 
-    async fn notif_client_disconnected(&self, uuid: Uuid) {
+    async fn notif_client_disconnected(&self, uuid: Uuid, reason: DisconnectReason) {
         let ws = TransportMsg::OutNotifClientDisconnected(TransportEnvelope {
             correlation_id: Uuid::new_v4(),
+            seq: None,
             payload: OutNotifClientDisconnected {
                 id: uuid.to_string(),
+                reason,
             },
         });
         self.broadcast(ws).await;
         debug!("OUT_NOTIF_clientDisconnected");
+        self.emit(ServerEvent::ClientDisconnected {
+            id: uuid.to_string(),
+        });
     }
-    async fn notif_admin_made(&self, uuid: Uuid) {
+    async fn notif_admin_made(&mut self, uuid: Uuid) {
+        self.log_event(RoomEventKind::AdminMade {
+            id: uuid.to_string(),
+        });
         let ws = TransportMsg::OutNotifAdminMade(TransportEnvelope {
             correlation_id: Uuid::new_v4(),
+            seq: None,
             payload: OutNotifAdminMade {
                 id: uuid.to_string(),
             },
@@ -156,254 +811,3396 @@ impl RoomActor {
         debug!("OUT_NOTIF_adminMade");
     }
 
+    async fn notif_admin_revoked(&mut self, uuid: Uuid) {
+        self.log_event(RoomEventKind::AdminRevoked {
+            id: uuid.to_string(),
+        });
+        let ws = TransportMsg::OutNotifAdminRevoked(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifAdminRevoked {
+                id: uuid.to_string(),
+            },
+        });
+        self.broadcast(ws).await;
+        debug!("OUT_NOTIF_adminRevoked");
+    }
+
+    async fn notif_provider_changed(&self, uuid: Uuid) {
+        let ws = TransportMsg::OutNotifQuestionProviderChanged(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifQuestionProviderChanged {
+                id: uuid.to_string(),
+            },
+        });
+        self.broadcast(ws).await;
+        debug!("OUT_NOTIF_questionProviderChanged");
+    }
+
+    async fn notif_ready_changed(&self, uuid: Uuid, ready: bool) {
+        let ws = TransportMsg::OutNotifReadyChanged(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifReadyChanged {
+                id: uuid.to_string(),
+                ready,
+            },
+        });
+        self.broadcast(ws).await;
+        debug!("OUT_NOTIF_readyChanged");
+    }
+
+    /// Validates and takes on new settings, then tells the room.
+    async fn update_settings(
+        &mut self,
+        requester: &ActorRef<SessionClientActor>,
+        correlation_id: Uuid,
+        mut game_settings: GameSettings,
+    ) {
+        if let Err(status) = resolve_tier(&mut game_settings, &self.config.difficulty_tiers) {
+            self.reply_status(requester, correlation_id, &status).await;
+            return;
+        }
+        if let Err(status) = validate_settings(&game_settings) {
+            self.reply_status(requester, correlation_id, status).await;
+            return;
+        }
+        if let Err(status) = self.word_parts.check(&game_settings) {
+            self.reply_status(requester, correlation_id, &status).await;
+            return;
+        }
+
+        self.game_settings = game_settings.clone();
+        self.reply_status(requester, correlation_id, "success")
+            .await;
+        if let Some((uuid, ..)) = self.find_client(requester.id()) {
+            self.log_event(RoomEventKind::SettingsChanged {
+                by: uuid.to_string(),
+            });
+        }
+
+        let notif = TransportMsg::OutNotifGameSettingsChanged(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifGameSettingsChanged { game_settings },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_gameSettingsChanged");
+
+        self.reset_ready().await;
+    }
+
+    async fn reset_ready(&mut self) {
+        let mut reset = Vec::new();
+        for (&uuid, client) in self.clients.iter_mut() {
+            if client.room_info.is_ready {
+                client.room_info.is_ready = false;
+                reset.push(uuid);
+            }
+        }
+        for uuid in reset {
+            self.notif_ready_changed(uuid, false).await;
+        }
+    }
+
     async fn finish_round(&mut self) {
-        if !self.is_game_running {
+        if !matches!(self.phase, GamePhase::RoundActive | GamePhase::Intermission) {
             return;
         }
 
         self.push_missing_answers();
+        self.record_round();
+        self.update_streaks().await;
+        if self
+            .current_answers
+            .iter()
+            .all(|a| a.kind == AnswerKind::TimedOut)
+        {
+            self.empty_rounds += 1;
+        }
+        let mut near_misses = self.near_misses();
+        self.send_answer_feedback(&near_misses).await;
+        if !self.game_settings.share_near_misses {
+            near_misses.clear();
+        }
 
         self.rounds_played += 1;
+        if let Some(next) = self.font_index(1) {
+            self.current_font_index = next;
+        }
 
-        if self.rounds_played >= self.game_settings.rounds_count {
-            self.is_game_running = false;
-
-            let stop_notif = TransportMsg::OutNotifGameStopped(TransportEnvelope {
-                correlation_id: Uuid::new_v4(),
-                payload: OutNotifGameStopped {
-                    question: self.current_question.clone().unwrap_or_default(),
-                    answers: self.current_answers.clone(),
-                },
-            });
-            self.broadcast(stop_notif).await;
-            debug!("OUT_NOTIF_gameStopped");
+        if self.is_last_round_played() {
+            self.end_game(GameStopReason::Completed).await;
+            return;
+        }
+        if self.time_up {
+            self.end_game(GameStopReason::TimeLimitReached).await;
+            return;
+        }
 
-            self.current_question = None;
-            self.current_answers.clear();
-            self.round_ticket = None;
-            self.round_start = None;
+        let abandoned_after = self.config.abandoned_after_rounds;
+        if abandoned_after > 0 && self.empty_rounds >= abandoned_after {
+            warn!(
+                "no answers for {} rounds – stopping game",
+                self.empty_rounds
+            );
+            self.end_game(GameStopReason::Abandoned).await;
             return;
         }
 
+        let accepted_readings = self.accepted_readings();
         let notif = TransportMsg::OutNotifRoundEnded(TransportEnvelope {
             correlation_id: Uuid::new_v4(),
+            seq: None,
             payload: OutNotifRoundEnded {
                 question: self.current_question.clone().unwrap_or_default(),
                 answers: self.current_answers.clone(),
+                primary_reading: accepted_readings.first().cloned().unwrap_or_default(),
+                accepted_readings,
+                streaks: self.round_streaks(),
+                near_misses,
+                game_id: self.game_id(),
             },
         });
         self.broadcast(notif).await;
         debug!("OUT_NOTIF_roundEnded");
+        if abandoned_after > 0 && self.empty_rounds + 1 == abandoned_after {
+            self.notif_idle_warning().await;
+        }
+        self.emit(ServerEvent::RoundEnded {
+            question: self.current_question.clone().unwrap_or_default(),
+            answers: self.current_answers.clone(),
+        });
 
         self.current_question = None;
+        self.current_question_id = None;
+        self.current_question_svg = None;
         self.current_answers.clear();
         self.round_ticket = None;
+        self.grace_ticket = None;
+        self.cancel_round_warnings();
         self.round_start = None;
 
-        self.request_question().await;
+        if let Some(NextQuestion { id, info, svg }) = self.next_question.take() {
+            self.start_round(id, info, svg).await;
+        } else {
+            self.cancel_prefetch();
+            self.request_question().await;
+        }
     }
 
-    async fn request_question(&mut self) {
-        let Some((&admin_uuid, admin)) = self.clients.iter().find(|(_, c)| c.room_info.is_admin)
-        else {
-            warn!("no admin left – stopping game");
-            self.is_game_running = false;
-            return;
+    async fn start_round(
+        &mut self,
+        question_id: Uuid,
+        question_info: QuestionInfo,
+        question_svg: String,
+    ) {
+        self.round_limit = match &self.game_settings.adaptive_duration {
+            Some(adaptive) => adaptive.round_duration(&question_info),
+            None => Duration::from_secs(self.game_settings.round_duration),
         };
+        self.used_words
+            .insert(word_hash(&question_info.word_info.word));
+        self.current_question = Some(question_info);
+        self.current_question_id = Some(question_id);
+        self.current_question_svg = Some(question_svg.clone());
+        self.current_answers.clear();
+        self.answer_sequence = 0;
+        self.current_reactions.clear();
+        self.expected_answers = self
+            .clients
+            .iter()
+            .filter(|(_, c)| !c.room_info.is_spectator)
+            .map(|(&uuid, _)| uuid)
+            .collect();
+        self.sitting_out = self
+            .provider()
+            .filter(|_| self.embedded_provider.is_none() && !self.game_settings.admin_plays);
+        if let Some(uuid) = self.sitting_out {
+            self.expected_answers.remove(&uuid);
+        }
 
-        let corr_id = self.pending.add(
-            RoomPending::Question { uuid: admin_uuid },
-            Duration::from_secs(5),
-        );
+        // each client gets its own copy, numbered as one broadcast
+        let asset_id = self.store_asset(&question_svg);
+        let seq = self.next_seq();
+        let mut held = Vec::new();
+        for (&uuid, client) in &self.clients {
+            let mut notif =
+                self.question_notif(client, question_id, &question_svg, &asset_id, None);
+            if !client.wants(&notif) {
+                continue;
+            }
+            set_seq(&mut notif, seq);
+            if self.is_delayed(client) {
+                held.push((uuid, notif));
+            } else {
+                client.sink.send(notif).await;
+            }
+        }
+        self.hold_back(held, true);
+        debug!("OUT_NOTIF_question");
 
-        let req = TransportMsg::OutReqQuestion(TransportEnvelope {
-            correlation_id: corr_id.into(),
-            payload: OutReqQuestion {},
-        });
-        admin.session.tell(SendWs(req)).await.ok();
-        debug!("OUT_REQ_question");
-    }
+        self.round_start = Some(tokio::time::Instant::now());
 
-    fn push_missing_answers(&mut self) {
-        let answered: std::collections::HashSet<String> =
-            self.current_answers.iter().map(|a| a.id.clone()).collect();
+        let ticket = self.pending.add(RoomPending::Round, self.round_limit);
+        self.round_ticket = Some(ticket);
+        self.phase = GamePhase::RoundActive;
+        self.arm_round_warnings();
 
-        let max_time = self.game_settings.round_duration * 1_000;
+        if self.is_endless() || self.rounds_played + 1 < self.game_settings.rounds_count {
+            self.request_prefetch().await;
+        }
+    }
+
+    /// Validates `game_settings` and starts a fresh game with them.
+    async fn start_game(
+        &mut self,
+        requester: &ActorRef<SessionClientActor>,
+        correlation_id: Uuid,
+        mut game_settings: GameSettings,
+        rematch: bool,
+    ) {
+        if let Err(status) = self.phase.check(GameAction::Start) {
+            self.reply_status(requester, correlation_id, status).await;
+            warn!("{status}");
+            return;
+        }
+
+        if let Err(status) = resolve_tier(&mut game_settings, &self.config.difficulty_tiers) {
+            self.reply_status(requester, correlation_id, &status).await;
+            warn!("{status}");
+            return;
+        }
+        if let Err(status) = validate_settings(&game_settings) {
+            self.reply_status(requester, correlation_id, status).await;
+            warn!("{status}");
+            return;
+        }
+        if let Err(status) = self.word_parts.check(&game_settings) {
+            self.reply_status(requester, correlation_id, &status).await;
+            warn!("{status}");
+            return;
+        }
+
+        if game_settings.require_all_ready {
+            let unready: Vec<String> = self
+                .clients
+                .iter()
+                .filter(|(_, c)| !c.room_info.is_spectator && !c.room_info.is_ready)
+                .map(|(uuid, _)| uuid.to_string())
+                .collect();
+            if !unready.is_empty() {
+                let status = format!("not ready: {}", unready.join(","));
+                self.reply_status(requester, correlation_id, &status).await;
+                warn!("{status}");
+                return;
+            }
+        }
+
+        self.current_question = None;
+        self.current_question_id = None;
+        self.current_question_svg = None;
+        self.current_answers.clear();
+        if let Some(ticket) = self.round_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+        self.game_settings = game_settings.clone();
+        self.phase = GamePhase::Countdown;
+        self.question_retries = 0;
+        self.prefetch_retries = 0;
+        self.relax_frequency = false;
+        self.used_words.clear();
+        self.current_font_index = 0;
+        self.font_names.clear();
+        self.streaks.clear();
+        self.streak_points.clear();
+        if let Some(first_font_name) = &game_settings.first_font_name {
+            self.font_names.insert(0, first_font_name.clone());
+        }
+        self.rounds_played = 0;
+        self.empty_rounds = 0;
+        self.last_activity = tokio::time::Instant::now();
+
+        let game_index = self.game_history.back().map_or(0, |g| g.game_index + 1);
+        if self.game_history.len() >= GAME_HISTORY_LIMIT {
+            self.game_history.pop_front();
+        }
+        self.game_history.push_back(GameRecord {
+            game_id: Uuid::new_v4(),
+            game_settings: game_settings.clone(),
+            rounds: Vec::new(),
+            stop_reason: None,
+            game_index,
+            started_at: unix_millis(),
+            ended_at: None,
+            player_names: BTreeMap::new(),
+            handicaps: BTreeMap::new(),
+        });
+
+        self.time_up = false;
+        self.game_time_left = None;
+        let deadline = game_settings
+            .game_time_limit
+            .map(|limit| self.arm_game_clock(Duration::from_secs(limit)));
+
+        self.reply_status(requester, correlation_id, "success")
+            .await;
+        if let Some((uuid, ..)) = self.find_client(requester.id()) {
+            self.log_event(RoomEventKind::GameStarted {
+                by: uuid.to_string(),
+            });
+        }
+
+        let notif = TransportMsg::OutNotifGameStarted(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifGameStarted {
+                game_settings: game_settings.clone(),
+                rematch,
+                game_index,
+                game_id: self.game_id(),
+                deadline,
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_gameStarted");
+        self.emit(ServerEvent::GameStarted { game_settings });
+        self.games_started += 1;
+        self.notify_game(RoomActivity::GameStarted).await;
+        self.report_status().await;
+
+        self.countdown_tick(self.game_settings.start_countdown)
+            .await;
+    }
+
+    /// Keeps `svg` for fetching by its asset id, dropping the oldest past
+    /// [`ASSET_LIMIT`].
+    fn store_asset(&mut self, svg: &str) -> String {
+        let id = asset_id(svg);
+        self.assets.retain(|(stored, _)| *stored != id);
+        self.assets.push_back((id.clone(), svg.to_string()));
+        while self.assets.len() > ASSET_LIMIT {
+            self.assets.pop_front();
+        }
+        id
+    }
+
+    /// The question as `client` takes it: inline, or by asset id for a
+    /// client that fetches images itself.
+    fn question_notif(
+        &self,
+        client: &RoomClient,
+        question_id: Uuid,
+        svg: &str,
+        asset_id: &str,
+        remaining_ms: Option<u64>,
+    ) -> TransportMsg {
+        let by_asset = client.info.capabilities.contains(&Capability::Assets);
+        TransportMsg::OutNotifQuestion(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifQuestion {
+                question_svg: if by_asset {
+                    String::new()
+                } else {
+                    svg.to_string()
+                },
+                question_id,
+                remaining_ms,
+                game_id: self.game_id(),
+                round_duration_ms: Some(self.round_limit.as_millis() as u64),
+                asset_id: by_asset.then(|| asset_id.to_string()),
+                asset_size: by_asset.then_some(svg.len() as u64),
+            },
+        })
+    }
+
+    /// Starts the whole-game clock, returning its deadline in Unix
+    /// milliseconds.
+    fn arm_game_clock(&mut self, limit: Duration) -> u64 {
+        if let Some(ticket) = self.game_clock_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+        self.game_clock_ticket = Some(self.pending.add(RoomPending::GameClock, limit));
+        self.game_deadline = Some(tokio::time::Instant::now() + limit);
+        unix_millis() + limit.as_millis() as u64
+    }
+
+    /// Drops what is held back for `uuid`, or for everyone.
+    fn cancel_delayed_sends(&mut self, uuid: Option<Uuid>) {
+        let mut emptied = Vec::new();
+        for (&ticket, held) in &mut self.delayed_sends {
+            held.sends
+                .retain(|(target, _)| uuid.is_some_and(|uuid| uuid != *target));
+            if held.sends.is_empty() {
+                emptied.push(ticket);
+            }
+        }
+        for ticket in emptied {
+            self.delayed_sends.remove(&ticket);
+            self.pending.cancel(ticket.into());
+        }
+    }
+
+    fn cancel_game_clock(&mut self) {
+        if let Some(ticket) = self.game_clock_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+        self.game_deadline = None;
+        self.game_time_left = None;
+    }
+
+    async fn game_clock_expired(&mut self) {
+        if !self.phase.is_running() {
+            return;
+        }
+        let in_round = matches!(self.phase, GamePhase::RoundActive | GamePhase::Intermission);
+        if in_round && !self.game_settings.cut_round_at_time_limit {
+            debug!("game time is up, finishing the round");
+            self.time_up = true;
+            return;
+        }
+        self.end_game(GameStopReason::TimeLimitReached).await;
+    }
+
+    /// Schedules a warning for every checkpoint still ahead in the round,
+    /// replacing any scheduled before.
+    fn arm_round_warnings(&mut self) {
+        self.cancel_round_warnings();
+        let Some(remaining) = self.round_remaining() else {
+            return;
+        };
+        for &checkpoint in &self.config.round_time_warnings {
+            if checkpoint < remaining {
+                let ticket = self
+                    .pending
+                    .add(RoomPending::RoundWarning, remaining - checkpoint);
+                self.warning_tickets.push(ticket);
+            }
+        }
+    }
+
+    fn cancel_round_warnings(&mut self) {
+        for ticket in self.warning_tickets.drain(..) {
+            self.pending.cancel(ticket);
+        }
+    }
+
+    async fn notif_round_time_warning(&self, remaining: Duration) {
+        let notif = TransportMsg::OutNotifRoundTimeWarning(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifRoundTimeWarning {
+                remaining_ms: remaining.as_millis() as u64,
+                game_id: self.game_id(),
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_roundTimeWarning");
+    }
+
+    fn round_remaining(&self) -> Option<Duration> {
+        let meta = self.pending.get(self.round_ticket?)?;
+        Some((meta.sent + meta.t_out).saturating_duration_since(tokio::time::Instant::now()))
+    }
+
+    fn countdown_remaining(&self) -> Option<Duration> {
+        let meta = self.pending.get(self.countdown_ticket?)?;
+        let RoomPending::Countdown { remaining } = meta.kind else {
+            return None;
+        };
+        let tick = (meta.sent + meta.t_out).saturating_duration_since(tokio::time::Instant::now());
+        Some(tick + Duration::from_secs(remaining))
+    }
+
+    /// The longest extra time among players with a handicap who have not
+    /// answered yet.
+    fn handicap_grace(&self) -> Option<Duration> {
+        self.expected_answers
+            .iter()
+            .filter(|&&uuid| !self.has_answered(uuid))
+            .filter_map(|uuid| self.clients.get(uuid)?.handicap)
+            .map(|h| h.extra_time_ms)
+            .max()
+            .filter(|&ms| ms > 0)
+            .map(Duration::from_millis)
+    }
+
+    /// Tells everyone that `uuid` answered, with the round's counts when
+    /// `live_answer_stats` is on. Nothing is sent in practice mode.
+    async fn broadcast_answered(&mut self, uuid: Uuid) {
+        if self.game_settings.practice_mode {
+            return;
+        }
+        let mut payload = OutNotifClientAnswered {
+            id: uuid.to_string(),
+            answered_count: None,
+            correct_count: None,
+            total_players: None,
+            game_id: self.game_id(),
+        };
+        if self.game_settings.live_answer_stats {
+            // late joiners may answer too, but aren't among the players
+            let counted: Vec<_> = self
+                .current_answers
+                .iter()
+                .filter(|a| {
+                    a.id.parse()
+                        .is_ok_and(|id| self.expected_answers.contains(&id))
+                })
+                .collect();
+            payload.answered_count = Some(counted.len() as u64);
+            payload.correct_count = Some(counted.iter().filter(|a| a.is_correct).count() as u64);
+            payload.total_players = Some(self.expected_answers.len() as u64);
+        }
+        let notif = TransportMsg::OutNotifClientAnswered(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload,
+        });
+        self.broadcast_ahead_of_spectators(notif).await;
+        debug!("OUT_NOTIF_clientAnswered");
+    }
+
+    fn has_answered(&self, uuid: Uuid) -> bool {
+        let id = uuid.to_string();
+        self.current_answers.iter().any(|a| a.id == id)
+    }
+
+    /// Whether the running round has nobody left to wait for: every expected
+    /// player answered, or, past the round's own time, every handicapped one.
+    fn can_end_round_early(&self) -> bool {
+        if self.round_ticket.is_none() && self.grace_ticket.is_none() {
+            return false;
+        }
+        let all_answered = self.game_settings.end_round_when_all_answered
+            && self
+                .expected_answers
+                .iter()
+                .all(|&uuid| self.has_answered(uuid));
+        let grace_done = self.grace_ticket.is_some() && self.handicap_grace().is_none();
+        all_answered || grace_done
+    }
+
+    async fn end_round_early(&mut self) {
+        if let Some(ticket) = self.round_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+        if let Some(ticket) = self.grace_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+        self.finish_round().await;
+    }
+
+    fn is_endless(&self) -> bool {
+        self.game_settings.practice_mode && self.game_settings.rounds_count == 0
+    }
+
+    fn is_last_round_played(&self) -> bool {
+        !self.is_endless() && self.rounds_played >= self.game_settings.rounds_count
+    }
+
+    fn cancel_prefetch(&mut self) {
+        if let Some(ticket) = self.prefetch_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+    }
+
+    async fn end_game(&mut self, reason: GameStopReason) {
+        if let Some(ticket) = self.round_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+        if let Some(ticket) = self.grace_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+        self.cancel_round_warnings();
+        if let Some(ticket) = self.question_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+        if let Some(ticket) = self.countdown_ticket.take() {
+            self.pending.cancel(ticket);
+        }
+        self.cancel_prefetch();
+        self.cancel_game_clock();
+        self.cancel_delayed_sends(None);
+        self.next_question = None;
+        self.log_event(RoomEventKind::GameStopped { reason });
+
+        let final_scores = self.final_scores();
+        let winner_id = match final_scores.as_slice() {
+            [first, second, ..] if first.score == second.score => None,
+            [first, ..] if first.score > 0 => Some(first.id.clone()),
+            _ => None,
+        };
+        let rounds = self.game_history.back().map(|g| g.rounds.as_slice());
+        let player_summaries = player_summaries(rounds.unwrap_or_default(), |id| {
+            self.clients.keys().any(|uuid| uuid.to_string() == id)
+        });
+        let tier_summaries = tier_summaries(rounds.unwrap_or_default());
+
+        let accepted_readings = self.accepted_readings();
+        let notif = TransportMsg::OutNotifGameStopped(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifGameStopped {
+                question: self.current_question.clone().unwrap_or_default(),
+                answers: self.current_answers.clone(),
+                reason,
+                final_scores: final_scores.clone(),
+                winner_id: winner_id.clone(),
+                player_summaries,
+                primary_reading: accepted_readings.first().cloned().unwrap_or_default(),
+                accepted_readings,
+                tier_summaries,
+                game_id: self.game_id(),
+                elapsed_ms: self
+                    .game_history
+                    .back()
+                    .map_or(0, |g| unix_millis().saturating_sub(g.started_at)),
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_gameStopped");
+        self.emit(ServerEvent::GameStopped {
+            reason,
+            final_scores,
+            winner_id,
+        });
+        self.notify_game(RoomActivity::GameStopped(reason)).await;
+        self.report_status().await;
+
+        if let Some(game) = self.game_history.back_mut() {
+            game.stop_reason = Some(reason);
+            game.ended_at = Some(unix_millis());
+        }
+
+        self.phase = GamePhase::Idle;
+        self.current_question = None;
+        self.current_question_id = None;
+        self.current_question_svg = None;
+        self.current_answers.clear();
+        self.round_start = None;
+        self.rounds_played = 0;
+
+        self.reset_ready().await;
+    }
+
+    /// The running game's id, or the last game's between games.
+    fn game_id(&self) -> Option<Uuid> {
+        self.game_history.back().map(|g| g.game_id)
+    }
+
+    async fn notif_idle_warning(&self) {
+        let notif = TransportMsg::OutNotifIdleWarning(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifIdleWarning {
+                empty_rounds: self.empty_rounds,
+                rounds_left: self.config.abandoned_after_rounds - self.empty_rounds,
+                idle_ms: self.last_activity.elapsed().as_millis() as u64,
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_idleWarning");
+    }
+
+    /// Right answers, and near misses when the game counts them.
+    fn is_accepted(&self, question: &QuestionInfo, answer: &str) -> bool {
+        is_correct_answer(question, answer)
+            || self.game_settings.accept_near_miss.is_some_and(|max| {
+                closest_reading(question, answer).is_some_and(|(_, distance)| distance <= max)
+            })
+    }
+
+    /// How close each wrong answer of the round came.
+    fn near_misses(&self) -> Vec<NearMiss> {
+        let Some(question) = &self.current_question else {
+            return Vec::new();
+        };
+        self.current_answers
+            .iter()
+            .filter(|a| !a.is_correct && a.kind == AnswerKind::Answered)
+            .filter_map(|a| {
+                let (closest_reading, distance) = closest_reading(question, &a.answer)?;
+                Some(NearMiss {
+                    id: a.id.clone(),
+                    closest_reading,
+                    distance,
+                })
+            })
+            .collect()
+    }
+
+    /// Tells each player privately how close their wrong answer was.
+    async fn send_answer_feedback(&self, near_misses: &[NearMiss]) {
+        for near_miss in near_misses {
+            let answer = self.current_answers.iter().find(|a| a.id == near_miss.id);
+            let client = near_miss
+                .id
+                .parse()
+                .ok()
+                .and_then(|uuid: Uuid| self.clients.get(&uuid));
+            let (Some(answer), Some(client)) = (answer, client) else {
+                continue;
+            };
+            let notif = TransportMsg::OutNotifAnswerFeedback(TransportEnvelope {
+                correlation_id: Uuid::new_v4(),
+                seq: None,
+                payload: OutNotifAnswerFeedback {
+                    answer: answer.answer.clone(),
+                    closest_reading: near_miss.closest_reading.clone(),
+                    distance: near_miss.distance,
+                },
+            });
+            client.sink.send(notif).await;
+        }
+        if !near_misses.is_empty() {
+            debug!("OUT_NOTIF_answerFeedback");
+        }
+    }
+
+    fn accepted_readings(&self) -> Vec<String> {
+        self.current_question
+            .as_ref()
+            .map(accepted_readings)
+            .unwrap_or_default()
+    }
+
+    fn final_scores(&self) -> Vec<PlayerScore> {
+        let mut correct: HashMap<String, u64> = HashMap::new();
+        let game = self.game_history.back();
+        for round in game.map(|g| g.rounds.as_slice()).unwrap_or_default() {
+            for answer in &round.answers {
+                *correct.entry(answer.id.clone()).or_default() += u64::from(answer.is_correct);
+            }
+        }
+        let mut scores: HashMap<String, u64> = correct
+            .into_iter()
+            .map(|(id, correct)| {
+                let multiplier = game
+                    .and_then(|g| g.handicaps.get(&id))
+                    .map_or(1.0, |h| h.score_multiplier);
+                let score = (correct as f64 * multiplier).round() as u64;
+                (id, score)
+            })
+            .collect();
+        for (id, points) in &self.streak_points {
+            *scores.entry(id.clone()).or_default() += points;
+        }
+
+        let mut scores: Vec<PlayerScore> = scores
+            .into_iter()
+            .map(|(id, score)| PlayerScore { id, score })
+            .collect();
+        scores.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+        scores
+    }
+
+    /// Advances streaks from the round just played. Streaks follow the
+    /// player rather than the session, so a reconnect only loses the streak
+    /// when a round ended while the player was away.
+    async fn update_streaks(&mut self) {
+        let player_id = |id: &str| {
+            let uuid = id.parse::<Uuid>().ok()?;
+            self.clients.get(&uuid).map(|c| c.info.player_id)
+        };
+        let correct: Vec<(String, Uuid)> = self
+            .current_answers
+            .iter()
+            .filter(|a| a.is_correct)
+            .filter_map(|a| Some((a.id.clone(), player_id(&a.id)?)))
+            .collect();
+
+        self.streaks
+            .retain(|player_id, _| correct.iter().any(|(_, p)| p == player_id));
+
+        let bonus_from = self.config.streak_milestones.iter().min().copied();
+        let mut reached = Vec::new();
+        for (id, player_id) in correct {
+            let streak = self.streaks.entry(player_id).or_default();
+            *streak += 1;
+
+            if let Some(bonus) = self.game_settings.streak_bonus
+                && bonus_from.is_some_and(|from| *streak >= from)
+            {
+                *self.streak_points.entry(id.clone()).or_default() += u64::from(bonus - 1);
+            }
+            if self.config.streak_milestones.contains(streak) {
+                reached.push((id, *streak));
+            }
+        }
+
+        for (id, length) in reached {
+            let notif = TransportMsg::OutNotifStreak(TransportEnvelope {
+                correlation_id: Uuid::new_v4(),
+                seq: None,
+                payload: OutNotifStreak { id, length },
+            });
+            self.broadcast_capable(Capability::Streaks, notif).await;
+            debug!("OUT_NOTIF_streak");
+        }
+    }
+
+    fn round_streaks(&self) -> Vec<PlayerStreak> {
+        self.current_answers
+            .iter()
+            .map(|answer| {
+                let streak = answer
+                    .id
+                    .parse::<Uuid>()
+                    .ok()
+                    .and_then(|uuid| self.clients.get(&uuid))
+                    .and_then(|c| self.streaks.get(&c.info.player_id))
+                    .copied()
+                    .unwrap_or_default();
+                PlayerStreak {
+                    id: answer.id.clone(),
+                    streak,
+                }
+            })
+            .collect()
+    }
+
+    fn max_frequency(&self) -> Option<u64> {
+        self.game_settings
+            .using_max_frequency
+            .then_some(self.game_settings.max_frequency)
+    }
+
+    fn room_info(&self) -> RoomInfo {
+        let spectator_count = self
+            .clients
+            .values()
+            .filter(|c| c.room_info.is_spectator)
+            .count();
+        let current_round = if self.phase.is_running() {
+            self.rounds_played + 1
+        } else {
+            0
+        };
+        RoomInfo {
+            name: self.name.clone(),
+            client_count: self.clients.len() as u64,
+            spectator_count: spectator_count as u64,
+            is_game_running: self.phase.is_running(),
+            current_round,
+            game_settings: self.game_settings.clone(),
+            chat_max_length: self.config.chat_max_length as u64,
+            spectator_delay_ms: self.game_settings.spectator_delay_ms,
+        }
+    }
+
+    /// Frequency bounds to ask the admin's client for.
+    fn frequency_bounds(&self) -> (u64, Option<u64>) {
+        if self.relax_frequency {
+            (0, None)
+        } else {
+            (self.game_settings.min_frequency, self.max_frequency())
+        }
+    }
+
+    fn question_tier(&self, question: &QuestionInfo) -> Option<String> {
+        let frequency = question.frequency?;
+        self.config
+            .difficulty_tiers
+            .iter()
+            .find(|tier| tier.contains(frequency))
+            .map(|tier| tier.name.clone())
+    }
+
+    fn record_round(&mut self) {
+        let Some(question) = self.current_question.clone() else {
+            return;
+        };
+        let tier = self.question_tier(&question);
+        if let Some(game) = self.game_history.back_mut() {
+            for answer in &self.current_answers {
+                let name = self
+                    .clients
+                    .iter()
+                    .find(|(uuid, _)| uuid.to_string() == answer.id)
+                    .map(|(_, c)| c.info.name.clone());
+                if let Some(name) = name {
+                    game.player_names.insert(answer.id.clone(), name);
+                }
+                let handicap = answer
+                    .id
+                    .parse::<Uuid>()
+                    .ok()
+                    .and_then(|uuid| self.clients.get(&uuid)?.handicap);
+                match handicap {
+                    Some(handicap) => game.handicaps.insert(answer.id.clone(), handicap),
+                    None => game.handicaps.remove(&answer.id),
+                };
+            }
+            game.rounds.push(RoundRecord {
+                question,
+                answers: self.current_answers.clone(),
+                aborted: false,
+                question_id: self.current_question_id,
+                reactions: std::mem::take(&mut self.current_reactions),
+                tier,
+            });
+        }
+    }
+
+    async fn request_question(&mut self) {
+        let asked = if self.embedded_provider.is_some() {
+            None
+        } else {
+            let Some(admin_uuid) = self.provider() else {
+                warn!("no admin left – stopping game");
+                self.end_game(GameStopReason::AbortedNoAdmin).await;
+                return;
+            };
+            Some(admin_uuid)
+        };
+
+        self.phase = GamePhase::AwaitingQuestion;
+        let question_type = self.question_type(self.rounds_played);
+        let (min_frequency, max_frequency) = self.frequency_bounds();
+        let corr_id = self.pending.add(
+            RoomPending::Question {
+                uuid: asked,
+                question_type,
+            },
+            Duration::from_secs(self.game_settings.question_timeout),
+        );
+        self.question_ticket = Some(corr_id);
+
+        let request = OutReqQuestion {
+            round_seed: self.round_seed(self.rounds_played),
+            font_index: self.font_index(0),
+            font_name: self.font_name(0),
+            question_type,
+            min_frequency,
+            max_frequency,
+            used_word_count: self.used_words.len() as u64,
+        };
+        self.send_question_request(asked, corr_id.into(), request)
+            .await;
+        debug!("OUT_REQ_question");
+    }
+
+    /// Hands `request` to the admin `asked`, or to the embedded provider,
+    /// whose answer comes back as a [`ProviderQuestion`].
+    async fn send_question_request(
+        &self,
+        asked: Option<Uuid>,
+        correlation_id: Uuid,
+        request: OutReqQuestion,
+    ) {
+        if let Some(admin_uuid) = asked {
+            if let Some(admin) = self.clients.get(&admin_uuid) {
+                let req = TransportMsg::OutReqQuestion(TransportEnvelope {
+                    correlation_id,
+                    seq: None,
+                    payload: request,
+                });
+                admin.sink.send(req).await;
+            }
+            return;
+        }
+
+        let Some(provider) = self.embedded_provider.clone() else {
+            return;
+        };
+        let settings = self.game_settings.clone();
+        let room = self.myself.clone();
+        tokio::spawn(async move {
+            let result = provider.next_question(&settings, &request).await;
+            if let Some(room) = room.upgrade() {
+                room.tell(ProviderQuestion {
+                    correlation_id,
+                    result,
+                })
+                .await
+                .ok();
+            }
+        });
+    }
+
+    async fn countdown_tick(&mut self, seconds_remaining: u64) {
+        if seconds_remaining == 0 {
+            self.request_question().await;
+            return;
+        }
+
+        self.phase = GamePhase::Countdown;
+        let notif = TransportMsg::OutNotifCountdown(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifCountdown { seconds_remaining },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_countdown {seconds_remaining}");
+
+        let ticket = self.pending.add(
+            RoomPending::Countdown {
+                remaining: seconds_remaining - 1,
+            },
+            Duration::from_secs(1),
+        );
+        self.countdown_ticket = Some(ticket);
+    }
+
+    async fn request_prefetch(&mut self) {
+        let asked = if self.embedded_provider.is_some() {
+            None
+        } else {
+            let Some(admin_uuid) = self.provider() else {
+                return;
+            };
+            Some(admin_uuid)
+        };
+
+        let question_type = self.question_type(self.rounds_played + 1);
+        let (min_frequency, max_frequency) = self.frequency_bounds();
+        let corr_id = self.pending.add(
+            RoomPending::PrefetchQuestion {
+                uuid: asked,
+                question_type,
+            },
+            Duration::from_secs(self.game_settings.question_timeout),
+        );
+        self.prefetch_ticket = Some(corr_id);
+
+        let request = OutReqQuestion {
+            round_seed: self.round_seed(self.rounds_played + 1),
+            font_index: self.font_index(1),
+            font_name: self.font_name(1),
+            question_type,
+            min_frequency,
+            max_frequency,
+            used_word_count: self.used_words.len() as u64,
+        };
+        self.send_question_request(asked, corr_id.into(), request)
+            .await;
+        debug!("OUT_REQ_question (prefetch)");
+    }
+
+    /// How many rounds ahead the question asked for under `correlation_id`
+    /// is, and of what type, if `requester` is the one asked; `None` stands
+    /// for the embedded provider.
+    fn pending_question(
+        &self,
+        requester: Option<&ActorRef<SessionClientActor>>,
+        correlation_id: Uuid,
+    ) -> Option<(u64, QuestionType)> {
+        let is_from = |uuid: Option<Uuid>| match (uuid, requester) {
+            (Some(uuid), Some(requester)) => self
+                .clients
+                .get(&uuid)
+                .is_some_and(|c| c.session.id() == requester.id()),
+            (None, None) => true,
+            _ => false,
+        };
+        // a provider that left has had its request handed to the new admin
+        // already, so its answer is only dropped
+        match self.pending.get(correlation_id.into())?.kind {
+            RoomPending::Question {
+                uuid,
+                question_type,
+            } if is_from(uuid) => Some((0, question_type)),
+            RoomPending::PrefetchQuestion {
+                uuid,
+                question_type,
+            } if is_from(uuid) => Some((1, question_type)),
+            _ => None,
+        }
+    }
+
+    /// Checks a question that came in for the request `correlation_id`,
+    /// `rounds_ahead` of the current round, and starts the round with it or
+    /// keeps it for the next. `requester` is the admin who sent it, `None`
+    /// for the embedded provider.
+    async fn accept_question(
+        &mut self,
+        requester: Option<&ActorRef<SessionClientActor>>,
+        correlation_id: Uuid,
+        (rounds_ahead, question_type): (u64, QuestionType),
+        mut question_info: QuestionInfo,
+        question_svg: String,
+    ) {
+        // answers are checked against what was asked for, whatever the
+        // admin's client put in the question
+        question_info.question_type = question_type;
+        let retries = match rounds_ahead {
+            0 => self.question_retries,
+            _ => self.prefetch_retries,
+        };
+
+        // a broken admin client must not stall the game, so once the retries
+        // run out the question goes through with a warning
+        if let Err(status) = self.check_font(rounds_ahead, &question_info.font_name) {
+            if retries < MAX_QUESTION_RETRIES {
+                self.reply_question_status(requester, correlation_id, status)
+                    .await;
+                warn!("{status}: {}", question_info.font_name);
+                self.retry_question(correlation_id, rounds_ahead).await;
+                return;
+            }
+            warn!("{status}, let through: {}", question_info.font_name);
+        }
+
+        if !has_word_part(&self.game_settings, &question_info) {
+            if retries < MAX_QUESTION_RETRIES {
+                self.reply_question_status(requester, correlation_id, "word part missing")
+                    .await;
+                warn!("word part missing: {}", question_info.word_info.word);
+                self.retry_question(correlation_id, rounds_ahead).await;
+                return;
+            }
+            warn!(
+                "word part missing, let through: {}",
+                question_info.word_info.word
+            );
+        }
+
+        if !self.game_settings.allow_repeat_words
+            && self
+                .used_words
+                .contains(&word_hash(&question_info.word_info.word))
+        {
+            if retries < MAX_QUESTION_RETRIES {
+                self.reply_question_status(requester, correlation_id, "duplicate word")
+                    .await;
+                warn!("duplicate word: {}", question_info.word_info.word);
+                self.retry_question(correlation_id, rounds_ahead).await;
+                return;
+            }
+            warn!(
+                "duplicate word, let through: {}",
+                question_info.word_info.word
+            );
+        }
+
+        if let Some(frequency) = question_info.frequency
+            && !self.relax_frequency
+            && !is_frequency_allowed(&self.game_settings, frequency)
+        {
+            if self.game_settings.enforce_frequency && retries < MAX_QUESTION_RETRIES {
+                self.reply_question_status(requester, correlation_id, "frequency out of range")
+                    .await;
+                warn!("frequency out of range: {frequency}");
+                self.retry_question(correlation_id, rounds_ahead).await;
+                return;
+            }
+
+            let notif = TransportMsg::OutNotifFrequencyWarning(TransportEnvelope {
+                correlation_id: Uuid::new_v4(),
+                seq: None,
+                payload: OutNotifFrequencyWarning {
+                    frequency,
+                    min_frequency: self.game_settings.min_frequency,
+                    max_frequency: self.max_frequency(),
+                },
+            });
+            self.broadcast(notif).await;
+            debug!("OUT_NOTIF_frequencyWarning {frequency}");
+        }
+
+        if rounds_ahead == 0 && self.phase != GamePhase::AwaitingQuestion {
+            warn!("question arrived in {:?}, dropped", self.phase);
+            return;
+        }
+        self.pending.take(correlation_id.into());
+        self.record_font(rounds_ahead, &question_info.font_name);
+        if rounds_ahead == 0 {
+            self.question_ticket = None;
+            self.question_retries = 0;
+            self.start_round(Uuid::new_v4(), question_info, question_svg)
+                .await;
+        } else {
+            self.prefetch_ticket = None;
+            self.prefetch_retries = 0;
+            self.next_question = Some(NextQuestion {
+                id: Uuid::new_v4(),
+                info: question_info,
+                svg: question_svg,
+            });
+            debug!("next question prefetched");
+        }
+    }
+
+    async fn reply_question_status(
+        &self,
+        requester: Option<&ActorRef<SessionClientActor>>,
+        correlation_id: Uuid,
+        status: &str,
+    ) {
+        if let Some(requester) = requester {
+            self.reply_status(requester, correlation_id, status).await;
+        }
+    }
+
+    /// Tells the room no question came for `correlation_id`, then asks
+    /// again, gives up on the game, or, for a prefetch, waits for the next
+    /// round.
+    async fn question_failed(
+        &mut self,
+        correlation_id: Uuid,
+        rounds_ahead: u64,
+        error_code: ErrorCode,
+        message: String,
+    ) {
+        let retries = match rounds_ahead {
+            0 => self.question_retries,
+            _ => self.prefetch_retries,
+        };
+        let has_bounds =
+            self.game_settings.min_frequency > 0 || self.game_settings.using_max_frequency;
+        let relaxed =
+            error_code == ErrorCode::NoWordsMatchFilters && has_bounds && !self.relax_frequency;
+        // no words for the filters won't change by asking again, unless the
+        // filters do
+        let retry = match error_code {
+            ErrorCode::NoWordsMatchFilters => relaxed,
+            _ => retries < MAX_QUESTION_RETRIES,
+        };
+
+        let notif = TransportMsg::OutNotifQuestionFailed(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifQuestionFailed {
+                error_code,
+                message,
+                relaxed,
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_questionFailed");
+
+        if retry {
+            self.relax_frequency |= relaxed;
+            self.retry_question(correlation_id, rounds_ahead).await;
+        } else if rounds_ahead == 0 {
+            self.pending.take(correlation_id.into());
+            self.question_ticket = None;
+            self.end_game(GameStopReason::NoQuestions).await;
+        } else {
+            // the round after this one asks again when it comes
+            self.pending.take(correlation_id.into());
+            self.prefetch_ticket = None;
+        }
+    }
+
+    /// Drops a turned away question and asks for another in its place.
+    async fn retry_question(&mut self, correlation_id: Uuid, rounds_ahead: u64) {
+        self.pending.take(correlation_id.into());
+        if rounds_ahead == 0 {
+            self.question_ticket = None;
+            self.question_retries += 1;
+            self.request_question().await;
+        } else {
+            self.prefetch_ticket = None;
+            self.prefetch_retries += 1;
+            self.request_prefetch().await;
+        }
+    }
+
+    /// Font rotation position `rounds_ahead` rounds after the current one.
+    fn font_index(&self, rounds_ahead: u64) -> Option<u64> {
+        let fonts_count = self.game_settings.fonts_count;
+        (fonts_count > 0).then(|| (self.current_font_index + rounds_ahead) % fonts_count)
+    }
+
+    fn font_name(&self, rounds_ahead: u64) -> Option<String> {
+        let index = self.font_index(rounds_ahead)?;
+        self.font_names.get(&index).cloned()
+    }
+
+    /// The first question accepted for each position fixes its font; later
+    /// cycles have to match it.
+    fn check_font(&self, rounds_ahead: u64, font_name: &str) -> Result<(), &'static str> {
+        match self.font_name(rounds_ahead) {
+            Some(expected) if expected != font_name => Err("wrong font"),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_font(&mut self, rounds_ahead: u64, font_name: &str) {
+        if let Some(index) = self.font_index(rounds_ahead) {
+            self.font_names
+                .entry(index)
+                .or_insert_with(|| font_name.to_string());
+        }
+    }
+
+    fn round_seed(&self, round_index: u64) -> Option<u64> {
+        self.game_settings
+            .seed
+            .map(|seed| round_seed(seed, round_index))
+    }
+
+    /// Question type for a round. Weighted picks follow the round seed, so
+    /// seeded games repeat them.
+    fn question_type(&self, round_index: u64) -> QuestionType {
+        let weights = &self.game_settings.question_type_weights;
+        let total: u64 = weights.values().map(|w| u64::from(*w)).sum();
+        if total == 0 {
+            return self.game_settings.question_type;
+        }
+
+        let roll = self
+            .round_seed(round_index)
+            .unwrap_or_else(|| Uuid::new_v4().as_u64_pair().0)
+            % total;
+        let mut upto = 0;
+        for (question_type, weight) in weights {
+            upto += u64::from(*weight);
+            if roll < upto {
+                return *question_type;
+            }
+        }
+        self.game_settings.question_type
+    }
+
+    fn push_missing_answers(&mut self) {
+        let max_time = self.round_limit.as_millis() as u64;
+
+        let missing: Vec<Uuid> = self
+            .expected_answers
+            .iter()
+            .copied()
+            .filter(|&uuid| !self.has_answered(uuid))
+            .collect();
+        for uuid in missing {
+            self.current_answers.push(AnswerInfo {
+                id: uuid.to_string(),
+                answer: String::new(),
+                is_correct: false,
+                answer_time: max_time,
+                kind: AnswerKind::TimedOut,
+                sequence: None,
+            });
+        }
+    }
+}
+
+/// Content address of a question image.
+fn asset_id(svg: &str) -> String {
+    let digest = Sha256::digest(svg.as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    Uuid::new_v8(bytes).to_string()
+}
+
+/// Who a question was asked of, for the logs.
+fn asked_name(uuid: Option<Uuid>) -> String {
+    match uuid {
+        Some(uuid) => format!("admin {uuid}"),
+        None => "question provider".into(),
+    }
+}
+
+fn word_hash(word: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    word.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether one of the question's readings is made up of the word part the
+/// settings ask for, in the reading asked for if there is one.
+fn has_word_part(settings: &GameSettings, question: &QuestionInfo) -> bool {
+    let Some(word_part) = &settings.word_part else {
+        return true;
+    };
+    let reading = settings.word_part_reading.as_deref().map(fold_kana);
+    question
+        .word_info
+        .readings
+        .iter()
+        .flat_map(|r| &r.parts)
+        .any(|p| {
+            &p.word_part == word_part
+                && reading
+                    .as_ref()
+                    .is_none_or(|r| fold_kana(&p.word_part_reading) == *r)
+        })
+}
+
+fn is_frequency_allowed(settings: &GameSettings, frequency: f64) -> bool {
+    frequency >= settings.min_frequency as f64
+        && (!settings.using_max_frequency || frequency <= settings.max_frequency as f64)
+}
+
+/// Replaces the frequency bounds with those of the tier the settings name.
+fn resolve_tier(settings: &mut GameSettings, tiers: &[DifficultyTier]) -> Result<(), String> {
+    let Some(name) = &settings.tier else {
+        return Ok(());
+    };
+    let Some(tier) = tiers.iter().find(|t| t.name.eq_ignore_ascii_case(name)) else {
+        let names: Vec<&str> = tiers.iter().map(|t| t.name.as_str()).collect();
+        return Err(format!("unknown tier: {}", names.join(",")));
+    };
+    settings.tier = Some(tier.name.clone());
+    settings.min_frequency = tier.min_frequency;
+    settings.max_frequency = tier.max_frequency.unwrap_or(0);
+    settings.using_max_frequency = tier.max_frequency.is_some();
+    Ok(())
+}
+
+fn validate_settings(settings: &GameSettings) -> Result<(), &'static str> {
+    if !QUESTION_TIMEOUT_RANGE.contains(&settings.question_timeout) {
+        return Err("invalid question timeout");
+    }
+    if settings.start_countdown > MAX_START_COUNTDOWN {
+        return Err("invalid start countdown");
+    }
+    if settings
+        .streak_bonus
+        .is_some_and(|bonus| !(1..=MAX_STREAK_BONUS).contains(&bonus))
+    {
+        return Err("invalid streak bonus");
+    }
+    if settings
+        .game_time_limit
+        .is_some_and(|limit| !GAME_TIME_LIMIT_RANGE.contains(&limit))
+    {
+        return Err("invalid game time limit");
+    }
+    if settings.spectator_delay_ms > MAX_SPECTATOR_DELAY_MS {
+        return Err("invalid spectator delay");
+    }
+    let weights = &settings.question_type_weights;
+    if !weights.is_empty() && weights.values().all(|w| *w == 0) {
+        return Err("invalid question weights");
+    }
+    if let Some(adaptive) = &settings.adaptive_duration {
+        let bounds_ok = ADAPTIVE_DURATION_RANGE.contains(&adaptive.min)
+            && ADAPTIVE_DURATION_RANGE.contains(&adaptive.max)
+            && adaptive.min <= adaptive.max
+            && adaptive.base <= adaptive.max;
+        if !bounds_ok
+            || adaptive.per_character_ms > MAX_ADAPTIVE_STEP_MS
+            || adaptive.per_rarity_step_ms > MAX_ADAPTIVE_STEP_MS
+        {
+            return Err("invalid adaptive duration");
+        }
+    }
+    Ok(())
+}
+
+// #endregion
+
+// #region TYPES
+#[derive(Debug, Clone, Copy)]
+pub struct RoomClientInfo {
+    pub is_admin: bool,
+    pub is_spectator: bool,
+    pub is_ready: bool,
+    pub is_muted: bool,
+}
+
+struct RoomClient {
+    session: ActorRef<SessionClientActor>,
+    sink: Box<dyn ClientSink>,
+    info: GameClientInfo,
+    room_info: RoomClientInfo,
+    joined_at: tokio::time::Instant,
+    chat_limiter: RateLimiter,
+    reaction_limiter: RateLimiter,
+    announcement_limiter: RateLimiter,
+    handicap: Option<Handicap>,
+    /// Categories the client gets; all of them until it says otherwise.
+    subscriptions: BTreeSet<Subscription>,
+}
+
+impl RoomClient {
+    fn wants(&self, msg: &TransportMsg) -> bool {
+        Subscription::of(msg).is_none_or(|s| self.subscriptions.contains(&s))
+    }
+}
+
+struct NextQuestion {
+    id: Uuid,
+    info: QuestionInfo,
+    svg: String,
+}
+
+/// Spectators' copies of one notification, waiting out `spectator_delay_ms`.
+struct HeldBack {
+    /// Given the next `seq` when released, so that spectators see them in
+    /// order with what was sent in the meantime.
+    numbered: bool,
+    sends: Vec<(Uuid, TransportMsg)>,
+}
+
+#[derive(Clone, Copy)]
+enum ChatKind {
+    Chat,
+    Announcement,
+}
+
+struct ChatEntry {
+    kind: ChatKind,
+    id: String,
+    message_id: Uuid,
+    message: String,
+    sent_at: u64,
+    reactions: BTreeMap<String, u32>,
+}
+
+impl ChatEntry {
+    fn to_notif(&self) -> TransportMsg {
+        let correlation_id = Uuid::new_v4();
+        match self.kind {
+            ChatKind::Chat => TransportMsg::OutNotifChatSent(TransportEnvelope {
+                correlation_id,
+                seq: None,
+                payload: OutNotifChatSent {
+                    id: self.id.clone(),
+                    message: self.message.clone(),
+                    message_id: self.message_id,
+                    reactions: self.reactions.clone(),
+                },
+            }),
+            ChatKind::Announcement => TransportMsg::OutNotifAnnouncement(TransportEnvelope {
+                correlation_id,
+                seq: None,
+                payload: OutNotifAnnouncement {
+                    id: self.id.clone(),
+                    message: self.message.clone(),
+                    sent_at: self.sent_at,
+                    message_id: self.message_id,
+                    reactions: self.reactions.clone(),
+                },
+            }),
+        }
+    }
+}
+// #endregion
+
+// #region MESSAGES
+impl Message<Timeout> for RoomActor {
+    type Reply = ();
+    async fn handle(&mut self, Timeout(id): Timeout, _ctx: &mut Context<Self, ()>) {
+        if let Some(meta) = self.pending.take(id.into()) {
+            match meta.kind {
+                RoomPending::Question { uuid, .. } => {
+                    self.question_ticket = None;
+                    warn!("{} didn't provide question in time", asked_name(uuid));
+                    // the round can't start without it, so it is asked for
+                    // again like a failed one and the game ends eventually
+                    if self.phase == GamePhase::AwaitingQuestion {
+                        let message = "no question in time".to_string();
+                        self.question_failed(id, 0, ErrorCode::TimedOut, message)
+                            .await;
+                    }
+                }
+                RoomPending::PrefetchQuestion { uuid, .. } => {
+                    self.prefetch_ticket = None;
+                    warn!("{} didn't provide next question in time", asked_name(uuid));
+                }
+                RoomPending::Countdown { remaining } => {
+                    self.countdown_ticket = None;
+                    if self.phase == GamePhase::Countdown {
+                        self.countdown_tick(remaining).await;
+                    }
+                }
+                RoomPending::Round => {
+                    self.round_ticket = None;
+                    if !self.config.round_time_warnings.is_empty() {
+                        self.notif_round_time_warning(Duration::ZERO).await;
+                    }
+                    match self.handicap_grace() {
+                        Some(grace) => {
+                            debug!("round over, waiting {grace:?} for handicapped players");
+                            self.grace_ticket =
+                                Some(self.pending.add(RoomPending::RoundGrace, grace));
+                            self.phase = GamePhase::Intermission;
+                        }
+                        None => self.finish_round().await,
+                    }
+                }
+                RoomPending::RoundGrace => {
+                    self.grace_ticket = None;
+                    self.finish_round().await;
+                }
+                RoomPending::RoundWarning => {
+                    self.warning_tickets.retain(|&t| t != id.into());
+                    if let Some(remaining) = self.round_remaining() {
+                        self.notif_round_time_warning(remaining).await;
+                    }
+                }
+                RoomPending::ChatPurge => {
+                    self.chat_purge_ticket = None;
+                    self.chat_history.clear();
+                    debug!("chat history purged after idle period");
+                }
+                RoomPending::GameClock => {
+                    self.game_clock_ticket = None;
+                    self.game_clock_expired().await;
+                }
+                RoomPending::SpectatorDelay => {
+                    let Some(held) = self.delayed_sends.remove(&id) else {
+                        return;
+                    };
+                    let seq = held.numbered.then(|| self.next_seq());
+                    for (uuid, mut msg) in held.sends {
+                        if let Some(seq) = seq {
+                            set_seq(&mut msg, seq);
+                        }
+                        if let Some(client) = self.clients.get(&uuid) {
+                            client.sink.send(msg).await;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct AddClient {
+    pub uuid: Uuid,
+    pub session: ActorRef<SessionClientActor>,
+    pub sink: Box<dyn ClientSink>,
+    pub info: GameClientInfo,
+    pub is_spectator: bool,
+    pub password: Option<Password>,
+}
+
+impl Message<AddClient> for RoomActor {
+    type Reply = Result<(), JoinRejection>;
+
+    async fn handle(
+        &mut self,
+        AddClient {
+            uuid,
+            session,
+            sink,
+            info,
+            is_spectator,
+            password,
+        }: AddClient,
+        ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        let password = password.filter(|p| !p.0.is_empty());
+        match (&self.room_config.join_password, &password) {
+            (Some(expected), Some(given))
+                if constant_time_eq(expected.0.as_bytes(), given.0.as_bytes()) => {}
+            (Some(_), _) => {
+                warn!("wrong room password from {uuid}");
+                return Err(JoinRejection::WrongPassword);
+            }
+            (None, _) => {}
+        }
+
+        if let Some(max) = self.room_config.max_clients
+            && self.clients.len() >= max
+        {
+            warn!("room full, rejecting {uuid}");
+            return Err(JoinRejection::RoomFull);
+        }
+
+        if self.phase.is_running() && self.game_settings.lock_room_during_game {
+            warn!("room locked during game, rejecting {uuid}");
+            return Err(JoinRejection::GameInProgress);
+        }
+
+        if self.clients.is_empty() && self.room_config.join_password.is_none() {
+            self.room_config.join_password = password;
+        }
+
+        let is_admin =
+            !is_spectator && !info.is_bot && !self.clients.values().any(|c| c.room_info.is_admin);
+
+        session.link(&ctx.actor_ref()).await;
+
+        self.clients.insert(
+            uuid,
+            RoomClient {
+                session: session.clone(),
+                sink,
+                info,
+                room_info: RoomClientInfo {
+                    is_admin,
+                    is_spectator,
+                    is_ready: false,
+                    is_muted: false,
+                },
+                joined_at: tokio::time::Instant::now(),
+                chat_limiter: RateLimiter::new(CHAT_RATE_LIMIT, CHAT_RATE_WINDOW),
+                reaction_limiter: RateLimiter::new(CHAT_RATE_LIMIT, CHAT_RATE_WINDOW),
+                announcement_limiter: RateLimiter::new(
+                    ANNOUNCEMENT_RATE_LIMIT,
+                    ANNOUNCEMENT_RATE_WINDOW,
+                ),
+                handicap: None,
+                subscriptions: Subscription::ALL.iter().copied().collect(),
+            },
+        );
+
+        if let Some(client_info) = self.client_info(uuid) {
+            self.log_event(RoomEventKind::ClientJoined {
+                id: client_info.id.clone(),
+                name: client_info.name.clone(),
+            });
+            self.notif_client_registered(client_info).await;
+        }
+        if is_admin {
+            self.notif_admin_made(uuid).await;
+        } else {
+            let notif = TransportMsg::OutNotifGameSettingsChanged(TransportEnvelope {
+                correlation_id: Uuid::new_v4(),
+                seq: None,
+                payload: OutNotifGameSettingsChanged {
+                    game_settings: self.game_settings.clone(),
+                },
+            });
+            self.broadcast(notif).await;
+            debug!("OUT_NOTIF_gameSettingsChanged");
+        }
+
+        let Some(client) = self.clients.get(&uuid) else {
+            error!("no client");
+            return Ok(());
+        };
+        for entry in &self.chat_history {
+            client.sink.send(entry.to_notif()).await;
+        }
+
+        if self.phase.is_running()
+            && let (Some(question_id), Some(question_svg)) =
+                (self.current_question_id, &self.current_question_svg)
+        {
+            let remaining_ms = self.round_remaining().map(|d| d.as_millis() as u64);
+            let notif = self.question_notif(
+                client,
+                question_id,
+                question_svg,
+                &asset_id(question_svg),
+                remaining_ms,
+            );
+            if self.is_delayed(client) {
+                self.hold_back(vec![(uuid, notif)], false);
+            } else {
+                client.sink.send(notif).await;
+            }
+            debug!("OUT_NOTIF_question (late join)");
+        }
+
+        self.report_status().await;
+        Ok(())
+    }
+}
+
+pub struct ClientListRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+}
+
+impl Message<ClientListRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        ClientListRequest {
+            requester,
+            correlation_id,
+        }: ClientListRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let clients: Vec<ClientInfo> = self
+            .clients
+            .keys()
+            .filter_map(|&uuid| self.client_info(uuid))
+            .collect();
+
+        let ws = TransportMsg::OutRespClientList(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload: OutRespClientList { clients },
+        });
+        self.send_to(&requester, ws).await;
+    }
+}
+
+pub struct SetGameSettingsRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub game_settings: GameSettings,
+}
+
+impl Message<SetGameSettingsRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SetGameSettingsRequest {
+            requester,
+            correlation_id,
+            game_settings,
+        }: SetGameSettingsRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        self.update_settings(&requester, correlation_id, game_settings)
+            .await;
+    }
+}
+
+pub struct PatchGameSettingsRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub patch: GameSettingsPatch,
+}
+
+impl Message<PatchGameSettingsRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        PatchGameSettingsRequest {
+            requester,
+            correlation_id,
+            patch,
+        }: PatchGameSettingsRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        let mut game_settings = self.game_settings.clone();
+        patch.apply(&mut game_settings);
+        self.update_settings(&requester, correlation_id, game_settings)
+            .await;
+    }
+}
+
+pub struct SendChatRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub message: String,
+}
+
+impl Message<SendChatRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SendChatRequest {
+            requester,
+            correlation_id,
+            message,
+        }: SendChatRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((sender_uuid, _, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        let message = match self.check_chat(sender_uuid, &message) {
+            Ok(message) => message,
+            Err(status) => {
+                self.reply_status(&requester, correlation_id, status).await;
+                return;
+            }
+        };
+
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+
+        let entry = ChatEntry {
+            kind: ChatKind::Chat,
+            id: sender_uuid.to_string(),
+            message_id: Uuid::new_v4(),
+            message,
+            sent_at: unix_millis(),
+            reactions: BTreeMap::new(),
+        };
+        self.broadcast(entry.to_notif()).await;
+        debug!("OUT_NOTIF_chatSent {}", entry.message);
+        self.emit(ServerEvent::ChatMessage {
+            id: entry.id.clone(),
+            message: entry.message.clone(),
+        });
+
+        self.push_chat_history(entry);
+    }
+}
+
+pub struct SendAnnouncementRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub message: String,
+}
+
+impl Message<SendAnnouncementRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SendAnnouncementRequest {
+            requester,
+            correlation_id,
+            message,
+        }: SendAnnouncementRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((sender_uuid, _, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        let message = match self.check_announcement(sender_uuid, &message) {
+            Ok(message) => message,
+            Err(status) => {
+                self.reply_status(&requester, correlation_id, status).await;
+                warn!("{status}");
+                return;
+            }
+        };
+
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+
+        let entry = ChatEntry {
+            kind: ChatKind::Announcement,
+            id: sender_uuid.to_string(),
+            message_id: Uuid::new_v4(),
+            message,
+            sent_at: unix_millis(),
+            reactions: BTreeMap::new(),
+        };
+        self.broadcast(entry.to_notif()).await;
+        debug!("OUT_NOTIF_announcement {}", entry.message);
+
+        self.push_chat_history(entry);
+    }
+}
+
+pub struct SendReactionRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub target: ReactionTarget,
+    pub emoji: String,
+}
+
+impl Message<SendReactionRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SendReactionRequest {
+            requester,
+            correlation_id,
+            target,
+            emoji,
+        }: SendReactionRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((sender_uuid, _, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if let Err(status) = self.add_reaction(sender_uuid, target, &emoji) {
+            self.reply_status(&requester, correlation_id, status).await;
+            warn!("{status}");
+            return;
+        }
+
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+
+        let notif = TransportMsg::OutNotifReaction(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifReaction {
+                from_id: sender_uuid.to_string(),
+                target,
+                emoji,
+            },
+        });
+        self.broadcast_capable(Capability::Reactions, notif).await;
+        debug!("OUT_NOTIF_reaction");
+    }
+}
+
+pub struct SendWhisperRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub target_id: String,
+    pub message: String,
+}
+
+impl Message<SendWhisperRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SendWhisperRequest {
+            requester,
+            correlation_id,
+            target_id,
+            message,
+        }: SendWhisperRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((sender_uuid, _, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        let Some(target) = Uuid::parse_str(&target_id)
+            .ok()
+            .filter(|uuid| self.clients.contains_key(uuid))
+        else {
+            self.reply_status(&requester, correlation_id, "client not found")
+                .await;
+            return;
+        };
+
+        let message = match self.check_chat(sender_uuid, &message) {
+            Ok(message) => message,
+            Err(status) => {
+                self.reply_status(&requester, correlation_id, status).await;
+                return;
+            }
+        };
+
+        let notif = TransportMsg::OutNotifWhisperReceived(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifWhisperReceived {
+                from_id: sender_uuid.to_string(),
+                message,
+            },
+        });
+        let delivered = match self.clients.get(&target) {
+            Some(client) => client.sink.send(notif).await,
+            None => false,
+        };
+        if !delivered {
+            self.reply_status(&requester, correlation_id, "client not found")
+                .await;
+            return;
+        }
+        debug!("OUT_NOTIF_whisperReceived");
+
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+    }
+}
+
+pub struct StartGameRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub game_settings: GameSettings,
+}
+
+impl Message<StartGameRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        StartGameRequest {
+            requester,
+            correlation_id,
+            game_settings,
+        }: StartGameRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_admin_uuid, room_info, _admin_session)) = self.find_client(requester.id())
+        else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            warn!("not admin");
+            return;
+        }
+
+        self.start_game(&requester, correlation_id, game_settings, false)
+            .await;
+    }
+}
+
+pub struct RematchRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub shuffle_settings: Option<GameSettingsOverride>,
+}
+
+impl Message<RematchRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        RematchRequest {
+            requester,
+            correlation_id,
+            shuffle_settings,
+        }: RematchRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            warn!("not admin");
+            return;
+        }
+
+        let Some(previous) = self.game_history.back() else {
+            self.reply_status(&requester, correlation_id, "no previous game")
+                .await;
+            return;
+        };
+
+        // a fixed seed would replay the exact same questions
+        let mut game_settings = GameSettings {
+            seed: None,
+            ..previous.game_settings.clone()
+        };
+        if let Some(shuffle_settings) = shuffle_settings {
+            shuffle_settings.apply(&mut game_settings);
+        }
+
+        self.start_game(&requester, correlation_id, game_settings, true)
+            .await;
+    }
+}
+
+pub struct LeaveRoomRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+}
+
+impl Message<LeaveRoomRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        LeaveRoomRequest {
+            requester,
+            correlation_id,
+        }: LeaveRoomRequest,
+        ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((uuid, _, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        requester.unlink(&ctx.actor_ref()).await;
+        self.remove_client(uuid, DisconnectReason::Left).await;
+
+        // the game has to learn the client left, or it could never rejoin
+        let Some(game) = self.game.upgrade() else {
+            error!("game actor gone, stopping room");
+            self.reply_status(&requester, correlation_id, "internal error")
+                .await;
+            self.notif_server_error("internal error").await;
+            ctx.actor_ref().kill();
+            return;
+        };
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+
+        requester.tell(LeftRoom).await.ok();
+        game.tell(ClientLeftRoom(uuid)).await.ok();
+    }
+}
+
+pub struct ResumeGameRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+}
+
+impl Message<ResumeGameRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        ResumeGameRequest {
+            requester,
+            correlation_id,
+        }: ResumeGameRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            warn!("not admin");
+            return;
+        }
+
+        if let Err(status) = self.phase.check(GameAction::Resume) {
+            self.reply_status(&requester, correlation_id, status).await;
+            warn!("{status}");
+            return;
+        }
+
+        self.phase = GamePhase::Countdown;
+        // the clock stood still while the game was suspended
+        let deadline = self
+            .game_time_left
+            .take()
+            .map(|left| self.arm_game_clock(left));
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+        self.report_status().await;
+
+        let notif = TransportMsg::OutNotifGameStarted(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifGameStarted {
+                game_settings: self.game_settings.clone(),
+                rematch: false,
+                game_index: self.game_history.back().map_or(0, |g| g.game_index),
+                game_id: self.game_id(),
+                deadline,
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_gameStarted (resumed)");
+
+        self.countdown_tick(self.game_settings.start_countdown)
+            .await;
+    }
+}
+
+pub struct ProvideQuestionResponse {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub question_info: QuestionInfo,
+    pub question_svg: String,
+}
+
+impl Message<ProvideQuestionResponse> for RoomActor {
+    type Reply = ();
+
+    async fn handle(&mut self, msg: ProvideQuestionResponse, _ctx: &mut Context<Self, ()>) {
+        let ProvideQuestionResponse {
+            requester,
+            correlation_id,
+            question_info,
+            question_svg,
+        } = msg;
+
+        let Some((rounds_ahead, question_type)) =
+            self.pending_question(Some(&requester), correlation_id)
+        else {
+            warn!("unexpected or late IN_RESP_question (id = {correlation_id})");
+            return;
+        };
+        self.accept_question(
+            Some(&requester),
+            correlation_id,
+            (rounds_ahead, question_type),
+            question_info,
+            question_svg,
+        )
+        .await;
+    }
+}
+
+pub struct QuestionErrorResponse {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub error_code: ErrorCode,
+    pub message: String,
+}
+
+impl Message<QuestionErrorResponse> for RoomActor {
+    type Reply = ();
+
+    async fn handle(&mut self, msg: QuestionErrorResponse, _ctx: &mut Context<Self, ()>) {
+        let QuestionErrorResponse {
+            requester,
+            correlation_id,
+            error_code,
+            message,
+        } = msg;
+
+        let Some((rounds_ahead, _)) = self.pending_question(Some(&requester), correlation_id)
+        else {
+            warn!("unexpected or late IN_RESP_questionError (id = {correlation_id})");
+            return;
+        };
+        warn!("no question from the admin's client: {error_code:?} {message}");
+        self.question_failed(correlation_id, rounds_ahead, error_code, message)
+            .await;
+    }
+}
+
+/// What the embedded provider made of the request sent under
+/// `correlation_id`.
+pub struct ProviderQuestion {
+    pub correlation_id: Uuid,
+    pub result: Result<ProvidedQuestion, ProviderError>,
+}
+
+impl Message<ProviderQuestion> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        ProviderQuestion {
+            correlation_id,
+            result,
+        }: ProviderQuestion,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some(pending) = self.pending_question(None, correlation_id) else {
+            warn!("late question from the provider (id = {correlation_id})");
+            return;
+        };
+        match result {
+            Ok(ProvidedQuestion {
+                question,
+                question_svg,
+            }) => {
+                self.accept_question(None, correlation_id, pending, question, question_svg)
+                    .await
+            }
+            Err(ProviderError {
+                error_code,
+                message,
+            }) => {
+                warn!("no question from the provider: {error_code:?} {message}");
+                self.question_failed(correlation_id, pending.0, error_code, message)
+                    .await
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SendAnswerRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub answer: String,
+    pub question_id: Option<Uuid>,
+    pub game_id: Option<Uuid>,
+}
+
+impl Message<SendAnswerRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SendAnswerRequest {
+            requester,
+            correlation_id,
+            answer,
+            question_id,
+            game_id,
+        }: SendAnswerRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((uuid, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if room_info.is_spectator {
+            self.reply_status(&requester, correlation_id, "spectator cannot answer")
+                .await;
+            return;
+        }
+
+        if let Err(status) = self.phase.check(GameAction::Answer) {
+            self.reply_status(&requester, correlation_id, status).await;
+            warn!("{status}");
+            return;
+        }
+
+        if self.sitting_out == Some(uuid) {
+            self.reply_status(
+                &requester,
+                correlation_id,
+                "question provider cannot answer",
+            )
+            .await;
+            return;
+        }
+
+        if game_id.is_some_and(|id| Some(id) != self.game_id()) {
+            self.reply_status(&requester, correlation_id, "stale game")
+                .await;
+            warn!("stale game");
+            return;
+        }
+
+        if question_id.is_some_and(|id| Some(id) != self.current_question_id) {
+            self.reply_status(&requester, correlation_id, "stale question")
+                .await;
+            warn!("stale question");
+            return;
+        }
+
+        let previous = self
+            .current_answers
+            .iter()
+            .position(|a| a.id == uuid.to_string());
+        if previous.is_some() && !self.game_settings.allow_answer_change {
+            self.reply_status(&requester, correlation_id, "already answered")
+                .await;
+            warn!("already answered");
+            return;
+        }
+
+        let elapsed = self
+            .round_start
+            .map(|t| t.elapsed().as_millis() as u64)
+            .unwrap_or(0);
+
+        // past the round's own time only handicapped players still answer
+        if self.phase == GamePhase::Intermission {
+            let extra = self
+                .clients
+                .get(&uuid)
+                .and_then(|c| c.handicap)
+                .map_or(0, |h| h.extra_time_ms);
+            if elapsed > self.round_limit.as_millis() as u64 + extra {
+                self.reply_status(&requester, correlation_id, "time is up")
+                    .await;
+                return;
+            }
+        }
+
+        self.empty_rounds = 0;
+        self.last_activity = tokio::time::Instant::now();
+
+        let is_correct = self
+            .current_question
+            .as_ref()
+            .is_some_and(|question| self.is_accepted(question, &answer));
+
+        // numbered before anything else can end the round, so a sequence
+        // number is always in the round's answers
+        let sequence = self.reply_accepted(&requester, correlation_id).await;
+        if let Some(index) = previous {
+            let existing = &mut self.current_answers[index];
+            existing.answer = answer;
+            existing.is_correct = is_correct;
+            existing.answer_time = elapsed;
+            existing.sequence = Some(sequence);
+            // the correct count may have changed with it
+            if self.game_settings.live_answer_stats {
+                self.broadcast_answered(uuid).await;
+            }
+            return;
+        }
+
+        self.current_answers.push(AnswerInfo {
+            id: uuid.to_string(),
+            answer: answer.clone(),
+            is_correct,
+            answer_time: elapsed,
+            kind: AnswerKind::Answered,
+            sequence: Some(sequence),
+        });
+        self.broadcast_answered(uuid).await;
+
+        if self.can_end_round_early() {
+            self.end_round_early().await;
+        }
+    }
+}
+
+pub struct StopGameRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+}
+
+impl Message<StopGameRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        StopGameRequest {
+            requester,
+            correlation_id,
+        }: StopGameRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_uuid, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        if let Err(status) = self.phase.check(GameAction::Stop) {
+            self.reply_status(&requester, correlation_id, status).await;
+            return;
+        }
+
+        if self.current_question.is_some() {
+            self.push_missing_answers();
+            self.record_round();
+        }
+
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+
+        self.end_game(GameStopReason::StoppedByAdmin).await;
+    }
+}
+
+pub struct ClientRenamed {
+    pub uuid: Uuid,
+    pub name: String,
+}
+
+impl Message<ClientRenamed> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        ClientRenamed { uuid, name }: ClientRenamed,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some(client) = self.clients.get_mut(&uuid) else {
+            return;
+        };
+        client.info.name = name.clone();
+
+        let notif = TransportMsg::OutNotifClientRenamed(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifClientRenamed {
+                id: uuid.to_string(),
+                name,
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_clientRenamed");
+    }
+}
+
+pub struct SetReadyRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub ready: bool,
+}
+
+impl Message<SetReadyRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SetReadyRequest {
+            requester,
+            correlation_id,
+            ready,
+        }: SetReadyRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((uuid, _, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if let Some(client) = self.clients.get_mut(&uuid) {
+            client.room_info.is_ready = ready;
+        }
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+
+        self.notif_ready_changed(uuid, ready).await;
+    }
+}
+
+pub struct SetRoomPasswordRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub password: Option<Password>,
+}
+
+impl Message<SetRoomPasswordRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SetRoomPasswordRequest {
+            requester,
+            correlation_id,
+            password,
+        }: SetRoomPasswordRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        self.room_config.join_password = password.filter(|p| !p.0.is_empty());
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+        self.report_status().await;
+
+        let notif = TransportMsg::OutNotifRoomPasswordChanged(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifRoomPasswordChanged {
+                has_password: self.room_config.join_password.is_some(),
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_roomPasswordChanged");
+    }
+}
+
+pub struct MuteClientRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub client_id: String,
+    pub muted: bool,
+}
+
+impl Message<MuteClientRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        MuteClientRequest {
+            requester,
+            correlation_id,
+            client_id,
+            muted,
+        }: MuteClientRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        let Some((target_uuid, target)) = Uuid::parse_str(&client_id)
+            .ok()
+            .and_then(|uuid| self.clients.get_mut(&uuid).map(|c| (uuid, c)))
+        else {
+            self.reply_status(&requester, correlation_id, "client not found")
+                .await;
+            return;
+        };
+
+        target.room_info.is_muted = muted;
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+
+        let notif = TransportMsg::OutNotifClientMuted(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifClientMuted {
+                id: target_uuid.to_string(),
+                muted,
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_clientMuted");
+    }
+}
+
+pub struct MakeAdminRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub client_id: String,
+    pub revoke_existing: bool,
+}
+
+impl Message<MakeAdminRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        MakeAdminRequest {
+            requester,
+            correlation_id,
+            client_id,
+            revoke_existing,
+        }: MakeAdminRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        let Some((target_uuid, target)) = Uuid::parse_str(&client_id)
+            .ok()
+            .and_then(|uuid| self.clients.get(&uuid).map(|c| (uuid, c)))
+        else {
+            self.reply_status(&requester, correlation_id, "client not found")
+                .await;
+            return;
+        };
+        if target.room_info.is_spectator {
+            self.reply_status(&requester, correlation_id, "spectator cannot be admin")
+                .await;
+            return;
+        }
+        if target.info.is_bot {
+            self.reply_status(&requester, correlation_id, "bot cannot be admin")
+                .await;
+            return;
+        }
+        let was_admin = target.room_info.is_admin;
+        if was_admin && !revoke_existing {
+            self.reply_status(&requester, correlation_id, "already admin")
+                .await;
+            return;
+        }
+
+        // a co-admin joins in without taking the questions over
+        if self.question_provider.is_none() {
+            self.question_provider = self.provider();
+        }
+        if let Some(target) = self.clients.get_mut(&target_uuid) {
+            target.room_info.is_admin = true;
+        }
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+        if !was_admin {
+            self.notif_admin_made(target_uuid).await;
+        }
+        if !revoke_existing {
+            return;
+        }
+
+        let mut revoked = Vec::new();
+        for (&uuid, client) in &mut self.clients {
+            if uuid != target_uuid && client.room_info.is_admin {
+                client.room_info.is_admin = false;
+                revoked.push(uuid);
+            }
+        }
+        for &uuid in &revoked {
+            self.notif_admin_revoked(uuid).await;
+        }
+        if self.question_provider != Some(target_uuid) {
+            self.question_provider = Some(target_uuid);
+            self.notif_provider_changed(target_uuid).await;
+        }
+        for uuid in revoked {
+            self.reassign_questions(uuid).await;
+        }
+    }
+}
+
+pub struct SetAdminSuccessorRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub client_id: Option<String>,
+}
+
+impl Message<SetAdminSuccessorRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SetAdminSuccessorRequest {
+            requester,
+            correlation_id,
+            client_id,
+        }: SetAdminSuccessorRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        let Some(client_id) = client_id else {
+            self.admin_successor = None;
+            self.reply_status(&requester, correlation_id, "success")
+                .await;
+            return;
+        };
+        let Some((target_uuid, target)) = Uuid::parse_str(&client_id)
+            .ok()
+            .and_then(|uuid| self.clients.get(&uuid).map(|c| (uuid, c)))
+        else {
+            self.reply_status(&requester, correlation_id, "client not found")
+                .await;
+            return;
+        };
+        if target.room_info.is_spectator {
+            self.reply_status(&requester, correlation_id, "spectator cannot be admin")
+                .await;
+            return;
+        }
+        if target.info.is_bot {
+            self.reply_status(&requester, correlation_id, "bot cannot be admin")
+                .await;
+            return;
+        }
+
+        self.admin_successor = Some(target_uuid);
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+    }
+}
+
+pub struct SetQuestionProviderRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub client_id: String,
+}
+
+impl Message<SetQuestionProviderRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SetQuestionProviderRequest {
+            requester,
+            correlation_id,
+            client_id,
+        }: SetQuestionProviderRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
 
-        for uuid in self.clients.keys() {
-            let id = uuid.to_string();
-            if answered.contains(&id) {
-                continue;
-            }
-            self.current_answers.push(AnswerInfo {
-                id,
-                answer: String::new(),
-                is_correct: false,
-                answer_time: max_time,
-            });
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        let Some(target_uuid) = Uuid::parse_str(&client_id)
+            .ok()
+            .filter(|uuid| self.clients.contains_key(uuid))
+        else {
+            self.reply_status(&requester, correlation_id, "client not found")
+                .await;
+            return;
+        };
+        if !self
+            .clients
+            .get(&target_uuid)
+            .is_some_and(|c| c.room_info.is_admin)
+        {
+            self.reply_status(&requester, correlation_id, "client is not admin")
+                .await;
+            return;
+        }
+
+        let previous = self.provider();
+        self.question_provider = Some(target_uuid);
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+        self.notif_provider_changed(target_uuid).await;
+        if let Some(previous) = previous
+            && previous != target_uuid
+        {
+            self.reassign_questions(previous).await;
         }
     }
 }
 
-// #endregion
+pub struct AddBotRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub difficulty: BotDifficulty,
+}
 
-// #region TYPES
-#[derive(Debug, Clone, Copy)]
-pub struct RoomClientInfo {
-    pub is_admin: bool,
+impl Message<AddBotRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        AddBotRequest {
+            requester,
+            correlation_id,
+            difficulty,
+        }: AddBotRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        // the bot registers like any client, so a full or locked room turns
+        // it away then
+        BotClientActor::spawn((self.game.clone(), self.config.bots.profile(difficulty)));
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+    }
 }
 
-#[derive(Debug)]
-struct RoomClient {
-    session: ActorRef<SessionClientActor>,
-    room_info: RoomClientInfo,
+pub struct RemoveBotRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub client_id: String,
 }
-// #endregion
 
-// #region MESSAGES
-impl Message<Timeout> for RoomActor {
+impl Message<RemoveBotRequest> for RoomActor {
     type Reply = ();
-    async fn handle(&mut self, Timeout(id): Timeout, _ctx: &mut Context<Self, ()>) {
-        if let Some(meta) = self.pending.take(id.into()) {
-            match meta.kind {
-                RoomPending::Question { uuid } => {
-                    warn!("admin {} didn't provide question in time", uuid);
-                }
-                RoomPending::Round => {
-                    self.round_ticket = None;
-                    self.finish_round().await;
-                }
-            }
+
+    async fn handle(
+        &mut self,
+        RemoveBotRequest {
+            requester,
+            correlation_id,
+            client_id,
+        }: RemoveBotRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        let Some((target_uuid, target)) = Uuid::parse_str(&client_id)
+            .ok()
+            .and_then(|uuid| self.clients.get(&uuid).map(|c| (uuid, c)))
+        else {
+            self.reply_status(&requester, correlation_id, "client not found")
+                .await;
+            return;
+        };
+        if !target.info.is_bot {
+            self.reply_status(&requester, correlation_id, "not a bot")
+                .await;
+            return;
         }
+
+        let session = target.session.clone();
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+        self.remove_client(target_uuid, DisconnectReason::Left)
+            .await;
+        // the bot goes down with its session
+        session.stop_gracefully().await.ok();
     }
 }
 
-pub struct AddClient {
-    pub uuid: Uuid,
-    pub session: ActorRef<SessionClientActor>,
+pub struct FetchAssetRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub asset_id: String,
 }
 
-impl Message<AddClient> for RoomActor {
+impl Message<FetchAssetRequest> for RoomActor {
     type Reply = ();
 
     async fn handle(
         &mut self,
-        AddClient { uuid, session }: AddClient,
-        ctx: &mut Context<Self, ()>,
+        FetchAssetRequest {
+            requester,
+            correlation_id,
+            asset_id,
+        }: FetchAssetRequest,
+        _ctx: &mut Context<Self, ()>,
     ) {
-        let is_admin = self.clients.is_empty();
+        let Some((_, content)) = self.assets.iter().find(|(id, _)| *id == asset_id) else {
+            self.reply_status(&requester, correlation_id, "asset expired")
+                .await;
+            return;
+        };
+        let resp = TransportMsg::OutRespAsset(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload: OutRespAsset {
+                asset_id,
+                content: content.clone(),
+            },
+        });
+        self.send_to(&requester, resp).await;
+        debug!("OUT_RESP_asset");
+    }
+}
 
-        session.link(&ctx.actor_ref()).await;
+/// What a bot answers when it gets the question right: the first accepted
+/// answer to the question, as long as it is still the current one.
+pub struct AnswerKey(pub Uuid);
 
-        self.clients.insert(
-            uuid,
-            RoomClient {
-                session: session.clone(),
-                room_info: RoomClientInfo { is_admin },
+impl Message<AnswerKey> for RoomActor {
+    type Reply = Option<String>;
+
+    async fn handle(
+        &mut self,
+        AnswerKey(question_id): AnswerKey,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Self::Reply {
+        if self.current_question_id != Some(question_id) {
+            return None;
+        }
+        self.current_question
+            .as_ref()
+            .and_then(|q| accepted_answers(q).into_iter().next())
+    }
+}
+
+pub struct SetHandicapRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub client_id: String,
+    pub extra_time_ms: u64,
+    pub score_multiplier: f64,
+}
+
+impl Message<SetHandicapRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SetHandicapRequest {
+            requester,
+            correlation_id,
+            client_id,
+            extra_time_ms,
+            score_multiplier,
+        }: SetHandicapRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
+            return;
+        };
+
+        if !room_info.is_admin {
+            warn!("non-admin tried to set a handicap");
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        if extra_time_ms > MAX_HANDICAP_EXTRA_MS
+            || !HANDICAP_MULTIPLIER_RANGE.contains(&score_multiplier)
+        {
+            self.reply_status(&requester, correlation_id, "invalid handicap")
+                .await;
+            warn!("invalid handicap: {extra_time_ms} ms, x{score_multiplier}");
+            return;
+        }
+
+        let Some((target_uuid, target)) = Uuid::parse_str(&client_id)
+            .ok()
+            .and_then(|uuid| self.clients.get_mut(&uuid).map(|c| (uuid, c)))
+        else {
+            self.reply_status(&requester, correlation_id, "client not found")
+                .await;
+            return;
+        };
+
+        let handicap = (extra_time_ms > 0 || score_multiplier != 1.0).then_some(Handicap {
+            extra_time_ms,
+            score_multiplier,
+        });
+        target.handicap = handicap;
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+
+        let notif = TransportMsg::OutNotifHandicapChanged(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifHandicapChanged {
+                id: target_uuid.to_string(),
+                handicap,
             },
-        );
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_handicapChanged");
+    }
+}
 
-        let Some(game) = self.game.upgrade() else {
+pub struct SetModerationSettingsRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub action: ModerationAction,
+}
+
+impl Message<SetModerationSettingsRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SetModerationSettingsRequest {
+            requester,
+            correlation_id,
+            action,
+        }: SetModerationSettingsRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
+            error!("no client");
             return;
         };
-        let Ok(mut infos) = game.ask(GetClientsInfo { ids: vec![uuid] }).await else {
+
+        if !room_info.is_admin {
+            warn!("non-admin tried to change moderation settings");
+            self.reply_status(&requester, correlation_id, "not admin")
+                .await;
+            return;
+        }
+
+        self.moderation_action = action;
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+        if let Some(game) = self.game.upgrade() {
+            game.tell(ModerationChanged(action)).await.ok();
+        }
+
+        let notif = TransportMsg::OutNotifModerationSettingsChanged(TransportEnvelope {
+            correlation_id: Uuid::new_v4(),
+            seq: None,
+            payload: OutNotifModerationSettingsChanged {
+                action,
+                active: !self.word_filter.is_empty(),
+            },
+        });
+        self.broadcast(notif).await;
+        debug!("OUT_NOTIF_moderationSettingsChanged");
+    }
+}
+
+pub struct RoomInfoRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+}
+
+impl Message<RoomInfoRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        RoomInfoRequest {
+            requester,
+            correlation_id,
+        }: RoomInfoRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let resp = TransportMsg::OutRespRoomInfo(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload: OutRespRoomInfo {
+                room: self.room_info(),
+                subscriptions: self
+                    .clients
+                    .values()
+                    .find(|c| c.session.id() == requester.id())
+                    .map_or(Subscription::ALL.to_vec(), |c| {
+                        c.subscriptions.iter().copied().collect()
+                    }),
+            },
+        });
+        self.send_to(&requester, resp).await;
+    }
+}
+
+pub struct SetSubscriptionsRequest {
+    pub requester: ActorRef<SessionClientActor>,
+    pub correlation_id: Uuid,
+    pub categories: Vec<String>,
+}
+
+impl Message<SetSubscriptionsRequest> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        SetSubscriptionsRequest {
+            requester,
+            correlation_id,
+            categories,
+        }: SetSubscriptionsRequest,
+        _ctx: &mut Context<Self, ()>,
+    ) {
+        let Some((uuid, ..)) = self.find_client(requester.id()) else {
+            error!("no client");
             return;
         };
-        let Some(g) = infos.pop() else { return };
 
-        let client_info = ClientInfo {
-            id: g.id.to_string(),
-            key: g.key,
-            name: g.name,
-            is_admin,
+        let mut subscriptions = BTreeSet::new();
+        for name in &categories {
+            match Subscription::parse(name) {
+                Some(subscription) => subscriptions.insert(subscription),
+                None => {
+                    let status = format!("unknown category: {name}");
+                    self.reply_status(&requester, correlation_id, &status).await;
+                    warn!("{status}");
+                    return;
+                }
+            };
+        }
+        if let Some(client) = self.clients.get_mut(&uuid) {
+            client.subscriptions = subscriptions;
+        }
+        self.reply_status(&requester, correlation_id, "success")
+            .await;
+    }
+}
+
+/// The room and a client's role in it, for the client's registration.
+pub struct GetRegistration(pub Uuid);
+
+#[derive(Reply)]
+pub struct Registration {
+    pub room: RoomInfo,
+    pub role: ClientRole,
+}
+
+impl Message<GetRegistration> for RoomActor {
+    type Reply = Registration;
+
+    async fn handle(
+        &mut self,
+        GetRegistration(uuid): GetRegistration,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> Registration {
+        let role = match self.clients.get(&uuid).map(|c| &c.room_info) {
+            Some(info) if info.is_admin => ClientRole::Admin,
+            Some(info) if info.is_spectator => ClientRole::Spectator,
+            _ => ClientRole::Player,
         };
-
-        self.notif_client_registered(client_info).await;
-        if is_admin {
-            self.notif_admin_made(uuid).await;
-        } else {
-            let notif = TransportMsg::OutNotifGameSettingsChanged(TransportEnvelope {
-                correlation_id: Uuid::new_v4(),
-                payload: OutNotifGameSettingsChanged {
-                    game_settings: self.game_settings.clone(),
-                },
-            });
-            self.broadcast(notif).await;
-            debug!("OUT_NOTIF_gameSettingsChanged");
+        Registration {
+            room: self.room_info(),
+            role,
         }
     }
 }
 
-pub struct ClientListRequest {
+pub struct RoundStateRequest {
     pub requester: ActorRef<SessionClientActor>,
     pub correlation_id: Uuid,
 }
 
-impl Message<ClientListRequest> for RoomActor {
+impl Message<RoundStateRequest> for RoomActor {
     type Reply = ();
 
     async fn handle(
         &mut self,
-        ClientListRequest {
+        RoundStateRequest {
             requester,
             correlation_id,
-        }: ClientListRequest,
+        }: RoundStateRequest,
         _ctx: &mut Context<Self, ()>,
     ) {
-        let ids: Vec<Uuid> = self.clients.keys().cloned().collect();
-
-        let Some(game) = self.game.upgrade() else {
-            return;
-        };
-
-        let game_infos = match game.ask(GetClientsInfo { ids: ids.clone() }).await {
-            Ok(v) => v,
-            Err(_) => return,
-        };
-        let mut by_id: HashMap<Uuid, GameClientInfo> =
-            game_infos.into_iter().map(|g| (g.id, g)).collect();
-
-        use crate::data_types::{
-            ClientInfo, OutRespClientList, TransportEnvelope as Msg, TransportMsg,
+        let active = self.phase.is_running() && self.current_question_id.is_some();
+        let round_number = if self.phase.is_running() {
+            self.rounds_played + 1
+        } else {
+            0
         };
-        let clients: Vec<ClientInfo> = ids
-            .into_iter()
-            .filter_map(|id| {
-                let g = by_id.remove(&id)?;
-                let r = &self.clients[&id].room_info;
-                Some(ClientInfo {
-                    id: g.id.to_string(),
-                    key: g.key,
-                    name: g.name,
-                    is_admin: r.is_admin,
-                })
-            })
-            .collect();
+        let requester_id = self
+            .find_client(requester.id())
+            .map(|(uuid, _, _)| uuid.to_string());
+        let has_answered = active
+            && requester_id.is_some_and(|id| self.current_answers.iter().any(|a| a.id == id));
 
-        let ws = TransportMsg::OutRespClientList(Msg {
+        let resp = TransportMsg::OutRespRoundState(TransportEnvelope {
             correlation_id,
-            payload: OutRespClientList { clients },
+            seq: None,
+            payload: OutRespRoundState {
+                active,
+                round_number,
+                remaining_ms: self
+                    .round_remaining()
+                    .filter(|_| active)
+                    .map(|d| d.as_millis() as u64),
+                next_round_ms: self.countdown_remaining().map(|d| d.as_millis() as u64),
+                answered_count: if active {
+                    self.current_answers.len() as u64
+                } else {
+                    0
+                },
+                has_answered,
+            },
         });
-        requester.tell(SendWs(ws)).await.ok();
+        self.send_to(&requester, resp).await;
     }
 }
 
-pub struct SetGameSettingsRequest {
+pub struct ExtendRoundRequest {
     pub requester: ActorRef<SessionClientActor>,
     pub correlation_id: Uuid,
-    pub game_settings: GameSettings,
+    pub extra_seconds: u64,
 }
 
-impl Message<SetGameSettingsRequest> for RoomActor {
+impl Message<ExtendRoundRequest> for RoomActor {
     type Reply = ();
 
     async fn handle(
         &mut self,
-        SetGameSettingsRequest {
+        ExtendRoundRequest {
             requester,
             correlation_id,
-            game_settings,
-        }: SetGameSettingsRequest,
+            extra_seconds,
+        }: ExtendRoundRequest,
         _ctx: &mut Context<Self, ()>,
     ) {
         let Some((_, room_info, _)) = self.find_client(requester.id()) else {
@@ -414,270 +4211,219 @@ impl Message<SetGameSettingsRequest> for RoomActor {
         if !room_info.is_admin {
             self.reply_status(&requester, correlation_id, "not admin")
                 .await;
+            warn!("not admin");
             return;
         }
 
-        self.game_settings = game_settings.clone();
+        if !(1..=MAX_ROUND_EXTENSION).contains(&extra_seconds) {
+            self.reply_status(&requester, correlation_id, "invalid extension")
+                .await;
+            warn!("invalid extension: {extra_seconds}");
+            return;
+        }
+
+        if let Err(status) = self.phase.check(GameAction::ExtendRound) {
+            self.reply_status(&requester, correlation_id, status).await;
+            warn!("{status}");
+            return;
+        }
+
+        let extra = Duration::from_secs(extra_seconds);
+        let remaining = self
+            .round_ticket
+            .and_then(|ticket| self.pending.extend(ticket, extra));
+        let Some(remaining) = remaining else {
+            self.reply_status(&requester, correlation_id, "no active round")
+                .await;
+            warn!("no active round");
+            return;
+        };
+        self.round_limit += extra;
+        self.arm_round_warnings();
+
         self.reply_status(&requester, correlation_id, "success")
             .await;
 
-        let notif = TransportMsg::OutNotifGameSettingsChanged(TransportEnvelope {
+        let notif = TransportMsg::OutNotifRoundExtended(TransportEnvelope {
             correlation_id: Uuid::new_v4(),
-            payload: OutNotifGameSettingsChanged { game_settings },
+            seq: None,
+            payload: OutNotifRoundExtended {
+                new_remaining_ms: remaining.as_millis() as u64,
+                game_id: self.game_id(),
+            },
         });
         self.broadcast(notif).await;
-        debug!("OUT_NOTIF_gameSettingsChanged");
+        debug!("OUT_NOTIF_roundExtended");
     }
 }
 
-pub struct SendChatRequest {
+pub struct GameReplayRequest {
     pub requester: ActorRef<SessionClientActor>,
     pub correlation_id: Uuid,
-    pub message: String,
 }
 
-impl Message<SendChatRequest> for RoomActor {
+impl Message<GameReplayRequest> for RoomActor {
     type Reply = ();
 
     async fn handle(
         &mut self,
-        SendChatRequest {
+        GameReplayRequest {
             requester,
             correlation_id,
-            message,
-        }: SendChatRequest,
+        }: GameReplayRequest,
         _ctx: &mut Context<Self, ()>,
     ) {
-        let Some((sender_uuid, _, _)) = self.find_client(requester.id()) else {
-            error!("no client");
+        let Some(game) = self
+            .game_history
+            .iter()
+            .rev()
+            .find(|g| g.stop_reason.is_some())
+        else {
+            self.reply_status(&requester, correlation_id, "no replay")
+                .await;
             return;
         };
 
-        self.reply_status(&requester, correlation_id, "success")
-            .await;
-
-        let notif = TransportMsg::OutNotifChatSent(TransportEnvelope {
-            correlation_id: Uuid::new_v4(),
-            payload: OutNotifChatSent {
-                id: sender_uuid.to_string(),
-                message: message.clone(),
+        let resp = TransportMsg::OutRespGameReplay(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload: OutRespGameReplay {
+                replay: GameReplay::from(game),
             },
         });
-        self.broadcast(notif).await;
-        debug!("OUT_NOTIF_chatSent {message}");
+        self.send_to(&requester, resp).await;
     }
 }
 
-pub struct StartGameRequest {
+pub struct RoomEventsRequest {
     pub requester: ActorRef<SessionClientActor>,
     pub correlation_id: Uuid,
-    pub game_settings: GameSettings,
 }
 
-impl Message<StartGameRequest> for RoomActor {
+impl Message<RoomEventsRequest> for RoomActor {
     type Reply = ();
 
     async fn handle(
         &mut self,
-        StartGameRequest {
+        RoomEventsRequest {
             requester,
             correlation_id,
-            game_settings,
-        }: StartGameRequest,
+        }: RoomEventsRequest,
         _ctx: &mut Context<Self, ()>,
     ) {
-        let Some((_admin_uuid, room_info, _admin_session)) = self.find_client(requester.id())
-        else {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
             error!("no client");
             return;
         };
 
         if !room_info.is_admin {
+            warn!("non-admin asked for the room events");
             self.reply_status(&requester, correlation_id, "not admin")
                 .await;
-            warn!("not admin");
-            return;
-        }
-
-        if self.is_game_running {
-            self.reply_status(&requester, correlation_id, "already running")
-                .await;
-            warn!("already running");
             return;
         }
 
-        self.current_question = None;
-        self.current_answers.clear();
-        self.round_ticket = None;
-        self.game_settings = game_settings.clone();
-        self.is_game_running = true;
-        self.rounds_played = 0;
-
-        self.reply_status(&requester, correlation_id, "success")
-            .await;
-
-        let notif = TransportMsg::OutNotifGameStarted(TransportEnvelope {
-            correlation_id: Uuid::new_v4(),
-            payload: OutNotifGameStarted { game_settings },
-        });
-        self.broadcast(notif).await;
-        debug!("OUT_NOTIF_gameStarted");
-
-        self.request_question().await;
-    }
-}
-
-pub struct ProvideQuestionResponse {
-    pub requester: ActorRef<SessionClientActor>,
-    pub correlation_id: Uuid,
-    pub question_info: QuestionInfo,
-    pub question_svg: String,
-}
-
-impl Message<ProvideQuestionResponse> for RoomActor {
-    type Reply = ();
-
-    async fn handle(&mut self, msg: ProvideQuestionResponse, _ctx: &mut Context<Self, ()>) {
-        let ProvideQuestionResponse {
-            requester,
+        let resp = TransportMsg::OutRespRoomEvents(TransportEnvelope {
             correlation_id,
-            question_info,
-            question_svg,
-        } = msg;
-
-        match self.pending.take(correlation_id.into()) {
-            Some(PendingMeta {
-                kind: RoomPending::Question { uuid },
-                ..
-            }) if requester.id() == self.clients[&uuid].session.id() => {
-                self.current_question = Some(question_info.clone());
-                self.current_answers.clear();
-
-                let notif = TransportMsg::OutNotifQuestion(TransportEnvelope {
-                    correlation_id: Uuid::new_v4(),
-                    payload: OutNotifQuestion { question_svg },
-                });
-                self.broadcast(notif).await;
-                debug!("OUT_NOTIF_question");
-
-                self.round_start = Some(Instant::now());
-
-                let ticket = self.pending.add(
-                    RoomPending::Round,
-                    Duration::from_secs(self.game_settings.round_duration),
-                );
-                self.round_ticket = Some(ticket);
-            }
-
-            _ => warn!("unexpected or late IN_RESP_question (id = {correlation_id})"),
-        }
+            seq: None,
+            payload: OutRespRoomEvents {
+                events: self.room_events.iter().cloned().collect(),
+            },
+        });
+        self.send_to(&requester, resp).await;
     }
 }
 
-#[derive(Debug)]
-pub struct SendAnswerRequest {
+pub struct ExportGameRequest {
     pub requester: ActorRef<SessionClientActor>,
     pub correlation_id: Uuid,
-    pub answer: String,
+    pub game_index: u64,
 }
 
-impl Message<SendAnswerRequest> for RoomActor {
+impl Message<ExportGameRequest> for RoomActor {
     type Reply = ();
 
     async fn handle(
         &mut self,
-        SendAnswerRequest {
+        ExportGameRequest {
             requester,
             correlation_id,
-            answer,
-        }: SendAnswerRequest,
+            game_index,
+        }: ExportGameRequest,
         _ctx: &mut Context<Self, ()>,
     ) {
-        let Some((uuid, _, _)) = self.find_client(requester.id()) else {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
             error!("no client");
             return;
         };
 
-        if !self.is_game_running || self.current_question.is_none() {
-            self.reply_status(&requester, correlation_id, "no active round")
+        if !room_info.is_admin {
+            warn!("non-admin asked for a game export");
+            self.reply_status(&requester, correlation_id, "not admin")
                 .await;
-            warn!("no active round");
             return;
         }
 
-        if self
-            .current_answers
+        let latest = self.game_history.back().map(|g| g.game_index);
+        let Some(game) = self
+            .game_history
             .iter()
-            .any(|a| a.id == uuid.to_string())
-        {
-            self.reply_status(&requester, correlation_id, "already answered")
+            .find(|g| g.game_index == game_index)
+        else {
+            // older games have dropped out of the history
+            let status = match latest {
+                Some(latest) if game_index < latest => "not available",
+                _ => "no such game",
+            };
+            self.reply_status(&requester, correlation_id, status).await;
+            return;
+        };
+        if game.stop_reason.is_none() {
+            self.reply_status(&requester, correlation_id, "game not finished")
                 .await;
-            warn!("already answered");
             return;
         }
 
-        let elapsed = self
-            .round_start
-            .map(|t| t.elapsed().as_millis() as u64)
-            .unwrap_or(0);
-
-        let is_correct = self
-            .current_question
-            .as_ref()
-            .map(|question| {
-                question
-                    .word_info
-                    .readings
-                    .iter()
-                    .any(|reading| reading.reading == answer)
-            })
-            .unwrap_or(false);
-
-        self.current_answers.push(AnswerInfo {
-            id: uuid.to_string(),
-            answer: answer.clone(),
-            is_correct,
-            answer_time: elapsed,
-        });
-
-        self.reply_status(&requester, correlation_id, "success")
-            .await;
-
-        let notif = TransportMsg::OutNotifClientAnswered(TransportEnvelope {
-            correlation_id: Uuid::new_v4(),
-            payload: OutNotifClientAnswered {
-                id: uuid.to_string(),
+        let export = GameExport::new(&self.name, game);
+        let document = match export.to_json() {
+            Ok(document) => document,
+            Err(e) => {
+                error!("game export failed: {e}");
+                self.reply_status(&requester, correlation_id, "error").await;
+                return;
+            }
+        };
+        let resp = TransportMsg::OutRespGameExport(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload: OutRespGameExport {
+                file_name: export.file_name(),
+                document,
             },
         });
-        self.broadcast(notif).await;
-        debug!("OUT_NOTIF_clientAnswered");
-
-        if self.current_answers.len() == self.clients.len() {
-            if let Some(ticket) = self.round_ticket.take() {
-                self.pending.cancel(ticket);
-            }
-            self.finish_round().await;
-        }
+        self.send_to(&requester, resp).await;
     }
 }
 
-pub struct StopGameRequest {
+pub struct SetRoomNameRequest {
     pub requester: ActorRef<SessionClientActor>,
     pub correlation_id: Uuid,
+    pub name: String,
 }
 
-impl Message<StopGameRequest> for RoomActor {
+impl Message<SetRoomNameRequest> for RoomActor {
     type Reply = ();
 
     async fn handle(
         &mut self,
-        StopGameRequest {
+        SetRoomNameRequest {
             requester,
             correlation_id,
-        }: StopGameRequest,
+            name,
+        }: SetRoomNameRequest,
         _ctx: &mut Context<Self, ()>,
     ) {
-        let Some((_uuid, room_info, _)) = self.find_client(requester.id()) else {
+        let Some((_, room_info, _)) = self.find_client(requester.id()) else {
             error!("no client");
             return;
         };
@@ -688,39 +4434,49 @@ impl Message<StopGameRequest> for RoomActor {
             return;
         }
 
-        if !self.is_game_running {
-            self.reply_status(&requester, correlation_id, "not running")
-                .await;
-            return;
-        }
-
-        if let Some(ticket) = self.round_ticket.take() {
-            self.pending.cancel(ticket);
-        }
-
-        if self.current_question.is_some() {
-            self.push_missing_answers();
-        }
+        let name = match normalize_name(&name) {
+            Ok(name) => name,
+            Err(status) => {
+                self.reply_status(&requester, correlation_id, status).await;
+                return;
+            }
+        };
 
+        self.name = name.clone();
         self.reply_status(&requester, correlation_id, "success")
             .await;
+        self.report_status().await;
 
-        let notif = TransportMsg::OutNotifGameStopped(TransportEnvelope {
+        let notif = TransportMsg::OutNotifRoomRenamed(TransportEnvelope {
             correlation_id: Uuid::new_v4(),
-            payload: OutNotifGameStopped {
-                question: self.current_question.clone().unwrap_or_default(),
-                answers: self.current_answers.clone(),
-            },
+            seq: None,
+            payload: OutNotifRoomRenamed { name },
         });
         self.broadcast(notif).await;
-        debug!("OUT_NOTIF_gameStopped");
+        debug!("OUT_NOTIF_roomRenamed");
+    }
+}
 
-        self.is_game_running = false;
-        self.current_question = None;
-        self.current_answers.clear();
-        self.round_start = None;
-        self.round_ticket = None;
-        self.rounds_played = 0;
+pub struct GetRoomStats;
+
+impl Message<GetRoomStats> for RoomActor {
+    type Reply = RoomStats;
+
+    async fn handle(
+        &mut self,
+        _: GetRoomStats,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> RoomStats {
+        let players = self.player_count() as u64;
+        RoomStats {
+            name: self.name.clone(),
+            clients: self.clients.len() as u64,
+            is_game_running: self.phase.is_running(),
+            players,
+            spectators: self.clients.len() as u64 - players,
+            rounds_played: self.rounds_played,
+            games_started: self.games_started,
+        }
     }
 }
 
@@ -730,7 +4486,125 @@ impl Message<IsGameRunning> for RoomActor {
     type Reply = bool;
 
     async fn handle(&mut self, _: IsGameRunning, _ctx: &mut Context<Self, Self::Reply>) -> bool {
-        self.is_game_running
+        self.phase.is_running()
+    }
+}
+
+/// The room's game phase, and how many round timers are live, which the
+/// phase keeps at one at most.
+pub struct GetGamePhase;
+
+#[derive(Reply, Debug)]
+pub struct GamePhaseReport {
+    pub phase: GamePhase,
+    pub round_timers: usize,
+}
+
+impl Message<GetGamePhase> for RoomActor {
+    type Reply = GamePhaseReport;
+
+    async fn handle(
+        &mut self,
+        _: GetGamePhase,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> GamePhaseReport {
+        let round_timers = self
+            .pending
+            .iter()
+            .filter(|(_, meta)| matches!(meta.kind, RoomPending::Round | RoomPending::RoundGrace))
+            .count();
+        GamePhaseReport {
+            phase: self.phase,
+            round_timers,
+        }
+    }
+}
+
+/// Captures the room for a restart. A round still being played is kept as
+/// an aborted round without answers.
+#[cfg(feature = "persistence")]
+pub struct TakeSnapshot;
+
+#[cfg(feature = "persistence")]
+impl Message<TakeSnapshot> for RoomActor {
+    type Reply = RoomSnapshot;
+
+    async fn handle(
+        &mut self,
+        _: TakeSnapshot,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) -> RoomSnapshot {
+        let mut game_history: Vec<GameRecord> = self.game_history.iter().cloned().collect();
+        if self.phase.is_running()
+            && let Some(question) = self.current_question.clone()
+            && let Some(game) = game_history.last_mut()
+        {
+            game.rounds.push(RoundRecord {
+                tier: self.question_tier(&question),
+                question,
+                answers: Vec::new(),
+                aborted: true,
+                question_id: self.current_question_id,
+                reactions: self.current_reactions.clone(),
+            });
+        }
+
+        RoomSnapshot {
+            version: SNAPSHOT_VERSION,
+            room_name: self.name.clone(),
+            clients: self
+                .clients
+                .iter()
+                .map(|(&id, c)| SavedClient {
+                    id,
+                    key: c.info.key.clone(),
+                    name: c.info.name.clone(),
+                })
+                .collect(),
+            game_settings: self.game_settings.clone(),
+            game_history,
+            rounds_played: self.rounds_played,
+            current_font_index: self.current_font_index,
+            font_names: self.font_names.clone(),
+            streaks: self.streaks.clone(),
+            streak_points: self.streak_points.clone(),
+            suspended: self.phase != GamePhase::Idle,
+            game_time_left_ms: self
+                .game_deadline
+                .map(|d| d.saturating_duration_since(tokio::time::Instant::now()))
+                .or(self.game_time_left)
+                .map(|left| left.as_millis() as u64),
+        }
+    }
+}
+
+/// Loads a saved room into a fresh one. The game, if any, stays suspended
+/// until the admin resumes it.
+#[cfg(feature = "persistence")]
+pub struct RestoreSnapshot(pub RoomSnapshot);
+
+#[cfg(feature = "persistence")]
+impl Message<RestoreSnapshot> for RoomActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        RestoreSnapshot(snapshot): RestoreSnapshot,
+        _ctx: &mut Context<Self, Self::Reply>,
+    ) {
+        self.name = snapshot.room_name;
+        self.game_settings = snapshot.game_settings;
+        self.game_history = snapshot.game_history.into();
+        self.rounds_played = snapshot.rounds_played;
+        self.current_font_index = snapshot.current_font_index;
+        self.font_names = snapshot.font_names;
+        self.streaks = snapshot.streaks;
+        self.streak_points = snapshot.streak_points;
+        if snapshot.suspended {
+            self.phase = GamePhase::Paused;
+        }
+        self.game_time_left = snapshot.game_time_left_ms.map(Duration::from_millis);
+        self.report_status().await;
     }
 }
 
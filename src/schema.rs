@@ -0,0 +1,145 @@
+use schemars::schema_for;
+use serde_json::{Map, Value};
+
+use crate::data_types::TransportMsg;
+
+/// JSON Schema for every message that can travel over the socket.
+pub fn protocol_schema() -> Value {
+    serde_json::to_value(schema_for!(TransportMsg)).expect("schema is valid json")
+}
+
+pub fn protocol_schema_pretty() -> String {
+    let mut text = serde_json::to_string_pretty(&protocol_schema()).expect("schema is valid json");
+    text.push('\n');
+    text
+}
+
+/// Renders the protocol schema as TypeScript declarations. Only the subset
+/// of JSON Schema that `schemars` emits for our types is understood.
+pub fn typescript_definitions(schema: &Value) -> String {
+    let mut out =
+        String::from("// Generated by `cargo run --features schema --bin export-schema`.\n");
+
+    if let Some(defs) = schema.get("$defs").and_then(Value::as_object) {
+        for (name, def) in defs {
+            out.push('\n');
+            push_type(&mut out, name, def);
+        }
+    }
+
+    let root = schema
+        .get("title")
+        .and_then(Value::as_str)
+        .unwrap_or("Message");
+    out.push('\n');
+    push_type(&mut out, root, schema);
+    out
+}
+
+fn push_type(out: &mut String, name: &str, schema: &Value) {
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        out.push_str(&format!("/** {description} */\n"));
+    }
+    let ty = match schema.get("oneOf").and_then(Value::as_array) {
+        Some(variants) => variants
+            .iter()
+            .map(|v| format!("\n  | {}", ts_type(v, 1)))
+            .collect(),
+        None => format!(" {}", ts_type(schema, 0)),
+    };
+    out.push_str(&format!("export type {name} ={ty};\n"));
+}
+
+fn ts_type(schema: &Value, depth: usize) -> String {
+    let Some(obj) = schema.as_object() else {
+        return "unknown".into();
+    };
+
+    if let Some(variants) = obj.get("oneOf").or_else(|| obj.get("anyOf")) {
+        let variants = variants.as_array().into_iter().flatten();
+        return variants
+            .map(|v| ts_type(v, depth))
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    if let Some(value) = obj.get("const") {
+        return value.to_string();
+    }
+    if let Some(values) = obj.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+
+    let mut parts = Vec::new();
+    if let Some(target) = obj.get("$ref").and_then(Value::as_str) {
+        parts.push(target.rsplit('/').next().unwrap_or(target).to_string());
+    }
+    if let Some(props) = obj.get("properties").and_then(Value::as_object) {
+        parts.push(ts_object(obj, props, depth));
+    }
+    if !parts.is_empty() {
+        return parts.join(" & ");
+    }
+
+    let types = match obj.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => return "unknown".into(),
+    };
+    types
+        .into_iter()
+        .map(|t| match t {
+            "string" => "string".into(),
+            "integer" | "number" => "number".into(),
+            "boolean" => "boolean".into(),
+            "null" => "null".into(),
+            "array" => match obj.get("items") {
+                Some(items) => format!("Array<{}>", ts_type(items, depth)),
+                None => "unknown[]".into(),
+            },
+            _ => match obj.get("additionalProperties") {
+                Some(values) if values.is_object() => {
+                    format!("Record<string, {}>", ts_type(values, depth))
+                }
+                _ => "Record<string, unknown>".into(),
+            },
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn ts_object(obj: &Map<String, Value>, props: &Map<String, Value>, depth: usize) -> String {
+    let required: Vec<&str> = obj
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let indent = "  ".repeat(depth + 1);
+
+    let mut body = String::from("{\n");
+    for (name, prop) in props {
+        let optional = if required.contains(&name.as_str()) {
+            ""
+        } else {
+            "?"
+        };
+        if prop.get("deprecated").and_then(Value::as_bool) == Some(true) {
+            body.push_str(&format!(
+                "{indent}/** @deprecated */
+"
+            ));
+        }
+        body.push_str(&format!(
+            "{indent}{name}{optional}: {};\n",
+            ts_type(prop, depth + 1)
+        ));
+    }
+    body.push_str(&"  ".repeat(depth));
+    body.push('}');
+    body
+}
@@ -1,70 +1,336 @@
-use std::sync::{Mutex, OnceLock};
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    io,
+    net::SocketAddr,
+    sync::{
+        Arc, Mutex, MutexGuard, OnceLock, PoisonError, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
-use kameo::Actor;
+use kameo::{Actor, actor::ActorRef};
 use tokio::{
-    net::TcpListener,
-    runtime::{Builder, Runtime},
+    net::{TcpListener, TcpStream},
+    runtime::{Builder, Handle, Runtime},
     sync::broadcast,
 };
+use tokio_rustls::TlsAcceptor;
+
+use crate::{
+    config::{ServerConfig, TlsConfig},
+    data_types::{EffectiveConfig, OutboxEntry, ServerStats},
+    events::{EventSender, ServerEvent, event_channel},
+    game_actor::{DumpOutbox, GameActor, GetEffectiveConfig, NewClient, collect_server_stats},
+    question_provider::QuestionProvider,
+    tls::load_acceptor,
+    websocket_client_actor::{ClientIo, ClientStream},
+};
+
+#[cfg(feature = "persistence")]
+use crate::game_actor::SaveState;
 
-use crate::game_actor::{GameActor, NewClient};
+/// Names one server launched in this process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ServerId(u64);
+
+impl fmt::Display for ServerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "server {}", self.0)
+    }
+}
 
 struct ServerState {
+    addr: SocketAddr,
     stop_tx: broadcast::Sender<()>,
-    _rt: Runtime,
+    game: ActorRef<GameActor>,
+    tls: Option<TlsState>,
+    /// Whether the room is saved on stop.
+    #[cfg(feature = "persistence")]
+    saves_state: bool,
+    rt: Runtime,
 }
 
-static STATE: OnceLock<Mutex<Option<ServerState>>> = OnceLock::new();
+struct TlsState {
+    config: TlsConfig,
+    acceptor: Arc<RwLock<TlsAcceptor>>,
+}
 
-pub fn call_launch_server(port: impl Into<String>) -> Result<(), String> {
-    let addr = format!("127.0.0.1:{}", port.into());
+static SERVERS: OnceLock<Mutex<HashMap<ServerId, ServerState>>> = OnceLock::new();
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static EVENTS: OnceLock<EventSender> = OnceLock::new();
 
-    let lock = STATE.get_or_init(|| Mutex::new(None));
-    let mut guard = lock.lock().unwrap();
-    if guard.is_some() {
-        return Err("server already running".into());
-    }
+/// The running servers. A panic while the lock was held leaves the map as
+/// it was, so it doesn't stop later launches.
+fn servers() -> MutexGuard<'static, HashMap<ServerId, ServerState>> {
+    SERVERS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The game and runtime of a running server, so the lock isn't held while
+/// waiting on it.
+fn server(id: ServerId) -> Result<(ActorRef<GameActor>, Handle), String> {
+    let servers = servers();
+    let state = servers
+        .get(&id)
+        .ok_or_else(|| format!("{id} is not running"))?;
+    Ok((state.game.clone(), state.rt.handle().clone()))
+}
+
+/// Runs `future` on the server's runtime and waits for it. Waited for on a
+/// thread of its own: `block_on` panics when the caller is itself running
+/// on a runtime, as `main` is.
+fn block_on<F>(handle: &Handle, future: F) -> F::Output
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    std::thread::scope(|s| {
+        s.spawn(|| handle.block_on(future))
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+fn events() -> &'static EventSender {
+    EVENTS.get_or_init(event_channel)
+}
+
+/// Receives game events from every server launched in this process. Can be
+/// called before the server starts; a receiver that falls behind skips the
+/// missed events instead of slowing the game down.
+pub fn subscribe_events() -> broadcast::Receiver<ServerEvent> {
+    events().subscribe()
+}
+
+pub fn call_launch_server(port: impl Into<String>) -> Result<ServerId, String> {
+    call_launch_server_with_config(ServerConfig {
+        port: port.into(),
+        ..Default::default()
+    })
+}
+
+/// Starts a server on its own runtime. Any number can run side by side as
+/// long as their ports differ; port `0` picks a free one, see
+/// [`get_server_addr`].
+pub fn call_launch_server_with_config(config: ServerConfig) -> Result<ServerId, String> {
+    launch_server(config, None)
+}
+
+/// Starts a server whose rooms get their questions from `provider` rather
+/// than from the admin's client.
+pub fn call_launch_server_with_provider(
+    config: ServerConfig,
+    provider: Arc<dyn QuestionProvider>,
+) -> Result<ServerId, String> {
+    launch_server(config, Some(provider))
+}
+
+fn launch_server(
+    config: ServerConfig,
+    provider: Option<Arc<dyn QuestionProvider>>,
+) -> Result<ServerId, String> {
+    let addr = format!("127.0.0.1:{}", config.port);
+
+    let tls = match &config.tls {
+        Some(tls_config) => Some(TlsState {
+            config: tls_config.clone(),
+            acceptor: Arc::new(RwLock::new(load_acceptor(tls_config)?)),
+        }),
+        None => None,
+    };
+    let accept_tls = tls.as_ref().map(|t| t.acceptor.clone());
+    #[cfg(feature = "persistence")]
+    let saves_state = config.state_path.is_some();
 
     let rt = Builder::new_multi_thread()
         .enable_all()
         .build()
         .map_err(|e| e.to_string())?;
 
+    // bound synchronously: `block_on` would panic when the caller is itself
+    // running on a runtime, as `main` is
+    let listener = std::net::TcpListener::bind(&addr)
+        .and_then(|listener| {
+            listener.set_nonblocking(true)?;
+            let _guard = rt.enter();
+            TcpListener::from_std(listener)
+        })
+        .map_err(|e| format!("can't listen on {addr}: {e}"))?;
+    let addr = listener.local_addr().map_err(|e| e.to_string())?;
+
     let (stop_tx, mut stop_rx) = broadcast::channel::<()>(1);
 
-    let handle = rt.handle().clone();
-    handle.spawn(async move {
-        let game = GameActor::spawn(());
-        let listener = TcpListener::bind(&addr).await.expect("bind tcp listener");
-
-        loop {
-            tokio::select! {
-                Ok((stream, _)) = listener.accept() => {
-                    let g = game.clone();
-                    tokio::spawn(async move { g.tell(NewClient(stream)).await.ok(); });
-                }
-                _ = stop_rx.recv() => {
-                    tracing::info!("server on {addr} shutting down");
-                    break;
-                }
+    let game = {
+        let _guard = rt.enter();
+        let effective_config = EffectiveConfig::from_server(&config);
+        GameActor::spawn((
+            Arc::new(config),
+            effective_config,
+            events().clone(),
+            provider,
+        ))
+    };
+    let shutdown = async move {
+        stop_rx.recv().await.ok();
+        tracing::info!("server on {addr} shutting down");
+    };
+    rt.spawn(accept_loop(listener, game.clone(), accept_tls, shutdown));
+
+    let id = ServerId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    servers().insert(
+        id,
+        ServerState {
+            addr,
+            stop_tx,
+            game,
+            tls,
+            #[cfg(feature = "persistence")]
+            saves_state,
+            rt,
+        },
+    );
+    tracing::info!("{id} listening on {addr}");
+    Ok(id)
+}
+
+/// The address a server listens on.
+pub fn get_server_addr(id: ServerId) -> Result<SocketAddr, String> {
+    servers()
+        .get(&id)
+        .map(|state| state.addr)
+        .ok_or_else(|| format!("{id} is not running"))
+}
+
+/// Where a server takes its connections from.
+pub trait Listener: Send + 'static {
+    type Io: ClientIo;
+
+    fn accept(&mut self) -> impl Future<Output = io::Result<(Self::Io, SocketAddr)>> + Send;
+}
+
+impl Listener for TcpListener {
+    type Io = TcpStream;
+
+    async fn accept(&mut self) -> io::Result<(TcpStream, SocketAddr)> {
+        TcpListener::accept(self).await
+    }
+}
+
+/// Hands every connection from `listener` to `game` until `shutdown`
+/// completes. For embedders that bind the socket themselves, or take one
+/// from socket activation; the launch functions do all of this for you.
+pub async fn serve(
+    listener: impl Listener,
+    game: ActorRef<GameActor>,
+    shutdown: impl Future<Output = ()> + Send,
+) {
+    accept_loop(listener, game, None, shutdown).await;
+}
+
+pub(crate) async fn accept_loop(
+    mut listener: impl Listener,
+    game: ActorRef<GameActor>,
+    tls: Option<Arc<RwLock<TlsAcceptor>>>,
+    shutdown: impl Future<Output = ()> + Send,
+) {
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            Ok((stream, peer_addr)) = listener.accept() => {
+                let g = game.clone();
+                let acceptor = tls.as_ref().map(|a| a.read().unwrap().clone());
+                tokio::spawn(async move {
+                    let stream: ClientStream = match acceptor {
+                        Some(acceptor) => match acceptor.accept(stream).await {
+                            Ok(tls_stream) => Box::new(tls_stream),
+                            Err(e) => {
+                                tracing::warn!("tls handshake failed: {e}");
+                                return;
+                            }
+                        },
+                        None => Box::new(stream),
+                    };
+                    g.tell(NewClient(stream, peer_addr)).await.ok();
+                });
             }
+            () = &mut shutdown => break,
         }
-    });
+    }
+}
+
+pub fn call_reload_tls(id: ServerId) -> Result<(), String> {
+    let servers = servers();
+    let state = servers
+        .get(&id)
+        .ok_or_else(|| format!("{id} is not running"))?;
+    let tls = state
+        .tls
+        .as_ref()
+        .ok_or_else(|| "tls is not enabled".to_string())?;
 
-    *guard = Some(ServerState { stop_tx, _rt: rt });
+    let acceptor = load_acceptor(&tls.config)?;
+    *tls.acceptor.write().unwrap() = acceptor;
+    tracing::info!("tls certificate reloaded");
     Ok(())
 }
 
-pub fn call_stop_server() -> Result<(), String> {
-    let lock = STATE
-        .get()
-        .ok_or_else(|| "server was never started".to_string())?;
-    let mut guard = lock.lock().unwrap();
+pub fn get_server_stats(id: ServerId) -> Result<ServerStats, String> {
+    let (game, handle) = server(id)?;
+    block_on(&handle, collect_server_stats(&game))
+}
+
+/// The limits and features the server tells its clients about.
+pub fn get_effective_config(id: ServerId) -> Result<EffectiveConfig, String> {
+    let (game, handle) = server(id)?;
+    block_on(&handle, async { game.ask(GetEffectiveConfig).await }).map_err(|e| e.to_string())
+}
+
+/// The messages last sent to a client, oldest first, for chasing client and
+/// server getting out of step.
+pub fn get_client_outbox(id: ServerId, client_id: &str) -> Result<Vec<OutboxEntry>, String> {
+    let (game, handle) = server(id)?;
+    block_on(&handle, async {
+        game.ask(DumpOutbox(client_id.to_string())).await
+    })
+    .map_err(|e| e.to_string())
+}
 
-    let ServerState { stop_tx, .. } = guard
-        .take()
-        .ok_or_else(|| "server is not running".to_string())?;
+pub fn call_stop_server(id: ServerId) -> Result<(), String> {
+    let state = servers()
+        .remove(&id)
+        .ok_or_else(|| format!("{id} is not running"))?;
 
-    let _ = stop_tx.send(());
+    let _ = state.stop_tx.send(());
+    #[cfg(feature = "persistence")]
+    save_state(&state);
+    // sessions still open are dropped rather than waited for
+    state.rt.shutdown_background();
     Ok(())
 }
+
+/// Stops the server when only one is running, for embedders that never
+/// launch a second.
+pub fn call_stop_sole_server() -> Result<(), String> {
+    let ids: Vec<ServerId> = servers().keys().copied().collect();
+    match ids[..] {
+        [id] => call_stop_server(id),
+        [] => Err("server is not running".into()),
+        _ => Err(format!("{} servers are running", ids.len())),
+    }
+}
+
+/// Saves the room before the runtime goes away. A failed save is logged
+/// rather than returned, since the server stops either way.
+#[cfg(feature = "persistence")]
+fn save_state(state: &ServerState) {
+    if !state.saves_state {
+        return;
+    }
+    if let Err(e) = block_on(state.rt.handle(), async { state.game.ask(SaveState).await }) {
+        tracing::error!("can't save room state: {e}");
+    }
+}
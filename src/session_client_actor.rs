@@ -1,63 +1,329 @@
 // #region IMPORTS
-use crate::{data_types::*, game_actor::*, room_actor::*, tools::*, websocket_client_actor::*};
+use crate::{
+    data_types::*, game_actor::*, metrics::*, room_actor::*, tools::*, websocket_client_actor::*,
+};
 use kameo::{
     Actor,
-    actor::{Recipient, WeakActorRef},
+    actor::{ActorID, ActorRef, Recipient, WeakActorRef},
+    error::{ActorStopReason, Infallible, SendError},
     message::{Context, Message},
 };
+use std::{
+    collections::{BTreeSet, HashMap},
+    net::SocketAddr,
+    ops::ControlFlow,
+    time::Duration,
+};
+use tokio::time::Instant;
 use tracing::{debug, error, warn};
 use uuid::Uuid;
 // #endregion
 
 // #region ACTOR
-#[derive(Actor)]
 pub struct SessionClientActor {
     transport: Option<Recipient<ToTransport>>,
 
     pub_key: Option<String>,
+    algorithm: SignatureAlgorithm,
     sign_challenge: Option<Uuid>,
-    signature_verified: bool,
+    state: SessionState,
+    capabilities: BTreeSet<Capability>,
 
     game: WeakActorRef<GameActor>,
     room: Option<WeakActorRef<RoomActor>>,
+    peer_addr: Option<SocketAddr>,
+    /// Driven by a server-side bot rather than a connection.
+    is_bot: bool,
+    /// Requests waiting for their response, by correlation id.
+    in_flight: HashMap<Uuid, RequestTiming>,
+    /// Correlation ids of recent requests.
+    recent_ids: RecentIds,
+}
+
+/// Requests timed at once per session; more go untimed.
+const MAX_IN_FLIGHT: usize = 64;
+/// How long a request waits for its response before it is no longer timed.
+const IN_FLIGHT_EXPIRY: Duration = Duration::from_secs(60);
+
+impl Actor for SessionClientActor {
+    type Args = Self;
+    type Error = Infallible;
+
+    async fn on_start(session: Self::Args, _ar: ActorRef<Self>) -> Result<Self, Self::Error> {
+        Ok(session)
+    }
+
+    async fn on_link_died(
+        &mut self,
+        _ar: WeakActorRef<Self>,
+        id: ActorID,
+        reason: ActorStopReason,
+    ) -> Result<ControlFlow<ActorStopReason>, Self::Error> {
+        // the game puts clients of a crashed room into a new one, so the
+        // session has to outlive it unless the game is gone too
+        if self.room.as_ref().is_some_and(|r| r.id() == id) {
+            warn!("room of {} died: {reason:?}", self.peer());
+            self.room = None;
+            if self.game.upgrade().is_some() {
+                return Ok(ControlFlow::Continue(()));
+            }
+        } else if id == self.game.id() && !matches!(reason, ActorStopReason::Normal) {
+            // a room tells its clients and then takes them down with it
+            if self.room.as_ref().is_some_and(|r| r.upgrade().is_some()) {
+                return Ok(ControlFlow::Continue(()));
+            }
+            let notif = TransportMsg::OutNotifServerError(TransportEnvelope {
+                correlation_id: Uuid::new_v4(),
+                seq: None,
+                payload: OutNotifServerError {
+                    message: "internal error".into(),
+                },
+            });
+            self.send_transport(notif).await;
+        }
+
+        match reason {
+            ActorStopReason::Normal => Ok(ControlFlow::Continue(())),
+            reason => Ok(ControlFlow::Break(ActorStopReason::LinkDied {
+                id,
+                reason: Box::new(reason),
+            })),
+        }
+    }
 }
 
 impl SessionClientActor {
-    pub fn new(game: WeakActorRef<GameActor>) -> Self {
+    pub fn new(game: WeakActorRef<GameActor>, peer_addr: Option<SocketAddr>) -> Self {
         Self {
             transport: None,
             pub_key: None,
+            algorithm: SignatureAlgorithm::default(),
             sign_challenge: None,
-            signature_verified: false,
+            state: SessionState::AwaitingKey,
+            capabilities: BTreeSet::new(),
             game,
             room: None,
+            peer_addr,
+            is_bot: false,
+            in_flight: HashMap::new(),
+            recent_ids: RecentIds::new(0, Duration::ZERO),
         }
     }
 
+    /// Turns away requests that reuse one of the correlation ids in
+    /// `recent_ids`.
+    pub fn with_recent_ids(mut self, recent_ids: RecentIds) -> Self {
+        self.recent_ids = recent_ids;
+        self
+    }
+
+    /// A session for a bot player, which registers as one.
+    pub fn bot(game: WeakActorRef<GameActor>) -> Self {
+        Self {
+            is_bot: true,
+            ..Self::new(game, None)
+        }
+    }
+
+    fn peer(&self) -> String {
+        self.peer_addr
+            .map_or_else(|| "local".to_string(), |addr| addr.to_string())
+    }
+
     async fn send(&self, msg: ToTransport) {
         if let Some(tx) = &self.transport {
             tx.tell(msg).await.ok();
         }
     }
 
-    pub async fn send_transport(&self, ws: TransportMsg) {
-        self.send(ToTransport::TransportMsg(ws)).await;
+    pub async fn send_transport(&mut self, ws: TransportMsg) {
+        let timing = response_id(&ws).and_then(|id| self.in_flight.remove(&id));
+        match timing {
+            Some(timing) => self.send(ToTransport::Response(Box::new(ws), timing)).await,
+            None => self.send(ToTransport::TransportMsg(Box::new(ws))).await,
+        }
+    }
+
+    /// Notes when a request arrived, to time its response. Requests never
+    /// answered are dropped once they expire.
+    fn track_request(&mut self, message_type: &str, correlation_id: Uuid, received_at: Instant) {
+        self.in_flight
+            .retain(|_, timing| received_at.duration_since(timing.received_at) < IN_FLIGHT_EXPIRY);
+        if self.in_flight.len() >= MAX_IN_FLIGHT {
+            return;
+        }
+        self.in_flight.insert(
+            correlation_id,
+            RequestTiming {
+                message_type: message_type.to_string(),
+                received_at,
+            },
+        );
+    }
+
+    async fn send_status<P>(&mut self, env: &TransportEnvelope<P>, status: &str) {
+        self.reply_status(env.correlation_id, status).await;
     }
 
-    async fn send_status<P>(&self, env: &TransportEnvelope<P>, status: &str) {
+    async fn reply_status(&mut self, correlation_id: Uuid, status: &str) {
         let ws = TransportMsg::OutRespStatus(TransportEnvelope {
-            correlation_id: env.correlation_id,
+            correlation_id,
+            seq: None,
             payload: OutRespStatus {
                 status: status.to_string(),
+                sequence: None,
             },
         });
 
-        self.send(ToTransport::TransportMsg(ws)).await;
+        self.send_transport(ws).await;
+    }
+
+    async fn send_session_token(&mut self, key: String) {
+        let Some(game) = self.game.upgrade() else {
+            return;
+        };
+        match game.ask(IssueSessionToken(key)).await {
+            Ok(token) => {
+                let notif = TransportMsg::OutNotifSessionToken(TransportEnvelope {
+                    correlation_id: Uuid::new_v4(),
+                    seq: None,
+                    payload: token,
+                });
+                self.send_transport(notif).await;
+            }
+            Err(e) => error!("can't issue session token: {e}"),
+        }
     }
 
     fn current_challenge_str(&self) -> Option<String> {
         self.sign_challenge.map(|u| u.to_string())
     }
+
+    /// What the rest of the server knows this client's key by.
+    fn key_identity(&self) -> Option<String> {
+        self.pub_key
+            .as_deref()
+            .map(|key| key_identity(self.algorithm, key))
+    }
+}
+// #endregion
+
+// #region TYPES
+/// Handshake progress of a session. Each step unlocks more messages, so the
+/// key/signature exchange cannot be skipped or reordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SessionState {
+    AwaitingKey,
+    AwaitingSignature,
+    Verified,
+    Registered,
+}
+
+impl SessionState {
+    /// Returns the status to reply with when `msg` is not allowed yet.
+    pub fn permits(self, msg: &TransportMsg) -> Result<(), &'static str> {
+        let required = match msg {
+            TransportMsg::InReqSendPublicKey(_) | TransportMsg::InReqResumeSession(_)
+                if self >= SessionState::Verified =>
+            {
+                return Err("already verified");
+            }
+            TransportMsg::InReqSendPublicKey(_) | TransportMsg::InReqResumeSession(_) => {
+                return Ok(());
+            }
+            // clients check the limits before they commit to a key
+            TransportMsg::InReqServerConfig(_) => return Ok(()),
+            TransportMsg::InReqVerifySignature(_) if self >= SessionState::Verified => {
+                return Err("already verified");
+            }
+            TransportMsg::InReqVerifySignature(_) => SessionState::AwaitingSignature,
+            TransportMsg::InReqRegisterClient(_)
+            | TransportMsg::InReqServerStats(_)
+            | TransportMsg::InReqDebugOutbox(_)
+            | TransportMsg::InReqDebugCapture(_) => SessionState::Verified,
+            _ => SessionState::Registered,
+        };
+
+        if self >= required {
+            return Ok(());
+        }
+        Err(match self {
+            SessionState::AwaitingKey => "awaiting public key",
+            SessionState::AwaitingSignature => "awaiting signature",
+            _ => "not registered",
+        })
+    }
+}
+
+/// Where a client message goes once the session has let it through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Destination {
+    /// The key/signature exchange, answered by the session itself.
+    Session,
+    Game,
+    /// The client's room; answered "no room" when it isn't in one.
+    Room,
+    /// Nothing a client sends; answered "unknown message".
+    Unknown,
+}
+
+pub fn route(msg: &TransportMsg) -> Destination {
+    match msg {
+        TransportMsg::InReqSendPublicKey(_) | TransportMsg::InReqVerifySignature(_) => {
+            Destination::Session
+        }
+
+        TransportMsg::InReqResumeSession(_)
+        | TransportMsg::InReqRegisterClient(_)
+        | TransportMsg::InReqRenameClient(_)
+        | TransportMsg::InReqSendLobbyChat(_)
+        | TransportMsg::InReqSubscribeRoomList(_)
+        | TransportMsg::InReqUnsubscribeRoomList(_)
+        | TransportMsg::InReqServerStats(_)
+        | TransportMsg::InReqServerConfig(_)
+        | TransportMsg::InReqDebugOutbox(_)
+        | TransportMsg::InReqDebugCapture(_)
+        | TransportMsg::InReqWordPartList(_)
+        | TransportMsg::InReqFindPlayer(_)
+        | TransportMsg::InReqSetPresenceVisibility(_) => Destination::Game,
+
+        TransportMsg::InReqSetModerationSettings(_)
+        | TransportMsg::InReqClientList(_)
+        | TransportMsg::InReqPatchGameSettings(_)
+        | TransportMsg::InReqSendGameSettings(_)
+        | TransportMsg::InReqSendChat(_)
+        | TransportMsg::InReqSendWhisper(_)
+        | TransportMsg::InReqSetReady(_)
+        | TransportMsg::InReqSetRoomPassword(_)
+        | TransportMsg::InReqSetHandicap(_)
+        | TransportMsg::InReqMakeAdmin(_)
+        | TransportMsg::InReqSetAdminSuccessor(_)
+        | TransportMsg::InReqSetSubscriptions(_)
+        | TransportMsg::InReqSetQuestionProvider(_)
+        | TransportMsg::InReqAddBot(_)
+        | TransportMsg::InReqRemoveBot(_)
+        | TransportMsg::InReqFetchAsset(_)
+        | TransportMsg::InReqMuteClient(_)
+        | TransportMsg::InReqRoomInfo(_)
+        | TransportMsg::InReqSetRoomName(_)
+        | TransportMsg::InReqGameReplay(_)
+        | TransportMsg::InReqRoomEvents(_)
+        | TransportMsg::InReqExportGame(_)
+        | TransportMsg::InReqExtendRound(_)
+        | TransportMsg::InReqSendAnnouncement(_)
+        | TransportMsg::InReqRematch(_)
+        | TransportMsg::InReqLeaveRoom(_)
+        | TransportMsg::InReqResumeGame(_)
+        | TransportMsg::InReqSendReaction(_)
+        | TransportMsg::InReqRoundState(_)
+        | TransportMsg::InReqStartGame(_)
+        | TransportMsg::InRespQuestion(_)
+        | TransportMsg::InRespQuestionError(_)
+        | TransportMsg::InReqSendAnswer(_)
+        | TransportMsg::InReqStopGame(_) => Destination::Room,
+
+        _ => Destination::Unknown,
+    }
 }
 // #endregion
 
@@ -85,7 +351,49 @@ pub struct SendWs(pub TransportMsg);
 impl Message<SendWs> for SessionClientActor {
     type Reply = ();
     async fn handle(&mut self, SendWs(ws): SendWs, _ctx: &mut Context<Self, Self::Reply>) {
-        self.send(ToTransport::TransportMsg(ws)).await;
+        self.send_transport(ws).await;
+    }
+}
+
+/// A message from the client, stamped with when it arrived.
+pub struct Inbound {
+    pub msg: TransportMsg,
+    pub received_at: Instant,
+}
+
+impl From<TransportMsg> for Inbound {
+    /// For senders that don't time their messages, like local clients.
+    fn from(msg: TransportMsg) -> Self {
+        Self {
+            msg,
+            received_at: Instant::now(),
+        }
+    }
+}
+
+impl Message<Inbound> for SessionClientActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        Inbound { msg, received_at }: Inbound,
+        ctx: &mut Context<Self, Self::Reply>,
+    ) {
+        if let Some((message_type, correlation_id)) = request_head(&msg) {
+            // registering is safe to repeat and answered again instead
+            let repeatable = matches!(msg, TransportMsg::InReqRegisterClient(_));
+            if !self.recent_ids.insert(correlation_id) && !repeatable {
+                warn!(
+                    "duplicate correlation id {correlation_id} in {message_type} from {}",
+                    self.peer()
+                );
+                self.reply_status(correlation_id, "duplicate correlation id")
+                    .await;
+                return;
+            }
+            self.track_request(message_type, correlation_id, received_at);
+        }
+        self.dispatch(msg, ctx).await;
     }
 }
 
@@ -93,27 +401,91 @@ impl Message<TransportMsg> for SessionClientActor {
     type Reply = ();
 
     async fn handle(&mut self, msg: TransportMsg, ctx: &mut Context<Self, Self::Reply>) {
+        <Self as Message<Inbound>>::handle(self, msg.into(), ctx).await;
+    }
+}
+
+impl SessionClientActor {
+    async fn dispatch(&mut self, msg: TransportMsg, ctx: &mut Context<Self, ()>) {
+        if let Err(status) = self.state.permits(&msg) {
+            warn!("{status}: rejected message from {}", self.peer());
+            self.reply_to(&msg, status).await;
+            return;
+        }
+
+        let session = ctx.actor_ref().clone();
+        match route(&msg) {
+            Destination::Session => self.handle_handshake(msg).await,
+            Destination::Game => {
+                let Some(game) = self.game.upgrade() else {
+                    warn!("game actor gone");
+                    self.reply_to(&msg, "error").await;
+                    return;
+                };
+                self.handle_game(msg, game, session).await;
+            }
+            Destination::Room => {
+                let Some(room) = self.room.as_ref().and_then(|r| r.upgrade()) else {
+                    warn!("message from {} needs a room it isn't in", self.peer());
+                    self.reply_to(&msg, "no room").await;
+                    return;
+                };
+                Self::handle_room(msg, room, session).await;
+            }
+            Destination::Unknown => {
+                error!("Unknown message: {msg:?}");
+                self.reply_to(&msg, "unknown message").await;
+            }
+        }
+    }
+
+    /// Status reply for a message that can't go where it was routed.
+    async fn reply_to(&mut self, msg: &TransportMsg, status: &str) {
+        if let Some(correlation_id) = correlation_id(msg) {
+            self.reply_status(correlation_id, status).await;
+        }
+    }
+
+    async fn handle_handshake(&mut self, msg: TransportMsg) {
         match msg {
             TransportMsg::InReqSendPublicKey(env) => {
                 debug!("IN_REQ_sendPublicKey {}", env.payload.key);
 
-                if self.signature_verified {
-                    warn!("signature already verified");
-                    self.send_status(&env, "signature already verified").await;
+                if !env.payload.algorithm.is_supported() {
+                    warn!("unsupported key algorithm from {}", self.peer());
+                    let supported: Vec<_> = SignatureAlgorithm::SUPPORTED
+                        .iter()
+                        .map(|a| a.as_str())
+                        .collect();
+                    let status = format!(
+                        "unsupported algorithm, expected one of: {}",
+                        supported.join(", ")
+                    );
+                    self.send_status(&env, &status).await;
+                    return;
                 }
 
                 self.pub_key = Some(env.payload.key.clone());
+                self.algorithm = env.payload.algorithm;
+                self.state = SessionState::AwaitingSignature;
+                self.capabilities = Capability::negotiate(&env.payload.capabilities);
+                if env.payload.encoding != Encoding::Json {
+                    self.send(ToTransport::SetEncoding(env.payload.encoding))
+                        .await;
+                }
 
                 let challenge = Uuid::new_v4();
                 self.sign_challenge = Some(challenge);
 
                 let resp = TransportMsg::OutRespSignMessage(TransportEnvelope {
                     correlation_id: env.correlation_id,
+                    seq: None,
                     payload: OutRespSignMessage {
                         message: challenge.to_string(),
+                        capabilities: self.capabilities.iter().copied().collect(),
                     },
                 });
-                self.send(ToTransport::TransportMsg(resp)).await;
+                self.send_transport(resp).await;
             }
 
             TransportMsg::InReqVerifySignature(env) => {
@@ -130,160 +502,679 @@ impl Message<TransportMsg> for SessionClientActor {
                     return;
                 };
 
-                let is_ok = match verify_signature(&challenge, &env.payload.signature, &key) {
+                let is_ok = match verify_signature(
+                    self.algorithm,
+                    &challenge,
+                    &env.payload.signature,
+                    &key,
+                ) {
                     Ok(ok) => ok,
                     Err(e) => {
-                        warn!("verify_signature error: {e}");
+                        warn!("verify_signature error from {}: {e}", self.peer());
                         self.send_status(&env, "error").await;
                         false
                     }
                 };
 
-                self.signature_verified = is_ok;
+                if is_ok {
+                    self.state = SessionState::Verified;
+                } else {
+                    warn!("signature rejected from {}", self.peer());
+                }
 
                 let status_text = if is_ok { "success" } else { "error" }.to_string();
                 let resp = TransportMsg::OutRespStatus(TransportEnvelope {
                     correlation_id: env.correlation_id,
+                    seq: None,
                     payload: OutRespStatus {
                         status: status_text,
+                        sequence: None,
                     },
                 });
-                self.send(ToTransport::TransportMsg(resp)).await;
+                self.send_transport(resp).await;
+                if is_ok {
+                    self.send_session_token(key_identity(self.algorithm, &key))
+                        .await;
+                }
             }
 
-            TransportMsg::InReqRegisterClient(env) => {
-                debug!("IN_REQ_registerClient {}", env.payload.name);
-                if !self.signature_verified {
-                    warn!("register requested before signature verified");
-                    self.send_status(&env, "error").await;
-                    return;
+            _ => unreachable!("not a handshake message"),
+        }
+    }
+
+    async fn handle_game(
+        &mut self,
+        msg: TransportMsg,
+        game: ActorRef<GameActor>,
+        session: ActorRef<Self>,
+    ) {
+        match msg {
+            TransportMsg::InReqResumeSession(env) => {
+                debug!("IN_REQ_resumeSession");
+
+                if env.payload.encoding != Encoding::Json {
+                    self.send(ToTransport::SetEncoding(env.payload.encoding))
+                        .await;
                 }
 
-                let Some(game) = self.game.upgrade() else {
-                    warn!("game actor gone");
-                    self.send_status(&env, "error").await;
-                    return;
+                let key = match game.ask(CheckSessionToken(env.payload.token.clone())).await {
+                    Ok(key) => key,
+                    Err(SendError::HandlerError(status)) => {
+                        warn!("session token rejected from {}: {status}", self.peer());
+                        self.send_status(&env, status).await;
+                        return;
+                    }
+                    Err(e) => {
+                        error!("can't check session token: {e}");
+                        self.send_status(&env, "error").await;
+                        return;
+                    }
                 };
-                let Some(key) = self.pub_key.clone() else {
+
+                let (algorithm, pub_key) = split_key_identity(&key);
+                self.pub_key = Some(pub_key.to_string());
+                self.algorithm = algorithm;
+                self.sign_challenge = None;
+                self.state = SessionState::Verified;
+                self.capabilities = Capability::negotiate(&env.payload.capabilities);
+                self.send_status(&env, "success").await;
+                self.send_session_token(key).await;
+            }
+
+            TransportMsg::InReqRegisterClient(env) => {
+                debug!("IN_REQ_registerClient {}", env.payload.name);
+
+                let Some(key) = self.key_identity() else {
                     warn!("no public key");
                     self.send_status(&env, "no public key").await;
                     return;
                 };
 
                 let req = RegisterClientRequest {
-                    session: ctx.actor_ref().clone(),
+                    session,
                     name: env.payload.name.clone(),
+                    spectator: env.payload.spectator,
+                    password: env.payload.password.clone(),
                     pub_key: key,
+                    capabilities: self.capabilities.clone(),
+                    is_bot: self.is_bot,
                     correlation_id: env.correlation_id,
                 };
                 game.tell(req).await.ok();
             }
 
+            TransportMsg::InReqRenameClient(env) => {
+                debug!("IN_REQ_renameClient {}", env.payload.name);
+
+                game.tell(RenameClientRequest {
+                    session,
+                    name: env.payload.name.clone(),
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSendLobbyChat(env) => {
+                debug!("IN_REQ_sendLobbyChat");
+                game.tell(SendLobbyChatRequest {
+                    session,
+                    message: env.payload.message,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSubscribeRoomList(env) => {
+                debug!("IN_REQ_subscribeRoomList");
+                game.tell(SubscribeRoomListRequest {
+                    session,
+                    subscribe: true,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqUnsubscribeRoomList(env) => {
+                debug!("IN_REQ_unsubscribeRoomList");
+                game.tell(SubscribeRoomListRequest {
+                    session,
+                    subscribe: false,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqServerStats(env) => {
+                debug!("IN_REQ_serverStats");
+                let key = self.key_identity().unwrap_or_default();
+                if !game.ask(MayReadStats(key.clone())).await.unwrap_or(false) {
+                    warn!("server stats refused for {}", self.peer());
+                    self.send_status(&env, "not operator").await;
+                    return;
+                }
+                // client addresses are for operators only
+                let operator = game.ask(IsOperator(key)).await.unwrap_or(false);
+
+                match collect_server_stats(&game).await {
+                    Ok(mut stats) => {
+                        if !operator {
+                            stats.connections_per_ip.clear();
+                        }
+                        let resp = TransportMsg::OutRespServerStats(TransportEnvelope {
+                            correlation_id: env.correlation_id,
+                            seq: None,
+                            payload: OutRespServerStats { stats },
+                        });
+                        self.send_transport(resp).await;
+                    }
+                    Err(e) => {
+                        warn!("server stats error: {e}");
+                        self.send_status(&env, "error").await;
+                    }
+                }
+            }
+
+            TransportMsg::InReqServerConfig(env) => {
+                debug!("IN_REQ_serverConfig");
+                match game.ask(GetEffectiveConfig).await {
+                    Ok(config) => {
+                        let resp = TransportMsg::OutRespServerConfig(TransportEnvelope {
+                            correlation_id: env.correlation_id,
+                            seq: None,
+                            payload: OutRespServerConfig { config },
+                        });
+                        self.send_transport(resp).await;
+                    }
+                    Err(e) => {
+                        warn!("server config error: {e}");
+                        self.send_status(&env, "error").await;
+                    }
+                }
+            }
+
+            TransportMsg::InReqDebugOutbox(env) => {
+                debug!("IN_REQ_debugOutbox");
+                let key = self.key_identity().unwrap_or_default();
+                if !game.ask(IsOperator(key)).await.unwrap_or(false) {
+                    warn!("outbox dump refused for {}", self.peer());
+                    self.send_status(&env, "not operator").await;
+                    return;
+                }
+
+                let client_id = env.payload.client_id.clone();
+                match game.ask(DebugOutbox(client_id.clone())).await {
+                    Ok(entries) => {
+                        let resp = TransportMsg::OutRespDebugOutbox(TransportEnvelope {
+                            correlation_id: env.correlation_id,
+                            seq: None,
+                            payload: OutRespDebugOutbox { client_id, entries },
+                        });
+                        self.send_transport(resp).await;
+                    }
+                    Err(SendError::HandlerError(status)) => self.send_status(&env, status).await,
+                    Err(e) => {
+                        warn!("outbox dump error: {e}");
+                        self.send_status(&env, "error").await;
+                    }
+                }
+            }
+
+            TransportMsg::InReqDebugCapture(env) => {
+                debug!("IN_REQ_debugCapture {:?}", env.payload);
+                let key = self.key_identity().unwrap_or_default();
+                if !game.ask(IsOperator(key)).await.unwrap_or(false) {
+                    warn!("capture refused for {}", self.peer());
+                    self.send_status(&env, "not operator").await;
+                    return;
+                }
+
+                let request = DebugCapture {
+                    client_id: env.payload.client_id.clone(),
+                    enabled: env.payload.enabled,
+                };
+                match game.ask(request).await {
+                    Ok(()) => self.send_status(&env, "success").await,
+                    Err(SendError::HandlerError(status)) => self.send_status(&env, status).await,
+                    Err(e) => {
+                        warn!("capture error: {e}");
+                        self.send_status(&env, "error").await;
+                    }
+                }
+            }
+
+            TransportMsg::InReqWordPartList(env) => {
+                debug!("IN_REQ_wordPartList");
+                match game.ask(WordPartList).await {
+                    Ok(word_parts) => {
+                        let resp = TransportMsg::OutRespWordPartList(TransportEnvelope {
+                            correlation_id: env.correlation_id,
+                            seq: None,
+                            payload: OutRespWordPartList { word_parts },
+                        });
+                        self.send_transport(resp).await;
+                    }
+                    Err(e) => {
+                        warn!("word part list error: {e}");
+                        self.send_status(&env, "error").await;
+                    }
+                }
+            }
+
+            TransportMsg::InReqFindPlayer(env) => {
+                debug!("IN_REQ_findPlayer {}", env.payload.player_id_or_name);
+                game.tell(FindPlayerRequest {
+                    session,
+                    player_id_or_name: env.payload.player_id_or_name,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSetPresenceVisibility(env) => {
+                debug!("IN_REQ_setPresenceVisibility {}", env.payload.visible);
+                game.tell(SetPresenceVisibilityRequest {
+                    session,
+                    visible: env.payload.visible,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            _ => unreachable!("not a game message"),
+        }
+    }
+
+    async fn handle_room(msg: TransportMsg, room: ActorRef<RoomActor>, requester: ActorRef<Self>) {
+        match msg {
+            TransportMsg::InReqSetModerationSettings(env) => {
+                debug!("IN_REQ_setModerationSettings {:?}", env.payload.action);
+                room.tell(SetModerationSettingsRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    action: env.payload.action,
+                })
+                .await
+                .ok();
+            }
+
             TransportMsg::InReqClientList(env) => {
                 debug!("IN_REQ_clientList");
-                if let Some(room) = self.room.as_ref().and_then(|r| r.upgrade()) {
-                    room.tell(ClientListRequest {
-                        requester: ctx.actor_ref().clone(),
-                        correlation_id: env.correlation_id,
-                    })
-                    .await
-                    .ok();
-                } else {
-                    warn!("client asked for clientList but has no room");
-                    self.send_status(&env, "no room").await;
-                }
+                room.tell(ClientListRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqPatchGameSettings(env) => {
+                debug!("IN_REQ_patchGameSettings");
+                room.tell(PatchGameSettingsRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    patch: env.payload.game_settings.clone(),
+                })
+                .await
+                .ok();
             }
 
             TransportMsg::InReqSendGameSettings(env) => {
                 debug!("IN_REQ_sendGameSettings");
-                if let Some(room) = self.room.as_ref().and_then(|r| r.upgrade()) {
-                    room.tell(SetGameSettingsRequest {
-                        requester: ctx.actor_ref().clone(),
-                        correlation_id: env.correlation_id,
-                        game_settings: env.payload.game_settings.clone(),
-                    })
-                    .await
-                    .ok();
-                } else {
-                    warn!("client asked to change settings but has no room");
-                    self.send_status(&env, "no room").await;
-                }
+                room.tell(SetGameSettingsRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    game_settings: env.payload.game_settings.clone(),
+                })
+                .await
+                .ok();
             }
 
             TransportMsg::InReqSendChat(env) => {
                 debug!("IN_REQ_sendChat");
-                if let Some(room) = self.room.as_ref().and_then(|r| r.upgrade()) {
-                    room.tell(SendChatRequest {
-                        requester: ctx.actor_ref().clone(),
-                        correlation_id: env.correlation_id,
-                        message: env.payload.message.clone(),
-                    })
-                    .await
-                    .ok();
-                } else {
-                    warn!("client asked to sendChat but has no room");
-                    self.send_status(&env, "no room").await;
-                }
+                room.tell(SendChatRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    message: env.payload.message.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSendWhisper(env) => {
+                debug!("IN_REQ_sendWhisper");
+                room.tell(SendWhisperRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    target_id: env.payload.target_id.clone(),
+                    message: env.payload.message.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSetReady(env) => {
+                debug!("IN_REQ_setReady {}", env.payload.ready);
+                room.tell(SetReadyRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    ready: env.payload.ready,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSetRoomPassword(env) => {
+                debug!("IN_REQ_setRoomPassword");
+                room.tell(SetRoomPasswordRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    password: env.payload.password.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSetHandicap(env) => {
+                debug!("IN_REQ_setHandicap {}", env.payload.client_id);
+                room.tell(SetHandicapRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    client_id: env.payload.client_id.clone(),
+                    extra_time_ms: env.payload.extra_time_ms,
+                    score_multiplier: env.payload.score_multiplier,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqMakeAdmin(env) => {
+                debug!(
+                    "IN_REQ_makeAdmin {} {}",
+                    env.payload.client_id, env.payload.revoke_existing
+                );
+                room.tell(MakeAdminRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    client_id: env.payload.client_id.clone(),
+                    revoke_existing: env.payload.revoke_existing,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSetAdminSuccessor(env) => {
+                debug!("IN_REQ_setAdminSuccessor {:?}", env.payload.client_id);
+                room.tell(SetAdminSuccessorRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    client_id: env.payload.client_id.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSetSubscriptions(env) => {
+                debug!("IN_REQ_setSubscriptions {:?}", env.payload.categories);
+                room.tell(SetSubscriptionsRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    categories: env.payload.categories.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSetQuestionProvider(env) => {
+                debug!("IN_REQ_setQuestionProvider {}", env.payload.client_id);
+                room.tell(SetQuestionProviderRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    client_id: env.payload.client_id.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqAddBot(env) => {
+                debug!("IN_REQ_addBot {:?}", env.payload.difficulty);
+                room.tell(AddBotRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    difficulty: env.payload.difficulty,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqRemoveBot(env) => {
+                debug!("IN_REQ_removeBot {}", env.payload.client_id);
+                room.tell(RemoveBotRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    client_id: env.payload.client_id.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqFetchAsset(env) => {
+                debug!("IN_REQ_fetchAsset {}", env.payload.asset_id);
+                room.tell(FetchAssetRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    asset_id: env.payload.asset_id.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqMuteClient(env) => {
+                debug!(
+                    "IN_REQ_muteClient {} {}",
+                    env.payload.client_id, env.payload.muted
+                );
+                room.tell(MuteClientRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    client_id: env.payload.client_id.clone(),
+                    muted: env.payload.muted,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqRoomInfo(env) => {
+                debug!("IN_REQ_roomInfo");
+                room.tell(RoomInfoRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSetRoomName(env) => {
+                debug!("IN_REQ_setRoomName {}", env.payload.name);
+                room.tell(SetRoomNameRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    name: env.payload.name.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqGameReplay(env) => {
+                debug!("IN_REQ_gameReplay");
+                room.tell(GameReplayRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqRoomEvents(env) => {
+                debug!("IN_REQ_roomEvents");
+                room.tell(RoomEventsRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqExportGame(env) => {
+                debug!("IN_REQ_exportGame {}", env.payload.game_index);
+                room.tell(ExportGameRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    game_index: env.payload.game_index,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqExtendRound(env) => {
+                debug!("IN_REQ_extendRound {}", env.payload.extra_seconds);
+                room.tell(ExtendRoundRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    extra_seconds: env.payload.extra_seconds,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSendAnnouncement(env) => {
+                debug!("IN_REQ_sendAnnouncement");
+                room.tell(SendAnnouncementRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    message: env.payload.message.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqRematch(env) => {
+                debug!("IN_REQ_rematch");
+                room.tell(RematchRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    shuffle_settings: env.payload.shuffle_settings.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqLeaveRoom(env) => {
+                debug!("IN_REQ_leaveRoom");
+                room.tell(LeaveRoomRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqResumeGame(env) => {
+                debug!("IN_REQ_resumeGame");
+                room.tell(ResumeGameRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqSendReaction(env) => {
+                debug!("IN_REQ_sendReaction");
+                room.tell(SendReactionRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    target: env.payload.target,
+                    emoji: env.payload.emoji.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InReqRoundState(env) => {
+                debug!("IN_REQ_roundState");
+                room.tell(RoundStateRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
             }
 
             TransportMsg::InReqStartGame(env) => {
                 debug!("IN_REQ_startGame");
-                if let Some(room) = self.room.as_ref().and_then(|r| r.upgrade()) {
-                    room.tell(StartGameRequest {
-                        requester: ctx.actor_ref().clone(),
-                        correlation_id: env.correlation_id,
-                        game_settings: env.payload.game_settings.clone(),
-                    })
-                    .await
-                    .ok();
-                } else {
-                    self.send_status(&env, "no room").await;
-                }
+                room.tell(StartGameRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    game_settings: env.payload.game_settings.clone(),
+                })
+                .await
+                .ok();
             }
 
             TransportMsg::InRespQuestion(env) => {
                 debug!("IN_RESP_question");
-                if let Some(room) = self.room.as_ref().and_then(|r| r.upgrade()) {
-                    room.tell(ProvideQuestionResponse {
-                        requester: ctx.actor_ref().clone(),
-                        correlation_id: env.correlation_id,
-                        question_info: env.payload.question.clone(),
-                        question_svg: env.payload.question_svg.clone(),
-                    })
-                    .await
-                    .ok();
-                }
+                room.tell(ProvideQuestionResponse {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    question_info: env.payload.question.clone(),
+                    question_svg: env.payload.question_svg.clone(),
+                })
+                .await
+                .ok();
+            }
+
+            TransportMsg::InRespQuestionError(env) => {
+                debug!("IN_RESP_questionError");
+                room.tell(QuestionErrorResponse {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    error_code: env.payload.error_code,
+                    message: env.payload.message.clone(),
+                })
+                .await
+                .ok();
             }
 
             TransportMsg::InReqSendAnswer(env) => {
                 debug!("IN_REQ_sendAnswer");
-                if let Some(room) = self.room.as_ref().and_then(|r| r.upgrade()) {
-                    room.tell(SendAnswerRequest {
-                        requester: ctx.actor_ref().clone(),
-                        correlation_id: env.correlation_id,
-                        answer: env.payload.answer.clone(),
-                    })
-                    .await
-                    .ok();
-                } else {
-                    self.send_status(&env, "no room").await;
-                }
+                room.tell(SendAnswerRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                    answer: env.payload.answer.clone(),
+                    question_id: env.payload.question_id,
+                    game_id: env.payload.game_id,
+                })
+                .await
+                .ok();
             }
 
             TransportMsg::InReqStopGame(env) => {
-                if let Some(room) = self.room.as_ref().and_then(|r| r.upgrade()) {
-                    room.tell(StopGameRequest {
-                        requester: ctx.actor_ref().clone(),
-                        correlation_id: env.correlation_id,
-                    })
-                    .await
-                    .ok();
-                } else {
-                    self.send_status(&env, "no room").await;
-                }
+                debug!("IN_REQ_stopGame");
+                room.tell(StopGameRequest {
+                    requester,
+                    correlation_id: env.correlation_id,
+                })
+                .await
+                .ok();
             }
 
-            _ => error!("Unknown message: {msg:?}"),
+            _ => unreachable!("not a room message"),
         }
     }
 }
@@ -301,6 +1192,25 @@ impl Message<SetRoom> for SessionClientActor {
     type Reply = ();
     async fn handle(&mut self, SetRoom(room): SetRoom, _ctx: &mut Context<Self, ()>) {
         self.room = Some(room);
+        self.state = SessionState::Registered;
+    }
+}
+
+/// The room the session is in, if any.
+pub struct GetRoom;
+impl Message<GetRoom> for SessionClientActor {
+    type Reply = Option<WeakActorRef<RoomActor>>;
+    async fn handle(&mut self, _: GetRoom, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        self.room.clone()
+    }
+}
+
+/// The client left its room but stays registered, so it can join again.
+pub struct LeftRoom;
+impl Message<LeftRoom> for SessionClientActor {
+    type Reply = ();
+    async fn handle(&mut self, _: LeftRoom, _ctx: &mut Context<Self, ()>) {
+        self.room = None;
     }
 }
 // #endregion
@@ -0,0 +1,112 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::data_types::{AnswerKind, PlayerSummary, RoundRecord, TierSummary};
+
+#[derive(Default)]
+struct Tally {
+    rounds: u64,
+    attempts: u64,
+    correct: u64,
+    correct_time: u64,
+    streak: u64,
+    longest_streak: u64,
+}
+
+/// Builds end-of-game summaries from the recorded rounds. A player only
+/// counts the rounds they have an answer in, so late joiners are not
+/// penalised for rounds they missed. Accuracy is over submitted answers, so
+/// timeouts do not count as attempts. Ties share a rank ("1224" ranking).
+pub fn player_summaries(
+    rounds: &[RoundRecord],
+    is_connected: impl Fn(&str) -> bool,
+) -> Vec<PlayerSummary> {
+    let mut tallies: HashMap<&str, Tally> = HashMap::new();
+    for round in rounds {
+        for answer in &round.answers {
+            let tally = tallies.entry(answer.id.as_str()).or_default();
+            tally.rounds += 1;
+            if answer.kind == AnswerKind::Answered {
+                tally.attempts += 1;
+            }
+            if answer.is_correct {
+                tally.correct += 1;
+                tally.correct_time += answer.answer_time;
+                tally.streak += 1;
+                tally.longest_streak = tally.longest_streak.max(tally.streak);
+            } else {
+                tally.streak = 0;
+            }
+        }
+    }
+
+    let mut summaries: Vec<PlayerSummary> = tallies
+        .into_iter()
+        .map(|(id, t)| PlayerSummary {
+            id: id.to_string(),
+            rounds_played: t.rounds,
+            correct_answers: t.correct,
+            accuracy: if t.attempts > 0 {
+                t.correct as f64 * 100.0 / t.attempts as f64
+            } else {
+                0.0
+            },
+            average_correct_time: (t.correct > 0).then(|| t.correct_time / t.correct),
+            longest_streak: t.longest_streak,
+            rank: 0,
+            disconnected: !is_connected(id),
+        })
+        .collect();
+
+    summaries.sort_by(|a, b| {
+        b.correct_answers
+            .cmp(&a.correct_answers)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    let mut rank = 0;
+    let mut prev_correct = None;
+    for (i, summary) in summaries.iter_mut().enumerate() {
+        if prev_correct != Some(summary.correct_answers) {
+            rank = i as u64 + 1;
+            prev_correct = Some(summary.correct_answers);
+        }
+        summary.rank = rank;
+    }
+
+    summaries
+}
+
+/// Per-tier totals over every answer in rounds that fell into a tier, by
+/// tier name. Accuracy counts submitted answers, as for players.
+pub fn tier_summaries(rounds: &[RoundRecord]) -> Vec<TierSummary> {
+    let mut tallies: BTreeMap<&str, Tally> = BTreeMap::new();
+    for round in rounds {
+        let Some(tier) = &round.tier else {
+            continue;
+        };
+        let tally = tallies.entry(tier.as_str()).or_default();
+        tally.rounds += 1;
+        for answer in &round.answers {
+            if answer.kind == AnswerKind::Answered {
+                tally.attempts += 1;
+            }
+            if answer.is_correct {
+                tally.correct += 1;
+            }
+        }
+    }
+
+    tallies
+        .into_iter()
+        .map(|(tier, t)| TierSummary {
+            tier: tier.to_string(),
+            rounds: t.rounds,
+            correct_answers: t.correct,
+            accuracy: if t.attempts > 0 {
+                t.correct as f64 * 100.0 / t.attempts as f64
+            } else {
+                0.0
+            },
+        })
+        .collect()
+}
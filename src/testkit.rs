@@ -0,0 +1,802 @@
+// #region IMPORTS
+use crate::{
+    client_sink::ClientSink,
+    config::ServerConfig,
+    data_types::*,
+    events::{EventSender, ServerEvent, event_channel},
+    filter::WordFilter,
+    game_actor::{AttachSession, GameActor, GameClientInfo},
+    question_provider::QuestionProvider,
+    room_actor::{AddClient, RoomActor},
+    server::{Listener, serve},
+    session_client_actor::{SessionClientActor, SetTransport},
+    tools::random_bytes,
+    websocket_client_actor::{ClientStream, ToTransport},
+    word_parts::WordPartIndex,
+};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use ed25519_dalek::{Signer, SigningKey};
+use futures_util::{SinkExt, StreamExt, future::BoxFuture};
+use kameo::{
+    Actor,
+    actor::ActorRef,
+    message::{Context, Message},
+};
+use std::{
+    collections::VecDeque,
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::DuplexStream,
+    net::{TcpListener, TcpStream},
+    sync::{broadcast, mpsc},
+    time::timeout,
+};
+use tokio_tungstenite::{
+    WebSocketStream, client_async,
+    tungstenite::{Message as WsMsg, client::IntoClientRequest},
+};
+use uuid::Uuid;
+// #endregion
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// #region SERVER
+pub struct TestServer {
+    addr: SocketAddr,
+    game: ActorRef<GameActor>,
+    stop_tx: broadcast::Sender<()>,
+    events: EventSender,
+    memory: Option<MemoryConnector>,
+}
+
+impl TestServer {
+    pub async fn start() -> Self {
+        Self::start_with_config(ServerConfig::default()).await
+    }
+
+    pub async fn start_with_config(config: ServerConfig) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("test listener address");
+        Self::serve(listener, addr, config, None)
+    }
+
+    /// Like [`TestServer::start`] but connections are in-memory pipes, so
+    /// no port is bound. Clients have to come from [`TestServer::client`]
+    /// or [`TestServer::connect`].
+    pub async fn start_in_memory() -> Self {
+        Self::start_in_memory_with_config(ServerConfig::default()).await
+    }
+
+    pub async fn start_in_memory_with_config(config: ServerConfig) -> Self {
+        let (listener, connector) = memory_listener();
+        Self::serve(listener, MEMORY_PEER, config, Some(connector))
+    }
+
+    fn serve(
+        listener: impl Listener,
+        addr: SocketAddr,
+        config: ServerConfig,
+        memory: Option<MemoryConnector>,
+    ) -> Self {
+        let events = event_channel();
+        let effective_config = EffectiveConfig::from_server(&config);
+        let game = GameActor::spawn((Arc::new(config), effective_config, events.clone(), None));
+        let (stop_tx, mut stop_rx) = broadcast::channel::<()>(1);
+        let shutdown = async move {
+            stop_rx.recv().await.ok();
+        };
+        tokio::spawn(serve(listener, game.clone(), shutdown));
+
+        Self {
+            addr,
+            game,
+            stop_tx,
+            events,
+            memory,
+        }
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+
+    pub fn game(&self) -> &ActorRef<GameActor> {
+        &self.game
+    }
+
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ServerEvent> {
+        self.events.subscribe()
+    }
+
+    /// Opens a connection the way this server takes them.
+    pub async fn connect(&self) -> Result<TestClient, String> {
+        match &self.memory {
+            Some(connector) => TestClient::connect_over(self.url(), connector.connect()?).await,
+            None => TestClient::connect(self.url()).await,
+        }
+    }
+
+    pub async fn client(&self, name: &str) -> TestClient {
+        let mut client = self.connect().await.expect("connect");
+        client.handshake().await.expect("handshake");
+        client.register(name).await.expect("register");
+        client
+    }
+
+    /// Like [`TestServer::client`] but signed in with `key`, such as one
+    /// of the configured `operator_keys`.
+    pub async fn client_with_key(&self, name: &str, key: SigningKey) -> TestClient {
+        let mut client = self.connect().await.expect("connect").with_key(key);
+        client.handshake().await.expect("handshake");
+        client.register(name).await.expect("register");
+        client
+    }
+
+    /// Like [`TestServer::client`] but skips the socket entirely, which keeps
+    /// tests deterministic under paused tokio time.
+    pub async fn local_client(&self, name: &str) -> TestClient {
+        let mut client = TestClient::local(&self.game).await;
+        client.handshake().await.expect("handshake");
+        client.register(name).await.expect("register");
+        client
+    }
+}
+
+/// A fixed key to sign in with through [`TestServer::client_with_key`].
+pub fn operator_key() -> SigningKey {
+    SigningKey::from_bytes(&[7; 32])
+}
+
+/// `operator_keys` that let [`operator_key`] in.
+pub fn operator_keys() -> Vec<String> {
+    vec![BASE64_STANDARD.encode(operator_key().verifying_key().as_bytes())]
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = self.stop_tx.send(());
+        self.game.kill();
+    }
+}
+
+/// Peer address the server sees on in-memory connections.
+const MEMORY_PEER: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+const MEMORY_BUFFER: usize = 64 * 1024;
+
+/// Accepts the pipes opened by its [`MemoryConnector`]s.
+pub struct MemoryListener(mpsc::UnboundedReceiver<DuplexStream>);
+
+#[derive(Clone)]
+pub struct MemoryConnector(mpsc::UnboundedSender<DuplexStream>);
+
+pub fn memory_listener() -> (MemoryListener, MemoryConnector) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (MemoryListener(rx), MemoryConnector(tx))
+}
+
+impl MemoryConnector {
+    /// The client's end of a new connection.
+    pub fn connect(&self) -> Result<ClientStream, String> {
+        let (client, server) = tokio::io::duplex(MEMORY_BUFFER);
+        self.0
+            .send(server)
+            .map_err(|_| "listener is gone".to_string())?;
+        Ok(Box::new(client))
+    }
+}
+
+impl Listener for MemoryListener {
+    type Io = DuplexStream;
+
+    async fn accept(&mut self) -> io::Result<(DuplexStream, SocketAddr)> {
+        match self.0.recv().await {
+            Some(stream) => Ok((stream, MEMORY_PEER)),
+            // nothing can connect any more
+            None => std::future::pending().await,
+        }
+    }
+}
+// #endregion
+
+// #region CLIENT
+enum Link {
+    Ws(Box<WebSocketStream<ClientStream>>),
+    Local {
+        session: ActorRef<SessionClientActor>,
+        rx: mpsc::UnboundedReceiver<ToTransport>,
+    },
+}
+
+pub struct TestClient {
+    link: Link,
+    key: SigningKey,
+    encoding: Encoding,
+    capabilities: Vec<String>,
+    backlog: VecDeque<TransportMsg>,
+    pub id: Option<String>,
+}
+
+impl TestClient {
+    pub async fn connect(request: impl IntoClientRequest + Unpin) -> Result<Self, String> {
+        let request = request.into_client_request().map_err(|e| e.to_string())?;
+        let host = request.uri().host().unwrap_or_default().to_string();
+        let port = request.uri().port_u16().unwrap_or(80);
+        let stream = TcpStream::connect((host, port))
+            .await
+            .map_err(|e| e.to_string())?;
+        Self::connect_over(request, Box::new(stream)).await
+    }
+
+    /// Speaks websocket over a connection that is already open.
+    pub async fn connect_over(
+        request: impl IntoClientRequest + Unpin,
+        stream: ClientStream,
+    ) -> Result<Self, String> {
+        let (ws, _) = client_async(request, stream)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(Self::with_link(Link::Ws(Box::new(ws))))
+    }
+
+    pub async fn local(game: &ActorRef<GameActor>) -> Self {
+        let session = SessionClientActor::spawn(SessionClientActor::new(game.downgrade(), None));
+        let (tx, rx) = mpsc::unbounded_channel();
+        let inbox = Inbox::spawn_link(&session, Inbox(tx)).await;
+        session
+            .tell(SetTransport(inbox.recipient::<ToTransport>()))
+            .await
+            .ok();
+        game.tell(AttachSession(session.clone())).await.ok();
+
+        Self::with_link(Link::Local { session, rx })
+    }
+
+    fn with_link(link: Link) -> Self {
+        Self {
+            link,
+            key: SigningKey::from_bytes(&random_bytes()),
+            encoding: Encoding::Json,
+            capabilities: Capability::ALL.iter().map(|c| c.as_str().into()).collect(),
+            backlog: VecDeque::new(),
+            id: None,
+        }
+    }
+
+    /// Signs in as the same player as another client.
+    pub fn with_key(mut self, key: SigningKey) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Asks for `encoding` in the handshake and speaks it from then on.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Advertises `capabilities` in the handshake instead of every one the
+    /// server knows.
+    pub fn with_capabilities(mut self, capabilities: &[&str]) -> Self {
+        self.capabilities = capabilities.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    pub fn key(&self) -> &SigningKey {
+        &self.key
+    }
+
+    pub fn public_key(&self) -> String {
+        BASE64_STANDARD.encode(self.key.verifying_key().as_bytes())
+    }
+
+    pub async fn handshake(&mut self) -> Result<(), String> {
+        let key = self.public_key();
+        let correlation_id = self
+            .send(TransportMsg::InReqSendPublicKey(envelope(
+                InReqSendPublicKey {
+                    key,
+                    algorithm: SignatureAlgorithm::Ed25519,
+                    encoding: self.encoding,
+                    capabilities: self.capabilities.clone(),
+                },
+            )))
+            .await?;
+
+        let challenge = match self.response(correlation_id, DEFAULT_TIMEOUT).await? {
+            TransportMsg::OutRespSignMessage(env) => env.payload.message,
+            other => return Err(format!("unexpected response: {other:?}")),
+        };
+
+        let signature = BASE64_STANDARD.encode(self.key.sign(challenge.as_bytes()).to_bytes());
+        let correlation_id = self
+            .send(TransportMsg::InReqVerifySignature(envelope(
+                InReqVerifySignature { signature },
+            )))
+            .await?;
+
+        match self.status(correlation_id, DEFAULT_TIMEOUT).await?.as_str() {
+            "success" => Ok(()),
+            status => Err(status.to_string()),
+        }
+    }
+
+    pub async fn register(&mut self, name: &str) -> Result<String, String> {
+        let correlation_id = self
+            .send(TransportMsg::InReqRegisterClient(envelope(
+                InReqRegisterClient {
+                    name: name.to_string(),
+                    spectator: false,
+                    password: None,
+                },
+            )))
+            .await?;
+
+        match self.response(correlation_id, DEFAULT_TIMEOUT).await? {
+            TransportMsg::OutRespClientRegistered(env) => {
+                self.id = Some(env.payload.id.clone());
+                Ok(env.payload.id)
+            }
+            TransportMsg::OutRespStatus(env) => Err(env.payload.status),
+            TransportMsg::OutRespError(env) => Err(env.payload.error),
+            other => Err(format!("unexpected response: {other:?}")),
+        }
+    }
+
+    pub async fn send(&mut self, msg: TransportMsg) -> Result<Uuid, String> {
+        let correlation_id = correlation_id(&msg).ok_or("message without correlation id")?;
+        // the hello itself always goes out as text
+        let hello = matches!(msg, TransportMsg::InReqSendPublicKey(_));
+        if self.encoding == Encoding::Msgpack && !hello && matches!(self.link, Link::Ws(_)) {
+            let data = serialize_msgpack(&msg).map_err(|e| e.to_string())?;
+            self.send_binary(data).await?;
+        } else {
+            let text = serialize(&msg).map_err(|e| e.to_string())?;
+            self.send_raw(text).await?;
+        }
+        Ok(correlation_id)
+    }
+
+    pub async fn send_raw(&mut self, text: impl Into<String>) -> Result<(), String> {
+        match &mut self.link {
+            Link::Ws(ws) => ws
+                .send(WsMsg::Text(text.into().into()))
+                .await
+                .map_err(|e| e.to_string()),
+            Link::Local { session, .. } => {
+                let msg = parse(&text.into()).map_err(|e| e.to_string())?;
+                session.tell(msg).await.map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    pub async fn send_binary(&mut self, data: Vec<u8>) -> Result<(), String> {
+        match &mut self.link {
+            Link::Ws(ws) => ws
+                .send(WsMsg::Binary(data.into()))
+                .await
+                .map_err(|e| e.to_string()),
+            Link::Local { .. } => Err("local clients have no frames".into()),
+        }
+    }
+
+    /// Next decoded message, `Ok(None)` for frames that carry none and an
+    /// error once the connection is gone.
+    async fn recv(&mut self) -> Result<Option<TransportMsg>, String> {
+        match &mut self.link {
+            Link::Ws(ws) => match ws.next().await {
+                None | Some(Ok(WsMsg::Close(_))) => Err("connection closed".into()),
+                Some(Err(e)) => Err(e.to_string()),
+                Some(Ok(WsMsg::Text(text))) => parse(&text).map(Some).map_err(|e| e.to_string()),
+                Some(Ok(WsMsg::Binary(data))) => {
+                    parse_msgpack(&data).map(Some).map_err(|e| e.to_string())
+                }
+                Some(Ok(_)) => Ok(None),
+            },
+            Link::Local { rx, .. } => match rx.recv().await {
+                None => Err("connection closed".into()),
+                Some(ToTransport::Raw(text)) => parse(&text).map(Some).map_err(|e| e.to_string()),
+                Some(ToTransport::TransportMsg(msg) | ToTransport::Response(msg, _)) => {
+                    Ok(Some(*msg))
+                }
+                Some(ToTransport::SetEncoding(_)) => Ok(None),
+            },
+        }
+    }
+
+    pub async fn expect<T: FromTransport>(
+        &mut self,
+        wait: Duration,
+    ) -> Result<TransportEnvelope<T>, String> {
+        let mut matched = None;
+        self.next_matching(wait, |msg| match T::from_transport(msg) {
+            Ok(env) => {
+                matched = Some(env);
+                None
+            }
+            Err(other) => Some(*other),
+        })
+        .await?;
+        matched.ok_or_else(|| "no matching message".to_string())
+    }
+
+    pub async fn response(
+        &mut self,
+        correlation_id: Uuid,
+        wait: Duration,
+    ) -> Result<TransportMsg, String> {
+        let mut matched = None;
+        self.next_matching(wait, |msg| {
+            if self::correlation_id(&msg) == Some(correlation_id) {
+                matched = Some(msg);
+                None
+            } else {
+                Some(msg)
+            }
+        })
+        .await?;
+        matched.ok_or_else(|| "no matching message".to_string())
+    }
+
+    /// The next message of any kind, queued ones first; `Ok(None)` if none
+    /// arrives within `wait`.
+    pub async fn next(&mut self, wait: Duration) -> Result<Option<TransportMsg>, String> {
+        if let Some(msg) = self.backlog.pop_front() {
+            return Ok(Some(msg));
+        }
+        let read = async {
+            loop {
+                if let Some(msg) = self.recv().await? {
+                    return Ok(msg);
+                }
+            }
+        };
+        match timeout(wait, read).await {
+            Ok(msg) => msg.map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    pub async fn status(&mut self, correlation_id: Uuid, wait: Duration) -> Result<String, String> {
+        match self.response(correlation_id, wait).await? {
+            TransportMsg::OutRespStatus(env) => Ok(env.payload.status),
+            other => Err(format!("expected status, got {other:?}")),
+        }
+    }
+
+    /// Sends `msg` and waits for the status it gets back.
+    pub async fn request(&mut self, msg: TransportMsg) -> Result<String, String> {
+        let correlation_id = self.send(msg).await?;
+        self.status(correlation_id, DEFAULT_TIMEOUT).await
+    }
+
+    /// Waits for the room to ask this admin for a question and serves it
+    /// `question`.
+    pub async fn provide_question(&mut self, question: QuestionInfo) -> Result<(), String> {
+        let req = self.expect::<OutReqQuestion>(DEFAULT_TIMEOUT).await?;
+        self.send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question,
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .map(drop)
+    }
+
+    pub async fn expect_closed(&mut self, wait: Duration) -> Result<(), String> {
+        let read = async {
+            loop {
+                match self.recv().await {
+                    Err(_) => return,
+                    Ok(Some(msg)) => self.backlog.push_back(msg),
+                    Ok(None) => {}
+                }
+            }
+        };
+        timeout(wait, read)
+            .await
+            .map_err(|_| "connection still open".to_string())
+    }
+
+    pub async fn close(self) {
+        match self.link {
+            Link::Ws(mut ws) => {
+                ws.as_mut().close(None).await.ok();
+            }
+            Link::Local { session, .. } => session.kill(),
+        }
+    }
+
+    /// `filter` gets each message once and hands back the ones it did not
+    /// consume; those stay queued for later `expect` calls.
+    async fn next_matching(
+        &mut self,
+        wait: Duration,
+        mut filter: impl FnMut(TransportMsg) -> Option<TransportMsg>,
+    ) -> Result<(), String> {
+        let queued: Vec<TransportMsg> = self.backlog.drain(..).collect();
+        let mut queued = queued.into_iter();
+        let mut found = false;
+        for msg in queued.by_ref() {
+            match filter(msg) {
+                Some(rest) => self.backlog.push_back(rest),
+                None => {
+                    found = true;
+                    break;
+                }
+            }
+        }
+        self.backlog.extend(queued);
+        if found {
+            return Ok(());
+        }
+
+        let read = async {
+            loop {
+                let Some(msg) = self.recv().await? else {
+                    continue;
+                };
+                match filter(msg) {
+                    Some(rest) => self.backlog.push_back(rest),
+                    None => return Ok::<_, String>(()),
+                }
+            }
+        };
+        timeout(wait, read)
+            .await
+            .map_err(|_| "timed out waiting for message".to_string())?
+    }
+}
+
+#[derive(Actor)]
+struct Inbox(mpsc::UnboundedSender<ToTransport>);
+
+impl Message<ToTransport> for Inbox {
+    type Reply = ();
+
+    async fn handle(&mut self, msg: ToTransport, _ctx: &mut Context<Self, Self::Reply>) {
+        self.0.send(msg).ok();
+    }
+}
+
+pub fn envelope<T>(payload: T) -> TransportEnvelope<T> {
+    TransportEnvelope {
+        correlation_id: Uuid::new_v4(),
+        seq: None,
+        payload,
+    }
+}
+
+pub fn start_game(game_settings: GameSettings) -> TransportMsg {
+    TransportMsg::InReqStartGame(envelope(InReqStartGame { game_settings }))
+}
+
+/// A single 30 second round without a countdown.
+pub fn one_round() -> GameSettings {
+    GameSettings {
+        round_duration: 30,
+        rounds_count: 1,
+        start_countdown: 0,
+        ..Default::default()
+    }
+}
+
+/// 日, read ひ.
+pub fn sample_question() -> QuestionInfo {
+    QuestionInfo {
+        word_info: WordInfo {
+            word: "日".into(),
+            meanings: Vec::new(),
+            readings: vec![ReadingWithParts {
+                reading: "ひ".into(),
+                parts: Vec::new(),
+            }],
+        },
+        ..Default::default()
+    }
+}
+
+// #endregion
+
+// #region ROOM
+/// A `RoomActor` driven directly, without sockets. Members get bare sessions
+/// for identity and a [`RecordingSink`] for everything the room sends them.
+pub struct TestRoom {
+    pub room: ActorRef<RoomActor>,
+    game: ActorRef<GameActor>,
+}
+
+pub struct RoomMember {
+    pub uuid: Uuid,
+    pub session: ActorRef<SessionClientActor>,
+    pub sink: RecordingSink,
+}
+
+impl TestRoom {
+    pub async fn start(config: ServerConfig) -> Self {
+        Self::start_with(config, None).await
+    }
+
+    /// A room that asks `provider` for its questions.
+    pub async fn start_with_provider(
+        config: ServerConfig,
+        provider: Arc<dyn QuestionProvider>,
+    ) -> Self {
+        Self::start_with(config, Some(provider)).await
+    }
+
+    async fn start_with(config: ServerConfig, provider: Option<Arc<dyn QuestionProvider>>) -> Self {
+        let config = Arc::new(config);
+        let events = event_channel();
+        let game = GameActor::spawn((
+            config.clone(),
+            EffectiveConfig::from_server(&config),
+            events.clone(),
+            provider.clone(),
+        ));
+        let word_filter = Arc::new(WordFilter::from_config(&config));
+        let word_parts = Arc::new(WordPartIndex::from_config(&config));
+        let room = RoomActor::spawn((
+            "test".into(),
+            game.downgrade(),
+            config,
+            events,
+            word_filter,
+            word_parts,
+            provider,
+        ));
+        Self { room, game }
+    }
+
+    pub async fn join(&self, name: &str, is_spectator: bool) -> Result<RoomMember, JoinRejection> {
+        let uuid = Uuid::new_v4();
+        let session =
+            SessionClientActor::spawn(SessionClientActor::new(self.game.downgrade(), None));
+        let sink = RecordingSink::default();
+
+        self.room
+            .ask(AddClient {
+                uuid,
+                session: session.clone(),
+                sink: Box::new(sink.clone()),
+                info: GameClientInfo {
+                    id: uuid,
+                    player_id: Uuid::new_v4(),
+                    key: String::new(),
+                    name: name.to_string(),
+                    capabilities: Capability::ALL.iter().copied().collect(),
+                    is_bot: false,
+                },
+                is_spectator,
+                password: None,
+            })
+            .await
+            .map_err(|e| match e {
+                kameo::error::SendError::HandlerError(rejection) => rejection,
+                _ => JoinRejection::Unavailable,
+            })?;
+
+        Ok(RoomMember {
+            uuid,
+            session,
+            sink,
+        })
+    }
+}
+
+impl Drop for TestRoom {
+    fn drop(&mut self) {
+        self.room.kill();
+        self.game.kill();
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct RecordingSink(Arc<Mutex<Vec<TransportMsg>>>);
+
+impl RecordingSink {
+    /// Drains everything recorded so far.
+    pub fn take(&self) -> Vec<TransportMsg> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl ClientSink for RecordingSink {
+    fn send(&self, msg: TransportMsg) -> BoxFuture<'_, bool> {
+        self.0.lock().unwrap().push(msg);
+        Box::pin(async { true })
+    }
+}
+
+/// The `messageType` tag of each message, e.g. `OUT_NOTIF_roundEnded`.
+pub fn message_types(msgs: &[TransportMsg]) -> Vec<String> {
+    msgs.iter()
+        .filter_map(|msg| {
+            let value = serde_json::to_value(msg).ok()?;
+            Some(value.get("messageType")?.as_str()?.to_string())
+        })
+        .collect()
+}
+// #endregion
+
+// #region FROM_TRANSPORT
+pub trait FromTransport: Sized {
+    fn from_transport(msg: TransportMsg) -> Result<TransportEnvelope<Self>, Box<TransportMsg>>;
+}
+
+macro_rules! from_transport {
+    ($($variant:ident => $payload:ty),* $(,)?) => {
+        $(
+            impl FromTransport for $payload {
+                fn from_transport(
+                    msg: TransportMsg,
+                ) -> Result<TransportEnvelope<Self>, Box<TransportMsg>> {
+                    match msg {
+                        TransportMsg::$variant(env) => Ok(env),
+                        other => Err(Box::new(other)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_transport! {
+    OutRespClientRegistered => OutRespClientRegistered,
+    OutRespStatus => OutRespStatus,
+    OutRespClientList => OutRespClientList,
+    OutRespSignMessage => OutRespSignMessage,
+    OutRespServerStats => OutRespServerStats,
+    OutRespError => OutRespError,
+    OutRespRoomInfo => OutRespRoomInfo,
+    OutRespGameReplay => OutRespGameReplay,
+    OutRespRoundState => OutRespRoundState,
+    OutReqQuestion => OutReqQuestion,
+    OutNotifClientRegistered => OutNotifClientRegistered,
+    OutNotifClientDisconnected => OutNotifClientDisconnected,
+    OutNotifChatSent => OutNotifChatSent,
+    OutNotifAdminMade => OutNotifAdminMade,
+    OutNotifGameStarted => OutNotifGameStarted,
+    OutNotifGameStopped => OutNotifGameStopped,
+    OutNotifQuestion => OutNotifQuestion,
+    OutNotifClientAnswered => OutNotifClientAnswered,
+    OutNotifRoundEnded => OutNotifRoundEnded,
+    OutNotifGameSettingsChanged => OutNotifGameSettingsChanged,
+    OutNotifWhisperReceived => OutNotifWhisperReceived,
+    OutNotifClientRenamed => OutNotifClientRenamed,
+    OutNotifReadyChanged => OutNotifReadyChanged,
+    OutNotifRoomPasswordChanged => OutNotifRoomPasswordChanged,
+    OutNotifClientMuted => OutNotifClientMuted,
+    OutNotifRoomRenamed => OutNotifRoomRenamed,
+    OutNotifCountdown => OutNotifCountdown,
+    OutNotifRoundExtended => OutNotifRoundExtended,
+    OutNotifStreak => OutNotifStreak,
+    OutNotifAnnouncement => OutNotifAnnouncement,
+    OutNotifReaction => OutNotifReaction,
+    OutNotifFrequencyWarning => OutNotifFrequencyWarning,
+    OutNotifSessionToken => OutNotifSessionToken,
+    OutNotifLobbyChat => OutNotifLobbyChat,
+    OutNotifServerError => OutNotifServerError,
+    OutNotifHandicapChanged => OutNotifHandicapChanged,
+    OutNotifRoomListChanged => OutNotifRoomListChanged,
+    OutNotifRoundTimeWarning => OutNotifRoundTimeWarning,
+    OutNotifModerationSettingsChanged => OutNotifModerationSettingsChanged,
+    OutNotifAnswerFeedback => OutNotifAnswerFeedback,
+    OutNotifIdleWarning => OutNotifIdleWarning,
+    OutNotifAdminRevoked => OutNotifAdminRevoked,
+    OutNotifQuestionProviderChanged => OutNotifQuestionProviderChanged,
+    OutNotifQuestionFailed => OutNotifQuestionFailed,
+}
+// #endregion
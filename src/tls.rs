@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{
+        ServerConfig as RustlsConfig,
+        pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+    },
+};
+
+use crate::config::TlsConfig;
+
+pub fn load_acceptor(tls: &TlsConfig) -> Result<TlsAcceptor, String> {
+    let cert_path = tls.cert_path.display();
+    let key_path = tls.key_path.display();
+
+    let certs = CertificateDer::pem_file_iter(&tls.cert_path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("can't read certificate {cert_path}: {e}"))?;
+    if certs.is_empty() {
+        return Err(format!("no certificates found in {cert_path}"));
+    }
+
+    let key = PrivateKeyDer::from_pem_file(&tls.key_path)
+        .map_err(|e| format!("can't read private key {key_path}: {e}"))?;
+
+    let config = RustlsConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("invalid certificate or key: {e}"))?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
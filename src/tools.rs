@@ -1,21 +1,334 @@
+use crate::data_types::{QuestionInfo, QuestionType, SignatureAlgorithm};
 use base64::{Engine, prelude::BASE64_STANDARD};
-use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 use tracing::Level;
 use tracing_subscriber::fmt::time::LocalTime;
+use uuid::Uuid;
 
-pub fn verify_signature(message: &str, signature: &str, key: &str) -> Result<bool, String> {
-    let public_key_bytes = BASE64_STANDARD
+/// Checks a base64 `signature` of `message` against a base64 `key` of the
+/// given algorithm. `Err` means the key or signature couldn't be decoded,
+/// `Ok(false)` that they decoded but don't match.
+pub fn verify_signature(
+    algorithm: SignatureAlgorithm,
+    message: &str,
+    signature: &str,
+    key: &str,
+) -> Result<bool, String> {
+    let key = BASE64_STANDARD
         .decode(key)
         .map_err(|e| format!("Invalid public key: {}", e))?;
-    let verifying_key = VerifyingKey::from_bytes(&public_key_bytes.try_into().unwrap())
-        .map_err(|e| format!("Invalid public key: {}", e))?;
-
-    let signature_bytes = BASE64_STANDARD
+    let signature = BASE64_STANDARD
         .decode(signature)
         .map_err(|e| format!("Invalid signature: {}", e))?;
-    let signature = Signature::from_bytes(&signature_bytes.try_into().unwrap());
 
-    Ok(verifying_key.verify(message.as_bytes(), &signature).is_ok())
+    match algorithm {
+        SignatureAlgorithm::Ed25519 => verify_ed25519(message.as_bytes(), &signature, &key),
+        #[cfg(feature = "ecdsa")]
+        SignatureAlgorithm::EcdsaP256 => verify_p256(message.as_bytes(), &signature, &key),
+        other => Err(format!("Unsupported algorithm: {}", other.as_str())),
+    }
+}
+
+fn verify_ed25519(message: &[u8], signature: &[u8], key: &[u8]) -> Result<bool, String> {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    let key: &[u8; 32] = key
+        .try_into()
+        .map_err(|_| format!("Invalid public key: expected 32 bytes, got {}", key.len()))?;
+    let verifying_key =
+        VerifyingKey::from_bytes(key).map_err(|e| format!("Invalid public key: {}", e))?;
+    let signature =
+        Signature::from_slice(signature).map_err(|e| format!("Invalid signature: {}", e))?;
+
+    Ok(verifying_key.verify(message, &signature).is_ok())
+}
+
+/// P-256 keys come SEC1-encoded, compressed or not; signatures as the
+/// 64-byte `r || s` that WebCrypto produces.
+#[cfg(feature = "ecdsa")]
+fn verify_p256(message: &[u8], signature: &[u8], key: &[u8]) -> Result<bool, String> {
+    use p256::ecdsa::{Signature, VerifyingKey, signature::Verifier};
+
+    let verifying_key =
+        VerifyingKey::from_sec1_bytes(key).map_err(|e| format!("Invalid public key: {}", e))?;
+    let signature =
+        Signature::from_slice(signature).map_err(|e| format!("Invalid signature: {}", e))?;
+
+    Ok(verifying_key.verify(message, &signature).is_ok())
+}
+
+/// The string a client's key is known by: in session tokens, operator
+/// lists and [`player_id`]. Ed25519 keys stay bare so ids from before
+/// other algorithms existed don't change; others carry the algorithm.
+pub fn key_identity(algorithm: SignatureAlgorithm, key: &str) -> String {
+    match algorithm {
+        SignatureAlgorithm::Ed25519 => key.to_string(),
+        other => format!("{}:{key}", other.as_str()),
+    }
+}
+
+/// Inverse of [`key_identity`]. Base64 has no `:`, so a bare key is
+/// always ed25519.
+pub fn split_key_identity(identity: &str) -> (SignatureAlgorithm, &str) {
+    let Some((name, key)) = identity.split_once(':') else {
+        return (SignatureAlgorithm::Ed25519, identity);
+    };
+    let algorithm = [SignatureAlgorithm::Ed25519, SignatureAlgorithm::EcdsaP256]
+        .into_iter()
+        .find(|a| a.as_str() == name)
+        .unwrap_or(SignatureAlgorithm::Unknown);
+    (algorithm, key)
+}
+
+/// Stable id for a key identity, so the same player keeps one id across
+/// reconnects while the session uuid changes every time. The same bytes
+/// under another algorithm are another player.
+pub fn player_id(key: &str) -> Uuid {
+    let digest = Sha256::digest(key.as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    Uuid::new_v8(bytes)
+}
+
+/// Random bytes for secrets and nonces, drawn from the same source as
+/// v4 uuids.
+pub fn random_bytes() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(Uuid::new_v4().as_bytes());
+    bytes[16..].copy_from_slice(Uuid::new_v4().as_bytes());
+    bytes
+}
+
+fn hmac_sha256(secret: &[u8], data: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut key = [0u8; BLOCK];
+    if secret.len() > BLOCK {
+        key[..32].copy_from_slice(&Sha256::digest(secret));
+    } else {
+        key[..secret.len()].copy_from_slice(secret);
+    }
+
+    let inner = Sha256::new()
+        .chain_update(key.map(|b| b ^ 0x36))
+        .chain_update(data)
+        .finalize();
+    Sha256::new()
+        .chain_update(key.map(|b| b ^ 0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+/// Issues a token that stands in for a verified `key` until `expires_at`
+/// (Unix milliseconds). The token carries a random nonce, the expiry and
+/// the key, followed by an HMAC of them under `secret`, so the server
+/// keeps nothing per token.
+pub fn issue_session_token(secret: &[u8], key: &str, expires_at: u64) -> String {
+    let mut body = random_bytes().to_vec();
+    body.extend_from_slice(&expires_at.to_be_bytes());
+    body.extend_from_slice(key.as_bytes());
+    let mac = hmac_sha256(secret, &body);
+    format!(
+        "{}.{}",
+        BASE64_STANDARD.encode(&body),
+        BASE64_STANDARD.encode(mac)
+    )
+}
+
+/// Returns the key a token was issued for if it is genuine and not
+/// expired at `now`.
+pub fn check_session_token(secret: &[u8], token: &str, now: u64) -> Result<String, &'static str> {
+    let (body, mac) = token.split_once('.').ok_or("invalid token")?;
+    let body = BASE64_STANDARD.decode(body).map_err(|_| "invalid token")?;
+    let mac = BASE64_STANDARD.decode(mac).map_err(|_| "invalid token")?;
+    if !constant_time_eq(&hmac_sha256(secret, &body), &mac) {
+        return Err("invalid token");
+    }
+
+    let (expires_at, key) = body
+        .get(32..)
+        .and_then(|rest| rest.split_first_chunk::<8>())
+        .ok_or("invalid token")?;
+    if now >= u64::from_be_bytes(*expires_at) {
+        return Err("token expired");
+    }
+    String::from_utf8(key.to_vec()).map_err(|_| "invalid token")
+}
+
+/// Compares in time that depends only on the lengths, not on where the
+/// inputs first differ.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let mut diff = a.len() ^ b.len();
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        diff |= usize::from(x ^ y);
+    }
+    diff == 0
+}
+
+pub const MAX_NAME_LENGTH: usize = 32;
+
+pub fn normalize_name(name: &str) -> Result<String, &'static str> {
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control() && !is_zero_width(*c))
+        .collect();
+    let trimmed = cleaned.trim();
+
+    if trimmed.is_empty() {
+        return Err("name empty");
+    }
+    if trimmed.chars().count() > MAX_NAME_LENGTH {
+        return Err("name too long");
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Applied to both the answer and the readings before they are compared.
+pub fn normalize_reading(reading: &str) -> String {
+    let cleaned: String = reading
+        .chars()
+        .filter(|c| !c.is_control() && !is_zero_width(*c))
+        .collect();
+    cleaned.trim().to_string()
+}
+
+/// Normalized readings of a question in their original order, without
+/// duplicates or empty entries.
+pub fn accepted_readings(question: &QuestionInfo) -> Vec<String> {
+    let mut readings: Vec<String> = Vec::new();
+    for reading in &question.word_info.readings {
+        let reading = normalize_reading(&reading.reading);
+        if !reading.is_empty() && !readings.contains(&reading) {
+            readings.push(reading);
+        }
+    }
+    readings
+}
+
+/// Folds katakana into hiragana so either script matches a reading.
+pub fn fold_kana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// Normalizes an answer or an accepted value the way `question_type`
+/// compares them.
+pub fn normalize_answer(answer: &str, question_type: QuestionType) -> String {
+    let answer = normalize_reading(answer);
+    match question_type {
+        QuestionType::ReadingOfWord => fold_kana(&answer),
+        QuestionType::WordFromReading => answer,
+        QuestionType::MeaningOfWord => answer.to_lowercase(),
+    }
+}
+
+/// Normalized answers accepted for a question: its readings, the word, or
+/// every meaning flattened, depending on the question type.
+pub fn accepted_answers(question: &QuestionInfo) -> Vec<String> {
+    let word_info = &question.word_info;
+    let candidates: Vec<&str> = match question.question_type {
+        QuestionType::ReadingOfWord => word_info
+            .readings
+            .iter()
+            .map(|r| r.reading.as_str())
+            .collect(),
+        QuestionType::WordFromReading => vec![word_info.word.as_str()],
+        QuestionType::MeaningOfWord => word_info
+            .meanings
+            .iter()
+            .flatten()
+            .flatten()
+            .map(String::as_str)
+            .collect(),
+    };
+
+    let mut answers: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let answer = normalize_answer(candidate, question.question_type);
+        if !answer.is_empty() && !answers.contains(&answer) {
+            answers.push(answer);
+        }
+    }
+    answers
+}
+
+pub fn is_correct_answer(question: &QuestionInfo, answer: &str) -> bool {
+    accepted_answers(question).contains(&normalize_answer(answer, question.question_type))
+}
+
+/// Levenshtein distance counted in Unicode scalar values, so a kana is one
+/// edit however many bytes it takes.
+pub fn edit_distance(a: &str, b: &str) -> u32 {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<u32> = (0..=b.len() as u32).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i as u32 + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + u32::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The accepted reading nearest to `answer` and its edit distance, both
+/// compared after normalizing. Only reading questions have one.
+pub fn closest_reading(question: &QuestionInfo, answer: &str) -> Option<(String, u32)> {
+    if question.question_type != QuestionType::ReadingOfWord {
+        return None;
+    }
+    let answer = normalize_answer(answer, question.question_type);
+    if answer.is_empty() {
+        return None;
+    }
+    accepted_answers(question)
+        .into_iter()
+        .map(|reading| {
+            let distance = edit_distance(&answer, &reading);
+            (reading, distance)
+        })
+        .min_by_key(|(_, distance)| *distance)
+}
+
+/// Normalizes line endings and drops control and zero-width characters,
+/// keeping newlines.
+pub fn sanitize_chat(message: &str) -> String {
+    let cleaned: String = message
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .chars()
+        .filter(|c| *c == '\n' || (!c.is_control() && !is_zero_width(*c)))
+        .collect();
+    cleaned.trim().to_string()
+}
+
+/// Sanitizes a chat message and checks it is neither empty nor longer than
+/// `max_length` characters.
+pub fn sanitize_message(message: &str, max_length: usize) -> Result<String, &'static str> {
+    let message = sanitize_chat(message);
+    if message.is_empty() {
+        return Err("message empty");
+    }
+    if message.chars().count() > max_length {
+        return Err("message too long");
+    }
+    Ok(message)
+}
+
+fn is_zero_width(c: char) -> bool {
+    matches!(c, '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}')
 }
 
 pub fn setup_tracing() {
@@ -29,3 +342,87 @@ pub fn setup_tracing() {
 
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set global logger");
 }
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    max: usize,
+    window: Duration,
+    hits: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(max: usize, window: Duration) -> Self {
+        Self {
+            max,
+            window,
+            hits: VecDeque::new(),
+        }
+    }
+
+    pub fn check(&mut self) -> bool {
+        let now = Instant::now();
+        while self
+            .hits
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= self.window)
+        {
+            self.hits.pop_front();
+        }
+
+        if self.hits.len() >= self.max {
+            return false;
+        }
+        self.hits.push_back(now);
+        true
+    }
+}
+
+/// Ids seen within a time window, up to a number of the latest.
+#[derive(Debug)]
+pub struct RecentIds {
+    limit: usize,
+    window: Duration,
+    seen: VecDeque<(Uuid, Instant)>,
+}
+
+impl RecentIds {
+    /// A `limit` of 0 remembers nothing.
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            seen: VecDeque::new(),
+        }
+    }
+
+    /// Notes `id`; false if it was already seen.
+    pub fn insert(&mut self, id: Uuid) -> bool {
+        if self.limit == 0 {
+            return true;
+        }
+        let now = Instant::now();
+        while self
+            .seen
+            .front()
+            .is_some_and(|(_, t)| now.duration_since(*t) >= self.window)
+        {
+            self.seen.pop_front();
+        }
+
+        if self.seen.iter().any(|(seen, _)| *seen == id) {
+            return false;
+        }
+        if self.seen.len() == self.limit {
+            self.seen.pop_front();
+        }
+        self.seen.push_back((id, now));
+        true
+    }
+}
+
+/// Wall-clock time in milliseconds since the Unix epoch.
+pub fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
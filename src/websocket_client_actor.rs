@@ -1,40 +1,341 @@
 // #region IMPORTS
 
-use crate::{data_types::*, session_client_actor::*};
+#[cfg(feature = "chaos")]
+use crate::chaos::Chaos;
+use crate::{
+    capture::{Capture, Direction},
+    config::*,
+    data_types::*,
+    metrics::*,
+    session_client_actor::*,
+    tools::unix_millis,
+};
 use futures_util::{SinkExt, stream::SplitSink};
 use kameo::{
     Actor,
-    actor::WeakActorRef,
+    actor::{ActorRef, WeakActorRef},
+    error::{ActorStopReason, Infallible},
     message::{Context, Message, StreamMessage},
 };
-use tokio::net::TcpStream;
-use tokio_tungstenite::{WebSocketStream, tungstenite::Message as WsMsg};
-use tracing::{error, trace};
-pub type RawResult = Result<String, String>;
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    task::AbortHandle,
+    time::{Instant, timeout},
+};
+use tokio_tungstenite::{
+    WebSocketStream,
+    tungstenite::{
+        Message as WsMsg,
+        protocol::{CloseFrame, frame::coding::CloseCode},
+    },
+};
+use tracing::{error, trace, warn};
+use uuid::Uuid;
+pub type RawResult = Result<Frame, String>;
 type StreamItem = StreamMessage<RawResult, (), ()>;
 
 // #endregion
 
+// #region TYPES
+
+pub trait ClientIo: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + 'static> ClientIo for T {}
+
+pub type ClientStream = Box<dyn ClientIo>;
+
+/// Data frames handed to the transport; control frames are handled by
+/// tungstenite and never reach the actor.
+#[derive(Debug)]
+pub enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl Frame {
+    pub fn from_ws(msg: WsMsg) -> Option<Self> {
+        match msg {
+            WsMsg::Text(text) => Some(Frame::Text(text.to_string())),
+            WsMsg::Binary(data) => Some(Frame::Binary(data.to_vec())),
+            _ => None,
+        }
+    }
+}
+
+/// The last messages sent on one connection, shared with the game so they
+/// can be read while the transport is busy or stuck.
+#[derive(Debug, Clone, Default)]
+pub struct Outbox {
+    entries: Arc<Mutex<VecDeque<OutboxEntry>>>,
+    capacity: usize,
+}
+
+impl Outbox {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    pub fn push(&self, entry: OutboxEntry) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Oldest first.
+    pub fn entries(&self) -> Vec<OutboxEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+// #endregion
+
 // #region ACTOR
 
-#[derive(Actor)]
 pub struct WebSocketClientActor {
-    write: SplitSink<WebSocketStream<TcpStream>, WsMsg>,
+    write: SplitSink<WebSocketStream<ClientStream>, WsMsg>,
     session: WeakActorRef<SessionClientActor>,
+    config: Arc<ServerConfig>,
+    binary_frames: u32,
+    binary_frames_total: Arc<AtomicU64>,
+    encoding: Encoding,
+    reader: Option<AbortHandle>,
+    stalled: bool,
+    outbox: Outbox,
+    capture: Capture,
+    latency: LatencyMetrics,
+    #[cfg(feature = "chaos")]
+    chaos: Option<Chaos>,
+}
+
+impl Actor for WebSocketClientActor {
+    type Args = Self;
+    type Error = Infallible;
+
+    async fn on_start(state: Self::Args, _ar: ActorRef<Self>) -> Result<Self, Self::Error> {
+        Ok(state)
+    }
+
+    async fn on_stop(
+        &mut self,
+        _ar: WeakActorRef<Self>,
+        _reason: ActorStopReason,
+    ) -> Result<(), Self::Error> {
+        // the read half outlives the actor, so the socket only closes once
+        // the sink is closed explicitly
+        if !self.stalled {
+            timeout(self.config.write_timeout, self.write.close())
+                .await
+                .ok();
+        } else if let Some(reader) = self.reader.take() {
+            // a stuck sink never finishes closing, dropping both halves does
+            reader.abort();
+        }
+        Ok(())
+    }
 }
 
 impl WebSocketClientActor {
     pub fn new(
-        write: SplitSink<WebSocketStream<TcpStream>, WsMsg>,
+        write: SplitSink<WebSocketStream<ClientStream>, WsMsg>,
         session: WeakActorRef<SessionClientActor>,
+        config: Arc<ServerConfig>,
+        binary_frames_total: Arc<AtomicU64>,
+        outbox: Outbox,
+        capture: Capture,
+        latency: LatencyMetrics,
     ) -> Self {
-        Self { write, session }
+        Self {
+            write,
+            session,
+            #[cfg(feature = "chaos")]
+            chaos: config.chaos.clone().map(Chaos::new),
+            config,
+            binary_frames: 0,
+            binary_frames_total,
+            encoding: Encoding::Json,
+            reader: None,
+            stalled: false,
+            outbox,
+            capture,
+            latency,
+        }
+    }
+
+    /// Holds the next frame back for the configured jitter; `false` means
+    /// it is lost. Always `true` without the `chaos` feature.
+    async fn pass_chaos(&mut self) -> bool {
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &mut self.chaos {
+            let Some(delay) = chaos.next_frame() else {
+                trace!("chaos: frame dropped");
+                return false;
+            };
+            tokio::time::sleep(delay).await;
+        }
+        true
+    }
+
+    /// Writes one frame, giving up on clients that stop reading. Returns
+    /// whether the frame went out.
+    async fn write(&mut self, frame: WsMsg, ctx: &mut Context<Self, ()>) -> bool {
+        if self.stalled {
+            return false;
+        }
+        match timeout(self.config.write_timeout, self.write.send(frame)).await {
+            Ok(sent) => sent.is_ok(),
+            Err(_) => {
+                warn!(
+                    "write blocked for {:?}, dropping client",
+                    self.config.write_timeout
+                );
+                self.stalled = true;
+                self.disconnect(ctx).await;
+                false
+            }
+        }
+    }
+
+    /// Writes a frame and keeps a note of it in the outbox.
+    async fn write_recorded(
+        &mut self,
+        frame: WsMsg,
+        message_type: &str,
+        correlation_id: Option<Uuid>,
+        ctx: &mut Context<Self, ()>,
+    ) {
+        let size = frame.len() as u64;
+        let delivered = self.write(frame, ctx).await;
+        self.outbox.push(OutboxEntry {
+            message_type: message_type.to_string(),
+            correlation_id,
+            size,
+            sent_at: unix_millis(),
+            delivered,
+        });
     }
 
-    async fn send_to_session(&self, ws_msg: TransportMsg) {
+    async fn disconnect(&mut self, ctx: &mut Context<Self, ()>) {
+        if !self.stalled {
+            timeout(self.config.write_timeout, self.write.close())
+                .await
+                .ok();
+        }
+        if let Some(session) = self.session.upgrade() {
+            session.kill();
+        } else {
+            ctx.actor_ref().kill();
+        }
+    }
+
+    async fn handle_binary(&mut self, size: usize, ctx: &mut Context<Self, ()>) {
+        self.binary_frames += 1;
+        self.binary_frames_total.fetch_add(1, Ordering::Relaxed);
+        warn!(
+            "binary frame of {size} bytes ({} so far)",
+            self.binary_frames
+        );
+        self.send_error(
+            &format!("only text frames are supported (received {size} byte binary frame)"),
+            ctx,
+        )
+        .await;
+
+        if self.binary_frames >= self.config.max_binary_frames {
+            let close = CloseFrame {
+                code: CloseCode::Unsupported,
+                reason: "binary frames are not supported".into(),
+            };
+            self.write_recorded(WsMsg::Close(Some(close)), "close", None, ctx)
+                .await;
+            self.disconnect(ctx).await;
+        }
+    }
+
+    async fn send_error(&mut self, error: &str, ctx: &mut Context<Self, ()>) {
+        let ws_msg = TransportMsg::OutRespError(TransportEnvelope {
+            correlation_id: Uuid::nil(),
+            seq: None,
+            payload: OutRespError {
+                error: error.to_string(),
+                code: None,
+            },
+        });
+        self.send_msg(&ws_msg, ctx).await;
+    }
+
+    async fn send_msg(&mut self, ws_msg: &TransportMsg, ctx: &mut Context<Self, ()>) {
+        let frame = match self.encoding {
+            Encoding::Json => serialize(ws_msg)
+                .map(|text| WsMsg::Text(text.into()))
+                .map_err(|e| e.to_string()),
+            Encoding::Msgpack => serialize_msgpack(ws_msg)
+                .map(|data| WsMsg::Binary(data.into()))
+                .map_err(|e| e.to_string()),
+        };
+        match frame {
+            Ok(_) if !self.pass_chaos().await => {}
+            Ok(frame) => {
+                let (message_type, correlation_id) = if self.outbox.is_enabled() {
+                    let value = serde_json::to_value(ws_msg).unwrap_or_default();
+                    (
+                        value["messageType"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        value["correlationId"]
+                            .as_str()
+                            .and_then(|id| id.parse().ok()),
+                    )
+                } else {
+                    Default::default()
+                };
+                self.capture.record_msg(Direction::Out, ws_msg);
+                self.write_recorded(frame, &message_type, correlation_id, ctx)
+                    .await;
+                trace!("---> {ws_msg:?}");
+            }
+            Err(e) => error!("serialize error: {e}"),
+        }
+    }
+
+    async fn send_to_session(&self, ws_msg: TransportMsg, received_at: Instant) {
         if let Some(session) = self.session.upgrade() {
             trace!("<--- {ws_msg:?}");
-            session.tell(ws_msg).await.ok();
+            session
+                .tell(Inbound {
+                    msg: ws_msg,
+                    received_at,
+                })
+                .await
+                .ok();
+        }
+    }
+
+    fn record_latency(&self, timing: RequestTiming) {
+        let latency = timing.received_at.elapsed();
+        self.latency.record(&timing.message_type, latency);
+        let threshold = self.config.slow_request_threshold;
+        if !threshold.is_zero() && latency > threshold {
+            warn!("slow request: {} took {latency:?}", timing.message_type);
         }
     }
 }
@@ -47,13 +348,56 @@ impl Message<StreamItem> for WebSocketClientActor {
     type Reply = ();
 
     async fn handle(&mut self, msg: StreamItem, ctx: &mut Context<Self, ()>) {
+        if matches!(msg, StreamMessage::Next(_)) && !self.pass_chaos().await {
+            return;
+        }
+        let received_at = Instant::now();
         match msg {
             StreamMessage::Started(()) => {}
 
-            StreamMessage::Next(Ok(text)) => match parse(&text) {
-                Ok(ws_msg) => self.send_to_session(ws_msg).await,
-                Err(e) => error!("bad incoming json: {e}"),
-            },
+            StreamMessage::Next(Ok(Frame::Text(text)))
+                if text.len() > self.config.max_message_size =>
+            {
+                warn!("incoming message too large: {} bytes", text.len());
+                self.send_error("message too large", ctx).await;
+                self.disconnect(ctx).await;
+            }
+
+            StreamMessage::Next(Ok(Frame::Binary(data)))
+                if data.len() > self.config.max_message_size =>
+            {
+                warn!("incoming message too large: {} bytes", data.len());
+                self.send_error("message too large", ctx).await;
+                self.disconnect(ctx).await;
+            }
+
+            StreamMessage::Next(Ok(Frame::Binary(data))) if self.encoding == Encoding::Msgpack => {
+                match parse_msgpack(&data) {
+                    Ok(ws_msg) => {
+                        self.capture.record_msg(Direction::In, &ws_msg);
+                        self.send_to_session(ws_msg, received_at).await
+                    }
+                    Err(e) => {
+                        error!("bad incoming msgpack: {e}");
+                        self.send_error("malformed message", ctx).await;
+                    }
+                }
+            }
+
+            StreamMessage::Next(Ok(Frame::Binary(data))) => {
+                self.handle_binary(data.len(), ctx).await;
+            }
+
+            StreamMessage::Next(Ok(Frame::Text(text))) => {
+                self.capture.record_text(Direction::In, &text);
+                match parse(&text) {
+                    Ok(ws_msg) => self.send_to_session(ws_msg, received_at).await,
+                    Err(e) => {
+                        error!("bad incoming json: {e}");
+                        self.send_error("malformed message", ctx).await;
+                    }
+                }
+            }
 
             StreamMessage::Next(Err(e)) => {
                 error!("WebSocket read error: {e}");
@@ -73,26 +417,42 @@ impl Message<StreamItem> for WebSocketClientActor {
 #[derive(Debug)]
 pub enum ToTransport {
     Raw(String),
-    TransportMsg(TransportMsg),
+    TransportMsg(Box<TransportMsg>),
+    /// A response, with when its request arrived.
+    Response(Box<TransportMsg>, RequestTiming),
+    /// Switches the frame encoding for everything sent after it.
+    SetEncoding(Encoding),
 }
 
 impl Message<ToTransport> for WebSocketClientActor {
     type Reply = ();
 
-    async fn handle(&mut self, msg: ToTransport, _ctx: &mut Context<Self, Self::Reply>) {
+    async fn handle(&mut self, msg: ToTransport, ctx: &mut Context<Self, Self::Reply>) {
         match msg {
             ToTransport::Raw(text) => {
-                self.write.send(WsMsg::Text(text.into())).await.ok();
+                self.capture.record_text(Direction::Out, &text);
+                self.write_recorded(WsMsg::Text(text.into()), "raw", None, ctx)
+                    .await
             }
-            ToTransport::TransportMsg(ws_msg) => match serialize(&ws_msg) {
-                Ok(text) => {
-                    self.write.send(WsMsg::Text(text.into())).await.ok();
-                    trace!("---> {ws_msg:?}");
-                }
-                Err(e) => error!("serialize error: {e}"),
-            },
+            ToTransport::TransportMsg(ws_msg) => self.send_msg(&ws_msg, ctx).await,
+            ToTransport::Response(ws_msg, timing) => {
+                self.send_msg(&ws_msg, ctx).await;
+                self.record_latency(timing);
+            }
+            ToTransport::SetEncoding(encoding) => self.encoding = encoding,
         }
     }
 }
 
+/// The task feeding incoming frames, aborted if the client gets stuck.
+pub struct SetReader(pub AbortHandle);
+
+impl Message<SetReader> for WebSocketClientActor {
+    type Reply = ();
+
+    async fn handle(&mut self, SetReader(reader): SetReader, _ctx: &mut Context<Self, ()>) {
+        self.reader = Some(reader);
+    }
+}
+
 // #endregion
@@ -0,0 +1,169 @@
+use std::{collections::HashMap, io, path::Path};
+
+use tracing::{error, info};
+
+use crate::{
+    config::ServerConfig,
+    data_types::{GameSettings, WordPartEntry},
+    tools::{edit_distance, fold_kana},
+};
+
+/// Suggestions offered for a word part that is not in the index.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// The word parts (kanji and their components) games can be narrowed to,
+/// with their readings and how many words use them.
+#[derive(Debug, Default)]
+pub struct WordPartIndex {
+    /// Most used first.
+    entries: Vec<WordPartEntry>,
+    by_part: HashMap<String, Vec<usize>>,
+}
+
+impl WordPartIndex {
+    pub fn new(entries: impl IntoIterator<Item = WordPartEntry>) -> Self {
+        let mut entries: Vec<WordPartEntry> = entries
+            .into_iter()
+            .filter(|e| !e.word_part.is_empty())
+            .collect();
+        entries.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.word_part.cmp(&b.word_part))
+        });
+        let mut by_part: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, entry) in entries.iter().enumerate() {
+            by_part.entry(entry.word_part.clone()).or_default().push(i);
+        }
+        Self { entries, by_part }
+    }
+
+    /// Reads one entry per line as `part<TAB>reading<TAB>count`; a part with
+    /// several readings has a line for each. Blank lines and lines starting
+    /// with `#` are skipped.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let mut entries = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: expected part, reading and count", i + 1),
+                )
+            };
+            let mut fields = line.split('\t').map(str::trim);
+            let (Some(word_part), Some(reading), Some(count)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid());
+            };
+            entries.push(WordPartEntry {
+                word_part: word_part.into(),
+                word_part_reading: reading.into(),
+                count: count.parse().map_err(|_| invalid())?,
+            });
+        }
+        Ok(Self::new(entries))
+    }
+
+    /// The index from the configured data file. A file that can't be read
+    /// is logged and leaves word parts unchecked rather than stopping the
+    /// server.
+    pub fn from_config(config: &ServerConfig) -> Self {
+        let Some(path) = &config.word_parts_path else {
+            return Self::default();
+        };
+        match Self::load(path) {
+            Ok(index) => {
+                info!("loaded {} word parts from {}", index.len(), path.display());
+                index
+            }
+            Err(e) => {
+                error!("can't load word parts from {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn entries(&self) -> &[WordPartEntry] {
+        &self.entries
+    }
+
+    pub fn readings(&self, word_part: &str) -> impl Iterator<Item = &WordPartEntry> {
+        self.by_part
+            .get(word_part)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.entries[i])
+    }
+
+    /// The parts closest to `text`, compared with both the parts and their
+    /// readings so a typed reading finds its part. Ties go to the part used
+    /// by more words.
+    pub fn suggestions(&self, text: &str) -> Vec<String> {
+        let text = fold_kana(text.trim());
+        let mut scored: Vec<(u32, &WordPartEntry)> = self
+            .entries
+            .iter()
+            .map(|e| {
+                let distance = edit_distance(&text, &e.word_part)
+                    .min(edit_distance(&text, &fold_kana(&e.word_part_reading)));
+                (distance, e)
+            })
+            .collect();
+        // stable, so the count order breaks ties
+        scored.sort_by_key(|(distance, _)| *distance);
+
+        let mut parts: Vec<String> = Vec::new();
+        for (_, entry) in scored {
+            if !parts.contains(&entry.word_part) {
+                parts.push(entry.word_part.clone());
+            }
+            if parts.len() == MAX_SUGGESTIONS {
+                break;
+            }
+        }
+        parts
+    }
+
+    /// Checks the word part settings against the index. Nothing is checked
+    /// when no index is loaded.
+    pub fn check(&self, settings: &GameSettings) -> Result<(), String> {
+        let Some(word_part) = &settings.word_part else {
+            return Ok(());
+        };
+        if self.is_empty() {
+            return Ok(());
+        }
+        if !self.by_part.contains_key(word_part) {
+            return Err(format!(
+                "unknown word part: {}",
+                self.suggestions(word_part).join(",")
+            ));
+        }
+        if let Some(reading) = &settings.word_part_reading
+            && !self
+                .readings(word_part)
+                .any(|e| fold_kana(&e.word_part_reading) == fold_kana(reading))
+        {
+            let readings: Vec<&str> = self
+                .readings(word_part)
+                .map(|e| e.word_part_reading.as_str())
+                .collect();
+            return Err(format!("unknown word part reading: {}", readings.join(",")));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    room_actor::{ProvideQuestionResponse, SendAnswerRequest, StartGameRequest},
+    testkit::{RoomMember, TestRoom, message_types, sample_question},
+};
+use uuid::Uuid;
+
+async fn room_with(names: &[&str], abandoned_after_rounds: u32) -> (TestRoom, Vec<RoomMember>) {
+    let room = TestRoom::start(ServerConfig {
+        abandoned_after_rounds,
+        round_time_warnings: Vec::new(),
+        ..Default::default()
+    })
+    .await;
+    let mut members = Vec::new();
+    for name in names {
+        members.push(room.join(name, false).await.unwrap());
+    }
+    for member in &members {
+        member.sink.take();
+    }
+    (room, members)
+}
+
+async fn start_game(room: &TestRoom, admin: &RoomMember) {
+    room.room
+        .ask(StartGameRequest {
+            requester: admin.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            game_settings: GameSettings {
+                round_duration: 30,
+                rounds_count: 10,
+                start_countdown: 0,
+                allow_repeat_words: true,
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+}
+
+/// Answers the question requests the admin has received so far and lets
+/// the round run out.
+async fn play_round(room: &TestRoom, admin: &RoomMember, answer: Option<&RoomMember>) {
+    for msg in admin.sink.take() {
+        if let TransportMsg::OutReqQuestion(req) = msg {
+            room.room
+                .ask(ProvideQuestionResponse {
+                    requester: admin.session.clone(),
+                    correlation_id: req.correlation_id,
+                    question_info: sample_question(),
+                    question_svg: "<svg/>".into(),
+                })
+                .await
+                .unwrap();
+        }
+    }
+    if let Some(member) = answer {
+        room.room
+            .ask(SendAnswerRequest {
+                requester: member.session.clone(),
+                correlation_id: Uuid::new_v4(),
+                answer: "x".into(),
+                question_id: None,
+                game_id: None,
+            })
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(Duration::from_secs(31)).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn game_without_answers_is_stopped_after_a_warning() {
+    let (room, members) = room_with(&["alice", "bob"], 2).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+
+    start_game(&room, alice).await;
+    play_round(&room, alice, None).await;
+    let sent = bob.sink.take();
+    let Some(TransportMsg::OutNotifIdleWarning(warning)) = sent
+        .iter()
+        .find(|msg| matches!(msg, TransportMsg::OutNotifIdleWarning(_)))
+    else {
+        panic!("expected a warning, got {:?}", message_types(&sent));
+    };
+    assert_eq!(warning.payload.empty_rounds, 1);
+    assert_eq!(warning.payload.rounds_left, 1);
+    assert!(warning.payload.idle_ms >= 30_000);
+
+    play_round(&room, alice, None).await;
+    let sent = bob.sink.take();
+    let Some(TransportMsg::OutNotifGameStopped(stopped)) = sent.last() else {
+        panic!("expected game end, got {:?}", message_types(&sent));
+    };
+    assert_eq!(stopped.payload.reason, GameStopReason::Abandoned);
+}
+
+#[tokio::test(start_paused = true)]
+async fn an_answer_resets_the_watchdog() {
+    let (room, members) = room_with(&["alice", "bob"], 2).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+
+    start_game(&room, alice).await;
+    play_round(&room, alice, None).await;
+    play_round(&room, alice, Some(bob)).await;
+    play_round(&room, alice, None).await;
+
+    let types = message_types(&bob.sink.take());
+    assert_eq!(
+        types
+            .iter()
+            .filter(|t| *t == "OUT_NOTIF_idleWarning")
+            .count(),
+        2
+    );
+    assert!(
+        !types.iter().any(|t| t == "OUT_NOTIF_gameStopped"),
+        "{types:?}"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn watchdog_can_be_turned_off() {
+    let (room, members) = room_with(&["alice", "bob"], 0).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+
+    start_game(&room, alice).await;
+    for _ in 0..5 {
+        play_round(&room, alice, None).await;
+    }
+
+    let types = message_types(&bob.sink.take());
+    assert!(
+        !types.iter().any(|t| t == "OUT_NOTIF_idleWarning"),
+        "{types:?}"
+    );
+    assert!(
+        !types.iter().any(|t| t == "OUT_NOTIF_gameStopped"),
+        "{types:?}"
+    );
+}
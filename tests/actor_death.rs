@@ -0,0 +1,65 @@
+use kanjilab_server::{
+    data_types::*,
+    game_actor::GetServerStats,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope},
+};
+
+#[tokio::test]
+async fn crashed_room_is_replaced_and_clients_rejoin() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    let old_room = server.game().ask(GetServerStats).await.unwrap().rooms[0].clone();
+    old_room.kill();
+
+    let notif = alice
+        .expect::<OutNotifServerError>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(notif.payload.message, "room restarted");
+    bob.expect::<OutNotifServerError>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    // the game handles the link death before this, so the rejoin is done
+    let rooms = server.game().ask(GetServerStats).await.unwrap().rooms;
+    assert_eq!(rooms.len(), 1);
+    assert_ne!(rooms[0].id(), old_room.id());
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespClientList(list) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected a client list");
+    };
+    assert_eq!(list.payload.clients.len(), 2);
+
+    let chat = TransportMsg::InReqSendChat(envelope(InReqSendChat {
+        message: "still here".into(),
+    }));
+    assert_eq!(bob.request(chat).await.unwrap(), "success");
+}
+
+#[tokio::test]
+async fn crashed_game_actor_closes_the_room_and_sessions() {
+    let server = TestServer::start().await;
+    // over a socket, so the last message is flushed before the close
+    let mut alice = server.client("alice").await;
+    let room = server.game().ask(GetServerStats).await.unwrap().rooms[0].clone();
+
+    server.game().kill();
+
+    let notif = alice
+        .expect::<OutNotifServerError>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(notif.payload.message, "internal error");
+    alice.expect_closed(DEFAULT_TIMEOUT).await.unwrap();
+    assert!(!room.is_alive());
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, one_round, start_game},
+};
+
+fn adaptive() -> AdaptiveDuration {
+    AdaptiveDuration {
+        base: 10,
+        per_character_ms: 2_000,
+        per_rarity_step_ms: 5_000,
+        common_frequency: 10_000,
+        min: 5,
+        max: 40,
+    }
+}
+
+fn question(word: &str, frequency: Option<f64>) -> QuestionInfo {
+    QuestionInfo {
+        word_info: WordInfo {
+            word: word.into(),
+            meanings: Vec::new(),
+            readings: vec![ReadingWithParts {
+                reading: "ひ".into(),
+                parts: Vec::new(),
+            }],
+        },
+        frequency,
+        ..Default::default()
+    }
+}
+
+fn start(adaptive_duration: Option<AdaptiveDuration>) -> TransportMsg {
+    start_game(GameSettings {
+        adaptive_duration,
+        ..one_round()
+    })
+}
+
+async fn round_duration_ms(server: &TestServer, adaptive: Option<AdaptiveDuration>) -> Option<u64> {
+    let mut alice = server.local_client("alice").await;
+    assert_eq!(alice.request(start(adaptive)).await.unwrap(), "success");
+    let req = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    alice
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: question("日曜日", Some(100.0)),
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .round_duration_ms
+}
+
+#[test]
+fn duration_grows_with_length_and_rarity() {
+    let adaptive = adaptive();
+    assert_eq!(
+        adaptive.round_duration(&question("日", None)),
+        Duration::from_secs(12)
+    );
+    // two powers of ten below common
+    assert_eq!(
+        adaptive.round_duration(&question("日曜日", Some(100.0))),
+        Duration::from_secs(26)
+    );
+    // common words get nothing for rarity
+    assert_eq!(
+        adaptive.round_duration(&question("日", Some(50_000.0))),
+        Duration::from_secs(12)
+    );
+    assert_eq!(
+        adaptive.round_duration(&question("日曜日曜日曜日曜", Some(1.0))),
+        Duration::from_secs(40)
+    );
+    let short = AdaptiveDuration {
+        base: 1,
+        ..adaptive
+    };
+    assert_eq!(
+        short.round_duration(&question("", None)),
+        Duration::from_secs(5)
+    );
+}
+
+#[tokio::test]
+async fn question_carries_its_round_length() {
+    let server = TestServer::start().await;
+    assert_eq!(
+        round_duration_ms(&server, Some(adaptive())).await,
+        Some(26_000)
+    );
+
+    let server = TestServer::start().await;
+    assert_eq!(round_duration_ms(&server, None).await, Some(30_000));
+}
+
+#[tokio::test]
+async fn absurd_bounds_are_rejected() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+
+    for adaptive in [
+        AdaptiveDuration {
+            min: 0,
+            ..adaptive()
+        },
+        AdaptiveDuration {
+            max: 3600,
+            ..adaptive()
+        },
+        AdaptiveDuration {
+            min: 50,
+            ..adaptive()
+        },
+        AdaptiveDuration {
+            per_character_ms: 600_000,
+            ..adaptive()
+        },
+    ] {
+        assert_eq!(
+            alice.request(start(Some(adaptive))).await.unwrap(),
+            "invalid adaptive duration"
+        );
+    }
+}
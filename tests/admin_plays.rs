@@ -0,0 +1,125 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope, sample_question, start_game},
+};
+
+fn start(rounds_count: u64) -> TransportMsg {
+    start_game(GameSettings {
+        round_duration: 30,
+        rounds_count,
+        start_countdown: 0,
+        allow_repeat_words: true,
+        admin_plays: false,
+        ..Default::default()
+    })
+}
+
+fn answer(answer: &str) -> TransportMsg {
+    TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+        answer: answer.into(),
+        question_id: None,
+        game_id: None,
+    }))
+}
+
+#[tokio::test]
+async fn question_provider_sits_the_round_out() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let bob_id = bob.id.clone().unwrap();
+
+    assert_eq!(alice.request(start(1)).await.unwrap(), "success");
+    alice.provide_question(sample_question()).await.unwrap();
+    alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(
+        alice.request(answer("ひ")).await.unwrap(),
+        "question provider cannot answer"
+    );
+
+    // bob is the only one the round waits on
+    assert_eq!(bob.request(answer("ひ")).await.unwrap(), "success");
+    let stopped = alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let ids: Vec<_> = stopped.payload.answers.iter().map(|a| &a.id).collect();
+    assert_eq!(ids, vec![&bob_id]);
+    let summaries: Vec<_> = stopped
+        .payload
+        .player_summaries
+        .iter()
+        .map(|s| &s.id)
+        .collect();
+    assert_eq!(summaries, vec![&bob_id]);
+}
+
+#[tokio::test]
+async fn exclusion_moves_to_the_new_admin_from_the_next_round() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let mut carol = server.local_client("carol").await;
+
+    assert_eq!(alice.request(start(2)).await.unwrap(), "success");
+    alice.provide_question(sample_question()).await.unwrap();
+    alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    bob.expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    carol
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let leave = TransportMsg::InReqLeaveRoom(envelope(InReqLeaveRoom {}));
+    assert_eq!(alice.request(leave).await.unwrap(), "success");
+
+    let made = bob
+        .expect::<OutNotifAdminMade>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let (admin, player) = if Some(&made.payload.id) == bob.id.as_ref() {
+        (&mut bob, &mut carol)
+    } else {
+        (&mut carol, &mut bob)
+    };
+    let admin_id = admin.id.clone().unwrap();
+    let player_id = player.id.clone().unwrap();
+
+    // the next question is asked of the new admin, who did not supply
+    // this one and so still answers it
+    admin.provide_question(sample_question()).await.unwrap();
+    assert_eq!(admin.request(answer("ひ")).await.unwrap(), "success");
+    assert_eq!(player.request(answer("つき")).await.unwrap(), "success");
+
+    admin
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(
+        admin.request(answer("ひ")).await.unwrap(),
+        "question provider cannot answer"
+    );
+    assert_eq!(player.request(answer("ひ")).await.unwrap(), "success");
+
+    let stopped = player
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let rounds_played = |id: &str| {
+        stopped
+            .payload
+            .player_summaries
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.rounds_played)
+    };
+    assert_eq!(rounds_played(&admin_id), Some(1));
+    assert_eq!(rounds_played(&player_id), Some(2));
+}
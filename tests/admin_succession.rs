@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    room_actor::{LeaveRoomRequest, SetAdminSuccessorRequest},
+    testkit::{RoomMember, TestRoom},
+};
+use uuid::Uuid;
+
+/// Joins `names` in order, a moment apart, so join times differ.
+async fn room_with(names: &[(&str, bool)]) -> (TestRoom, Vec<RoomMember>) {
+    let room = TestRoom::start(ServerConfig::default()).await;
+    let mut members = Vec::new();
+    for &(name, is_spectator) in names {
+        members.push(room.join(name, is_spectator).await.unwrap());
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    for member in &members {
+        member.sink.take();
+    }
+    (room, members)
+}
+
+async fn leave(room: &TestRoom, member: &RoomMember) {
+    room.room
+        .ask(LeaveRoomRequest {
+            requester: member.session.clone(),
+            correlation_id: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+}
+
+async fn set_successor(room: &TestRoom, admin: &RoomMember, successor: &RoomMember) -> String {
+    room.room
+        .ask(SetAdminSuccessorRequest {
+            requester: admin.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            client_id: Some(successor.uuid.to_string()),
+        })
+        .await
+        .unwrap();
+    match admin.sink.take().pop() {
+        Some(TransportMsg::OutRespStatus(env)) => env.payload.status,
+        other => panic!("expected a status, got {other:?}"),
+    }
+}
+
+/// The ids announced by `OUT_NOTIF_adminMade` since the last check.
+fn admins_made(member: &RoomMember) -> Vec<String> {
+    member
+        .sink
+        .take()
+        .into_iter()
+        .filter_map(|msg| match msg {
+            TransportMsg::OutNotifAdminMade(env) => Some(env.payload.id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn longest_present_player_inherits() {
+    let names = ["alice", "bob", "carol", "dave", "erin", "frank"];
+    let (room, members) = room_with(&names.map(|name| (name, false))).await;
+
+    for (i, member) in members.iter().enumerate().take(names.len() - 1) {
+        leave(&room, member).await;
+        let next = &members[i + 1];
+        assert_eq!(admins_made(next).last(), Some(&next.uuid.to_string()));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn designated_successor_goes_first() {
+    let (room, members) = room_with(&[("alice", false), ("bob", false), ("carol", false)]).await;
+    let [alice, _bob, carol] = members.as_slice() else {
+        unreachable!()
+    };
+
+    assert_eq!(set_successor(&room, carol, alice).await, "not admin");
+    assert_eq!(set_successor(&room, alice, carol).await, "success");
+    leave(&room, alice).await;
+    assert_eq!(admins_made(carol), [carol.uuid.to_string()]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn successor_who_left_falls_back_to_join_order() {
+    let (room, members) = room_with(&[("alice", false), ("bob", false), ("carol", false)]).await;
+    let [alice, bob, carol] = members.as_slice() else {
+        unreachable!()
+    };
+
+    assert_eq!(set_successor(&room, alice, carol).await, "success");
+    leave(&room, carol).await;
+    leave(&room, alice).await;
+    assert_eq!(admins_made(bob), [bob.uuid.to_string()]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn spectators_are_never_promoted() {
+    let (room, members) = room_with(&[("alice", false), ("viewer", true), ("bob", false)]).await;
+    let [alice, viewer, bob] = members.as_slice() else {
+        unreachable!()
+    };
+
+    assert_eq!(
+        set_successor(&room, alice, viewer).await,
+        "spectator cannot be admin"
+    );
+    leave(&room, alice).await;
+    assert_eq!(admins_made(viewer), [bob.uuid.to_string()]);
+
+    // with nobody but spectators left the room goes without an admin
+    leave(&room, bob).await;
+    assert!(admins_made(viewer).is_empty());
+}
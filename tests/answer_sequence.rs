@@ -0,0 +1,75 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, sample_question, start_game},
+};
+
+fn start() -> TransportMsg {
+    start_game(GameSettings {
+        round_duration: 1,
+        // the last round ends the game without a roundEnded
+        rounds_count: 2,
+        start_countdown: 0,
+        allow_answer_change: true,
+        admin_plays: true,
+        ..Default::default()
+    })
+}
+
+async fn answer(client: &mut TestClient, text: &str) -> OutRespStatus {
+    let correlation_id = client
+        .send(TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+            answer: text.into(),
+            question_id: None,
+            game_id: None,
+        })))
+        .await
+        .unwrap();
+    match client.response(correlation_id, DEFAULT_TIMEOUT).await {
+        Ok(TransportMsg::OutRespStatus(env)) => env.payload,
+        other => panic!("expected a status, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn accepted_answers_are_numbered_in_arrival_order() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let mut carol = server.local_client("carol").await;
+
+    let correlation_id = alice.send(start()).await.unwrap();
+    alice.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap();
+    alice.provide_question(sample_question()).await.unwrap();
+    bob.expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    let first = answer(&mut bob, "つき").await;
+    assert_eq!(first.status, "success");
+    assert_eq!(first.sequence, Some(1));
+    // a changed answer goes to the back
+    assert_eq!(answer(&mut carol, "ひ").await.sequence, Some(2));
+    assert_eq!(answer(&mut bob, "ひ").await.sequence, Some(3));
+
+    // alice never answers, so the round runs out
+    let ended = carol
+        .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let sequence = |id: &Option<String>| {
+        ended
+            .payload
+            .answers
+            .iter()
+            .find(|a| Some(&a.id) == id.as_ref())
+            .unwrap()
+            .sequence
+    };
+    assert_eq!(sequence(&bob.id), Some(3));
+    assert_eq!(sequence(&carol.id), Some(2));
+    assert_eq!(sequence(&alice.id), None);
+
+    let late = answer(&mut bob, "ひ").await;
+    assert_ne!(late.status, "success");
+    assert_eq!(late.sequence, None);
+}
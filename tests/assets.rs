@@ -0,0 +1,98 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, start_game},
+};
+
+async fn start(admin: &mut TestClient) {
+    let start = start_game(GameSettings {
+        round_duration: 1,
+        rounds_count: 4,
+        start_countdown: 0,
+        ..Default::default()
+    });
+    assert_eq!(admin.request(start).await.unwrap(), "success");
+}
+
+async fn provide(admin: &mut TestClient, word: &str) {
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo {
+                    word_info: WordInfo {
+                        word: word.into(),
+                        meanings: Vec::new(),
+                        readings: Vec::new(),
+                    },
+                    ..Default::default()
+                },
+                question_svg: format!("<svg>{word}</svg>"),
+            },
+        }))
+        .await
+        .unwrap();
+}
+
+async fn fetch(client: &mut TestClient, asset_id: &str) -> Result<String, String> {
+    let correlation_id = client
+        .send(TransportMsg::InReqFetchAsset(envelope(InReqFetchAsset {
+            asset_id: asset_id.into(),
+        })))
+        .await
+        .unwrap();
+    match client.response(correlation_id, DEFAULT_TIMEOUT).await {
+        Ok(TransportMsg::OutRespAsset(env)) => Ok(env.payload.content),
+        Ok(TransportMsg::OutRespStatus(env)) => Err(env.payload.status),
+        other => panic!("expected an asset, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn question_image_is_fetched_by_id() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = TestClient::local(server.game())
+        .await
+        .with_capabilities(&[]);
+    bob.handshake().await.unwrap();
+    bob.register("bob").await.unwrap();
+    start(&mut alice).await;
+
+    provide(&mut alice, "日").await;
+    let question = alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload;
+    assert!(question.question_svg.is_empty());
+    assert_eq!(question.asset_size, Some("<svg>日</svg>".len() as u64));
+    let first = question.asset_id.unwrap();
+    assert_eq!(fetch(&mut alice, &first).await.unwrap(), "<svg>日</svg>");
+
+    // a client without the capability still gets it inline
+    let question = bob
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload;
+    assert_eq!(question.question_svg, "<svg>日</svg>");
+    assert_eq!(question.asset_id, None);
+
+    // the previous round's image is kept, the one before is not
+    for word in ["月", "火"] {
+        provide(&mut alice, word).await;
+        alice
+            .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        fetch(&mut alice, &first).await.unwrap_err(),
+        "asset expired"
+    );
+}
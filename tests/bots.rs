@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    ServerConfig,
+    config::{BotConfig, BotProfile},
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+async fn start_server(empty_room_bots: usize) -> TestServer {
+    let quick = BotProfile {
+        answer_delay: Duration::from_millis(20),
+        accuracy: 1.0,
+    };
+    TestServer::start_with_config(ServerConfig {
+        bots: BotConfig {
+            empty_room_bots,
+            empty_room_difficulty: BotDifficulty::Hard,
+            hard: quick,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .await
+}
+
+fn add_bot() -> TransportMsg {
+    TransportMsg::InReqAddBot(envelope(InReqAddBot {
+        difficulty: BotDifficulty::Hard,
+    }))
+}
+
+fn remove_bot(client_id: &str) -> TransportMsg {
+    TransportMsg::InReqRemoveBot(envelope(InReqRemoveBot {
+        client_id: client_id.into(),
+    }))
+}
+
+async fn added_bot(admin: &mut TestClient) -> ClientInfo {
+    // players who joined before come first
+    loop {
+        let registered = admin
+            .expect::<OutNotifClientRegistered>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        if registered.payload.client.is_bot {
+            return registered.payload.client;
+        }
+    }
+}
+
+async fn client_list(client: &mut TestClient) -> Vec<ClientInfo> {
+    let correlation_id = client
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    match client.response(correlation_id, DEFAULT_TIMEOUT).await {
+        Ok(TransportMsg::OutRespClientList(env)) => env.payload.clients,
+        other => panic!("expected the client list, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn bot_answers_and_says_gg() {
+    let server = start_server(0).await;
+    let mut alice = server.local_client("alice").await;
+    assert_eq!(alice.request(add_bot()).await.unwrap(), "success");
+    let bot = added_bot(&mut alice).await;
+    assert!(!bot.is_admin);
+
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings {
+            round_duration: 30,
+            rounds_count: 1,
+            start_countdown: 0,
+            admin_plays: false,
+            ..Default::default()
+        },
+    }));
+    assert_eq!(alice.request(start).await.unwrap(), "success");
+    let req = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    alice
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo {
+                    word_info: WordInfo {
+                        word: "日".into(),
+                        meanings: Vec::new(),
+                        readings: vec![ReadingWithParts {
+                            reading: "ひ".into(),
+                            parts: Vec::new(),
+                        }],
+                    },
+                    ..Default::default()
+                },
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+
+    // the admin sits out, so the bot's answer ends the game
+    let stopped = alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let answer = &stopped.payload.answers[0];
+    assert_eq!(answer.id, bot.id);
+    assert!(answer.is_correct);
+    let chat = alice
+        .expect::<OutNotifChatSent>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(
+        (chat.payload.id, chat.payload.message),
+        (bot.id, "gg".into())
+    );
+}
+
+#[tokio::test]
+async fn only_admins_manage_bots() {
+    let server = start_server(0).await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    assert_eq!(bob.request(add_bot()).await.unwrap(), "not admin");
+    assert_eq!(alice.request(add_bot()).await.unwrap(), "success");
+    let bot = added_bot(&mut alice).await;
+
+    let make_admin = TransportMsg::InReqMakeAdmin(envelope(InReqMakeAdmin {
+        admin_password: String::new(),
+        client_id: bot.id.clone(),
+        revoke_existing: false,
+    }));
+    assert_eq!(
+        alice.request(make_admin).await.unwrap(),
+        "bot cannot be admin"
+    );
+    assert_eq!(bob.request(remove_bot(&bot.id)).await.unwrap(), "not admin");
+    let bob_id = bob.id.clone().unwrap();
+    assert_eq!(
+        alice.request(remove_bot(&bob_id)).await.unwrap(),
+        "not a bot"
+    );
+
+    assert_eq!(alice.request(remove_bot(&bot.id)).await.unwrap(), "success");
+    let gone = bob
+        .expect::<OutNotifClientDisconnected>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(gone.payload.id, bot.id);
+    assert_eq!(client_list(&mut alice).await.len(), 2);
+}
+
+#[tokio::test]
+async fn bots_fill_a_new_room_but_never_become_admin() {
+    let server = start_server(2).await;
+    let mut alice = server.local_client("alice").await;
+
+    let mut clients = client_list(&mut alice).await;
+    for _ in 0..100 {
+        if clients.len() == 3 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        clients = client_list(&mut alice).await;
+    }
+    let bots: Vec<&ClientInfo> = clients.iter().filter(|c| c.is_bot).collect();
+    assert_eq!(bots.len(), 2);
+    assert!(bots.iter().all(|c| !c.is_admin));
+    assert!(
+        clients
+            .iter()
+            .any(|c| c.is_admin && Some(&c.id) == alice.id.as_ref())
+    );
+
+    // with only bots left nobody is promoted, and the next player is admin
+    let leave = TransportMsg::InReqLeaveRoom(envelope(InReqLeaveRoom {}));
+    assert_eq!(alice.request(leave).await.unwrap(), "success");
+    let mut bob = server.local_client("bob").await;
+    let clients = client_list(&mut bob).await;
+    let admins: Vec<&str> = clients
+        .iter()
+        .filter(|c| c.is_admin)
+        .map(|c| c.id.as_str())
+        .collect();
+    assert_eq!(admins, [bob.id.as_deref().unwrap()]);
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+fn chat(message: &str) -> TransportMsg {
+    TransportMsg::InReqSendChat(envelope(InReqSendChat {
+        message: message.into(),
+    }))
+}
+
+#[tokio::test]
+async fn hello_echoes_the_supported_capabilities() {
+    let server = TestServer::start().await;
+    let mut client = TestClient::local(server.game()).await;
+
+    let hello = TransportMsg::InReqSendPublicKey(envelope(InReqSendPublicKey {
+        key: client.public_key(),
+        algorithm: SignatureAlgorithm::Ed25519,
+        encoding: Encoding::Json,
+        capabilities: vec!["telepathy".into(), "reactions".into()],
+    }));
+    let correlation_id = client.send(hello).await.unwrap();
+    let TransportMsg::OutRespSignMessage(resp) = client
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected a challenge");
+    };
+    assert_eq!(resp.payload.capabilities, vec![Capability::Reactions]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn reactions_only_reach_clients_that_asked_for_them() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = TestClient::local(server.game())
+        .await
+        .with_capabilities(&[]);
+    bob.handshake().await.unwrap();
+    bob.register("bob").await.unwrap();
+
+    assert_eq!(alice.request(chat("hello")).await.unwrap(), "success");
+    let sent = alice
+        .expect::<OutNotifChatSent>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let reaction = TransportMsg::InReqSendReaction(envelope(InReqSendReaction {
+        target: ReactionTarget {
+            kind: ReactionTargetKind::Chat,
+            id: sent.payload.message_id,
+        },
+        emoji: "👏".into(),
+    }));
+    assert_eq!(alice.request(reaction).await.unwrap(), "success");
+    alice
+        .expect::<OutNotifReaction>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(alice.request(chat("again")).await.unwrap(), "success");
+
+    // anything sent before the second chat is already queued up by now
+    for message in ["hello", "again"] {
+        let notif = bob
+            .expect::<OutNotifChatSent>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(notif.payload.message, message);
+    }
+    assert!(
+        bob.expect::<OutNotifReaction>(Duration::from_millis(100))
+            .await
+            .is_err()
+    );
+}
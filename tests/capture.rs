@@ -0,0 +1,205 @@
+use std::{fs, path::PathBuf};
+
+use kanjilab_server::{
+    CaptureConfig, ServerConfig,
+    capture::{CaptureEntry, Direction, REDACTED, outcome, read_capture},
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, operator_key, operator_keys},
+};
+use uuid::Uuid;
+
+fn capture_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("kanjilab-capture-{}", Uuid::new_v4()))
+}
+
+/// Every capture file in `dir`, parsed.
+fn captures(dir: &PathBuf) -> Vec<Vec<CaptureEntry>> {
+    let Ok(files) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    files
+        .map(|file| read_capture(&file.unwrap().path()).unwrap())
+        .collect()
+}
+
+async fn chat(client: &mut TestClient, message: &str) {
+    let correlation_id = client
+        .send(TransportMsg::InReqSendChat(envelope(InReqSendChat {
+            message: message.into(),
+        })))
+        .await
+        .unwrap();
+    let status = client
+        .status(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(status, "success");
+}
+
+async fn debug_capture(client: &mut TestClient, client_id: &str, enabled: bool) -> String {
+    let correlation_id = client
+        .send(TransportMsg::InReqDebugCapture(envelope(
+            InReqDebugCapture {
+                client_id: client_id.into(),
+                enabled,
+            },
+        )))
+        .await
+        .unwrap();
+    client
+        .status(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn connections_are_recorded_without_their_secrets() {
+    let dir = capture_dir();
+    let server = TestServer::start_with_config(ServerConfig {
+        capture: Some(CaptureConfig {
+            record_all: true,
+            ..CaptureConfig::new(&dir)
+        }),
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client("alice").await;
+    chat(&mut alice, "hello").await;
+
+    let [entries] = &captures(&dir)[..] else {
+        panic!("expected one capture");
+    };
+    let hello = &entries[0];
+    assert_eq!(hello.direction, Direction::In);
+    assert_eq!(hello.frame["messageType"], "IN_REQ_sendPublicKey");
+    assert_eq!(hello.frame["payload"]["key"], REDACTED);
+    let verify = entries
+        .iter()
+        .find(|e| e.frame["messageType"] == "IN_REQ_verifysignature")
+        .unwrap();
+    assert_eq!(verify.frame["payload"]["signature"], REDACTED);
+    assert!(entries.windows(2).all(|w| w[0].at <= w[1].at));
+
+    let sent = entries
+        .iter()
+        .position(|e| e.frame["payload"]["message"] == "hello")
+        .unwrap();
+    let correlation_id = &entries[sent].frame["correlationId"];
+    let response = entries[sent..]
+        .iter()
+        .find(|e| e.direction == Direction::Out && e.frame["correlationId"] == *correlation_id)
+        .unwrap();
+    assert_eq!(outcome(&response.frame), "OUT_RESP_status success");
+
+    let text = fs::read_dir(&dir)
+        .unwrap()
+        .map(|file| fs::read_to_string(file.unwrap().path()).unwrap())
+        .collect::<String>();
+    assert!(!text.contains(&alice.public_key()));
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn operators_start_and_stop_a_capture() {
+    let dir = capture_dir();
+    let server = TestServer::start_with_config(ServerConfig {
+        capture: Some(CaptureConfig::new(&dir)),
+        operator_keys: operator_keys(),
+        ..Default::default()
+    })
+    .await;
+    let mut bob = server.client("bob").await;
+    let mut alice = server.client_with_key("alice", operator_key()).await;
+    let alice_id = alice.id.clone().unwrap();
+    assert!(captures(&dir).is_empty());
+
+    assert_eq!(
+        debug_capture(&mut bob, &alice_id, true).await,
+        "not operator"
+    );
+
+    assert_eq!(
+        debug_capture(&mut alice, &Uuid::new_v4().to_string(), true).await,
+        "no such client"
+    );
+    assert_eq!(debug_capture(&mut alice, &alice_id, true).await, "success");
+    chat(&mut alice, "recorded").await;
+    assert_eq!(debug_capture(&mut alice, &alice_id, false).await, "success");
+    chat(&mut alice, "not recorded").await;
+
+    let [entries] = &captures(&dir)[..] else {
+        panic!("expected one capture");
+    };
+    let chats: Vec<_> = entries
+        .iter()
+        .filter(|e| e.direction == Direction::In)
+        .filter_map(|e| e.frame["payload"]["message"].as_str())
+        .collect();
+    assert_eq!(chats, ["recorded"]);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[tokio::test]
+async fn captures_are_refused_unless_configured() {
+    let server = TestServer::start_with_config(ServerConfig {
+        operator_keys: operator_keys(),
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client_with_key("alice", operator_key()).await;
+    let alice_id = alice.id.clone().unwrap();
+
+    assert_eq!(
+        debug_capture(&mut alice, &alice_id, true).await,
+        "capture disabled"
+    );
+}
+
+#[tokio::test]
+async fn nobody_may_capture_without_operator_keys() {
+    let dir = capture_dir();
+    let server = TestServer::start_with_config(ServerConfig {
+        capture: Some(CaptureConfig::new(&dir)),
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client("alice").await;
+    let alice_id = alice.id.clone().unwrap();
+
+    assert_eq!(
+        debug_capture(&mut alice, &alice_id, true).await,
+        "not operator"
+    );
+    assert!(captures(&dir).is_empty());
+}
+
+#[tokio::test]
+async fn a_capture_stops_at_its_size_cap() {
+    let dir = capture_dir();
+    let server = TestServer::start_with_config(ServerConfig {
+        capture: Some(CaptureConfig {
+            record_all: true,
+            max_bytes: 3000,
+            ..CaptureConfig::new(&dir)
+        }),
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client("alice").await;
+    for i in 0..3 {
+        chat(&mut alice, &format!("{i}{}", "あ".repeat(150))).await;
+    }
+
+    let file = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+    let size = fs::metadata(&file).unwrap().len();
+    assert!(size > 0 && size <= 3000, "{size} bytes");
+    // whole lines only, and none once the cap was hit
+    let entries = read_capture(&file).unwrap();
+    assert!(!entries.is_empty());
+    assert!(entries.iter().all(|e| {
+        !e.frame
+            .to_string()
+            .contains(&format!("2{}", "あ".repeat(150)))
+    }));
+    fs::remove_dir_all(&dir).ok();
+}
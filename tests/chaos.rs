@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    ServerConfig,
+    chaos::*,
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope, start_game},
+};
+
+fn config(seed: u64) -> ChaosConfig {
+    ChaosConfig {
+        jitter: Duration::ZERO..=Duration::from_millis(40),
+        drop_probability: 0.0,
+        seed: Some(seed),
+    }
+}
+
+async fn chaos_server(seed: u64) -> TestServer {
+    TestServer::start_with_config(ServerConfig {
+        chaos: Some(config(seed)),
+        ..Default::default()
+    })
+    .await
+}
+
+fn start(question_timeout: u64) -> TransportMsg {
+    start_game(GameSettings {
+        round_duration: 1,
+        rounds_count: 1,
+        start_countdown: 0,
+        question_timeout,
+        ..Default::default()
+    })
+}
+
+fn question(correlation_id: uuid::Uuid) -> TransportMsg {
+    TransportMsg::InRespQuestion(TransportEnvelope {
+        correlation_id,
+        seq: None,
+        payload: InRespQuestion {
+            question: QuestionInfo::default(),
+            question_svg: "<svg/>".into(),
+        },
+    })
+}
+
+#[test]
+fn seeded_chaos_repeats_itself() {
+    let lossy = ChaosConfig {
+        drop_probability: 0.3,
+        ..config(42)
+    };
+    let run = |config: ChaosConfig| {
+        let mut chaos = Chaos::new(config);
+        (0..100).map(|_| chaos.next_frame()).collect::<Vec<_>>()
+    };
+
+    let frames = run(lossy.clone());
+    assert_eq!(frames, run(lossy));
+    assert!(frames.iter().any(Option::is_none));
+    assert!(
+        frames
+            .iter()
+            .flatten()
+            .all(|d| *d <= Duration::from_millis(40))
+    );
+    assert!(run(config(42)).iter().all(Option::is_some));
+}
+
+#[tokio::test]
+async fn answers_racing_the_round_end_are_settled_once() {
+    let server = chaos_server(7).await;
+    let mut admin = server.client("admin").await;
+    let mut player = server.client("player").await;
+
+    for _ in 0..3 {
+        assert_eq!(admin.request(start(10)).await.unwrap(), "success");
+        let req = admin
+            .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        admin.send(question(req.correlation_id)).await.unwrap();
+        player
+            .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+
+        // lands just before or just after the round ends
+        tokio::time::sleep(Duration::from_millis(980)).await;
+        let answer = TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+            answer: "a".into(),
+            question_id: None,
+            game_id: None,
+        }));
+        let status = player.request(answer).await.unwrap();
+        assert!(
+            ["success", "no active round"].contains(&status.as_str()),
+            "{status}"
+        );
+
+        let stopped = player
+            .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        let answers = stopped
+            .payload
+            .answers
+            .iter()
+            .filter(|a| Some(&a.id) == player.id.as_ref())
+            .count();
+        assert_eq!(answers, 1);
+        admin
+            .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn question_after_its_timeout_is_ignored() {
+    let server = chaos_server(11).await;
+    let mut admin = server.client("admin").await;
+
+    assert_eq!(admin.request(start(2)).await.unwrap(), "success");
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(2_100)).await;
+    admin.send(question(req.correlation_id)).await.unwrap();
+
+    assert!(
+        admin
+            .expect::<OutNotifQuestion>(Duration::from_millis(500))
+            .await
+            .is_err()
+    );
+    let stop = TransportMsg::InReqStopGame(envelope(InReqStopGame {}));
+    assert_eq!(admin.request(stop).await.unwrap(), "success");
+}
@@ -0,0 +1,109 @@
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+async fn chat(client: &mut TestClient, message: &str) -> String {
+    let correlation_id = client
+        .send(TransportMsg::InReqSendChat(envelope(InReqSendChat {
+            message: message.into(),
+        })))
+        .await
+        .unwrap();
+    client
+        .status(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+}
+
+async fn server() -> TestServer {
+    TestServer::start_with_config(ServerConfig {
+        chat_max_length: 10,
+        ..Default::default()
+    })
+    .await
+}
+
+#[tokio::test]
+async fn chat_is_sanitized_before_broadcast() {
+    let server = server().await;
+    let mut alice = server.local_client("alice").await;
+
+    assert_eq!(chat(&mut alice, "  hi\r\nthere\u{7}  ").await, "success");
+    let sent = alice
+        .expect::<OutNotifChatSent>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(sent.payload.message, "hi\nthere");
+}
+
+#[tokio::test]
+async fn chat_limits_are_enforced_and_advertised() {
+    let server = server().await;
+    let mut alice = server.local_client("alice").await;
+
+    assert_eq!(
+        chat(&mut alice, "x".repeat(11).as_str()).await,
+        "message too long"
+    );
+    assert_eq!(
+        chat(&mut alice, &format!("  {}  ", "x".repeat(10))).await,
+        "success"
+    );
+    assert_eq!(chat(&mut alice, " \u{200B}\r\n ").await, "message empty");
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqRoomInfo(envelope(InReqRoomInfo {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespRoomInfo(info) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected room info");
+    };
+    assert_eq!(info.payload.room.chat_max_length, 10);
+}
+
+async fn announce(client: &mut TestClient, message: &str) -> String {
+    let correlation_id = client
+        .send(TransportMsg::InReqSendAnnouncement(envelope(
+            InReqSendAnnouncement {
+                message: message.into(),
+            },
+        )))
+        .await
+        .unwrap();
+    client
+        .status(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn announcements_are_admin_only_and_backfilled() {
+    let server = server().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    assert_eq!(announce(&mut bob, "hello").await, "not admin");
+    assert_eq!(announce(&mut alice, " last call ").await, "success");
+    let notif = bob
+        .expect::<OutNotifAnnouncement>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(notif.payload.id, alice.id.clone().unwrap());
+    assert_eq!(notif.payload.message, "last call");
+
+    assert_eq!(announce(&mut alice, "again").await, "rate limited");
+    assert_eq!(chat(&mut alice, "still ok").await, "success");
+
+    let mut carol = server.local_client("carol").await;
+    let backfill = carol
+        .expect::<OutNotifAnnouncement>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(backfill.payload.sent_at, notif.payload.sent_at);
+}
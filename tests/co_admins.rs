@@ -0,0 +1,172 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, one_round, start_game},
+};
+
+fn make_admin(client: &TestClient, revoke_existing: bool) -> TransportMsg {
+    TransportMsg::InReqMakeAdmin(envelope(InReqMakeAdmin {
+        admin_password: String::new(),
+        client_id: client.id.clone().unwrap(),
+        revoke_existing,
+    }))
+}
+
+fn set_provider(client: &TestClient) -> TransportMsg {
+    TransportMsg::InReqSetQuestionProvider(envelope(InReqSetQuestionProvider {
+        client_id: client.id.clone().unwrap(),
+    }))
+}
+
+#[tokio::test]
+async fn co_admins_share_the_admin_checks() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let mut carol = server.local_client("carol").await;
+
+    assert_eq!(
+        bob.request(make_admin(&carol, false)).await.unwrap(),
+        "not admin"
+    );
+    assert_eq!(
+        alice.request(make_admin(&bob, false)).await.unwrap(),
+        "success"
+    );
+    let made = carol
+        .expect::<OutNotifAdminMade>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(Some(made.payload.id), bob.id);
+    assert_eq!(
+        alice.request(make_admin(&bob, false)).await.unwrap(),
+        "already admin"
+    );
+
+    // the co-admin runs the game, the first admin still gets the questions
+    assert_eq!(
+        bob.request(start_game(one_round())).await.unwrap(),
+        "success"
+    );
+    alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let stop = TransportMsg::InReqStopGame(envelope(InReqStopGame {}));
+    assert_eq!(bob.request(stop).await.unwrap(), "success");
+
+    let correlation_id = carol
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespClientList(list) = carol
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected the client list");
+    };
+    let admins = list.payload.clients.iter().filter(|c| c.is_admin).count();
+    assert_eq!(admins, 2);
+}
+
+#[tokio::test]
+async fn questions_move_to_a_co_admin_when_the_provider_leaves() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let mut carol = server.local_client("carol").await;
+    assert_eq!(
+        alice.request(make_admin(&bob, false)).await.unwrap(),
+        "success"
+    );
+    carol
+        .expect::<OutNotifAdminMade>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        alice.request(start_game(one_round())).await.unwrap(),
+        "success"
+    );
+    alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let leave = TransportMsg::InReqLeaveRoom(envelope(InReqLeaveRoom {}));
+    assert_eq!(alice.request(leave).await.unwrap(), "success");
+
+    let changed = carol
+        .expect::<OutNotifQuestionProviderChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(Some(changed.payload.id), bob.id);
+    bob.expect::<OutReqQuestion>(DEFAULT_TIMEOUT).await.unwrap();
+    // bob was an admin already, so nobody is promoted
+    assert!(
+        carol
+            .expect::<OutNotifAdminMade>(std::time::Duration::from_millis(100))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn provider_can_be_designated() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let carol = server.local_client("carol").await;
+
+    assert_eq!(
+        alice.request(set_provider(&carol)).await.unwrap(),
+        "client is not admin"
+    );
+    assert_eq!(
+        alice.request(make_admin(&bob, false)).await.unwrap(),
+        "success"
+    );
+    assert_eq!(alice.request(set_provider(&bob)).await.unwrap(), "success");
+    let changed = alice
+        .expect::<OutNotifQuestionProviderChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(changed.payload.id, bob.id.clone().unwrap());
+
+    assert_eq!(
+        alice.request(start_game(one_round())).await.unwrap(),
+        "success"
+    );
+    bob.expect::<OutReqQuestion>(DEFAULT_TIMEOUT).await.unwrap();
+}
+
+#[tokio::test]
+async fn revoking_hands_admin_over() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    assert_eq!(
+        alice.request(make_admin(&bob, true)).await.unwrap(),
+        "success"
+    );
+    let revoked = bob
+        .expect::<OutNotifAdminRevoked>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(Some(revoked.payload.id), alice.id);
+    let changed = bob
+        .expect::<OutNotifQuestionProviderChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(Some(changed.payload.id), bob.id);
+
+    assert_eq!(
+        alice.request(start_game(one_round())).await.unwrap(),
+        "not admin"
+    );
+    assert_eq!(
+        bob.request(start_game(one_round())).await.unwrap(),
+        "success"
+    );
+    bob.expect::<OutReqQuestion>(DEFAULT_TIMEOUT).await.unwrap();
+}
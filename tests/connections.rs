@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    game_actor::collect_server_stats,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+async fn connections(server: &TestServer) -> Vec<IpConnections> {
+    collect_server_stats(server.game())
+        .await
+        .unwrap()
+        .connections_per_ip
+}
+
+#[tokio::test]
+async fn connections_per_ip_are_limited() {
+    let server = TestServer::start_with_config(ServerConfig {
+        max_connections_per_ip: Some(1),
+        ..Default::default()
+    })
+    .await;
+
+    let mut first = TestClient::connect(server.url()).await.unwrap();
+    first.handshake().await.unwrap();
+    assert_eq!(
+        connections(&server).await,
+        vec![IpConnections {
+            ip: "127.0.0.1".into(),
+            connections: 1,
+        }]
+    );
+
+    let err = TestClient::connect(server.url()).await.err().unwrap();
+    assert!(err.contains("429"), "{err}");
+
+    first.close().await;
+    for _ in 0..50 {
+        if connections(&server).await.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let mut second = TestClient::connect(server.url()).await.unwrap();
+    second.handshake().await.unwrap();
+}
+
+#[tokio::test]
+async fn client_that_stops_reading_is_dropped() {
+    let server = TestServer::start_with_config(ServerConfig {
+        max_message_size: 64 * 1024 * 1024,
+        chat_max_length: 64 * 1024 * 1024,
+        write_timeout: Duration::from_millis(300),
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client("alice").await;
+    let mut bob = server.client("bob").await;
+    // never read from again, so the whisper below fills its socket buffers
+    let stuck = server.client("stuck").await;
+    let stuck_id = stuck.id.clone().unwrap();
+
+    alice
+        .send(TransportMsg::InReqSendWhisper(envelope(InReqSendWhisper {
+            target_id: stuck_id.clone(),
+            message: "x".repeat(16 * 1024 * 1024),
+        })))
+        .await
+        .unwrap();
+
+    loop {
+        let gone = bob
+            .expect::<OutNotifClientDisconnected>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        if gone.payload.id == stuck_id {
+            break;
+        }
+    }
+
+    alice
+        .send(TransportMsg::InReqSendChat(envelope(InReqSendChat {
+            message: "still here".into(),
+        })))
+        .await
+        .unwrap();
+    let chat = bob
+        .expect::<OutNotifChatSent>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(chat.payload.message, "still here");
+    drop(stuck);
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+use tokio::time::Instant;
+
+async fn start_game(admin: &mut TestClient, start_countdown: u64) {
+    let correlation_id = admin
+        .send(TransportMsg::InReqStartGame(envelope(InReqStartGame {
+            game_settings: GameSettings {
+                round_duration: 30,
+                rounds_count: 1,
+                start_countdown,
+                ..Default::default()
+            },
+        })))
+        .await
+        .unwrap();
+    assert_eq!(
+        admin.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn countdown_ticks_once_per_second_before_first_question() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+
+    start_game(&mut admin, 3).await;
+    let started = Instant::now();
+
+    for expected in [3, 2, 1] {
+        let tick = admin
+            .expect::<OutNotifCountdown>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(tick.payload.seconds_remaining, expected);
+        assert_eq!(started.elapsed(), Duration::from_secs(3 - expected));
+    }
+
+    admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(started.elapsed(), Duration::from_secs(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn zero_countdown_requests_question_immediately() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+
+    start_game(&mut admin, 0).await;
+    let started = Instant::now();
+
+    admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(started.elapsed(), Duration::ZERO);
+    assert!(
+        admin
+            .expect::<OutNotifCountdown>(Duration::from_secs(1))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn stop_game_cancels_countdown() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+
+    start_game(&mut admin, 3).await;
+    admin
+        .expect::<OutNotifCountdown>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    let correlation_id = admin
+        .send(TransportMsg::InReqStopGame(envelope(InReqStopGame {})))
+        .await
+        .unwrap();
+    assert_eq!(
+        admin.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+    admin
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    assert!(
+        admin
+            .expect::<OutNotifCountdown>(Duration::from_secs(10))
+            .await
+            .is_err()
+    );
+    assert!(
+        admin
+            .expect::<OutReqQuestion>(Duration::from_secs(10))
+            .await
+            .is_err()
+    );
+}
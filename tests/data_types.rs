@@ -0,0 +1,156 @@
+use kanjilab_server::data_types::*;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+#[test]
+fn game_stopped_serializes_reason_and_scores_in_camel_case() {
+    let msg = TransportMsg::OutNotifGameStopped(TransportEnvelope {
+        correlation_id: Uuid::nil(),
+        seq: None,
+        payload: OutNotifGameStopped {
+            question: QuestionInfo::default(),
+            answers: Vec::new(),
+            reason: GameStopReason::StoppedByAdmin,
+            final_scores: vec![PlayerScore {
+                id: "a".into(),
+                score: 3,
+            }],
+            winner_id: Some("a".into()),
+            player_summaries: Vec::new(),
+            accepted_readings: Vec::new(),
+            primary_reading: String::new(),
+            tier_summaries: Vec::new(),
+            game_id: Some(Uuid::nil()),
+            elapsed_ms: 61_000,
+        },
+    });
+
+    let value: Value = serde_json::from_str(&serialize(&msg).unwrap()).unwrap();
+    assert_eq!(value["messageType"], "OUT_NOTIF_gameStopped");
+    let payload = &value["payload"];
+    assert_eq!(payload["reason"], "stopped_by_admin");
+    assert_eq!(payload["finalScores"], json!([{ "id": "a", "score": 3 }]));
+    assert_eq!(payload["winnerId"], "a");
+    assert_eq!(payload["gameId"], Uuid::nil().to_string());
+    assert_eq!(payload["elapsedMs"], 61_000);
+    assert!(payload.get("question").is_some());
+    assert!(payload.get("answers").is_some());
+}
+
+#[test]
+fn game_stopped_without_new_fields_still_parses() {
+    let text = json!({
+        "messageType": "OUT_NOTIF_gameStopped",
+        "correlationId": Uuid::nil(),
+        "payload": {
+            "question": QuestionInfo::default(),
+            "answers": [],
+        },
+    })
+    .to_string();
+
+    let TransportMsg::OutNotifGameStopped(env) = parse(&text).unwrap() else {
+        panic!("wrong variant");
+    };
+    assert_eq!(env.payload.reason, GameStopReason::Completed);
+    assert!(env.payload.final_scores.is_empty());
+    assert_eq!(env.payload.winner_id, None);
+}
+
+#[test]
+fn answers_without_kind_count_as_answered() {
+    let old = json!({ "id": "a", "answer": "ひ", "isCorrect": true, "answerTime": 100 });
+    let answer: AnswerInfo = serde_json::from_value(old).unwrap();
+    assert_eq!(answer.kind, AnswerKind::Answered);
+
+    let timed_out = AnswerInfo {
+        kind: AnswerKind::TimedOut,
+        ..answer
+    };
+    assert_eq!(
+        serde_json::to_value(timed_out).unwrap()["kind"],
+        "timed_out"
+    );
+}
+
+#[test]
+fn stop_reasons_use_snake_case() {
+    let reasons = [
+        (GameStopReason::Completed, "completed"),
+        (GameStopReason::StoppedByAdmin, "stopped_by_admin"),
+        (GameStopReason::AbortedNoAdmin, "aborted_no_admin"),
+        (GameStopReason::AbortedEmptyRoom, "aborted_empty_room"),
+        (GameStopReason::Abandoned, "abandoned"),
+    ];
+    for (reason, text) in reasons {
+        assert_eq!(serde_json::to_value(reason).unwrap(), text);
+    }
+}
+
+#[test]
+fn round_end_always_carries_reading_fields() {
+    let msg = TransportMsg::OutNotifRoundEnded(TransportEnvelope {
+        correlation_id: Uuid::nil(),
+        seq: None,
+        payload: OutNotifRoundEnded {
+            question: QuestionInfo::default(),
+            answers: Vec::new(),
+            accepted_readings: Vec::new(),
+            primary_reading: String::new(),
+            streaks: Vec::new(),
+            near_misses: Vec::new(),
+            game_id: None,
+        },
+    });
+
+    let value: Value = serde_json::from_str(&serialize(&msg).unwrap()).unwrap();
+    assert_eq!(value["payload"]["acceptedReadings"], json!([]));
+    assert_eq!(value["payload"]["primaryReading"], "");
+}
+
+#[test]
+fn accepted_readings_are_normalized_and_deduplicated() {
+    let question = QuestionInfo {
+        word_info: WordInfo {
+            word: "日".into(),
+            meanings: Vec::new(),
+            readings: [" ひ", "にち\u{200B}", "ひ", ""]
+                .into_iter()
+                .map(|reading| ReadingWithParts {
+                    reading: reading.into(),
+                    parts: Vec::new(),
+                })
+                .collect(),
+        },
+        font_name: String::new(),
+        question_type: QuestionType::ReadingOfWord,
+        frequency: None,
+    };
+
+    assert_eq!(
+        kanjilab_server::tools::accepted_readings(&question),
+        vec!["ひ", "にち"]
+    );
+}
+
+#[test]
+fn settings_patch_tells_a_missing_field_from_null() {
+    let patch: GameSettingsPatch =
+        serde_json::from_value(json!({ "roundsCount": 5, "wordPart": null })).unwrap();
+    assert_eq!(patch.rounds_count, Some(5));
+    assert_eq!(patch.word_part, Some(None));
+    assert_eq!(patch.dictionary_name, None);
+
+    let mut settings = GameSettings {
+        word_part: Some("日".into()),
+        dictionary_name: Some("jmdict".into()),
+        ..Default::default()
+    };
+    patch.apply(&mut settings);
+    assert_eq!(settings.rounds_count, 5);
+    assert_eq!(settings.word_part, None);
+    assert_eq!(settings.dictionary_name.as_deref(), Some("jmdict"));
+
+    let value = serde_json::to_value(&patch).unwrap();
+    assert_eq!(value, json!({ "roundsCount": 5, "wordPart": null }));
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer},
+};
+use uuid::Uuid;
+
+async fn start_server() -> TestServer {
+    TestServer::start_with_config(ServerConfig {
+        duplicate_id_history: 4,
+        duplicate_id_window: Duration::from_millis(200),
+        ..Default::default()
+    })
+    .await
+}
+
+async fn ready_status(client: &mut TestClient, correlation_id: Uuid) -> String {
+    let ready = TransportMsg::InReqSetReady(TransportEnvelope {
+        correlation_id,
+        seq: None,
+        payload: InReqSetReady { ready: true },
+    });
+    client.send(ready).await.unwrap();
+    client
+        .status(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn reused_id_is_turned_away() {
+    let server = start_server().await;
+    let mut alice = server.client("alice").await;
+
+    let id = Uuid::new_v4();
+    assert_eq!(ready_status(&mut alice, id).await, "success");
+    assert_eq!(
+        ready_status(&mut alice, id).await,
+        "duplicate correlation id"
+    );
+    // ids are per connection
+    let mut bob = server.client("bob").await;
+    assert_eq!(ready_status(&mut bob, id).await, "success");
+}
+
+#[tokio::test]
+async fn id_can_be_reused_once_forgotten() {
+    let server = start_server().await;
+    let mut alice = server.client("alice").await;
+
+    let id = Uuid::new_v4();
+    assert_eq!(ready_status(&mut alice, id).await, "success");
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(ready_status(&mut alice, id).await, "success");
+
+    // pushed out by newer ones
+    for _ in 0..4 {
+        assert_eq!(ready_status(&mut alice, Uuid::new_v4()).await, "success");
+    }
+    assert_eq!(ready_status(&mut alice, id).await, "success");
+}
+
+#[tokio::test]
+async fn repeated_registration_is_answered_again() {
+    let server = start_server().await;
+    let mut alice = server.client("alice").await;
+
+    let correlation_id = Uuid::new_v4();
+    let register = TransportMsg::InReqRegisterClient(TransportEnvelope {
+        correlation_id,
+        seq: None,
+        payload: InReqRegisterClient {
+            name: "alice".into(),
+            spectator: false,
+            password: None,
+        },
+    });
+    for _ in 0..2 {
+        alice.send(register.clone()).await.unwrap();
+        let Ok(TransportMsg::OutRespClientRegistered(resp)) =
+            alice.response(correlation_id, DEFAULT_TIMEOUT).await
+        else {
+            panic!("expected the registration");
+        };
+        assert_eq!(Some(resp.payload.id), alice.id);
+    }
+}
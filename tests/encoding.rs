@@ -0,0 +1,111 @@
+use kanjilab_server::{
+    data_types::*,
+    schema::protocol_schema,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+use serde_json::{Map, Value, json};
+
+/// Builds a value for `schema` with every property filled in, so optional
+/// fields are exercised too.
+fn example(schema: &Value, defs: &Value) -> Value {
+    let mut value = match schema.get("$ref").and_then(Value::as_str) {
+        Some(path) => example(&defs[path.trim_start_matches("#/$defs/")], defs),
+        None => Value::Null,
+    };
+
+    if let Some(constant) = schema.get("const") {
+        return constant.clone();
+    }
+    if let Some(first) = schema.get("enum").and_then(|e| e.get(0)) {
+        return first.clone();
+    }
+    if let Some(branches) = schema.get("anyOf").or_else(|| schema.get("oneOf")) {
+        let branch = branches
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|b| b.get("type") != Some(&json!("null")))
+            .unwrap();
+        return example(branch, defs);
+    }
+
+    let ty = match &schema["type"] {
+        Value::Array(types) => types.iter().find(|t| *t != "null").cloned(),
+        Value::String(_) => Some(schema["type"].clone()),
+        _ => None,
+    };
+    match ty.as_ref().and_then(Value::as_str) {
+        Some("object") => {
+            let mut object = value.as_object().cloned().unwrap_or_else(Map::new);
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                for (name, property) in properties {
+                    object.insert(name.clone(), example(property, defs));
+                }
+            }
+            value = Value::Object(object);
+        }
+        Some("array") => value = json!([example(&schema["items"], defs)]),
+        Some("string") if schema.get("format") == Some(&json!("uuid")) => {
+            value = json!("7d444840-9dc0-11d1-b245-5ffdce74fad2")
+        }
+        Some("string") => value = json!("x"),
+        Some("integer") => value = json!(1),
+        Some("number") => value = json!(1.5),
+        Some("boolean") => value = json!(true),
+        _ => {}
+    }
+    value
+}
+
+fn every_message() -> Vec<TransportMsg> {
+    let schema = protocol_schema();
+    schema["oneOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|variant| {
+            let value = example(variant, &schema["$defs"]);
+            serde_json::from_value(value.clone()).unwrap_or_else(|e| panic!("{e} in {value}"))
+        })
+        .collect()
+}
+
+#[test]
+fn every_message_round_trips_as_json() {
+    for msg in every_message() {
+        let text = serialize(&msg).unwrap();
+        assert_eq!(parse(&text).unwrap(), msg, "{text}");
+    }
+}
+
+#[test]
+fn every_message_round_trips_as_msgpack() {
+    for msg in every_message() {
+        let data = serialize_msgpack(&msg).unwrap();
+        assert_eq!(parse_msgpack(&data).unwrap(), msg, "{msg:?}");
+    }
+}
+
+#[tokio::test]
+async fn msgpack_client_can_register_and_make_requests() {
+    let server = TestServer::start().await;
+    let mut alice = TestClient::connect(&server.url())
+        .await
+        .unwrap()
+        .with_encoding(Encoding::Msgpack);
+    alice.handshake().await.unwrap();
+    let id = alice.register("alice").await.unwrap();
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespClientList(list) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected client list");
+    };
+    assert_eq!(list.payload.clients[0].id, id);
+}
@@ -0,0 +1,47 @@
+use kanjilab_server::{
+    ServerEvent,
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope},
+};
+use tokio::{sync::broadcast, time::timeout};
+
+async fn next_event(events: &mut broadcast::Receiver<ServerEvent>) -> ServerEvent {
+    timeout(DEFAULT_TIMEOUT, events.recv())
+        .await
+        .expect("event timeout")
+        .expect("event channel")
+}
+
+#[tokio::test]
+async fn host_receives_room_events() {
+    let server = TestServer::start().await;
+    let mut events = server.subscribe_events();
+
+    let mut alice = server.client("alice").await;
+    let alice_id = alice.id.clone().unwrap();
+    let ServerEvent::ClientRegistered { client } = next_event(&mut events).await else {
+        panic!("expected client registered");
+    };
+    assert_eq!(client.id, alice_id);
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqSendChat(envelope(InReqSendChat {
+            message: "hello".into(),
+        })))
+        .await
+        .unwrap();
+    alice.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap();
+    assert_eq!(
+        next_event(&mut events).await,
+        ServerEvent::ChatMessage {
+            id: alice_id.clone(),
+            message: "hello".into(),
+        }
+    );
+
+    alice.close().await;
+    assert_eq!(
+        next_event(&mut events).await,
+        ServerEvent::ClientDisconnected { id: alice_id }
+    );
+}
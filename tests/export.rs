@@ -0,0 +1,112 @@
+use kanjilab_server::{
+    data_types::*,
+    export::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, sample_question, start_game},
+};
+
+fn start(rounds_count: u64) -> TransportMsg {
+    start_game(GameSettings {
+        round_duration: 30,
+        rounds_count,
+        start_countdown: 0,
+        ..Default::default()
+    })
+}
+
+fn export(game_index: u64) -> TransportMsg {
+    TransportMsg::InReqExportGame(envelope(InReqExportGame { game_index }))
+}
+
+async fn answer(client: &mut TestClient, answer: &str) {
+    let answer = TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+        answer: answer.into(),
+        question_id: None,
+        game_id: None,
+    }));
+    assert_eq!(client.request(answer).await.unwrap(), "success");
+}
+
+#[tokio::test]
+async fn finished_game_is_exported_with_players_and_answers() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let alice_id = alice.id.clone().unwrap();
+
+    assert_eq!(alice.request(export(0)).await.unwrap(), "no such game");
+    assert_eq!(alice.request(start(1)).await.unwrap(), "success");
+    let started = alice
+        .expect::<OutNotifGameStarted>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(started.payload.game_index, 0);
+    assert_eq!(alice.request(export(0)).await.unwrap(), "game not finished");
+
+    alice.provide_question(sample_question()).await.unwrap();
+    answer(&mut alice, " ヒ ").await;
+    answer(&mut bob, "つき").await;
+    alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    assert_eq!(bob.request(export(0)).await.unwrap(), "not admin");
+    let correlation_id = alice.send(export(0)).await.unwrap();
+    let TransportMsg::OutRespGameExport(resp) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected an export");
+    };
+    assert_eq!(resp.payload.file_name, "kanjilab-default-game-0.json");
+
+    let doc: GameExport = serde_json::from_str(&resp.payload.document).unwrap();
+    assert_eq!(doc.format, EXPORT_FORMAT);
+    assert_eq!(doc.version, EXPORT_VERSION);
+    assert_eq!(doc.server_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(doc.stop_reason, Some(GameStopReason::Completed));
+    assert!(doc.ended_at.is_some_and(|end| end >= doc.started_at));
+    assert_eq!(doc.game_settings.rounds_count, 1);
+
+    assert_eq!(doc.players.len(), 2);
+    let winner = &doc.players[0];
+    assert_eq!(winner.id, alice_id);
+    assert_eq!(winner.name.as_deref(), Some("alice"));
+    assert_eq!(winner.rank, 1);
+    assert_eq!(doc.players[1].name.as_deref(), Some("bob"));
+
+    let round = &doc.rounds[0];
+    assert_eq!(round.word, "日");
+    let alice_answer = round
+        .answers
+        .iter()
+        .find(|a| a.player_id == alice_id)
+        .unwrap();
+    assert_eq!(alice_answer.answer, " ヒ ");
+    assert_eq!(alice_answer.normalized, "ひ");
+    assert!(alice_answer.is_correct);
+}
+
+#[tokio::test]
+async fn games_past_the_history_are_not_available() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let stop = TransportMsg::InReqStopGame(envelope(InReqStopGame {}));
+
+    for _ in 0..11 {
+        assert_eq!(alice.request(start(5)).await.unwrap(), "success");
+        assert_eq!(alice.request(stop.clone()).await.unwrap(), "success");
+    }
+
+    assert_eq!(alice.request(export(0)).await.unwrap(), "not available");
+    assert_eq!(alice.request(export(11)).await.unwrap(), "no such game");
+    let correlation_id = alice.send(export(1)).await.unwrap();
+    assert!(matches!(
+        alice
+            .response(correlation_id, DEFAULT_TIMEOUT)
+            .await
+            .unwrap(),
+        TransportMsg::OutRespGameExport(_)
+    ));
+}
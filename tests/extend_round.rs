@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+use tokio::time::Instant;
+
+async fn start_round(admin: &mut TestClient) {
+    let correlation_id = admin
+        .send(TransportMsg::InReqStartGame(envelope(InReqStartGame {
+            game_settings: GameSettings {
+                round_duration: 10,
+                rounds_count: 1,
+                start_countdown: 0,
+                ..Default::default()
+            },
+        })))
+        .await
+        .unwrap();
+    assert_eq!(
+        admin.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo::default(),
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    admin
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
+
+async fn extend(client: &mut TestClient, extra_seconds: u64) -> String {
+    let correlation_id = client
+        .send(TransportMsg::InReqExtendRound(envelope(InReqExtendRound {
+            extra_seconds,
+        })))
+        .await
+        .unwrap();
+    client
+        .status(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn extension_pushes_round_end_out() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+    let mut player = server.local_client("player").await;
+
+    start_round(&mut admin).await;
+    let started = Instant::now();
+    tokio::time::sleep(Duration::from_secs(4)).await;
+
+    assert_eq!(extend(&mut player, 5).await, "not admin");
+    assert_eq!(extend(&mut admin, 121).await, "invalid extension");
+    assert_eq!(extend(&mut admin, 5).await, "success");
+    let extended = player
+        .expect::<OutNotifRoundExtended>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(extended.payload.new_remaining_ms, 11_000);
+
+    let stopped = admin
+        .expect::<OutNotifGameStopped>(Duration::from_secs(30))
+        .await
+        .unwrap();
+    assert_eq!(started.elapsed(), Duration::from_secs(15));
+    let missing = stopped
+        .payload
+        .answers
+        .iter()
+        .find(|a| a.answer.is_empty())
+        .unwrap();
+    assert_eq!(missing.answer_time, 15_000);
+}
+
+#[tokio::test(start_paused = true)]
+async fn extension_needs_active_round() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+
+    assert_eq!(extend(&mut admin, 5).await, "no active round");
+}
@@ -0,0 +1,70 @@
+use kanjilab_server::{data_types::ModerationAction, filter::WordFilter};
+
+#[test]
+fn case_and_substitutions_do_not_evade_the_filter() {
+    let filter = WordFilter::new(["darn", "heck"]);
+
+    for text in [
+        "darn",
+        "DaRn",
+        "d4rn",
+        "D@RN",
+        "h3ck",
+        "H3CK!",
+        "well, d4rn it",
+    ] {
+        assert!(filter.is_match(text), "{text}");
+    }
+    for text in ["dam", "hack", "d a r n", ""] {
+        assert!(!filter.is_match(text), "{text}");
+    }
+}
+
+#[test]
+fn list_entries_are_normalized_too() {
+    let filter = WordFilter::new(["Sh00t"]);
+    assert!(filter.is_match("shoot"));
+    assert!(filter.is_match("5HOOT"));
+}
+
+#[test]
+fn masking_keeps_everything_but_the_blocked_words() {
+    let filter = WordFilter::new(["darn", "arnold", "n1ce"]);
+
+    assert_eq!(filter.mask("oh d4rn it"), "oh **** it");
+    // overlapping words are masked as one run
+    assert_eq!(filter.mask("darnold!"), "*******!");
+    assert_eq!(filter.mask("so NICE"), "so ****");
+    assert_eq!(filter.mask("日本語 darn"), "日本語 ****");
+}
+
+#[test]
+fn actions_reject_mask_or_let_through() {
+    let filter = WordFilter::new(["darn"]);
+
+    assert_eq!(
+        filter.moderate(ModerationAction::Reject, "darn"),
+        Err("blocked word")
+    );
+    assert_eq!(
+        filter.moderate(ModerationAction::Mask, "darn it"),
+        Ok("**** it".into())
+    );
+    assert_eq!(
+        filter.moderate(ModerationAction::Warn, "darn it"),
+        Ok("darn it".into())
+    );
+    assert_eq!(
+        filter.moderate(ModerationAction::Reject, "fine"),
+        Ok("fine".into())
+    );
+}
+
+#[test]
+fn empty_filter_matches_nothing() {
+    let filter = WordFilter::new(["", "  "]);
+    assert!(filter.is_empty());
+    assert!(!filter.is_match("  anything  "));
+    assert!(WordFilter::default().is_empty());
+    assert!(!WordFilter::default().is_match("darn"));
+}
@@ -0,0 +1,185 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, start_game},
+};
+use uuid::Uuid;
+
+fn in_font(font_name: &str) -> QuestionInfo {
+    QuestionInfo {
+        font_name: font_name.into(),
+        question_type: QuestionType::ReadingOfWord,
+        frequency: None,
+        ..Default::default()
+    }
+}
+
+async fn respond(admin: &mut TestClient, correlation_id: Uuid, question: QuestionInfo) {
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question,
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+}
+
+/// Starts a game with two fonts and returns the first question request.
+async fn start(
+    admin: &mut TestClient,
+    game_settings: GameSettings,
+) -> TransportEnvelope<OutReqQuestion> {
+    let start = start_game(GameSettings {
+        round_duration: 30,
+        rounds_count: 3,
+        start_countdown: 0,
+        allow_repeat_words: true,
+        fonts_count: 2,
+        ..game_settings
+    });
+    assert_eq!(admin.request(start).await.unwrap(), "success");
+    admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn fonts_rotate_and_are_checked() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+    let first = start(
+        &mut admin,
+        GameSettings {
+            first_font_name: Some("A".into()),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(first.payload.font_index, Some(0));
+    assert_eq!(first.payload.font_name.as_deref(), Some("A"));
+    respond(&mut admin, first.correlation_id, in_font("B")).await;
+    assert_eq!(
+        admin
+            .status(first.correlation_id, DEFAULT_TIMEOUT)
+            .await
+            .unwrap(),
+        "wrong font"
+    );
+
+    // asked for again, like any question turned away
+    let retry = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(retry.payload.font_index, Some(0));
+    respond(&mut admin, retry.correlation_id, in_font("A")).await;
+    admin
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    let second = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(second.payload.font_index, Some(1));
+    assert_eq!(second.payload.font_name, None);
+    respond(&mut admin, second.correlation_id, in_font("B")).await;
+
+    let correlation_id = admin
+        .send(TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+            answer: "x".into(),
+            question_id: None,
+            game_id: None,
+        })))
+        .await
+        .unwrap();
+    admin.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap();
+    admin
+        .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    let third = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(third.payload.font_index, Some(0));
+    assert_eq!(third.payload.font_name.as_deref(), Some("A"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_wrong_font_is_let_through_once_the_retries_run_out() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+    let mut request = start(
+        &mut admin,
+        GameSettings {
+            first_font_name: Some("A".into()),
+            ..Default::default()
+        },
+    )
+    .await;
+
+    for _ in 0..3 {
+        respond(&mut admin, request.correlation_id, in_font("B")).await;
+        assert_eq!(
+            admin
+                .status(request.correlation_id, DEFAULT_TIMEOUT)
+                .await
+                .unwrap(),
+            "wrong font"
+        );
+        request = admin
+            .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+    }
+    respond(&mut admin, request.correlation_id, in_font("B")).await;
+    admin
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_question_turned_away_does_not_fix_the_font() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+    let first = start(
+        &mut admin,
+        GameSettings {
+            min_frequency: 10,
+            enforce_frequency: true,
+            ..Default::default()
+        },
+    )
+    .await;
+    let rare = QuestionInfo {
+        frequency: Some(1.0),
+        ..in_font("B")
+    };
+    respond(&mut admin, first.correlation_id, rare).await;
+    assert_eq!(
+        admin
+            .status(first.correlation_id, DEFAULT_TIMEOUT)
+            .await
+            .unwrap(),
+        "frequency out of range"
+    );
+
+    let retry = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(retry.payload.font_name, None);
+    respond(&mut admin, retry.correlation_id, in_font("C")).await;
+    admin
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
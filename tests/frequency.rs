@@ -0,0 +1,104 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, one_round, start_game},
+};
+
+fn start(enforce_frequency: bool) -> TransportMsg {
+    start_game(GameSettings {
+        min_frequency: 100,
+        max_frequency: 1000,
+        using_max_frequency: true,
+        enforce_frequency,
+        ..one_round()
+    })
+}
+
+/// Answers the next question request and returns the request's id.
+async fn provide(admin: &mut TestClient, frequency: f64) -> uuid::Uuid {
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo {
+                    frequency: Some(frequency),
+                    ..Default::default()
+                },
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    req.correlation_id
+}
+
+#[tokio::test]
+async fn out_of_range_question_is_requested_again() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    assert_eq!(alice.request(start(true)).await.unwrap(), "success");
+
+    let rejected = provide(&mut alice, 5000.0).await;
+    assert_eq!(
+        alice.status(rejected, DEFAULT_TIMEOUT).await.unwrap(),
+        "frequency out of range"
+    );
+    provide(&mut alice, 500.0).await;
+    alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn question_goes_through_once_retries_run_out() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    assert_eq!(alice.request(start(true)).await.unwrap(), "success");
+
+    for _ in 0..3 {
+        let rejected = provide(&mut alice, 50.0).await;
+        assert_eq!(
+            alice.status(rejected, DEFAULT_TIMEOUT).await.unwrap(),
+            "frequency out of range"
+        );
+    }
+    provide(&mut alice, 50.0).await;
+    let warning = alice
+        .expect::<OutNotifFrequencyWarning>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(
+        warning.payload,
+        OutNotifFrequencyWarning {
+            frequency: 50.0,
+            min_frequency: 100,
+            max_frequency: Some(1000),
+        }
+    );
+    alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn unenforced_bounds_only_warn() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    assert_eq!(alice.request(start(false)).await.unwrap(), "success");
+
+    provide(&mut alice, 5000.0).await;
+    alice
+        .expect::<OutNotifFrequencyWarning>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
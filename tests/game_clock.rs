@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, start_game},
+};
+use tokio::time::Instant;
+
+fn start(game_time_limit: Option<u64>, cut_round_at_time_limit: bool) -> TransportMsg {
+    start_game(GameSettings {
+        round_duration: 30,
+        rounds_count: 10,
+        start_countdown: 0,
+        game_time_limit,
+        cut_round_at_time_limit,
+        ..Default::default()
+    })
+}
+
+async fn provide_question(admin: &mut TestClient) {
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo::default(),
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    admin
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn round_under_way_is_played_out() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    assert_eq!(
+        alice.request(start(Some(5), false)).await.unwrap(),
+        "success"
+    );
+    let started = alice
+        .expect::<OutNotifGameStarted>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert!(started.payload.deadline.is_some());
+    let start = Instant::now();
+
+    provide_question(&mut alice).await;
+    let stopped = alice
+        .expect::<OutNotifGameStopped>(Duration::from_secs(60))
+        .await
+        .unwrap();
+    assert_eq!(stopped.payload.reason, GameStopReason::TimeLimitReached);
+    assert!(start.elapsed() >= Duration::from_secs(30));
+}
+
+#[tokio::test(start_paused = true)]
+async fn round_can_be_cut_short() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    assert_eq!(
+        alice.request(start(Some(5), true)).await.unwrap(),
+        "success"
+    );
+    let start = Instant::now();
+
+    provide_question(&mut alice).await;
+    let stopped = alice
+        .expect::<OutNotifGameStopped>(Duration::from_secs(60))
+        .await
+        .unwrap();
+    assert_eq!(stopped.payload.reason, GameStopReason::TimeLimitReached);
+    assert!(start.elapsed() < Duration::from_secs(30));
+}
+
+#[tokio::test(start_paused = true)]
+async fn stopped_game_leaves_no_clock_behind() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    assert_eq!(
+        alice.request(start(Some(0), false)).await.unwrap(),
+        "invalid game time limit"
+    );
+    assert_eq!(
+        alice.request(start(Some(5), true)).await.unwrap(),
+        "success"
+    );
+    alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let stop = TransportMsg::InReqStopGame(envelope(InReqStopGame {}));
+    assert_eq!(alice.request(stop).await.unwrap(), "success");
+    alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    // a game without a limit isn't stopped by the old one's clock
+    assert_eq!(alice.request(start(None, false)).await.unwrap(), "success");
+    provide_question(&mut alice).await;
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert!(
+        alice
+            .expect::<OutNotifGameStopped>(Duration::from_millis(100))
+            .await
+            .is_err()
+    );
+}
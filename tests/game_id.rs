@@ -0,0 +1,115 @@
+use kanjilab_server::{
+    data_types::*,
+    export::GameExport,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope, one_round, sample_question, start_game},
+};
+use uuid::Uuid;
+
+fn answer(game_id: Option<Uuid>) -> TransportMsg {
+    TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+        answer: "ひ".into(),
+        question_id: None,
+        game_id,
+    }))
+}
+
+#[tokio::test]
+async fn game_notifications_carry_the_game_id() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    assert_eq!(
+        alice.request(start_game(one_round())).await.unwrap(),
+        "success"
+    );
+    let game_id = bob
+        .expect::<OutNotifGameStarted>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .game_id;
+    assert!(game_id.is_some());
+
+    alice.provide_question(sample_question()).await.unwrap();
+    let question = bob
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(question.payload.game_id, game_id);
+
+    assert_eq!(
+        bob.request(answer(Some(Uuid::new_v4()))).await.unwrap(),
+        "stale game"
+    );
+    assert_eq!(bob.request(answer(game_id)).await.unwrap(), "success");
+    let answered = alice
+        .expect::<OutNotifClientAnswered>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(answered.payload.game_id, game_id);
+    assert_eq!(alice.request(answer(None)).await.unwrap(), "success");
+
+    let stopped = bob
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(stopped.payload.game_id, game_id);
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqExportGame(envelope(InReqExportGame {
+            game_index: 0,
+        })))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespGameExport(resp) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected an export");
+    };
+    let doc: GameExport = serde_json::from_str(&resp.payload.document).unwrap();
+    assert_eq!(Some(doc.game_id), game_id);
+}
+
+#[tokio::test]
+async fn every_game_gets_a_new_id() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let stop = TransportMsg::InReqStopGame(envelope(InReqStopGame {}));
+
+    assert_eq!(
+        alice.request(start_game(one_round())).await.unwrap(),
+        "success"
+    );
+    let first = alice
+        .expect::<OutNotifGameStarted>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .game_id;
+    alice.provide_question(sample_question()).await.unwrap();
+    bob.expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(alice.request(stop).await.unwrap(), "success");
+    assert_eq!(
+        alice.request(start_game(one_round())).await.unwrap(),
+        "success"
+    );
+    let second = alice
+        .expect::<OutNotifGameStarted>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .game_id;
+    assert_ne!(first, second);
+
+    alice.provide_question(sample_question()).await.unwrap();
+    bob.expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(bob.request(answer(first)).await.unwrap(), "stale game");
+}
@@ -0,0 +1,286 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    room_actor::{
+        ExtendRoundRequest, GamePhase, GamePhaseReport, GetGamePhase, ProvideQuestionResponse,
+        ResumeGameRequest, SendAnswerRequest, StartGameRequest, StopGameRequest,
+    },
+    testkit::{RoomMember, TestRoom, sample_question},
+};
+use uuid::Uuid;
+
+fn settings() -> GameSettings {
+    GameSettings {
+        round_duration: 2,
+        rounds_count: 3,
+        start_countdown: 1,
+        allow_repeat_words: true,
+        ..Default::default()
+    }
+}
+
+/// Small deterministic generator, so a failing sequence can be replayed
+/// from its seed.
+struct XorShift(u64);
+
+impl XorShift {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
+
+struct Game {
+    room: TestRoom,
+    alice: RoomMember,
+    bob: RoomMember,
+    /// Question requests the admin has yet to answer.
+    asked: Vec<Uuid>,
+}
+
+impl Game {
+    async fn start() -> Self {
+        let room = TestRoom::start(ServerConfig::default()).await;
+        let alice = room.join("alice", false).await.unwrap();
+        let bob = room.join("bob", false).await.unwrap();
+        Self {
+            room,
+            alice,
+            bob,
+            asked: Vec::new(),
+        }
+    }
+
+    async fn phase(&self) -> GamePhaseReport {
+        self.room.room.ask(GetGamePhase).await.unwrap()
+    }
+
+    /// The status `member` got for `correlation_id`, keeping note of the
+    /// question requests that came along.
+    fn status(&mut self, bob: bool, correlation_id: Uuid) -> Option<String> {
+        let member = if bob { &self.bob } else { &self.alice };
+        let mut status = None;
+        for msg in member.sink.take() {
+            match msg {
+                TransportMsg::OutReqQuestion(req) => self.asked.push(req.correlation_id),
+                TransportMsg::OutRespStatus(env) if env.correlation_id == correlation_id => {
+                    status = Some(env.payload.status)
+                }
+                _ => {}
+            }
+        }
+        status
+    }
+
+    async fn start_game(&mut self) -> Option<String> {
+        let correlation_id = Uuid::new_v4();
+        self.room
+            .room
+            .ask(StartGameRequest {
+                requester: self.alice.session.clone(),
+                correlation_id,
+                game_settings: settings(),
+            })
+            .await
+            .unwrap();
+        self.status(false, correlation_id)
+    }
+
+    async fn stop_game(&mut self) -> Option<String> {
+        let correlation_id = Uuid::new_v4();
+        self.room
+            .room
+            .ask(StopGameRequest {
+                requester: self.alice.session.clone(),
+                correlation_id,
+            })
+            .await
+            .unwrap();
+        self.status(false, correlation_id)
+    }
+
+    async fn resume_game(&mut self) -> Option<String> {
+        let correlation_id = Uuid::new_v4();
+        self.room
+            .room
+            .ask(ResumeGameRequest {
+                requester: self.alice.session.clone(),
+                correlation_id,
+            })
+            .await
+            .unwrap();
+        self.status(false, correlation_id)
+    }
+
+    async fn extend_round(&mut self) -> Option<String> {
+        let correlation_id = Uuid::new_v4();
+        self.room
+            .room
+            .ask(ExtendRoundRequest {
+                requester: self.alice.session.clone(),
+                correlation_id,
+                extra_seconds: 1,
+            })
+            .await
+            .unwrap();
+        self.status(false, correlation_id)
+    }
+
+    async fn answer(&mut self) -> Option<String> {
+        let correlation_id = Uuid::new_v4();
+        self.room
+            .room
+            .ask(SendAnswerRequest {
+                requester: self.bob.session.clone(),
+                correlation_id,
+                answer: "ひ".into(),
+                question_id: None,
+                game_id: None,
+            })
+            .await
+            .unwrap();
+        self.status(true, correlation_id)
+    }
+
+    async fn provide_questions(&mut self) {
+        self.status(false, Uuid::nil());
+        for correlation_id in std::mem::take(&mut self.asked) {
+            self.room
+                .room
+                .ask(ProvideQuestionResponse {
+                    requester: self.alice.session.clone(),
+                    correlation_id,
+                    question_info: sample_question(),
+                    question_svg: "<svg/>".into(),
+                })
+                .await
+                .unwrap();
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_second_start_in_the_mailbox_is_turned_away() {
+    let game = Game::start().await;
+    let start = || StartGameRequest {
+        requester: game.alice.session.clone(),
+        correlation_id: Uuid::new_v4(),
+        game_settings: settings(),
+    };
+    let (first, second) = (start(), start());
+    let (first_id, second_id) = (first.correlation_id, second.correlation_id);
+    game.room.room.tell(first).await.unwrap();
+    game.room.room.tell(second).await.unwrap();
+
+    assert_eq!(game.phase().await.phase, GamePhase::Countdown);
+    let statuses: Vec<_> = game
+        .alice
+        .sink
+        .take()
+        .into_iter()
+        .filter_map(|msg| match msg {
+            TransportMsg::OutRespStatus(env) => Some((env.correlation_id, env.payload.status)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            (first_id, "success".to_string()),
+            (second_id, "already running".to_string())
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn each_phase_turns_away_what_it_cannot_take() {
+    let mut game = Game::start().await;
+    assert_eq!(game.answer().await.as_deref(), Some("no active round"));
+    assert_eq!(game.stop_game().await.as_deref(), Some("not running"));
+    assert_eq!(
+        game.resume_game().await.as_deref(),
+        Some("no suspended game")
+    );
+
+    assert_eq!(game.start_game().await.as_deref(), Some("success"));
+    assert_eq!(game.phase().await.phase, GamePhase::Countdown);
+    assert_eq!(game.answer().await.as_deref(), Some("countdown running"));
+    assert_eq!(
+        game.extend_round().await.as_deref(),
+        Some("countdown running")
+    );
+
+    tokio::time::sleep(Duration::from_millis(1010)).await;
+    assert_eq!(game.phase().await.phase, GamePhase::AwaitingQuestion);
+    assert_eq!(game.answer().await.as_deref(), Some("no active round"));
+
+    game.provide_questions().await;
+    let report = game.phase().await;
+    assert_eq!(report.phase, GamePhase::RoundActive);
+    assert_eq!(report.round_timers, 1);
+    assert_eq!(game.start_game().await.as_deref(), Some("already running"));
+    assert_eq!(game.extend_round().await.as_deref(), Some("success"));
+
+    assert_eq!(game.stop_game().await.as_deref(), Some("success"));
+    let report = game.phase().await;
+    assert_eq!(report.phase, GamePhase::Idle);
+    assert_eq!(report.round_timers, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn random_request_sequences_keep_the_phase_consistent() {
+    for seed in 1..=20u64 {
+        let mut rng = XorShift(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        let mut game = Game::start().await;
+        for step in 0..150 {
+            let before = game.phase().await.phase;
+            match rng.below(7) {
+                0 => {
+                    let status = game.start_game().await.unwrap();
+                    let allowed = matches!(before, GamePhase::Idle | GamePhase::Paused);
+                    assert_eq!(status == "success", allowed, "seed {seed} step {step}");
+                }
+                1 => {
+                    let status = game.stop_game().await.unwrap();
+                    assert_eq!(status == "success", before.is_running(), "seed {seed}");
+                }
+                2 | 3 => {
+                    let status = game.answer().await.unwrap();
+                    if before == GamePhase::Idle {
+                        assert_eq!(status, "no active round", "seed {seed} step {step}");
+                    }
+                    if status == "success" {
+                        assert!(
+                            matches!(before, GamePhase::RoundActive | GamePhase::Intermission),
+                            "answer taken in {before:?}, seed {seed} step {step}"
+                        );
+                    }
+                }
+                4 => {
+                    let status = game.extend_round().await.unwrap();
+                    assert_eq!(status == "success", before == GamePhase::RoundActive);
+                }
+                5 => game.provide_questions().await,
+                _ => {
+                    let millis = rng.below(2500);
+                    tokio::time::sleep(Duration::from_millis(millis)).await;
+                }
+            }
+
+            let report = game.phase().await;
+            let in_round = matches!(
+                report.phase,
+                GamePhase::RoundActive | GamePhase::Intermission
+            );
+            assert_eq!(
+                report.round_timers,
+                usize::from(in_round),
+                "{report:?}, seed {seed} step {step}"
+            );
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+use tokio::time::Instant;
+
+fn set_handicap(client_id: &str, extra_time_ms: u64, score_multiplier: f64) -> TransportMsg {
+    TransportMsg::InReqSetHandicap(envelope(InReqSetHandicap {
+        client_id: client_id.into(),
+        extra_time_ms,
+        score_multiplier,
+    }))
+}
+
+fn answer(answer: &str) -> TransportMsg {
+    TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+        answer: answer.into(),
+        question_id: None,
+        game_id: None,
+    }))
+}
+
+async fn start_round(admin: &mut TestClient) {
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings {
+            round_duration: 10,
+            rounds_count: 1,
+            start_countdown: 0,
+            ..Default::default()
+        },
+    }));
+    assert_eq!(admin.request(start).await.unwrap(), "success");
+
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo {
+                    word_info: WordInfo {
+                        word: "日".into(),
+                        meanings: Vec::new(),
+                        readings: vec![ReadingWithParts {
+                            reading: "ひ".into(),
+                            parts: Vec::new(),
+                        }],
+                    },
+                    ..Default::default()
+                },
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    admin
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn admin_sets_and_clears_a_handicap() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+    let mut player = server.local_client("player").await;
+    let player_id = player.id.clone().unwrap();
+
+    assert_eq!(
+        player
+            .request(set_handicap(&player_id, 1_000, 1.0))
+            .await
+            .unwrap(),
+        "not admin"
+    );
+    assert_eq!(
+        admin
+            .request(set_handicap(&player_id, 60_000, 1.0))
+            .await
+            .unwrap(),
+        "invalid handicap"
+    );
+    assert_eq!(
+        admin
+            .request(set_handicap(&player_id, 0, 10.0))
+            .await
+            .unwrap(),
+        "invalid handicap"
+    );
+    assert_eq!(
+        admin.request(set_handicap("nobody", 0, 1.5)).await.unwrap(),
+        "client not found"
+    );
+
+    assert_eq!(
+        admin
+            .request(set_handicap(&player_id, 5_000, 1.5))
+            .await
+            .unwrap(),
+        "success"
+    );
+    let notif = player
+        .expect::<OutNotifHandicapChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let handicap = Handicap {
+        extra_time_ms: 5_000,
+        score_multiplier: 1.5,
+    };
+    assert_eq!(notif.payload.id, player_id);
+    assert_eq!(notif.payload.handicap, Some(handicap));
+
+    let correlation_id = admin
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespClientList(list) = admin
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected a client list");
+    };
+    let listed = list
+        .payload
+        .clients
+        .iter()
+        .find(|c| c.id == player_id)
+        .unwrap();
+    assert_eq!(listed.handicap, Some(handicap));
+
+    assert_eq!(
+        admin
+            .request(set_handicap(&player_id, 0, 1.0))
+            .await
+            .unwrap(),
+        "success"
+    );
+    let notif = player
+        .expect::<OutNotifHandicapChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(notif.payload.handicap, None);
+}
+
+#[tokio::test(start_paused = true)]
+async fn handicapped_player_answers_after_the_round_time() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+    let mut player = server.local_client("player").await;
+    let mut slow = server.local_client("slow").await;
+    let slow_id = slow.id.clone().unwrap();
+
+    assert_eq!(
+        admin
+            .request(set_handicap(&slow_id, 5_000, 2.0))
+            .await
+            .unwrap(),
+        "success"
+    );
+    start_round(&mut admin).await;
+    let started = Instant::now();
+    assert_eq!(admin.request(answer("ひ")).await.unwrap(), "success");
+
+    tokio::time::sleep(Duration::from_secs(12)).await;
+    assert_eq!(player.request(answer("ひ")).await.unwrap(), "time is up");
+    assert_eq!(slow.request(answer("ひ")).await.unwrap(), "success");
+
+    // the round ends as soon as the handicapped player is in
+    let stopped = admin
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(started.elapsed(), Duration::from_secs(12));
+    let score = |id: &str| {
+        stopped
+            .payload
+            .final_scores
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.score)
+    };
+    assert_eq!(score(&slow_id), Some(2));
+    assert_eq!(score(admin.id.as_deref().unwrap()), Some(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn grace_ends_with_the_longest_extra_time() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+    let slow = server.local_client("slow").await;
+    let slow_id = slow.id.clone().unwrap();
+
+    assert_eq!(
+        admin
+            .request(set_handicap(&slow_id, 5_000, 1.0))
+            .await
+            .unwrap(),
+        "success"
+    );
+    start_round(&mut admin).await;
+    let started = Instant::now();
+
+    admin
+        .expect::<OutNotifGameStopped>(Duration::from_secs(30))
+        .await
+        .unwrap();
+    assert_eq!(started.elapsed(), Duration::from_secs(15));
+}
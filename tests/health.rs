@@ -0,0 +1,72 @@
+use kanjilab_server::{
+    data_types::*,
+    health::HealthReport,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// Sends a plain HTTP GET and returns the whole response.
+async fn get(server: &TestServer, path: &str) -> String {
+    let mut stream = TcpStream::connect(server.addr()).await.unwrap();
+    let request = format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    tokio::time::timeout(DEFAULT_TIMEOUT, stream.read_to_string(&mut response))
+        .await
+        .unwrap()
+        .unwrap();
+    response
+}
+
+fn body(response: &str) -> HealthReport {
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    serde_json::from_str(body).unwrap()
+}
+
+#[tokio::test]
+async fn probes_are_answered_over_plain_http() {
+    let server = TestServer::start().await;
+    let _alice = server.local_client("alice").await;
+
+    for path in ["/healthz", "/info", "/healthz?verbose=1"] {
+        let response = get(&server, path).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.contains("Content-Type: application/json\r\n"));
+        let report = body(&response);
+        assert_eq!(report.status, "ok");
+        assert_eq!(report.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(report.clients, 1);
+    }
+}
+
+#[tokio::test]
+async fn other_paths_still_need_a_websocket_upgrade() {
+    let server = TestServer::start().await;
+
+    let response = get(&server, "/status").await;
+    assert!(!response.starts_with("HTTP/1.1 200"), "{response}");
+}
+
+#[tokio::test]
+async fn game_clients_share_the_port_with_probes() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+    get(&server, "/healthz").await;
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespClientList(list) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected a client list");
+    };
+    assert_eq!(list.payload.clients.len(), 1);
+    assert_eq!(body(&get(&server, "/info").await).clients, 1);
+}
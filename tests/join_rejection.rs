@@ -0,0 +1,82 @@
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+async fn register(client: &mut TestClient, name: &str, password: Option<&str>) -> TransportMsg {
+    let correlation_id = client
+        .send(TransportMsg::InReqRegisterClient(envelope(
+            InReqRegisterClient {
+                name: name.into(),
+                spectator: false,
+                password: password.map(|p| Password(p.into())),
+            },
+        )))
+        .await
+        .unwrap();
+    client
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+}
+
+fn rejection(resp: TransportMsg) -> Option<JoinRejection> {
+    match resp {
+        TransportMsg::OutRespError(env) => env.payload.code,
+        other => panic!("expected an error, got {other:?}"),
+    }
+}
+
+async fn verified(server: &TestServer) -> TestClient {
+    let mut client = TestClient::local(server.game()).await;
+    client.handshake().await.unwrap();
+    client
+}
+
+#[tokio::test]
+async fn rejected_client_can_try_again() {
+    let server = TestServer::start_with_config(ServerConfig {
+        max_clients: Some(2),
+        ..Default::default()
+    })
+    .await;
+    let mut alice = verified(&server).await;
+    let resp = register(&mut alice, "alice", Some("hunter2")).await;
+    assert!(matches!(resp, TransportMsg::OutRespClientRegistered(_)));
+
+    let mut bob = verified(&server).await;
+    let resp = register(&mut bob, "bob", None).await;
+    assert_eq!(rejection(resp), Some(JoinRejection::WrongPassword));
+    let resp = register(&mut bob, "bob", Some("hunter2")).await;
+    assert!(matches!(resp, TransportMsg::OutRespClientRegistered(_)));
+
+    let mut carol = verified(&server).await;
+    let resp = register(&mut carol, "carol", Some("hunter2")).await;
+    assert_eq!(rejection(resp), Some(JoinRejection::RoomFull));
+}
+
+#[tokio::test]
+async fn locked_room_turns_registrations_away() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings {
+            lock_room_during_game: true,
+            ..Default::default()
+        },
+    }));
+    let correlation_id = alice.send(start).await.unwrap();
+    assert_eq!(
+        alice.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+
+    let mut bob = verified(&server).await;
+    let resp = register(&mut bob, "bob", None).await;
+    let TransportMsg::OutRespError(env) = resp else {
+        panic!("expected an error, got {resp:?}");
+    };
+    assert_eq!(env.payload.code, Some(JoinRejection::GameInProgress));
+    assert_eq!(env.payload.error, "game in progress");
+}
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    data_types::*,
+    metrics::{LATENCY_BUCKETS_MS, LatencyMetrics},
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+async fn server_stats(client: &mut TestClient) -> ServerStats {
+    let correlation_id = client
+        .send(TransportMsg::InReqServerStats(envelope(
+            InReqServerStats {},
+        )))
+        .await
+        .unwrap();
+    match client.response(correlation_id, DEFAULT_TIMEOUT).await {
+        Ok(TransportMsg::OutRespServerStats(env)) => env.payload.stats,
+        other => panic!("expected server stats, got {other:?}"),
+    }
+}
+
+#[test]
+fn latencies_land_in_their_buckets() {
+    let metrics = LatencyMetrics::default();
+    metrics.record("IN_REQ_sendAnswer", Duration::from_micros(300));
+    metrics.record("IN_REQ_sendAnswer", Duration::from_millis(5));
+    metrics.record("IN_REQ_sendAnswer", Duration::from_secs(3));
+
+    let histograms = metrics.snapshot();
+    let answers = &histograms["IN_REQ_sendAnswer"];
+    assert_eq!(answers.count, 3);
+    assert_eq!(answers.max_us, 3_000_000);
+    assert_eq!(answers.total_us, 3_005_300);
+    assert_eq!(answers.buckets.len(), LATENCY_BUCKETS_MS.len() + 1);
+    assert_eq!(answers.buckets[0], 1);
+    // bounds are inclusive
+    assert_eq!(answers.buckets[1], 1);
+    assert_eq!(answers.buckets[LATENCY_BUCKETS_MS.len()], 1);
+}
+
+#[tokio::test]
+async fn socket_requests_are_timed_by_type() {
+    // the socket path without a port
+    let server = TestServer::start_in_memory().await;
+    let mut alice = server.client("alice").await;
+
+    for i in 0..3 {
+        let chat = TransportMsg::InReqSendChat(envelope(InReqSendChat {
+            message: format!("hello {i}"),
+        }));
+        let correlation_id = alice.send(chat).await.unwrap();
+        alice.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap();
+    }
+
+    // a response is timed just after it is written, so the client can get
+    // ahead of the histogram by a little
+    let mut stats = server_stats(&mut alice).await;
+    for _ in 0..100 {
+        if stats
+            .request_latency
+            .get("IN_REQ_sendChat")
+            .is_some_and(|h| h.count == 3)
+        {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        stats = server_stats(&mut alice).await;
+    }
+    let chat = &stats.request_latency["IN_REQ_sendChat"];
+    assert_eq!(chat.count, 3);
+    assert_eq!(chat.buckets.iter().sum::<u64>(), 3);
+    assert!(chat.max_us <= chat.total_us);
+    assert_eq!(stats.request_latency["IN_REQ_registerClient"].count, 1);
+}
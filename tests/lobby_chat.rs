@@ -0,0 +1,71 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope},
+};
+
+fn lobby_chat(message: &str) -> TransportMsg {
+    TransportMsg::InReqSendLobbyChat(envelope(InReqSendLobbyChat {
+        message: message.into(),
+    }))
+}
+
+#[tokio::test]
+async fn lobby_chat_reaches_clients_outside_the_room() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    let leave = TransportMsg::InReqLeaveRoom(envelope(InReqLeaveRoom {}));
+    assert_eq!(bob.request(leave).await.unwrap(), "success");
+
+    assert_eq!(
+        alice.request(lobby_chat(" hello ")).await.unwrap(),
+        "success"
+    );
+    let notif = bob
+        .expect::<OutNotifLobbyChat>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(notif.payload.id, alice.id.clone().unwrap());
+    assert_eq!(notif.payload.name, "alice");
+    assert_eq!(notif.payload.message, "hello");
+
+    assert_eq!(
+        alice.request(lobby_chat("   ")).await.unwrap(),
+        "message empty"
+    );
+}
+
+#[tokio::test]
+async fn lobby_history_is_replayed_on_registration() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    assert_eq!(alice.request(lobby_chat("first")).await.unwrap(), "success");
+    assert_eq!(
+        alice.request(lobby_chat("second")).await.unwrap(),
+        "success"
+    );
+
+    let mut bob = server.local_client("bob").await;
+    for expected in ["first", "second"] {
+        let notif = bob
+            .expect::<OutNotifLobbyChat>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(notif.payload.message, expected);
+    }
+}
+
+#[tokio::test]
+async fn lobby_chat_is_rate_limited() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+
+    for _ in 0..5 {
+        assert_eq!(alice.request(lobby_chat("spam")).await.unwrap(), "success");
+    }
+    assert_eq!(
+        alice.request(lobby_chat("spam")).await.unwrap(),
+        "rate limited"
+    );
+}
@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+use uuid::Uuid;
+
+fn word_list(words: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("kanjilab-words-{}.txt", Uuid::new_v4()));
+    std::fs::write(&path, words).unwrap();
+    path
+}
+
+async fn start(action: ModerationAction) -> TestServer {
+    TestServer::start_with_config(ServerConfig {
+        blocked_words_path: Some(word_list("# test list\ndarn\n\nheck\n")),
+        moderation_action: action,
+        ..Default::default()
+    })
+    .await
+}
+
+fn chat(message: &str) -> TransportMsg {
+    TransportMsg::InReqSendChat(envelope(InReqSendChat {
+        message: message.into(),
+    }))
+}
+
+fn moderation(action: ModerationAction) -> TransportMsg {
+    TransportMsg::InReqSetModerationSettings(envelope(InReqSetModerationSettings { action }))
+}
+
+#[tokio::test]
+async fn chat_is_masked_by_default() {
+    let server = start(ModerationAction::default()).await;
+    let mut alice = server.local_client("alice").await;
+
+    assert_eq!(alice.request(chat("oh d4rn it")).await.unwrap(), "success");
+    let notif = alice
+        .expect::<OutNotifChatSent>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(notif.payload.message, "oh **** it");
+}
+
+#[tokio::test]
+async fn admin_switches_the_action_at_runtime() {
+    let server = start(ModerationAction::Mask).await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    assert_eq!(
+        bob.request(moderation(ModerationAction::Warn))
+            .await
+            .unwrap(),
+        "not admin"
+    );
+    assert_eq!(
+        alice
+            .request(moderation(ModerationAction::Reject))
+            .await
+            .unwrap(),
+        "success"
+    );
+    let notif = bob
+        .expect::<OutNotifModerationSettingsChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(notif.payload.action, ModerationAction::Reject);
+    assert!(notif.payload.active);
+    assert_eq!(bob.request(chat("HECK")).await.unwrap(), "blocked word");
+
+    assert_eq!(
+        alice
+            .request(moderation(ModerationAction::Warn))
+            .await
+            .unwrap(),
+        "success"
+    );
+    assert_eq!(bob.request(chat("HECK")).await.unwrap(), "success");
+    let notif = alice
+        .expect::<OutNotifChatSent>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(notif.payload.message, "HECK");
+}
+
+#[tokio::test]
+async fn names_follow_the_current_action() {
+    let server = start(ModerationAction::Reject).await;
+    let mut alice = server.local_client("alice").await;
+
+    let mut client = TestClient::local(server.game()).await;
+    client.handshake().await.unwrap();
+    assert_eq!(client.register("d4rn").await, Err("blocked word".into()));
+
+    let rename = |name: &str| {
+        TransportMsg::InReqRenameClient(envelope(InReqRenameClient { name: name.into() }))
+    };
+    assert_eq!(
+        alice.request(rename("heckler")).await.unwrap(),
+        "blocked word"
+    );
+
+    assert_eq!(
+        alice
+            .request(moderation(ModerationAction::Mask))
+            .await
+            .unwrap(),
+        "success"
+    );
+    client.register("d4rnit").await.unwrap();
+    let correlation_id = alice
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespClientList(list) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected a client list");
+    };
+    assert!(list.payload.clients.iter().any(|c| c.name == "****it"));
+}
+
+#[tokio::test]
+async fn without_a_word_list_nothing_is_filtered() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+
+    assert_eq!(
+        alice
+            .request(moderation(ModerationAction::Reject))
+            .await
+            .unwrap(),
+        "success"
+    );
+    let notif = alice
+        .expect::<OutNotifModerationSettingsChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert!(!notif.payload.active);
+    assert_eq!(alice.request(chat("darn")).await.unwrap(), "success");
+}
@@ -0,0 +1,152 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope, start_game},
+    tools::{closest_reading, edit_distance},
+};
+
+fn question(question_type: QuestionType) -> QuestionInfo {
+    QuestionInfo {
+        word_info: WordInfo {
+            word: "日本".into(),
+            meanings: Vec::new(),
+            readings: vec![
+                ReadingWithParts {
+                    reading: "にほん".into(),
+                    parts: Vec::new(),
+                },
+                ReadingWithParts {
+                    reading: "にっぽん".into(),
+                    parts: Vec::new(),
+                },
+            ],
+        },
+        question_type,
+        ..Default::default()
+    }
+}
+
+fn start(accept_near_miss: Option<u32>, share_near_misses: bool) -> TransportMsg {
+    start_game(GameSettings {
+        round_duration: 30,
+        rounds_count: 2,
+        start_countdown: 0,
+        accept_near_miss,
+        share_near_misses,
+        ..Default::default()
+    })
+}
+
+fn answer(answer: &str) -> TransportMsg {
+    TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+        answer: answer.into(),
+        question_id: None,
+        game_id: None,
+    }))
+}
+
+#[test]
+fn distance_counts_kana_not_bytes() {
+    assert_eq!(edit_distance("にほん", "にほん"), 0);
+    // a small kana for a full-size one
+    assert_eq!(edit_distance("しよう", "しょう"), 1);
+    // a long vowel written with a mark, and one left out
+    assert_eq!(edit_distance("こうこう", "こーこー"), 2);
+    assert_eq!(edit_distance("とうきょう", "ときょう"), 1);
+    assert_eq!(edit_distance("さくら", "やま"), 3);
+    assert_eq!(edit_distance("", "やま"), 2);
+}
+
+#[test]
+fn closest_reading_is_picked_after_folding() {
+    let reading = question(QuestionType::ReadingOfWord);
+    assert_eq!(
+        closest_reading(&reading, " ニッポソ "),
+        Some(("にっぽん".into(), 1))
+    );
+    assert_eq!(
+        closest_reading(&reading, "にぽん"),
+        Some(("にほん".into(), 1))
+    );
+    assert_eq!(closest_reading(&reading, ""), None);
+    assert_eq!(
+        closest_reading(&question(QuestionType::MeaningOfWord), "japan"),
+        None
+    );
+}
+
+#[tokio::test]
+async fn wrong_answers_get_private_feedback() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    assert_eq!(alice.request(start(None, false)).await.unwrap(), "success");
+    alice
+        .provide_question(question(QuestionType::ReadingOfWord))
+        .await
+        .unwrap();
+    assert_eq!(alice.request(answer("ニポン")).await.unwrap(), "success");
+    assert_eq!(bob.request(answer("やま")).await.unwrap(), "success");
+
+    let feedback = alice
+        .expect::<OutNotifAnswerFeedback>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload;
+    assert_eq!(feedback.answer, "ニポン");
+    assert_eq!(feedback.closest_reading, "にほん");
+    assert_eq!(feedback.distance, 1);
+    let feedback = bob
+        .expect::<OutNotifAnswerFeedback>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload;
+    assert_eq!(feedback.answer, "やま");
+
+    let ended = bob
+        .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload;
+    assert!(ended.answers.iter().all(|a| !a.is_correct));
+    assert!(ended.near_misses.is_empty());
+}
+
+#[tokio::test]
+async fn near_misses_can_count_and_be_shared() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let bob_id = bob.id.clone().unwrap();
+
+    assert_eq!(
+        alice.request(start(Some(1), true)).await.unwrap(),
+        "success"
+    );
+    alice
+        .provide_question(question(QuestionType::ReadingOfWord))
+        .await
+        .unwrap();
+    assert_eq!(alice.request(answer("にぽん")).await.unwrap(), "success");
+    assert_eq!(bob.request(answer("にぽ")).await.unwrap(), "success");
+
+    let ended = alice
+        .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload;
+    let alice_answer = ended
+        .answers
+        .iter()
+        .find(|a| Some(&a.id) == alice.id.as_ref())
+        .unwrap();
+    assert!(alice_answer.is_correct);
+    assert_eq!(
+        ended.near_misses,
+        vec![NearMiss {
+            id: bob_id,
+            closest_reading: "にほん".into(),
+            distance: 2,
+        }]
+    );
+}
@@ -0,0 +1,107 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, start_game},
+};
+
+fn start() -> TransportMsg {
+    start_game(GameSettings {
+        round_duration: 1,
+        rounds_count: 2,
+        start_countdown: 0,
+        allow_repeat_words: true,
+        ..Default::default()
+    })
+}
+
+fn seq(msg: &TransportMsg) -> Option<u64> {
+    serde_json::to_value(msg).ok()?.get("seq")?.as_u64()
+}
+
+fn message_type(msg: &TransportMsg) -> String {
+    serde_json::to_value(msg).unwrap()["messageType"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+/// Numbered notifications from the start to the end of a game. The admin
+/// serves the questions and answers them.
+async fn play_through(client: &mut TestClient, is_admin: bool) -> Vec<(u64, String)> {
+    let mut numbered = Vec::new();
+    loop {
+        let msg = client.next(DEFAULT_TIMEOUT).await.unwrap().unwrap();
+        if let Some(seq) = seq(&msg) {
+            let started = matches!(msg, TransportMsg::OutNotifGameStarted(_));
+            if started || !numbered.is_empty() {
+                numbered.push((seq, message_type(&msg)));
+            }
+        }
+        match msg {
+            TransportMsg::OutReqQuestion(req) if is_admin => {
+                client
+                    .send(TransportMsg::InRespQuestion(TransportEnvelope {
+                        correlation_id: req.correlation_id,
+                        seq: None,
+                        payload: InRespQuestion {
+                            question: QuestionInfo::default(),
+                            question_svg: "<svg/>".into(),
+                        },
+                    }))
+                    .await
+                    .unwrap();
+            }
+            TransportMsg::OutNotifQuestion(question) if is_admin => {
+                client
+                    .send(TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+                        answer: "ひ".into(),
+                        question_id: Some(question.payload.question_id),
+                        game_id: question.payload.game_id,
+                    })))
+                    .await
+                    .unwrap();
+            }
+            TransportMsg::OutNotifGameStopped(_) => return numbered,
+            _ => {}
+        }
+    }
+}
+
+#[tokio::test]
+async fn everyone_sees_the_same_numbered_game() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    assert_eq!(alice.request(start()).await.unwrap(), "success");
+    let seen_by_alice = play_through(&mut alice, true).await;
+    let seen_by_bob = play_through(&mut bob, false).await;
+
+    assert_eq!(seen_by_alice, seen_by_bob);
+    assert!(seen_by_alice.windows(2).all(|w| w[0].0 < w[1].0));
+    let position = |message_type: &str| {
+        seen_by_alice
+            .iter()
+            .position(|(_, t)| t == message_type)
+            .unwrap()
+    };
+    assert!(position("OUT_NOTIF_clientAnswered") < position("OUT_NOTIF_roundEnded"));
+
+    // neither an admin change nor a new game starts the count over
+    let last = seen_by_alice.last().unwrap().0;
+    let handover = TransportMsg::InReqMakeAdmin(envelope(InReqMakeAdmin {
+        admin_password: String::new(),
+        client_id: bob.id.clone().unwrap(),
+        revoke_existing: true,
+    }));
+    assert_eq!(alice.request(handover).await.unwrap(), "success");
+    let made = bob
+        .expect::<OutNotifAdminMade>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert!(made.seq.unwrap() > last);
+
+    assert_eq!(bob.request(start()).await.unwrap(), "success");
+    let seen_by_bob = play_through(&mut bob, true).await;
+    assert!(seen_by_bob[0].0 > made.seq.unwrap());
+    assert_eq!(play_through(&mut alice, false).await, seen_by_bob);
+}
@@ -0,0 +1,53 @@
+use kanjilab_server::{
+    ServerConfig,
+    game_actor::collect_server_stats,
+    testkit::{TestClient, TestServer},
+};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+async fn connect_from(server: &TestServer, origin: &str) -> Result<TestClient, String> {
+    let mut request = server.url().into_client_request().unwrap();
+    request
+        .headers_mut()
+        .insert("Origin", origin.parse().unwrap());
+    TestClient::connect(request).await
+}
+
+#[tokio::test]
+async fn only_allowed_origins_can_connect() {
+    let server = TestServer::start_with_config(ServerConfig {
+        allowed_origins: vec!["https://kanjilab.example".into()],
+        ..Default::default()
+    })
+    .await;
+
+    let err = connect_from(&server, "https://evil.example")
+        .await
+        .err()
+        .unwrap();
+    assert!(err.contains("403"), "{err}");
+
+    let mut allowed = connect_from(&server, "https://kanjilab.example")
+        .await
+        .unwrap();
+    allowed.handshake().await.unwrap();
+    let mut desktop = TestClient::connect(server.url()).await.unwrap();
+    desktop.handshake().await.unwrap();
+
+    let stats = collect_server_stats(server.game()).await.unwrap();
+    assert_eq!(stats.rejected_origins, 1);
+}
+
+#[tokio::test]
+async fn wildcard_allows_any_origin() {
+    let server = TestServer::start_with_config(ServerConfig {
+        allowed_origins: vec!["*".into()],
+        ..Default::default()
+    })
+    .await;
+
+    let mut client = connect_from(&server, "https://anywhere.example")
+        .await
+        .unwrap();
+    client.handshake().await.unwrap();
+}
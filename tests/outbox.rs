@@ -0,0 +1,179 @@
+use kameo::error::SendError;
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    game_actor::DumpOutbox,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, operator_key, operator_keys},
+    tools::unix_millis,
+};
+
+async fn debug_outbox(
+    client: &mut TestClient,
+    client_id: &str,
+) -> Result<Vec<OutboxEntry>, String> {
+    let correlation_id = client
+        .send(TransportMsg::InReqDebugOutbox(envelope(InReqDebugOutbox {
+            client_id: client_id.into(),
+        })))
+        .await?;
+    match client.response(correlation_id, DEFAULT_TIMEOUT).await? {
+        TransportMsg::OutRespDebugOutbox(env) => Ok(env.payload.entries),
+        TransportMsg::OutRespStatus(env) => Err(env.payload.status),
+        other => Err(format!("unexpected response: {other:?}")),
+    }
+}
+
+async fn request(client: &mut TestClient, msg: TransportMsg) -> (uuid::Uuid, String) {
+    let correlation_id = client.send(msg).await.unwrap();
+    let status = client
+        .status(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    (correlation_id, status)
+}
+
+#[tokio::test]
+async fn outbox_keeps_the_latest_messages() {
+    let server = TestServer::start_with_config(ServerConfig {
+        outbox_size: 3,
+        debug_outbox: true,
+        operator_keys: operator_keys(),
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client_with_key("alice", operator_key()).await;
+    let alice_id = alice.id.clone().unwrap();
+
+    let mut last = None;
+    for i in 0..4 {
+        let chat = TransportMsg::InReqSendChat(envelope(InReqSendChat {
+            message: format!("hello {i}"),
+        }));
+        let (correlation_id, status) = request(&mut alice, chat).await;
+        assert_eq!(status, "success");
+        last = Some(correlation_id);
+    }
+
+    // read over the same socket, so everything before it has been written
+    let entries = debug_outbox(&mut alice, &alice_id).await.unwrap();
+    assert_eq!(entries.len(), 3);
+    let status = entries
+        .iter()
+        .find(|e| e.correlation_id == last)
+        .expect("the last status is kept");
+    assert_eq!(status.message_type, "OUT_RESP_status");
+    assert!(status.delivered);
+    assert!(status.size > 0);
+    assert!(entries.windows(2).all(|w| w[0].sent_at <= w[1].sent_at));
+}
+
+#[tokio::test]
+async fn outbox_requests_are_refused_unless_enabled() {
+    let server = TestServer::start_with_config(ServerConfig {
+        operator_keys: operator_keys(),
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client_with_key("alice", operator_key()).await;
+    let alice_id = alice.id.clone().unwrap();
+
+    assert_eq!(
+        debug_outbox(&mut alice, &alice_id).await.unwrap_err(),
+        "outbox debugging disabled"
+    );
+    // embedders can always read it
+    let entries = server.game().ask(DumpOutbox(alice_id)).await.unwrap();
+    assert!(!entries.is_empty());
+}
+
+#[tokio::test]
+async fn outbox_requests_need_an_operator_and_a_socket_client() {
+    let server = TestServer::start_with_config(ServerConfig {
+        operator_keys: operator_keys(),
+        debug_outbox: true,
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client("alice").await;
+    let alice_id = alice.id.clone().unwrap();
+    let bob = server.local_client("bob").await;
+
+    assert_eq!(
+        debug_outbox(&mut alice, &alice_id).await.unwrap_err(),
+        "not operator"
+    );
+
+    let mut monitor = TestClient::connect(server.url())
+        .await
+        .unwrap()
+        .with_key(operator_key());
+    monitor.handshake().await.unwrap();
+    assert!(
+        !debug_outbox(&mut monitor, &alice_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        debug_outbox(&mut monitor, "nobody").await.unwrap_err(),
+        "no such client"
+    );
+    assert_eq!(
+        debug_outbox(&mut monitor, bob.id.as_deref().unwrap())
+            .await
+            .unwrap_err(),
+        "no outbox"
+    );
+}
+
+#[tokio::test]
+async fn nobody_is_an_operator_without_operator_keys() {
+    let server = TestServer::start_with_config(ServerConfig {
+        debug_outbox: true,
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client("alice").await;
+    let alice_id = alice.id.clone().unwrap();
+
+    assert_eq!(
+        debug_outbox(&mut alice, &alice_id).await.unwrap_err(),
+        "not operator"
+    );
+}
+
+#[tokio::test]
+async fn reconnecting_starts_a_new_outbox() {
+    let server = TestServer::start_with_config(ServerConfig {
+        debug_outbox: true,
+        replace_duplicate_sessions: true,
+        operator_keys: operator_keys(),
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client_with_key("alice", operator_key()).await;
+    let first_id = alice.id.clone().unwrap();
+    assert!(
+        !debug_outbox(&mut alice, &first_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let reconnected_at = unix_millis();
+    let mut again = TestClient::connect(server.url())
+        .await
+        .unwrap()
+        .with_key(alice.key().clone());
+    again.handshake().await.unwrap();
+    let second_id = again.register("alice").await.unwrap();
+    alice.expect_closed(DEFAULT_TIMEOUT).await.unwrap();
+
+    let entries = debug_outbox(&mut again, &second_id).await.unwrap();
+    assert!(!entries.is_empty());
+    assert!(entries.iter().all(|e| e.sent_at >= reconnected_at));
+    assert!(matches!(
+        server.game().ask(DumpOutbox(first_id)).await,
+        Err(SendError::HandlerError("no such client"))
+    ));
+}
@@ -0,0 +1,80 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope},
+};
+
+fn patch(game_settings: GameSettingsPatch) -> TransportMsg {
+    TransportMsg::InReqPatchGameSettings(envelope(InReqPatchGameSettings { game_settings }))
+}
+
+#[tokio::test]
+async fn patches_from_two_panels_both_stick() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+    let mut player = server.local_client("player").await;
+    // the settings sent on joining
+    player
+        .expect::<OutNotifGameSettingsChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    let rounds = patch(GameSettingsPatch {
+        rounds_count: Some(7),
+        ..Default::default()
+    });
+    assert_eq!(admin.request(rounds).await.unwrap(), "success");
+    let word_part = patch(GameSettingsPatch {
+        word_part: Some(Some("日".into())),
+        ..Default::default()
+    });
+    assert_eq!(admin.request(word_part).await.unwrap(), "success");
+
+    player
+        .expect::<OutNotifGameSettingsChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let changed = player
+        .expect::<OutNotifGameSettingsChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .game_settings;
+    assert_eq!(changed.rounds_count, 7);
+    assert_eq!(changed.word_part.as_deref(), Some("日"));
+
+    let clear = patch(GameSettingsPatch {
+        word_part: Some(None),
+        ..Default::default()
+    });
+    assert_eq!(admin.request(clear).await.unwrap(), "success");
+    let changed = player
+        .expect::<OutNotifGameSettingsChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .game_settings;
+    assert_eq!(changed.rounds_count, 7);
+    assert_eq!(changed.word_part, None);
+}
+
+#[tokio::test]
+async fn patch_needs_an_admin_and_a_valid_result() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+    let mut player = server.local_client("player").await;
+
+    let rounds = patch(GameSettingsPatch {
+        rounds_count: Some(7),
+        ..Default::default()
+    });
+    assert_eq!(player.request(rounds).await.unwrap(), "not admin");
+
+    let invalid = patch(GameSettingsPatch {
+        question_timeout: Some(0),
+        ..Default::default()
+    });
+    assert_eq!(
+        admin.request(invalid).await.unwrap(),
+        "invalid question timeout"
+    );
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use kameo::{
+    Actor,
+    actor::ActorRef,
+    error::Infallible,
+    message::{Context, Message},
+};
+use kanjilab_server::pending_tracker::*;
+
+/// Never acts on a `Timeout`, as if every one of them got lost.
+struct Deaf;
+
+impl Actor for Deaf {
+    type Args = ();
+    type Error = Infallible;
+
+    async fn on_start(_: Self::Args, _: ActorRef<Self>) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+}
+
+impl Message<Timeout> for Deaf {
+    type Reply = ();
+
+    async fn handle(&mut self, _: Timeout, _ctx: &mut Context<Self, ()>) {}
+}
+
+fn tracker() -> PendingTracker<Deaf, u8> {
+    let actor = Deaf::spawn(());
+    PendingTracker::new(actor.downgrade()).with_slack(Duration::from_secs(1))
+}
+
+#[tokio::test(start_paused = true)]
+async fn expired_entries_are_refused() {
+    let mut pending = tracker();
+    let late = pending.add(1, Duration::from_secs(2));
+    let on_time = pending.add(2, Duration::from_secs(10));
+
+    // within the slack the entry is still accepted
+    tokio::time::advance(Duration::from_millis(2_500)).await;
+    assert_eq!(pending.get(late).map(|m| m.kind), Some(1));
+
+    tokio::time::advance(Duration::from_secs(1)).await;
+    assert!(pending.get(late).is_none());
+    assert!(pending.take(late).is_none());
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.take(on_time).map(|m| m.kind), Some(2));
+    assert!(pending.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn purge_drops_only_expired_entries() {
+    let mut pending = tracker();
+    pending.add(1, Duration::from_secs(1));
+    pending.add(2, Duration::from_secs(1));
+    let live = pending.add(3, Duration::from_secs(30));
+
+    tokio::time::advance(Duration::from_secs(5)).await;
+    assert_eq!(pending.purge_expired(), 2);
+    assert_eq!(pending.purge_expired(), 0);
+    let left: Vec<_> = pending.iter().map(|(t, m)| (t, m.kind)).collect();
+    assert_eq!(left, vec![(live, 3)]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn add_sweeps_expired_entries() {
+    let mut pending = tracker();
+    pending.add(1, Duration::from_secs(1));
+    tokio::time::advance(Duration::from_secs(5)).await;
+
+    pending.add(2, Duration::from_secs(1));
+    assert_eq!(pending.len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancel_drops_an_expired_ticket_without_reporting_it_live() {
+    let mut pending = tracker();
+    let ticket = pending.add(1, Duration::from_secs(1));
+    tokio::time::advance(Duration::from_secs(5)).await;
+
+    assert!(!pending.cancel(ticket));
+    assert!(pending.is_empty());
+    assert!(!pending.cancel(ticket));
+
+    let live = pending.add(2, Duration::from_secs(10));
+    assert!(pending.cancel(live));
+}
@@ -0,0 +1,231 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use kanjilab_server::{
+    ServerConfig, call_launch_server_with_config, call_stop_server,
+    data_types::*,
+    game_actor::SaveState,
+    get_server_addr,
+    persistence::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, sample_question},
+};
+use uuid::Uuid;
+
+fn state_path() -> PathBuf {
+    std::env::temp_dir().join(format!("kanjilab-state-{}.json", Uuid::new_v4()))
+}
+
+fn config(state_path: &Path) -> ServerConfig {
+    ServerConfig {
+        state_path: Some(state_path.to_path_buf()),
+        ..Default::default()
+    }
+}
+
+async fn answer(client: &mut TestClient) {
+    let answer = TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+        answer: "ひ".into(),
+        question_id: None,
+        game_id: None,
+    }));
+    assert_eq!(client.request(answer).await.unwrap(), "success");
+}
+
+#[test]
+fn snapshot_round_trips() {
+    let path = state_path();
+    assert_eq!(load_snapshot(&path), Ok(None));
+
+    let snapshot = RoomSnapshot {
+        version: SNAPSHOT_VERSION,
+        room_name: "default".into(),
+        clients: vec![SavedClient {
+            id: Uuid::new_v4(),
+            key: "key".into(),
+            name: "alice".into(),
+        }],
+        game_settings: GameSettings {
+            rounds_count: 3,
+            ..Default::default()
+        },
+        game_history: vec![GameRecord {
+            game_id: Uuid::new_v4(),
+            game_settings: GameSettings::default(),
+            rounds: vec![RoundRecord {
+                question: sample_question(),
+                answers: Vec::new(),
+                aborted: true,
+                question_id: None,
+                reactions: BTreeMap::new(),
+                tier: None,
+            }],
+            stop_reason: None,
+            game_index: 0,
+            started_at: 1,
+            ended_at: None,
+            player_names: BTreeMap::new(),
+            handicaps: BTreeMap::new(),
+        }],
+        rounds_played: 1,
+        current_font_index: 2,
+        font_names: HashMap::from([(2, "serif".into())]),
+        streaks: HashMap::from([(Uuid::new_v4(), 1)]),
+        streak_points: HashMap::from([("alice".into(), 1)]),
+        suspended: true,
+        game_time_left_ms: Some(90_000),
+    };
+    save_snapshot(&path, &snapshot).unwrap();
+    assert_eq!(load_snapshot(&path), Ok(Some(snapshot)));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn suspended_game_resumes_with_the_players_that_came_back() {
+    let path = state_path();
+
+    let server = TestServer::start_with_config(config(&path)).await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let alice_id = alice.id.clone().unwrap();
+    let bob_id = bob.id.clone().unwrap();
+
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings {
+            round_duration: 30,
+            rounds_count: 3,
+            start_countdown: 0,
+            allow_repeat_words: true,
+            ..Default::default()
+        },
+    }));
+    assert_eq!(alice.request(start).await.unwrap(), "success");
+    alice.provide_question(sample_question()).await.unwrap();
+    alice.provide_question(sample_question()).await.unwrap();
+    answer(&mut alice).await;
+    answer(&mut bob).await;
+    alice
+        .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    // the second round is cut short by the shutdown
+    server.game().ask(SaveState).await.unwrap();
+    drop(server);
+
+    let saved = load_snapshot(&path).unwrap().unwrap();
+    assert!(saved.suspended);
+    assert_eq!(saved.rounds_played, 1);
+    let rounds = &saved.game_history.last().unwrap().rounds;
+    assert_eq!(rounds.len(), 2);
+    assert!(!rounds[0].aborted);
+    assert!(rounds[1].aborted && rounds[1].answers.is_empty());
+
+    let server = TestServer::start_with_config(config(&path)).await;
+    let mut alice = TestClient::local(server.game())
+        .await
+        .with_key(alice.key().clone());
+    alice.handshake().await.unwrap();
+    assert_eq!(alice.register("someone").await.unwrap(), alice_id);
+    assert!(!path.exists());
+
+    let mut carol = server.local_client("carol").await;
+    assert_ne!(carol.id, Some(bob_id.clone()));
+
+    let list = alice
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespClientList(list) =
+        alice.response(list, DEFAULT_TIMEOUT).await.unwrap()
+    else {
+        panic!("expected a client list");
+    };
+    let alice_info = list
+        .payload
+        .clients
+        .iter()
+        .find(|c| c.id == alice_id)
+        .unwrap();
+    assert_eq!(alice_info.name, "alice");
+
+    let resume = TransportMsg::InReqResumeGame(envelope(InReqResumeGame {}));
+    assert_eq!(carol.request(resume.clone()).await.unwrap(), "not admin");
+    assert_eq!(alice.request(resume.clone()).await.unwrap(), "success");
+    assert_eq!(alice.request(resume).await.unwrap(), "no suspended game");
+
+    alice.provide_question(sample_question()).await.unwrap();
+    alice.provide_question(sample_question()).await.unwrap();
+    for _ in 0..2 {
+        answer(&mut alice).await;
+        answer(&mut carol).await;
+    }
+
+    let stopped = alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(stopped.payload.reason, GameStopReason::Completed);
+    assert_eq!(stopped.payload.winner_id, Some(alice_id.clone()));
+    let score = |id: &str| {
+        stopped
+            .payload
+            .final_scores
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.score)
+    };
+    assert_eq!(score(&alice_id), Some(3));
+    assert_eq!(score(&bob_id), Some(1));
+}
+
+#[tokio::test]
+async fn resume_needs_a_suspended_game() {
+    let path = state_path();
+    let server = TestServer::start_with_config(config(&path)).await;
+    let mut alice = server.local_client("alice").await;
+
+    let resume = TransportMsg::InReqResumeGame(envelope(InReqResumeGame {}));
+    assert_eq!(alice.request(resume).await.unwrap(), "no suspended game");
+
+    // an idle room is saved too, just not as a game to resume
+    server.game().ask(SaveState).await.unwrap();
+    let saved = load_snapshot(&path).unwrap().unwrap();
+    assert!(!saved.suspended);
+    assert_eq!(saved.clients.len(), 1);
+    std::fs::remove_file(&path).unwrap();
+}
+
+// launched like `main` launches it: from code already on a runtime
+#[tokio::test]
+async fn a_server_stopped_from_async_code_saves_its_room() {
+    let path = state_path();
+    let id = call_launch_server_with_config(ServerConfig {
+        port: "0".into(),
+        ..config(&path)
+    })
+    .unwrap();
+    let addr = get_server_addr(id).unwrap();
+    let mut alice = TestClient::connect(format!("ws://{addr}")).await.unwrap();
+    alice.handshake().await.unwrap();
+    alice.register("alice").await.unwrap();
+
+    call_stop_server(id).unwrap();
+    let saved = load_snapshot(&path).unwrap().unwrap();
+    assert_eq!(saved.clients.len(), 1);
+    std::fs::remove_file(&path).unwrap();
+
+    // and one with nothing to save stops all the same
+    let id = call_launch_server_with_config(ServerConfig {
+        port: "0".into(),
+        ..Default::default()
+    })
+    .unwrap();
+    call_stop_server(id).unwrap();
+}
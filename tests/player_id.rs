@@ -0,0 +1,56 @@
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer},
+};
+
+async fn same_player(server: &TestServer, of: &TestClient) -> TestClient {
+    let mut client = TestClient::connect(server.url())
+        .await
+        .unwrap()
+        .with_key(of.key().clone());
+    client.handshake().await.unwrap();
+    client
+}
+
+#[tokio::test]
+async fn second_session_with_same_key_is_rejected() {
+    let server = TestServer::start().await;
+    let alice = server.client("alice").await;
+
+    let mut again = same_player(&server, &alice).await;
+    assert_eq!(
+        again.register("alice2").await.unwrap_err(),
+        "already connected"
+    );
+}
+
+#[tokio::test]
+async fn player_id_survives_reconnect() {
+    let server = TestServer::start_with_config(ServerConfig {
+        replace_duplicate_sessions: true,
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client("alice").await;
+    let first = alice
+        .expect::<OutNotifClientRegistered>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .client;
+
+    let mut again = same_player(&server, &alice).await;
+    let second_id = again.register("alice").await.unwrap();
+    assert_ne!(second_id, first.id);
+    alice.expect_closed(DEFAULT_TIMEOUT).await.unwrap();
+
+    let second = again
+        .expect::<OutNotifClientRegistered>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .client;
+    assert_eq!(second.id, second_id);
+    assert_eq!(second.player_id, first.player_id);
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope},
+};
+use tokio::time::Instant;
+
+#[tokio::test(start_paused = true)]
+async fn endless_practice_runs_until_stopped() {
+    let server = TestServer::start().await;
+    let mut solo = server.local_client("solo").await;
+
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings {
+            round_duration: 5,
+            rounds_count: 0,
+            start_countdown: 0,
+            allow_repeat_words: true,
+            practice_mode: true,
+            end_round_when_all_answered: false,
+            ..Default::default()
+        },
+    }));
+    assert_eq!(solo.request(start).await.unwrap(), "success");
+
+    solo.provide_question(QuestionInfo::default())
+        .await
+        .unwrap();
+    for _ in 0..3 {
+        solo.expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        let started = Instant::now();
+        solo.provide_question(QuestionInfo::default())
+            .await
+            .unwrap();
+
+        let answer = TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+            answer: "x".into(),
+            question_id: None,
+            game_id: None,
+        }));
+        assert_eq!(solo.request(answer).await.unwrap(), "success");
+        let chat = TransportMsg::InReqSendChat(envelope(InReqSendChat {
+            message: "hi".into(),
+        }));
+        assert_eq!(solo.request(chat).await.unwrap(), "practice mode");
+
+        solo.expect::<OutNotifRoundEnded>(Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+    }
+
+    let stop = TransportMsg::InReqStopGame(envelope(InReqStopGame {}));
+    assert_eq!(solo.request(stop).await.unwrap(), "success");
+    let stopped = solo
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(stopped.payload.reason, GameStopReason::StoppedByAdmin);
+    assert!(
+        solo.expect::<OutNotifClientAnswered>(Duration::ZERO)
+            .await
+            .is_err()
+    );
+}
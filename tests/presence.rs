@@ -0,0 +1,90 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+async fn find(client: &mut TestClient, player_id_or_name: &str) -> OutRespPlayerLocation {
+    let correlation_id = client
+        .send(TransportMsg::InReqFindPlayer(envelope(InReqFindPlayer {
+            player_id_or_name: player_id_or_name.into(),
+        })))
+        .await
+        .unwrap();
+    match client.response(correlation_id, DEFAULT_TIMEOUT).await {
+        Ok(TransportMsg::OutRespPlayerLocation(env)) => env.payload,
+        other => panic!("expected a location, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn players_are_found_by_name_or_id() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let _bob = server.local_client("Bob").await;
+    let bob_player_id = loop {
+        let client = alice
+            .expect::<OutNotifClientRegistered>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap()
+            .payload
+            .client;
+        if client.name == "Bob" {
+            break client.player_id;
+        }
+    };
+
+    let in_room = OutRespPlayerLocation {
+        found: true,
+        room_name: Some("default".into()),
+        in_game: false,
+    };
+    assert_eq!(find(&mut alice, " bob ").await, in_room);
+    assert_eq!(find(&mut alice, &bob_player_id).await, in_room);
+    assert_eq!(
+        find(&mut alice, "carol").await,
+        OutRespPlayerLocation::default()
+    );
+
+    let subscribe = TransportMsg::InReqSubscribeRoomList(envelope(InReqSubscribeRoomList {}));
+    assert_eq!(alice.request(subscribe).await.unwrap(), "success");
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings::default(),
+    }));
+    assert_eq!(alice.request(start).await.unwrap(), "success");
+    loop {
+        let change = alice
+            .expect::<OutNotifRoomListChanged>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap()
+            .payload
+            .change;
+        if let RoomListChange::Updated { room } = change
+            && room.is_game_running
+        {
+            break;
+        }
+    }
+    assert!(find(&mut alice, "bob").await.in_game);
+}
+
+#[tokio::test]
+async fn hidden_players_are_not_found() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    let leave = TransportMsg::InReqLeaveRoom(envelope(InReqLeaveRoom {}));
+    assert_eq!(bob.request(leave).await.unwrap(), "success");
+    let lobby = find(&mut alice, "bob").await;
+    assert!(lobby.found);
+    assert_eq!(lobby.room_name, None);
+
+    let hide = TransportMsg::InReqSetPresenceVisibility(envelope(InReqSetPresenceVisibility {
+        visible: false,
+    }));
+    assert_eq!(bob.request(hide).await.unwrap(), "success");
+    assert_eq!(
+        find(&mut alice, "bob").await,
+        OutRespPlayerLocation::default()
+    );
+}
@@ -0,0 +1,486 @@
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    game_actor::collect_server_stats,
+    replay::round_seed,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+fn question(word: &str, reading: &str) -> QuestionInfo {
+    QuestionInfo {
+        word_info: WordInfo {
+            word: word.into(),
+            meanings: Vec::new(),
+            readings: vec![ReadingWithParts {
+                reading: reading.into(),
+                parts: Vec::new(),
+            }],
+        },
+        font_name: "test".into(),
+        question_type: QuestionType::ReadingOfWord,
+        frequency: None,
+    }
+}
+
+fn settings(rounds_count: u64) -> GameSettings {
+    GameSettings {
+        round_duration: 30,
+        rounds_count,
+        start_countdown: 0,
+        ..Default::default()
+    }
+}
+
+async fn provide_question(admin: &mut TestClient, word: &str, reading: &str) {
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: question(word, reading),
+                question_svg: format!("<svg>{word}</svg>"),
+            },
+        }))
+        .await
+        .unwrap();
+}
+
+async fn answer(client: &mut TestClient, answer: &str) -> String {
+    let correlation_id = client
+        .send(TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+            answer: answer.into(),
+            question_id: None,
+            game_id: None,
+        })))
+        .await
+        .unwrap();
+    client
+        .status(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+}
+
+async fn start_game(admin: &mut TestClient, rounds_count: u64) {
+    let correlation_id = admin
+        .send(TransportMsg::InReqStartGame(envelope(InReqStartGame {
+            game_settings: settings(rounds_count),
+        })))
+        .await
+        .unwrap();
+    assert_eq!(
+        admin.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+}
+
+#[tokio::test]
+async fn registration_makes_first_client_admin() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+    let alice_id = alice.id.clone().unwrap();
+
+    let notif = alice
+        .expect::<OutNotifClientRegistered>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(notif.payload.client.id, alice_id);
+    assert!(notif.payload.client.is_admin);
+
+    let mut bob = server.client("bob").await;
+    let correlation_id = bob
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespClientList(list) =
+        bob.response(correlation_id, DEFAULT_TIMEOUT).await.unwrap()
+    else {
+        panic!("expected client list");
+    };
+
+    assert_eq!(list.payload.clients.len(), 2);
+    let admins: Vec<_> = list
+        .payload
+        .clients
+        .iter()
+        .filter(|c| c.is_admin)
+        .map(|c| c.id.clone())
+        .collect();
+    assert_eq!(admins, vec![alice_id]);
+}
+
+#[tokio::test]
+async fn duplicate_name_is_rejected() {
+    let server = TestServer::start().await;
+    let _alice = server.client("alice").await;
+
+    let mut other = TestClient::connect(&server.url()).await.unwrap();
+    other.handshake().await.unwrap();
+    assert_eq!(other.register("Alice").await.unwrap_err(), "name taken");
+}
+
+#[tokio::test]
+async fn two_round_game_with_two_clients() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+    let mut bob = server.client("bob").await;
+
+    start_game(&mut alice, 2).await;
+    bob.expect::<OutNotifGameStarted>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    provide_question(&mut alice, "木", "き").await;
+    bob.expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    // the next question is requested as soon as the round starts
+    provide_question(&mut alice, "水", "みず").await;
+    assert_eq!(answer(&mut alice, "き").await, "success");
+    assert_eq!(answer(&mut bob, "ぼく").await, "success");
+
+    let ended = bob
+        .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(ended.payload.answers.len(), 2);
+    assert_eq!(ended.payload.accepted_readings, vec!["き"]);
+    assert_eq!(ended.payload.primary_reading, "き");
+    let alice_id = alice.id.clone().unwrap();
+    for a in &ended.payload.answers {
+        assert_eq!(a.is_correct, a.id == alice_id);
+    }
+
+    bob.expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(answer(&mut alice, "みず").await, "success");
+    assert_eq!(answer(&mut bob, "みず").await, "success");
+
+    let stopped = bob
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(stopped.payload.question.word_info.word, "水");
+    assert!(stopped.payload.answers.iter().all(|a| a.is_correct));
+    assert_eq!(stopped.payload.reason, GameStopReason::Completed);
+    assert_eq!(stopped.payload.winner_id, Some(alice_id));
+    alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn admin_is_handed_over_on_disconnect() {
+    let server = TestServer::start().await;
+    let alice = server.client("alice").await;
+    let alice_id = alice.id.clone().unwrap();
+    let mut bob = server.client("bob").await;
+    let bob_id = bob.id.clone().unwrap();
+
+    alice.close().await;
+
+    let gone = bob
+        .expect::<OutNotifClientDisconnected>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(gone.payload.id, alice_id);
+    assert_eq!(gone.payload.reason, DisconnectReason::ConnectionLost);
+    let admin = bob
+        .expect::<OutNotifAdminMade>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(admin.payload.id, bob_id);
+}
+
+#[tokio::test]
+async fn leaving_the_room_keeps_the_client_registered() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+    let alice_id = alice.id.clone().unwrap();
+    let mut bob = server.client("bob").await;
+    let bob_id = bob.id.clone().unwrap();
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqLeaveRoom(envelope(InReqLeaveRoom {})))
+        .await
+        .unwrap();
+    assert_eq!(
+        alice.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+
+    let gone = bob
+        .expect::<OutNotifClientDisconnected>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(gone.payload.id, alice_id);
+    assert_eq!(gone.payload.reason, DisconnectReason::Left);
+    let admin = bob
+        .expect::<OutNotifAdminMade>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(admin.payload.id, bob_id);
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    assert_eq!(
+        alice.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "no room"
+    );
+
+    // joining again needs no new handshake
+    assert_eq!(alice.register("alice").await.unwrap(), alice_id);
+    // bob's own registration notice is still queued ahead of alice's
+    loop {
+        let joined = bob
+            .expect::<OutNotifClientRegistered>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        if joined.payload.client.id == alice_id {
+            break;
+        }
+    }
+}
+
+#[tokio::test]
+async fn second_answer_is_rejected() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+    let mut bob = server.client("bob").await;
+
+    start_game(&mut alice, 1).await;
+    provide_question(&mut alice, "火", "ひ").await;
+    bob.expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    assert_eq!(answer(&mut alice, "か").await, "success");
+    assert_eq!(answer(&mut alice, "ひ").await, "already answered");
+}
+
+#[tokio::test]
+async fn oversized_message_closes_only_that_connection() {
+    let server = TestServer::start_with_config(ServerConfig {
+        max_message_size: 1024,
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client("alice").await;
+    let mut bob = server.client("bob").await;
+
+    bob.send_raw("x".repeat(2048)).await.unwrap();
+    let error = bob.expect::<OutRespError>(DEFAULT_TIMEOUT).await.unwrap();
+    assert_eq!(error.payload.error, "message too large");
+    bob.expect_closed(DEFAULT_TIMEOUT).await.unwrap();
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqSendChat(envelope(InReqSendChat {
+            message: "still here".into(),
+        })))
+        .await
+        .unwrap();
+    assert_eq!(
+        alice.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+}
+
+#[tokio::test]
+async fn repeated_registration_returns_same_id() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+    let first_id = alice.id.clone().unwrap();
+
+    assert_eq!(alice.register("alice").await.unwrap(), first_id);
+    assert_eq!(alice.register("alicia").await.unwrap(), first_id);
+    let renamed = alice
+        .expect::<OutNotifClientRenamed>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(renamed.payload.name, "alicia");
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespClientList(list) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected client list");
+    };
+    assert_eq!(list.payload.clients.len(), 1);
+    assert_eq!(list.payload.clients[0].id, first_id);
+    assert_eq!(list.payload.clients[0].name, "alicia");
+}
+
+#[tokio::test]
+async fn seeded_game_exposes_round_seeds_and_replay() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqStartGame(envelope(InReqStartGame {
+            game_settings: GameSettings {
+                seed: Some(7),
+                ..settings(1)
+            },
+        })))
+        .await
+        .unwrap();
+    assert_eq!(
+        alice.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+    let started = alice
+        .expect::<OutNotifGameStarted>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(started.payload.game_settings.seed, Some(7));
+
+    let req = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(req.payload.round_seed, Some(round_seed(7, 0)));
+    alice
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: question("木", "き"),
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    assert_eq!(answer(&mut alice, "き").await, "success");
+    alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqGameReplay(envelope(InReqGameReplay {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespGameReplay(resp) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected game replay");
+    };
+    let replay = resp.payload.replay;
+    assert_eq!(replay.seed, Some(7));
+    assert_eq!(replay.rounds.len(), 1);
+    assert_eq!(replay.rounds[0].question.word_info.word, "木");
+    assert_eq!(replay.stop_reason, Some(GameStopReason::Completed));
+}
+
+#[tokio::test]
+async fn answer_for_previous_question_is_stale() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+    let mut bob = server.client("bob").await;
+
+    start_game(&mut alice, 2).await;
+    provide_question(&mut alice, "木", "き").await;
+    let first = bob
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    provide_question(&mut alice, "水", "みず").await;
+    assert_eq!(answer(&mut alice, "き").await, "success");
+    assert_eq!(answer(&mut bob, "き").await, "success");
+
+    let second = bob
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_ne!(first.payload.question_id, second.payload.question_id);
+
+    let correlation_id = bob
+        .send(TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+            answer: "き".into(),
+            question_id: Some(first.payload.question_id),
+            game_id: None,
+        })))
+        .await
+        .unwrap();
+    assert_eq!(
+        bob.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "stale question"
+    );
+
+    let correlation_id = bob
+        .send(TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+            answer: "みず".into(),
+            question_id: Some(second.payload.question_id),
+            game_id: None,
+        })))
+        .await
+        .unwrap();
+    assert_eq!(
+        bob.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+}
+
+#[tokio::test]
+async fn binary_frames_are_reported_then_disconnected() {
+    let server = TestServer::start_with_config(ServerConfig {
+        max_binary_frames: 2,
+        ..Default::default()
+    })
+    .await;
+    let mut alice = server.client("alice").await;
+
+    alice.send_binary(vec![0; 16]).await.unwrap();
+    let error = alice.expect::<OutRespError>(DEFAULT_TIMEOUT).await.unwrap();
+    assert!(
+        error.payload.error.contains("16 byte"),
+        "{}",
+        error.payload.error
+    );
+
+    alice.send_binary(vec![0; 4]).await.unwrap();
+    alice.expect::<OutRespError>(DEFAULT_TIMEOUT).await.unwrap();
+    alice.expect_closed(DEFAULT_TIMEOUT).await.unwrap();
+
+    let stats = collect_server_stats(server.game()).await.unwrap();
+    assert_eq!(stats.binary_frames, 2);
+}
+
+#[tokio::test]
+async fn client_joining_mid_round_receives_the_question() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+
+    start_game(&mut alice, 1).await;
+    provide_question(&mut alice, "日", "ひ").await;
+    alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    let mut bob = server.client("bob").await;
+    let question = bob
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    // the test client fetches images itself
+    assert_eq!(
+        question.payload.asset_size,
+        Some("<svg>日</svg>".len() as u64)
+    );
+    assert!(question.payload.remaining_ms.is_some());
+}
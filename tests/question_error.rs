@@ -0,0 +1,124 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, one_round, start_game},
+};
+use uuid::Uuid;
+
+async fn start(admin: &mut TestClient, min_frequency: u64) {
+    let start = start_game(GameSettings {
+        min_frequency,
+        ..one_round()
+    });
+    assert_eq!(admin.request(start).await.unwrap(), "success");
+}
+
+async fn fail(admin: &mut TestClient, correlation_id: Uuid, error_code: ErrorCode) {
+    admin
+        .send(TransportMsg::InRespQuestionError(TransportEnvelope {
+            correlation_id,
+            seq: None,
+            payload: InRespQuestionError {
+                error_code,
+                message: "nothing to show".into(),
+            },
+        }))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn failed_render_is_asked_again() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    start(&mut alice, 0).await;
+
+    let req = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    fail(&mut alice, req.correlation_id, ErrorCode::RenderFailed).await;
+
+    let failed = alice
+        .expect::<OutNotifQuestionFailed>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(failed.payload.error_code, ErrorCode::RenderFailed);
+    assert!(!failed.payload.relaxed);
+    let again = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_ne!(again.correlation_id, req.correlation_id);
+}
+
+#[tokio::test]
+async fn no_words_relaxes_the_frequency_bounds_once() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    start(&mut alice, 5000).await;
+
+    let req = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(req.payload.min_frequency, 5000);
+    fail(
+        &mut alice,
+        req.correlation_id,
+        ErrorCode::NoWordsMatchFilters,
+    )
+    .await;
+
+    let failed = alice
+        .expect::<OutNotifQuestionFailed>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert!(failed.payload.relaxed);
+    let relaxed = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(relaxed.payload.min_frequency, 0);
+    assert_eq!(relaxed.payload.max_frequency, None);
+
+    fail(
+        &mut alice,
+        relaxed.correlation_id,
+        ErrorCode::NoWordsMatchFilters,
+    )
+    .await;
+    let stopped = alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(stopped.payload.reason, GameStopReason::NoQuestions);
+}
+
+#[tokio::test]
+async fn no_words_without_bounds_stops_the_game() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    start(&mut alice, 0).await;
+
+    let req = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    fail(
+        &mut alice,
+        req.correlation_id,
+        ErrorCode::NoWordsMatchFilters,
+    )
+    .await;
+
+    let failed = alice
+        .expect::<OutNotifQuestionFailed>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert!(!failed.payload.relaxed);
+    let stopped = alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(stopped.payload.reason, GameStopReason::NoQuestions);
+}
@@ -0,0 +1,164 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use futures_util::future::BoxFuture;
+use kanjilab_server::{
+    ProvidedQuestion, ProviderError, QuestionProvider, ServerConfig,
+    data_types::*,
+    room_actor::{SendAnswerRequest, StartGameRequest},
+    testkit::{RoomMember, TestRoom, message_types, sample_question},
+};
+use uuid::Uuid;
+
+/// Serves 日 after `delay`, or fails with `error` when set, and keeps the
+/// requests it got.
+#[derive(Default)]
+struct Dictionary {
+    delay: Duration,
+    error: Option<ErrorCode>,
+    calls: AtomicU64,
+    requests: Mutex<Vec<(u64, OutReqQuestion)>>,
+}
+
+impl QuestionProvider for Dictionary {
+    fn next_question<'a>(
+        &'a self,
+        settings: &'a GameSettings,
+        request: &'a OutReqQuestion,
+    ) -> BoxFuture<'a, Result<ProvidedQuestion, ProviderError>> {
+        Box::pin(async move {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.requests
+                .lock()
+                .unwrap()
+                .push((settings.rounds_count, request.clone()));
+            tokio::time::sleep(self.delay).await;
+            if let Some(error_code) = self.error {
+                return Err(ProviderError {
+                    error_code,
+                    message: "dictionary unavailable".into(),
+                });
+            }
+            Ok(ProvidedQuestion {
+                question: sample_question(),
+                question_svg: "<svg/>".into(),
+            })
+        })
+    }
+}
+
+async fn start_game(provider: Arc<Dictionary>) -> (TestRoom, RoomMember, RoomMember) {
+    let room = TestRoom::start_with_provider(ServerConfig::default(), provider).await;
+    let alice = room.join("alice", false).await.unwrap();
+    let bob = room.join("bob", false).await.unwrap();
+    room.room
+        .ask(StartGameRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            game_settings: GameSettings {
+                round_duration: 30,
+                rounds_count: 2,
+                start_countdown: 0,
+                question_timeout: 2,
+                allow_repeat_words: true,
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+    (room, alice, bob)
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_provider_serves_the_questions_instead_of_the_admin() {
+    let provider = Arc::new(Dictionary::default());
+    let (room, alice, bob) = start_game(provider.clone()).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let seen_by_alice = message_types(&alice.sink.take());
+    assert!(!seen_by_alice.contains(&"OUT_REQ_question".to_string()));
+    assert!(seen_by_alice.contains(&"OUT_NOTIF_question".to_string()));
+    assert!(message_types(&bob.sink.take()).contains(&"OUT_NOTIF_question".to_string()));
+
+    // the round's question and the next one's, for the game's settings
+    let requests = provider.requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|(rounds_count, _)| *rounds_count == 2));
+
+    // the admin made no question, so doesn't sit the round out
+    room.room
+        .ask(SendAnswerRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            answer: "ひ".into(),
+            question_id: None,
+            game_id: None,
+        })
+        .await
+        .unwrap();
+    let Some(TransportMsg::OutRespStatus(status)) = alice
+        .sink
+        .take()
+        .into_iter()
+        .find(|msg| matches!(msg, TransportMsg::OutRespStatus(_)))
+    else {
+        panic!("expected a status");
+    };
+    assert_eq!(status.payload.status, "success");
+}
+
+#[tokio::test(start_paused = true)]
+async fn provider_errors_fail_the_question_like_the_admins() {
+    let provider = Arc::new(Dictionary {
+        error: Some(ErrorCode::RenderFailed),
+        ..Default::default()
+    });
+    let (_room, _alice, bob) = start_game(provider.clone()).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let msgs = bob.sink.take();
+    let failures = message_types(&msgs)
+        .iter()
+        .filter(|t| *t == "OUT_NOTIF_questionFailed")
+        .count();
+    // asked once and then again for each retry
+    assert_eq!(failures as u64, provider.calls.load(Ordering::Relaxed));
+    assert!(failures > 1);
+    let Some(TransportMsg::OutNotifGameStopped(stopped)) = msgs.last() else {
+        panic!("expected the game to stop");
+    };
+    assert_eq!(stopped.payload.reason, GameStopReason::NoQuestions);
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_question_past_the_timeout_is_dropped_and_asked_again() {
+    let provider = Arc::new(Dictionary {
+        delay: Duration::from_secs(3),
+        ..Default::default()
+    });
+    let (_room, _alice, bob) = start_game(provider.clone()).await;
+
+    tokio::time::sleep(Duration::from_millis(2010)).await;
+    let msgs = bob.sink.take();
+    assert!(!message_types(&msgs).contains(&"OUT_NOTIF_question".to_string()));
+    let Some(TransportMsg::OutNotifQuestionFailed(failed)) = msgs.last() else {
+        panic!("expected the question to fail");
+    };
+    assert_eq!(failed.payload.error_code, ErrorCode::TimedOut);
+    // asked once more
+    assert_eq!(provider.calls.load(Ordering::Relaxed), 2);
+
+    // every retry times out as well, until the game gives up
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    let msgs = bob.sink.take();
+    assert!(!message_types(&msgs).contains(&"OUT_NOTIF_question".to_string()));
+    let Some(TransportMsg::OutNotifGameStopped(stopped)) = msgs.last() else {
+        panic!("expected the game to stop");
+    };
+    assert_eq!(stopped.payload.reason, GameStopReason::NoQuestions);
+}
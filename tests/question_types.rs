@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope, start_game},
+    tools::{accepted_answers, is_correct_answer},
+};
+
+fn word(question_type: QuestionType) -> QuestionInfo {
+    QuestionInfo {
+        word_info: WordInfo {
+            word: "日".into(),
+            meanings: vec![vec![vec!["Sun".into(), "day".into()]]],
+            readings: vec![
+                ReadingWithParts {
+                    reading: "ひ".into(),
+                    parts: Vec::new(),
+                },
+                ReadingWithParts {
+                    reading: "にち".into(),
+                    parts: Vec::new(),
+                },
+            ],
+        },
+        font_name: String::new(),
+        question_type,
+        frequency: None,
+    }
+}
+
+fn start(game_settings: GameSettings) -> TransportMsg {
+    start_game(GameSettings {
+        round_duration: 30,
+        rounds_count: 2,
+        start_countdown: 0,
+        ..game_settings
+    })
+}
+
+#[test]
+fn each_type_accepts_its_own_answers() {
+    assert_eq!(
+        accepted_answers(&word(QuestionType::ReadingOfWord)),
+        ["ひ", "にち"]
+    );
+    assert_eq!(
+        accepted_answers(&word(QuestionType::WordFromReading)),
+        ["日"]
+    );
+    assert_eq!(
+        accepted_answers(&word(QuestionType::MeaningOfWord)),
+        ["sun", "day"]
+    );
+
+    let reading = word(QuestionType::ReadingOfWord);
+    assert!(is_correct_answer(&reading, "ニチ"));
+    assert!(!is_correct_answer(&reading, "sun"));
+
+    let meaning = word(QuestionType::MeaningOfWord);
+    assert!(is_correct_answer(&meaning, "  SUN "));
+    assert!(!is_correct_answer(&meaning, "ひ"));
+}
+
+#[tokio::test]
+async fn answers_are_checked_against_the_requested_type() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+
+    let settings = GameSettings {
+        question_type: QuestionType::MeaningOfWord,
+        ..Default::default()
+    };
+    assert_eq!(alice.request(start(settings)).await.unwrap(), "success");
+
+    let req = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(req.payload.question_type, QuestionType::MeaningOfWord);
+    // the admin's client labels it wrongly, the server keeps what it asked for
+    alice
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: word(QuestionType::ReadingOfWord),
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    let question = alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    let answer = TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+        answer: "day".into(),
+        question_id: Some(question.payload.question_id),
+        game_id: None,
+    }));
+    assert_eq!(alice.request(answer).await.unwrap(), "success");
+    let ended = alice
+        .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert!(ended.payload.answers[0].is_correct);
+}
+
+#[tokio::test]
+async fn weights_pick_the_type_per_round() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+
+    let zero = GameSettings {
+        question_type_weights: BTreeMap::from([(QuestionType::MeaningOfWord, 0)]),
+        ..Default::default()
+    };
+    assert_eq!(
+        alice.request(start(zero)).await.unwrap(),
+        "invalid question weights"
+    );
+
+    let only_words = GameSettings {
+        question_type_weights: BTreeMap::from([
+            (QuestionType::ReadingOfWord, 0),
+            (QuestionType::WordFromReading, 1),
+        ]),
+        ..Default::default()
+    };
+    assert_eq!(alice.request(start(only_words)).await.unwrap(), "success");
+    let req = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(req.payload.question_type, QuestionType::WordFromReading);
+}
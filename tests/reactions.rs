@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope},
+};
+use uuid::Uuid;
+
+fn react(kind: ReactionTargetKind, id: Uuid, emoji: &str) -> TransportMsg {
+    TransportMsg::InReqSendReaction(envelope(InReqSendReaction {
+        target: ReactionTarget { kind, id },
+        emoji: emoji.into(),
+    }))
+}
+
+#[tokio::test]
+async fn chat_reactions_are_broadcast_and_backfilled() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+
+    let chat = TransportMsg::InReqSendChat(envelope(InReqSendChat {
+        message: "hello".into(),
+    }));
+    assert_eq!(alice.request(chat).await.unwrap(), "success");
+    let sent = alice
+        .expect::<OutNotifChatSent>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let message_id = sent.payload.message_id;
+
+    let reaction = react(ReactionTargetKind::Chat, message_id, "👏");
+    assert_eq!(alice.request(reaction).await.unwrap(), "success");
+    let notif = alice
+        .expect::<OutNotifReaction>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(notif.payload.from_id, alice.id.clone().unwrap());
+    assert_eq!(notif.payload.target.id, message_id);
+    assert_eq!(notif.payload.emoji, "👏");
+
+    let unknown = react(ReactionTargetKind::Chat, Uuid::new_v4(), "👏");
+    assert_eq!(alice.request(unknown).await.unwrap(), "unknown target");
+    let round = react(ReactionTargetKind::Round, message_id, "👏");
+    assert_eq!(alice.request(round).await.unwrap(), "unknown target");
+    let disallowed = react(ReactionTargetKind::Chat, message_id, "💩");
+    assert_eq!(
+        alice.request(disallowed).await.unwrap(),
+        "emoji not allowed"
+    );
+
+    let mut bob = server.local_client("bob").await;
+    let backfill = bob
+        .expect::<OutNotifChatSent>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(backfill.payload.message_id, message_id);
+    assert_eq!(
+        backfill.payload.reactions,
+        BTreeMap::from([("👏".to_string(), 1)])
+    );
+}
+
+#[tokio::test]
+async fn round_reactions_are_kept_in_the_replay() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings {
+            round_duration: 30,
+            rounds_count: 1,
+            start_countdown: 0,
+            ..Default::default()
+        },
+    }));
+    assert_eq!(alice.request(start).await.unwrap(), "success");
+    let req = alice
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    alice
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo::default(),
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    let question_id = alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .question_id;
+
+    let during = react(ReactionTargetKind::Round, question_id, "🔥");
+    assert_eq!(alice.request(during).await.unwrap(), "success");
+    let answer = TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+        answer: "x".into(),
+        question_id: None,
+        game_id: None,
+    }));
+    assert_eq!(alice.request(answer).await.unwrap(), "success");
+    alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let after = react(ReactionTargetKind::Round, question_id, "😭");
+    assert_eq!(alice.request(after).await.unwrap(), "success");
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqGameReplay(envelope(InReqGameReplay {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespGameReplay(resp) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected a replay");
+    };
+    let round = &resp.payload.replay.rounds[0];
+    assert_eq!(round.question_id, Some(question_id));
+    assert_eq!(
+        round.reactions,
+        BTreeMap::from([("😭".to_string(), 1), ("🔥".to_string(), 1)])
+    );
+}
@@ -0,0 +1,61 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+async fn register(client: &mut TestClient, name: &str, spectator: bool) -> OutRespClientRegistered {
+    client.handshake().await.unwrap();
+    let correlation_id = client
+        .send(TransportMsg::InReqRegisterClient(envelope(
+            InReqRegisterClient {
+                name: name.into(),
+                spectator,
+                password: None,
+            },
+        )))
+        .await
+        .unwrap();
+    match client.response(correlation_id, DEFAULT_TIMEOUT).await {
+        Ok(TransportMsg::OutRespClientRegistered(env)) => env.payload,
+        other => panic!("expected the registration, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn registration_describes_the_room() {
+    let server = TestServer::start().await;
+    let mut alice = TestClient::local(server.game()).await;
+    let registered = register(&mut alice, "alice", false).await;
+    assert_eq!(registered.role, ClientRole::Admin);
+    assert!(!registered.room.is_game_running);
+
+    let game_settings = GameSettings {
+        round_duration: 45,
+        rounds_count: 2,
+        ..Default::default()
+    };
+    let send = TransportMsg::InReqSendGameSettings(envelope(InReqSendGameSettings {
+        game_settings: game_settings.clone(),
+    }));
+    let correlation_id = alice.send(send).await.unwrap();
+    assert_eq!(
+        alice.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+
+    let mut bob = TestClient::local(server.game())
+        .await
+        .with_capabilities(&["streaks"]);
+    let registered = register(&mut bob, "bob", false).await;
+    assert_eq!(registered.role, ClientRole::Player);
+    assert_eq!(registered.capabilities, vec![Capability::Streaks]);
+    assert_eq!(registered.room.client_count, 2);
+    assert_eq!(registered.room.game_settings.round_duration, 45);
+    #[allow(deprecated)]
+    let kept = registered.game_settings;
+    assert_eq!(kept, registered.room.game_settings);
+
+    let mut carol = TestClient::local(server.game()).await;
+    let registered = register(&mut carol, "carol", true).await;
+    assert_eq!(registered.role, ClientRole::Spectator);
+}
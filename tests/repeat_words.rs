@@ -0,0 +1,85 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, start_game},
+};
+use uuid::Uuid;
+
+async fn start(admin: &mut TestClient, allow_repeat_words: bool) {
+    let start = start_game(GameSettings {
+        round_duration: 1,
+        rounds_count: 3,
+        start_countdown: 0,
+        allow_repeat_words,
+        ..Default::default()
+    });
+    assert_eq!(admin.request(start).await.unwrap(), "success");
+}
+
+/// Answers the next question request with `word`.
+async fn provide(admin: &mut TestClient, word: &str) -> (Uuid, OutReqQuestion) {
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo {
+                    word_info: WordInfo {
+                        word: word.into(),
+                        meanings: Vec::new(),
+                        readings: Vec::new(),
+                    },
+                    ..Default::default()
+                },
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    (req.correlation_id, req.payload)
+}
+
+#[tokio::test]
+async fn repeated_word_is_requested_again() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    start(&mut alice, false).await;
+
+    let (_, first) = provide(&mut alice, "日曜").await;
+    assert_eq!(first.used_word_count, 0);
+    // the prefetch for the second round is checked as well
+    let (rejected, second) = provide(&mut alice, "日曜").await;
+    assert_eq!(second.used_word_count, 1);
+    assert_eq!(
+        alice.status(rejected, DEFAULT_TIMEOUT).await.unwrap(),
+        "duplicate word"
+    );
+    provide(&mut alice, "月曜").await;
+
+    for word in ["日曜", "月曜"] {
+        let ended = alice
+            .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(ended.payload.question.word_info.word, word);
+    }
+}
+
+#[tokio::test]
+async fn repeats_can_be_allowed() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    start(&mut alice, true).await;
+
+    provide(&mut alice, "日曜").await;
+    let (accepted, _) = provide(&mut alice, "日曜").await;
+    assert!(
+        alice
+            .status(accepted, std::time::Duration::from_millis(200))
+            .await
+            .is_err()
+    );
+}
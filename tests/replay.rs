@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+use kanjilab_server::{
+    data_types::*,
+    replay::{REPLAY_VERSION, ReplayError, parse_replay, round_seed, serialize_replay},
+};
+
+fn replay() -> GameReplay {
+    GameReplay {
+        version: REPLAY_VERSION,
+        seed: Some(42),
+        game_settings: GameSettings {
+            rounds_count: 1,
+            seed: Some(42),
+            ..Default::default()
+        },
+        rounds: vec![RoundRecord {
+            question: QuestionInfo::default(),
+            answers: Vec::new(),
+            aborted: false,
+            question_id: None,
+            reactions: BTreeMap::new(),
+            tier: None,
+        }],
+        stop_reason: Some(GameStopReason::Completed),
+    }
+}
+
+#[test]
+fn replay_round_trips() {
+    let json = serialize_replay(&replay()).unwrap();
+    assert_eq!(parse_replay(&json).unwrap(), replay());
+}
+
+#[test]
+fn newer_replay_version_is_rejected() {
+    let mut value = serde_json::to_value(replay()).unwrap();
+    value["version"] = (REPLAY_VERSION as u64 + 1).into();
+    assert!(matches!(
+        parse_replay(&value.to_string()),
+        Err(ReplayError::UnsupportedVersion(_))
+    ));
+}
+
+#[test]
+fn round_seeds_are_stable_and_distinct() {
+    assert_eq!(round_seed(42, 3), round_seed(42, 3));
+    assert_ne!(round_seed(42, 0), round_seed(42, 1));
+    assert_ne!(round_seed(42, 0), round_seed(43, 0));
+}
@@ -0,0 +1,450 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    room_actor::{
+        LeaveRoomRequest, ProvideQuestionResponse, RematchRequest, SendAnswerRequest,
+        StartGameRequest,
+    },
+    testkit::{RoomMember, TestRoom, message_types, sample_question},
+};
+use uuid::Uuid;
+
+async fn room_with(names: &[&str]) -> (TestRoom, Vec<RoomMember>) {
+    let room = TestRoom::start(ServerConfig::default()).await;
+    let mut members = Vec::new();
+    for name in names {
+        members.push(room.join(name, false).await.unwrap());
+    }
+    for member in &members {
+        member.sink.take();
+    }
+    (room, members)
+}
+
+fn settings(rounds_count: u64) -> GameSettings {
+    GameSettings {
+        round_duration: 30,
+        rounds_count,
+        start_countdown: 0,
+        allow_repeat_words: true,
+        ..Default::default()
+    }
+}
+
+async fn start_game(room: &TestRoom, admin: &RoomMember, game_settings: GameSettings) {
+    room.room
+        .ask(StartGameRequest {
+            requester: admin.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            game_settings,
+        })
+        .await
+        .unwrap();
+}
+
+/// Answers every question request the admin has received so far.
+async fn provide_questions(room: &TestRoom, admin: &RoomMember, sent: &[TransportMsg]) {
+    for msg in sent {
+        if let TransportMsg::OutReqQuestion(req) = msg {
+            room.room
+                .ask(ProvideQuestionResponse {
+                    requester: admin.session.clone(),
+                    correlation_id: req.correlation_id,
+                    question_info: sample_question(),
+                    question_svg: "<svg/>".into(),
+                })
+                .await
+                .unwrap();
+        }
+    }
+}
+
+async fn answer(room: &TestRoom, member: &RoomMember, answer: &str) {
+    room.room
+        .ask(SendAnswerRequest {
+            requester: member.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            answer: answer.into(),
+            question_id: None,
+            game_id: None,
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn round_ends_early_when_everyone_answered() {
+    let (room, members) = room_with(&["alice", "bob"]).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+
+    start_game(&room, alice, settings(2)).await;
+    provide_questions(&room, alice, &alice.sink.take()).await;
+    provide_questions(&room, alice, &alice.sink.take()).await;
+    answer(&room, alice, "ひ").await;
+    answer(&room, bob, "x").await;
+
+    let sent = bob.sink.take();
+    assert_eq!(
+        message_types(&sent),
+        [
+            "OUT_NOTIF_gameStarted",
+            "OUT_NOTIF_question",
+            "OUT_NOTIF_clientAnswered",
+            "OUT_RESP_status",
+            "OUT_NOTIF_clientAnswered",
+            "OUT_NOTIF_answerFeedback",
+            "OUT_NOTIF_roundEnded",
+            "OUT_NOTIF_question",
+        ]
+    );
+    let TransportMsg::OutNotifRoundEnded(ended) = &sent[6] else {
+        unreachable!()
+    };
+    let correct: Vec<_> = ended.payload.answers.iter().map(|a| a.is_correct).collect();
+    assert_eq!(correct, [true, false]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn missing_answers_are_filled_in_when_the_round_times_out() {
+    let (room, members) = room_with(&["alice", "bob"]).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+
+    start_game(&room, alice, settings(1)).await;
+    provide_questions(&room, alice, &alice.sink.take()).await;
+    answer(&room, alice, "ひ").await;
+    tokio::time::sleep(Duration::from_secs(31)).await;
+
+    let sent = bob.sink.take();
+    assert_eq!(
+        message_types(&sent),
+        [
+            "OUT_NOTIF_gameStarted",
+            "OUT_NOTIF_question",
+            "OUT_NOTIF_clientAnswered",
+            "OUT_NOTIF_roundTimeWarning",
+            "OUT_NOTIF_roundTimeWarning",
+            "OUT_NOTIF_roundTimeWarning",
+            "OUT_NOTIF_gameStopped",
+        ]
+    );
+    let TransportMsg::OutNotifGameStopped(stopped) = &sent[6] else {
+        unreachable!()
+    };
+    let missing = AnswerInfo {
+        id: bob.uuid.to_string(),
+        answer: String::new(),
+        is_correct: false,
+        answer_time: 30_000,
+        kind: AnswerKind::TimedOut,
+        sequence: None,
+    };
+    assert_eq!(stopped.payload.answers[1], missing);
+    assert_eq!(stopped.payload.winner_id, Some(alice.uuid.to_string()));
+}
+
+#[tokio::test(start_paused = true)]
+async fn admin_is_handed_over_when_the_admin_leaves() {
+    let (_room, members) = room_with(&["alice", "bob"]).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+
+    alice.session.kill();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let sent = bob.sink.take();
+    assert_eq!(
+        message_types(&sent),
+        ["OUT_NOTIF_clientDisconnected", "OUT_NOTIF_adminMade"]
+    );
+    let TransportMsg::OutNotifAdminMade(made) = &sent[1] else {
+        unreachable!()
+    };
+    assert_eq!(made.payload.id, bob.uuid.to_string());
+}
+
+#[tokio::test(start_paused = true)]
+async fn game_survives_the_admin_dropping_between_rounds() {
+    let (room, members) = room_with(&["alice", "bob", "carol"]).await;
+    let [alice, bob, carol] = members.as_slice() else {
+        unreachable!()
+    };
+
+    start_game(&room, alice, settings(3)).await;
+    provide_questions(&room, alice, &alice.sink.take()).await;
+    tokio::time::sleep(Duration::from_secs(31)).await;
+
+    // the next question was requested from alice, who never answers it
+    alice.session.kill();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let mut seen = [bob.sink.take(), carol.sink.take()];
+    let made = seen[0].iter().find_map(|msg| match msg {
+        TransportMsg::OutNotifAdminMade(made) => Some(made.payload.id.clone()),
+        _ => None,
+    });
+    // promotion picks any remaining player
+    let [bob_seen, carol_seen] = &mut seen;
+    let (admin, other, sent, other_seen) = if made == Some(bob.uuid.to_string()) {
+        (bob, carol, bob_seen, carol_seen)
+    } else {
+        (carol, bob, carol_seen, bob_seen)
+    };
+    assert_eq!(
+        message_types(sent).last().map(String::as_str),
+        Some("OUT_REQ_question")
+    );
+
+    provide_questions(&room, admin, sent).await;
+    for _ in 0..2 {
+        tokio::time::sleep(Duration::from_secs(31)).await;
+        provide_questions(&room, admin, &admin.sink.take()).await;
+    }
+
+    let mut sent = std::mem::take(other_seen);
+    sent.extend(other.sink.take());
+    let rounds = sent
+        .iter()
+        .filter(|msg| matches!(msg, TransportMsg::OutNotifQuestion(_)))
+        .count();
+    assert_eq!(rounds, 3);
+    let Some(TransportMsg::OutNotifGameStopped(stopped)) = sent.last() else {
+        panic!("expected game end, got {:?}", message_types(&sent));
+    };
+    assert_eq!(stopped.payload.reason, GameStopReason::Completed);
+}
+
+#[tokio::test(start_paused = true)]
+async fn question_from_a_provider_who_left_is_dropped() {
+    let (room, members) = room_with(&["alice", "bob"]).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+
+    start_game(&room, alice, settings(2)).await;
+    let requested = alice.sink.take();
+    room.room
+        .ask(LeaveRoomRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+    // the answer to the request alice got before leaving
+    provide_questions(&room, alice, &requested).await;
+    assert!(room.room.is_alive());
+
+    let sent = bob.sink.take();
+    let types = message_types(&sent);
+    assert!(
+        !types.iter().any(|t| t == "OUT_NOTIF_question"),
+        "{types:?}"
+    );
+    assert_eq!(types.last().map(String::as_str), Some("OUT_REQ_question"));
+
+    provide_questions(&room, bob, &sent).await;
+    let types = message_types(&bob.sink.take());
+    assert!(types.iter().any(|t| t == "OUT_NOTIF_question"), "{types:?}");
+}
+
+#[tokio::test(start_paused = true)]
+async fn late_joiner_sees_the_current_question_without_a_penalty() {
+    let (room, members) = room_with(&["alice"]).await;
+    let [alice] = members.as_slice() else {
+        unreachable!()
+    };
+
+    start_game(&room, alice, settings(1)).await;
+    provide_questions(&room, alice, &alice.sink.take()).await;
+    tokio::time::sleep(Duration::from_secs(10)).await;
+
+    let carol = room.join("carol", false).await.unwrap();
+    let sent = carol.sink.take();
+    let Some(TransportMsg::OutNotifQuestion(question)) = sent.last() else {
+        panic!(
+            "expected the current question, got {:?}",
+            message_types(&sent)
+        );
+    };
+    assert_eq!(question.payload.remaining_ms, Some(20_000));
+
+    tokio::time::sleep(Duration::from_secs(21)).await;
+    let sent = carol.sink.take();
+    let Some(TransportMsg::OutNotifGameStopped(stopped)) = sent.last() else {
+        panic!("expected game end, got {:?}", message_types(&sent));
+    };
+    let answered: Vec<_> = stopped
+        .payload
+        .answers
+        .iter()
+        .map(|a| a.id.clone())
+        .collect();
+    assert_eq!(answered, [alice.uuid.to_string()]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn late_joiners_and_spectators_do_not_hold_up_the_round() {
+    let (room, members) = room_with(&["alice", "bob"]).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+    let _viewer = room.join("viewer", true).await.unwrap();
+
+    start_game(&room, alice, settings(2)).await;
+    provide_questions(&room, alice, &alice.sink.take()).await;
+    provide_questions(&room, alice, &alice.sink.take()).await;
+    let _carol = room.join("carol", false).await.unwrap();
+    answer(&room, alice, "ひ").await;
+    answer(&room, bob, "ひ").await;
+
+    let types = message_types(&bob.sink.take());
+    assert!(
+        types.iter().any(|t| t == "OUT_NOTIF_roundEnded"),
+        "{types:?}"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn live_answer_stats_count_the_awaited_players() {
+    let (room, members) = room_with(&["alice", "bob"]).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+    let game_settings = GameSettings {
+        live_answer_stats: true,
+        allow_answer_change: true,
+        ..settings(1)
+    };
+    start_game(&room, alice, game_settings).await;
+    provide_questions(&room, alice, &alice.sink.take()).await;
+    let carol = room.join("carol", false).await.unwrap();
+    bob.sink.take();
+
+    let last_counts = || {
+        let Some(TransportMsg::OutNotifClientAnswered(answered)) = bob.sink.take().pop() else {
+            panic!("expected clientAnswered");
+        };
+        let payload = answered.payload;
+        (
+            payload.answered_count.unwrap(),
+            payload.correct_count.unwrap(),
+            payload.total_players.unwrap(),
+        )
+    };
+    answer(&room, &carol, "ひ").await;
+    assert_eq!(last_counts(), (0, 0, 2));
+    answer(&room, alice, "か").await;
+    assert_eq!(last_counts(), (1, 0, 2));
+    // a changed answer is counted again
+    answer(&room, alice, "ひ").await;
+    assert_eq!(last_counts(), (1, 1, 2));
+}
+
+#[tokio::test(start_paused = true)]
+async fn round_ends_when_the_last_awaited_player_leaves() {
+    let (room, members) = room_with(&["alice", "bob"]).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+
+    start_game(&room, alice, settings(1)).await;
+    provide_questions(&room, alice, &alice.sink.take()).await;
+    answer(&room, alice, "ひ").await;
+    room.room
+        .ask(LeaveRoomRequest {
+            requester: bob.session.clone(),
+            correlation_id: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+
+    let sent = alice.sink.take();
+    let Some(TransportMsg::OutNotifGameStopped(stopped)) = sent.last() else {
+        panic!("expected game end, got {:?}", message_types(&sent));
+    };
+    assert_eq!(stopped.payload.answers.len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn locked_room_rejects_joins_during_a_game() {
+    let (room, members) = room_with(&["alice"]).await;
+    let [alice] = members.as_slice() else {
+        unreachable!()
+    };
+
+    let game_settings = GameSettings {
+        lock_room_during_game: true,
+        ..settings(1)
+    };
+    start_game(&room, alice, game_settings).await;
+
+    assert_eq!(
+        room.join("carol", false).await.err(),
+        Some(JoinRejection::GameInProgress)
+    );
+}
+
+fn last_status(sent: &[TransportMsg]) -> Option<String> {
+    sent.iter().rev().find_map(|msg| match msg {
+        TransportMsg::OutRespStatus(env) => Some(env.payload.status.clone()),
+        _ => None,
+    })
+}
+
+async fn rematch(room: &TestRoom, admin: &RoomMember, shuffle: Option<GameSettingsOverride>) {
+    room.room
+        .ask(RematchRequest {
+            requester: admin.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            shuffle_settings: shuffle,
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn rematch_restarts_with_previous_settings() {
+    let (room, members) = room_with(&["alice", "bob"]).await;
+    let [alice, bob] = members.as_slice() else {
+        unreachable!()
+    };
+
+    rematch(&room, alice, None).await;
+    assert_eq!(
+        last_status(&alice.sink.take()).as_deref(),
+        Some("no previous game")
+    );
+
+    start_game(&room, alice, settings(1)).await;
+    provide_questions(&room, alice, &alice.sink.take()).await;
+    rematch(&room, alice, None).await;
+    assert_eq!(
+        last_status(&alice.sink.take()).as_deref(),
+        Some("already running")
+    );
+    tokio::time::sleep(Duration::from_secs(31)).await;
+    bob.sink.take();
+
+    let shuffle = GameSettingsOverride {
+        rounds_count: Some(3),
+        ..Default::default()
+    };
+    rematch(&room, alice, Some(shuffle)).await;
+    let sent = bob.sink.take();
+    let Some(TransportMsg::OutNotifGameStarted(started)) = sent.first() else {
+        panic!("expected game start, got {:?}", message_types(&sent));
+    };
+    assert!(started.payload.rematch);
+    assert_eq!(
+        started.payload.game_settings,
+        GameSettings {
+            rounds_count: 3,
+            ..settings(1)
+        }
+    );
+}
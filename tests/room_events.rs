@@ -0,0 +1,99 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+async fn room_events(client: &mut TestClient) -> Vec<RoomEventKind> {
+    let correlation_id = client
+        .send(TransportMsg::InReqRoomEvents(envelope(InReqRoomEvents {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespRoomEvents(resp) = client
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected the room events");
+    };
+    resp.payload.events.into_iter().map(|e| e.kind).collect()
+}
+
+#[tokio::test]
+async fn room_events_record_moderation_history() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+    let alice_id = alice.id.clone().unwrap();
+    let bob_id = bob.id.clone().unwrap();
+
+    let settings = TransportMsg::InReqSendGameSettings(envelope(InReqSendGameSettings {
+        game_settings: GameSettings::default(),
+    }));
+    assert_eq!(alice.request(settings).await.unwrap(), "success");
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings::default(),
+    }));
+    assert_eq!(alice.request(start).await.unwrap(), "success");
+    let stop = TransportMsg::InReqStopGame(envelope(InReqStopGame {}));
+    assert_eq!(alice.request(stop).await.unwrap(), "success");
+
+    let leave = TransportMsg::InReqLeaveRoom(envelope(InReqLeaveRoom {}));
+    assert_eq!(bob.request(leave).await.unwrap(), "success");
+
+    assert_eq!(
+        room_events(&mut alice).await,
+        vec![
+            RoomEventKind::ClientJoined {
+                id: alice_id.clone(),
+                name: "alice".into(),
+            },
+            RoomEventKind::AdminMade {
+                id: alice_id.clone(),
+            },
+            RoomEventKind::ClientJoined {
+                id: bob_id.clone(),
+                name: "bob".into(),
+            },
+            RoomEventKind::SettingsChanged {
+                by: alice_id.clone(),
+            },
+            RoomEventKind::GameStarted {
+                by: alice_id.clone(),
+            },
+            RoomEventKind::GameStopped {
+                reason: GameStopReason::StoppedByAdmin,
+            },
+            RoomEventKind::ClientLeft {
+                id: bob_id,
+                reason: DisconnectReason::Left,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn room_events_are_admin_only() {
+    let server = TestServer::start().await;
+    let _alice = server.local_client("alice").await;
+    let mut bob = server.local_client("bob").await;
+
+    let events = TransportMsg::InReqRoomEvents(envelope(InReqRoomEvents {}));
+    assert_eq!(bob.request(events).await.unwrap(), "not admin");
+}
+
+#[test]
+fn room_event_is_tagged_by_kind() {
+    let event = RoomEvent {
+        at: 1,
+        kind: RoomEventKind::GameStopped {
+            reason: GameStopReason::Completed,
+        },
+    };
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        serde_json::json!({
+            "at": 1,
+            "kind": { "type": "game_stopped", "reason": "completed" },
+        })
+    );
+}
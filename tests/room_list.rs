@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    data_types::*,
+    game_actor::GetServerStats,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+fn subscribe() -> TransportMsg {
+    TransportMsg::InReqSubscribeRoomList(envelope(InReqSubscribeRoomList {}))
+}
+
+async fn next_change(client: &mut TestClient) -> RoomListChange {
+    client
+        .expect::<OutNotifRoomListChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .change
+}
+
+/// A client that left the room and watches the room list from the lobby.
+async fn lobby_client(server: &TestServer, name: &str) -> TestClient {
+    let mut client = server.local_client(name).await;
+    let leave = TransportMsg::InReqLeaveRoom(envelope(InReqLeaveRoom {}));
+    assert_eq!(client.request(leave).await.unwrap(), "success");
+    assert_eq!(client.request(subscribe()).await.unwrap(), "success");
+    client
+}
+
+#[tokio::test]
+async fn subscribers_follow_the_room_from_the_lobby() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let mut bob = lobby_client(&server, "bob").await;
+
+    let RoomListChange::Opened { room } = next_change(&mut bob).await else {
+        panic!("expected the current room first");
+    };
+    assert_eq!(room.name, "default");
+    assert_eq!(room.player_count, 1);
+    assert!(!room.is_game_running);
+
+    let _carol = server.local_client("carol").await;
+    let RoomListChange::Updated { room: joined } = next_change(&mut bob).await else {
+        panic!("expected an update");
+    };
+    assert_eq!(joined.id, room.id);
+    assert_eq!(joined.player_count, 2);
+
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings::default(),
+    }));
+    assert_eq!(alice.request(start).await.unwrap(), "success");
+    let RoomListChange::Updated { room: started } = next_change(&mut bob).await else {
+        panic!("expected an update");
+    };
+    assert!(started.is_game_running);
+
+    let unsubscribe = TransportMsg::InReqUnsubscribeRoomList(envelope(InReqUnsubscribeRoomList {}));
+    assert_eq!(bob.request(unsubscribe).await.unwrap(), "success");
+    let stop = TransportMsg::InReqStopGame(envelope(InReqStopGame {}));
+    assert_eq!(alice.request(stop).await.unwrap(), "success");
+    assert!(
+        bob.expect::<OutNotifRoomListChanged>(Duration::from_millis(200))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn crashed_room_is_closed_and_reopened() {
+    let server = TestServer::start().await;
+    let mut bob = lobby_client(&server, "bob").await;
+    let RoomListChange::Opened { room: old } = next_change(&mut bob).await else {
+        panic!("expected the current room first");
+    };
+
+    let rooms = server.game().ask(GetServerStats).await.unwrap().rooms;
+    rooms[0].kill();
+
+    assert_eq!(
+        next_change(&mut bob).await,
+        RoomListChange::Closed { id: old.id.clone() }
+    );
+    let RoomListChange::Opened { room } = next_change(&mut bob).await else {
+        panic!("expected the new room");
+    };
+    assert_ne!(room.id, old.id);
+}
@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, start_game},
+};
+
+fn start(start_countdown: u64) -> TransportMsg {
+    start_game(GameSettings {
+        round_duration: 10,
+        rounds_count: 1,
+        start_countdown,
+        ..Default::default()
+    })
+}
+
+async fn round_state(client: &mut TestClient) -> OutRespRoundState {
+    let correlation_id = client
+        .send(TransportMsg::InReqRoundState(envelope(InReqRoundState {})))
+        .await
+        .unwrap();
+    match client
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    {
+        TransportMsg::OutRespRoundState(env) => env.payload,
+        other => panic!("unexpected response: {other:?}"),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn active_round_reports_time_left_and_answers() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+    let mut player = server.local_client("player").await;
+
+    assert_eq!(admin.request(start(0)).await.unwrap(), "success");
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo::default(),
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    admin
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_secs(4)).await;
+    let extend = TransportMsg::InReqExtendRound(envelope(InReqExtendRound { extra_seconds: 5 }));
+    assert_eq!(admin.request(extend).await.unwrap(), "success");
+    let answer = TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+        answer: "x".into(),
+        question_id: None,
+        game_id: None,
+    }));
+    assert_eq!(player.request(answer).await.unwrap(), "success");
+
+    let state = round_state(&mut player).await;
+    assert_eq!(
+        state,
+        OutRespRoundState {
+            active: true,
+            round_number: 1,
+            remaining_ms: Some(11_000),
+            next_round_ms: None,
+            answered_count: 1,
+            has_answered: true,
+        }
+    );
+    assert!(!round_state(&mut admin).await.has_answered);
+}
+
+#[tokio::test(start_paused = true)]
+async fn countdown_reports_time_until_the_round() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+
+    let idle = round_state(&mut admin).await;
+    assert!(!idle.active);
+    assert_eq!(idle.round_number, 0);
+    assert_eq!(idle.next_round_ms, None);
+
+    assert_eq!(admin.request(start(3)).await.unwrap(), "success");
+    let state = round_state(&mut admin).await;
+    assert!(!state.active);
+    assert_eq!(state.round_number, 1);
+    assert_eq!(state.remaining_ms, None);
+    assert_eq!(state.next_round_ms, Some(3_000));
+}
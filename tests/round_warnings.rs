@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+use tokio::time::Instant;
+
+async fn start_round(admin: &mut TestClient, round_duration: u64) {
+    let correlation_id = admin
+        .send(TransportMsg::InReqStartGame(envelope(InReqStartGame {
+            game_settings: GameSettings {
+                round_duration,
+                rounds_count: 1,
+                start_countdown: 0,
+                ..Default::default()
+            },
+        })))
+        .await
+        .unwrap();
+    assert_eq!(
+        admin.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo::default(),
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    admin
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
+
+/// Waits for the next warning, returning it with when it came.
+async fn next_warning(client: &mut TestClient, started: Instant) -> (Duration, u64) {
+    let warning = client
+        .expect::<OutNotifRoundTimeWarning>(Duration::from_secs(30))
+        .await
+        .unwrap();
+    (started.elapsed(), warning.payload.remaining_ms)
+}
+
+#[tokio::test(start_paused = true)]
+async fn warnings_fire_at_checkpoints_and_at_the_deadline() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+
+    start_round(&mut admin, 15).await;
+    let started = Instant::now();
+
+    assert_eq!(
+        next_warning(&mut admin, started).await,
+        (Duration::from_secs(5), 10_000)
+    );
+    assert_eq!(
+        next_warning(&mut admin, started).await,
+        (Duration::from_secs(12), 3_000)
+    );
+    assert_eq!(
+        next_warning(&mut admin, started).await,
+        (Duration::from_secs(15), 0)
+    );
+    admin
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn extension_rearms_the_warnings() {
+    let server = TestServer::start().await;
+    let mut admin = server.local_client("admin").await;
+
+    start_round(&mut admin, 10).await;
+    let started = Instant::now();
+    tokio::time::sleep(Duration::from_secs(4)).await;
+
+    let extend = TransportMsg::InReqExtendRound(envelope(InReqExtendRound { extra_seconds: 5 }));
+    let correlation_id = admin.send(extend).await.unwrap();
+    assert_eq!(
+        admin.status(correlation_id, DEFAULT_TIMEOUT).await.unwrap(),
+        "success"
+    );
+
+    assert_eq!(
+        next_warning(&mut admin, started).await,
+        (Duration::from_secs(5), 10_000)
+    );
+    assert_eq!(
+        next_warning(&mut admin, started).await,
+        (Duration::from_secs(12), 3_000)
+    );
+    assert_eq!(
+        next_warning(&mut admin, started).await,
+        (Duration::from_secs(15), 0)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn warnings_can_be_turned_off() {
+    let server = TestServer::start_with_config(ServerConfig {
+        round_time_warnings: Vec::new(),
+        ..Default::default()
+    })
+    .await;
+    let mut admin = server.local_client("admin").await;
+
+    start_round(&mut admin, 5).await;
+    admin
+        .expect::<OutNotifGameStopped>(Duration::from_secs(30))
+        .await
+        .unwrap();
+    assert!(
+        admin
+            .expect::<OutNotifRoundTimeWarning>(Duration::ZERO)
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,22 @@
+use kanjilab_server::schema::{protocol_schema, protocol_schema_pretty, typescript_definitions};
+
+const REGENERATE: &str = "regenerate with `cargo run --features schema --bin export-schema -- \
+     schema/protocol.schema.json --ts schema/protocol.d.ts`";
+
+#[test]
+fn schema_matches_snapshot() {
+    let snapshot = include_str!("../schema/protocol.schema.json");
+    assert!(
+        protocol_schema_pretty() == snapshot,
+        "protocol schema changed, {REGENERATE}"
+    );
+}
+
+#[test]
+fn typescript_matches_snapshot() {
+    let snapshot = include_str!("../schema/protocol.d.ts");
+    assert!(
+        typescript_definitions(&protocol_schema()) == snapshot,
+        "typescript definitions changed, {REGENERATE}"
+    );
+}
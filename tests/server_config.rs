@@ -0,0 +1,79 @@
+use std::{path::PathBuf, time::Duration};
+
+use kanjilab_server::{
+    ServerConfig, TlsConfig,
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+};
+
+async fn server_config(client: &mut TestClient) -> EffectiveConfig {
+    let correlation_id = client
+        .send(TransportMsg::InReqServerConfig(envelope(
+            InReqServerConfig {},
+        )))
+        .await
+        .unwrap();
+    match client.response(correlation_id, DEFAULT_TIMEOUT).await {
+        Ok(TransportMsg::OutRespServerConfig(env)) => env.payload.config,
+        other => panic!("expected the server config, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn config_is_available_before_the_handshake() {
+    let server = TestServer::start_with_config(ServerConfig {
+        chat_max_length: 120,
+        max_clients: Some(8),
+        max_connections_per_ip: Some(4),
+        session_token_ttl: Duration::from_secs(60),
+        ..Default::default()
+    })
+    .await;
+    let mut client = server.connect().await.unwrap();
+
+    let config = server_config(&mut client).await;
+    assert_eq!(config.protocol_version, PROTOCOL_VERSION);
+    assert_eq!(config.chat_max_length, 120);
+    assert_eq!(config.max_room_clients, Some(8));
+    assert_eq!(config.max_connections_per_ip, Some(4));
+    assert_eq!(config.session_token_ttl_seconds, 60);
+    assert_eq!(config.max_message_size, 1024 * 1024);
+    assert_eq!(config.capabilities, Capability::ALL);
+    assert!(config.encodings.contains(&Encoding::Msgpack));
+    assert!(
+        config
+            .signature_algorithms
+            .contains(&SignatureAlgorithm::Ed25519)
+    );
+    assert_eq!(config.features, ServerFeatures::default());
+
+    // and the same after it
+    client.handshake().await.unwrap();
+    client.register("alice").await.unwrap();
+    assert_eq!(server_config(&mut client).await, config);
+}
+
+#[tokio::test]
+async fn secrets_are_left_out() {
+    let operator = "operator-key-that-stays-private";
+    let server = TestServer::start_with_config(ServerConfig {
+        operator_keys: vec![operator.into()],
+        blocked_words_path: Some(PathBuf::from("/nonexistent/blocked.txt")),
+        tls: Some(TlsConfig {
+            cert_path: PathBuf::from("/secret/cert.pem"),
+            key_path: PathBuf::from("/secret/key.pem"),
+        }),
+        ..Default::default()
+    })
+    .await;
+    let mut client = server.local_client("alice").await;
+
+    let config = server_config(&mut client).await;
+    assert!(config.features.tls);
+    // the word list couldn't be read, so nothing is filtered
+    assert!(!config.features.word_filter);
+    let json = serde_json::to_string(&config).unwrap();
+    for secret in [operator, "/secret", "/nonexistent"] {
+        assert!(!json.contains(secret), "{json}");
+    }
+}
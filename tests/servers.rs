@@ -0,0 +1,84 @@
+use std::{
+    net::{SocketAddr, TcpStream},
+    time::{Duration, Instant},
+};
+
+use kanjilab_server::{
+    call_launch_server, call_stop_server, call_stop_sole_server, get_client_outbox,
+    get_effective_config, get_server_addr, get_server_stats, testkit::TestClient,
+};
+use tokio::runtime::Runtime;
+
+// the server API runs its own runtimes, so this is a plain test with a
+// separate runtime for the clients. One test, as the servers are shared by
+// the whole process.
+
+#[test]
+fn servers_in_one_process_are_independent() {
+    let rt = Runtime::new().unwrap();
+    let lan = call_launch_server("0").unwrap();
+    let admin = call_launch_server("0").unwrap();
+    assert_ne!(lan, admin);
+    let lan_addr = get_server_addr(lan).unwrap();
+    let admin_addr = get_server_addr(admin).unwrap();
+    assert_ne!(lan_addr.port(), admin_addr.port());
+
+    let (alice, bob, carol) = rt.block_on(async {
+        let join = |addr: SocketAddr, name: &'static str| async move {
+            let mut client = TestClient::connect(&format!("ws://{addr}")).await.unwrap();
+            client.handshake().await.unwrap();
+            client.register(name).await.unwrap();
+            client
+        };
+        (
+            join(lan_addr, "alice").await,
+            join(lan_addr, "bob").await,
+            join(admin_addr, "carol").await,
+        )
+    });
+    assert_eq!(get_server_stats(lan).unwrap().registered_clients, 2);
+    assert_eq!(get_server_stats(admin).unwrap().registered_clients, 1);
+    assert_eq!(
+        get_effective_config(lan).unwrap().protocol_version,
+        kanjilab_server::data_types::PROTOCOL_VERSION
+    );
+    // and from code already on a runtime, as `main` calls them
+    rt.block_on(async {
+        assert_eq!(get_server_stats(lan).unwrap().registered_clients, 2);
+        assert!(get_effective_config(lan).is_ok());
+        assert!(get_client_outbox(lan, alice.id.as_deref().unwrap()).is_ok());
+    });
+
+    assert_eq!(
+        call_stop_sole_server().unwrap_err(),
+        "2 servers are running"
+    );
+    call_stop_server(lan).unwrap();
+    assert!(get_server_stats(lan).is_err());
+    assert!(call_stop_server(lan).is_err());
+    assert!(stops_listening(lan_addr));
+    assert_eq!(get_server_stats(admin).unwrap().registered_clients, 1);
+
+    let err = call_launch_server(admin_addr.port().to_string()).unwrap_err();
+    assert!(err.starts_with("can't listen on"), "{err}");
+
+    call_stop_sole_server().unwrap();
+    assert!(get_server_stats(admin).is_err());
+    assert_eq!(
+        call_stop_sole_server().unwrap_err(),
+        "server is not running"
+    );
+    drop((alice, bob, carol));
+}
+
+/// The runtime winds down in the background after a stop.
+fn stops_listening(addr: SocketAddr) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if TcpStream::connect(addr).is_err() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    false
+}
@@ -0,0 +1,469 @@
+use kanjilab_server::{
+    data_types::*,
+    schema::protocol_schema,
+    session_client_actor::{Destination, SessionState, route},
+    testkit::{TestClient, TestServer, envelope, message_types},
+};
+use uuid::Uuid;
+
+const KEY: &str = "awaiting public key";
+const SIGNATURE: &str = "awaiting signature";
+const UNREGISTERED: &str = "not registered";
+const VERIFIED: &str = "already verified";
+
+const STATES: [SessionState; 4] = [
+    SessionState::AwaitingKey,
+    SessionState::AwaitingSignature,
+    SessionState::Verified,
+    SessionState::Registered,
+];
+
+type Row = [Result<(), &'static str>; 4];
+
+const AUTH_KEY: Row = [Ok(()), Ok(()), Err(VERIFIED), Err(VERIFIED)];
+const AUTH_SIGNATURE: Row = [Err(KEY), Ok(()), Err(VERIFIED), Err(VERIFIED)];
+const VERIFIED_ONLY: Row = [Err(KEY), Err(SIGNATURE), Ok(()), Ok(())];
+const REGISTERED_ONLY: Row = [Err(KEY), Err(SIGNATURE), Err(UNREGISTERED), Ok(())];
+const ANY: Row = [Ok(()), Ok(()), Ok(()), Ok(())];
+
+fn matrix() -> Vec<(TransportMsg, Row, Destination)> {
+    vec![
+        (
+            TransportMsg::InReqSendPublicKey(envelope(InReqSendPublicKey {
+                key: "k".into(),
+                algorithm: SignatureAlgorithm::Ed25519,
+                encoding: Encoding::Json,
+                capabilities: Vec::new(),
+            })),
+            AUTH_KEY,
+            Destination::Session,
+        ),
+        (
+            TransportMsg::InReqVerifySignature(envelope(InReqVerifySignature {
+                signature: "s".into(),
+            })),
+            AUTH_SIGNATURE,
+            Destination::Session,
+        ),
+        (
+            TransportMsg::InReqResumeSession(envelope(InReqResumeSession {
+                token: "t".into(),
+                encoding: Encoding::Json,
+                capabilities: Vec::new(),
+            })),
+            AUTH_KEY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqRegisterClient(envelope(InReqRegisterClient {
+                name: "n".into(),
+                spectator: false,
+                password: None,
+            })),
+            VERIFIED_ONLY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqServerStats(envelope(InReqServerStats {})),
+            VERIFIED_ONLY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqServerConfig(envelope(InReqServerConfig {})),
+            ANY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqRenameClient(envelope(InReqRenameClient { name: "n".into() })),
+            REGISTERED_ONLY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqSendChat(envelope(InReqSendChat {
+                message: "hi".into(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqMakeAdmin(envelope(InReqMakeAdmin {
+                admin_password: String::new(),
+                client_id: String::new(),
+                revoke_existing: false,
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSetAdminSuccessor(envelope(InReqSetAdminSuccessor {
+                client_id: None,
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSetSubscriptions(envelope(InReqSetSubscriptions {
+                categories: Vec::new(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqClientList(envelope(InReqClientList {})),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqStartGame(envelope(InReqStartGame {
+                game_settings: GameSettings::default(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqStopGame(envelope(InReqStopGame {})),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+                answer: "a".into(),
+                question_id: None,
+                game_id: None,
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSendGameSettings(envelope(InReqSendGameSettings {
+                game_settings: GameSettings::default(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqPatchGameSettings(envelope(InReqPatchGameSettings {
+                game_settings: GameSettingsPatch::default(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqExportGame(envelope(InReqExportGame { game_index: 0 })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSetHandicap(envelope(InReqSetHandicap {
+                client_id: String::new(),
+                extra_time_ms: 0,
+                score_multiplier: 1.0,
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqRoomEvents(envelope(InReqRoomEvents {})),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSubscribeRoomList(envelope(InReqSubscribeRoomList {})),
+            REGISTERED_ONLY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqUnsubscribeRoomList(envelope(InReqUnsubscribeRoomList {})),
+            REGISTERED_ONLY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqSetModerationSettings(envelope(InReqSetModerationSettings {
+                action: ModerationAction::Reject,
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqDebugOutbox(envelope(InReqDebugOutbox {
+                client_id: "c".into(),
+            })),
+            VERIFIED_ONLY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqDebugCapture(envelope(InReqDebugCapture {
+                client_id: "c".into(),
+                enabled: true,
+            })),
+            VERIFIED_ONLY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqWordPartList(envelope(InReqWordPartList {})),
+            REGISTERED_ONLY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqSetQuestionProvider(envelope(InReqSetQuestionProvider {
+                client_id: String::new(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqAddBot(envelope(InReqAddBot {
+                difficulty: BotDifficulty::Easy,
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqRemoveBot(envelope(InReqRemoveBot {
+                client_id: String::new(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqFetchAsset(envelope(InReqFetchAsset {
+                asset_id: String::new(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSendWhisper(envelope(InReqSendWhisper {
+                target_id: String::new(),
+                message: "hi".into(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSetReady(envelope(InReqSetReady { ready: true })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSetRoomPassword(envelope(InReqSetRoomPassword { password: None })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqMuteClient(envelope(InReqMuteClient {
+                client_id: String::new(),
+                muted: true,
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqRoomInfo(envelope(InReqRoomInfo {})),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSetRoomName(envelope(InReqSetRoomName { name: "r".into() })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqGameReplay(envelope(InReqGameReplay {})),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqExtendRound(envelope(InReqExtendRound { extra_seconds: 5 })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSendAnnouncement(envelope(InReqSendAnnouncement {
+                message: "hi".into(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqRematch(envelope(InReqRematch {
+                shuffle_settings: None,
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqLeaveRoom(envelope(InReqLeaveRoom {})),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqResumeGame(envelope(InReqResumeGame {})),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSendReaction(envelope(InReqSendReaction {
+                target: ReactionTarget {
+                    kind: ReactionTargetKind::Chat,
+                    id: Uuid::new_v4(),
+                },
+                emoji: "👏".into(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqRoundState(envelope(InReqRoundState {})),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqSendLobbyChat(envelope(InReqSendLobbyChat {
+                message: "hi".into(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InRespQuestion(envelope(InRespQuestion {
+                question: QuestionInfo::default(),
+                question_svg: String::new(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InRespQuestionError(envelope(InRespQuestionError {
+                error_code: ErrorCode::RenderFailed,
+                message: String::new(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Room,
+        ),
+        (
+            TransportMsg::InReqFindPlayer(envelope(InReqFindPlayer {
+                player_id_or_name: "alice".into(),
+            })),
+            REGISTERED_ONLY,
+            Destination::Game,
+        ),
+        (
+            TransportMsg::InReqSetPresenceVisibility(envelope(InReqSetPresenceVisibility {
+                visible: true,
+            })),
+            REGISTERED_ONLY,
+            Destination::Game,
+        ),
+    ]
+}
+
+#[test]
+fn every_message_is_gated_by_state() {
+    for (msg, row, _) in matrix() {
+        for (state, expected) in STATES.into_iter().zip(row) {
+            assert_eq!(state.permits(&msg), expected, "{msg:?} in {state:?}");
+        }
+    }
+}
+
+#[test]
+fn every_message_is_routed() {
+    for (msg, _, destination) in matrix() {
+        assert_eq!(route(&msg), destination, "{msg:?}");
+    }
+    let notif = TransportMsg::OutNotifServerError(envelope(OutNotifServerError {
+        message: String::new(),
+    }));
+    assert_eq!(route(&notif), Destination::Unknown);
+}
+
+#[test]
+fn matrix_covers_every_client_message() {
+    let schema = protocol_schema();
+    let listed = message_types(
+        &matrix()
+            .into_iter()
+            .map(|(msg, ..)| msg)
+            .collect::<Vec<_>>(),
+    );
+    let variants = schema["oneOf"].as_array().unwrap();
+    for message_type in variants
+        .iter()
+        .filter_map(|v| v["properties"]["messageType"]["const"].as_str())
+        .filter(|t| t.starts_with("IN_"))
+    {
+        assert!(
+            listed.iter().any(|l| l == message_type),
+            "{message_type} is missing from the matrix"
+        );
+    }
+}
+
+#[tokio::test]
+async fn handshake_cannot_be_skipped() {
+    let server = TestServer::start().await;
+    let mut client = TestClient::connect(&server.url()).await.unwrap();
+
+    let chat = || {
+        TransportMsg::InReqSendChat(envelope(InReqSendChat {
+            message: "hi".into(),
+        }))
+    };
+    assert_eq!(client.request(chat()).await.unwrap(), KEY);
+
+    let verify = TransportMsg::InReqVerifySignature(envelope(InReqVerifySignature {
+        signature: String::new(),
+    }));
+    assert_eq!(client.request(verify).await.unwrap(), KEY);
+
+    client.handshake().await.unwrap();
+    assert_eq!(client.request(chat()).await.unwrap(), UNREGISTERED);
+
+    client.register("alice").await.unwrap();
+    let key = TransportMsg::InReqSendPublicKey(envelope(InReqSendPublicKey {
+        key: client.public_key(),
+        algorithm: SignatureAlgorithm::Ed25519,
+        encoding: Encoding::Json,
+        capabilities: Vec::new(),
+    }));
+    assert_eq!(client.request(key).await.unwrap(), VERIFIED);
+    assert_eq!(client.request(chat()).await.unwrap(), "success");
+}
+
+#[tokio::test]
+async fn messages_are_answered_the_same_way_before_the_handshake() {
+    let server = TestServer::start().await;
+    let mut client = TestClient::local(server.game()).await;
+    for (msg, [expected, ..], _) in matrix() {
+        if let Err(status) = expected {
+            assert_eq!(
+                client.request(msg.clone()).await.unwrap(),
+                status,
+                "{msg:?}"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn every_room_message_needs_a_room() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+    let leave = TransportMsg::InReqLeaveRoom(envelope(InReqLeaveRoom {}));
+    assert_eq!(alice.request(leave).await.unwrap(), "success");
+
+    for (msg, _, destination) in matrix() {
+        if destination == Destination::Room {
+            assert_eq!(
+                alice.request(msg.clone()).await.unwrap(),
+                "no room",
+                "{msg:?}"
+            );
+        }
+    }
+    let notif = TransportMsg::OutNotifServerError(envelope(OutNotifServerError {
+        message: String::new(),
+    }));
+    assert_eq!(alice.request(notif).await.unwrap(), "unknown message");
+}
@@ -0,0 +1,107 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+    tools::{check_session_token, issue_session_token},
+};
+
+const SECRET: &[u8] = b"test secret";
+
+fn resume(token: &str) -> TransportMsg {
+    TransportMsg::InReqResumeSession(envelope(InReqResumeSession {
+        token: token.into(),
+        encoding: Encoding::Json,
+        capabilities: Vec::new(),
+    }))
+}
+
+#[test]
+fn token_is_bound_to_its_key_until_expiry() {
+    let token = issue_session_token(SECRET, "key", 1_000);
+    assert_eq!(check_session_token(SECRET, &token, 999), Ok("key".into()));
+    assert_eq!(
+        check_session_token(SECRET, &token, 1_000),
+        Err("token expired")
+    );
+    assert_ne!(token, issue_session_token(SECRET, "key", 1_000));
+}
+
+#[test]
+fn token_from_another_secret_is_rejected() {
+    let token = issue_session_token(b"other secret", "key", 1_000);
+    assert_eq!(check_session_token(SECRET, &token, 0), Err("invalid token"));
+}
+
+#[test]
+fn tampered_token_is_rejected() {
+    let token = issue_session_token(SECRET, "key", 1_000);
+    let (body, mac) = token.split_once('.').unwrap();
+
+    // swap in a later expiry and another key under the original mac
+    let forged = issue_session_token(SECRET, "other", u64::MAX);
+    let (forged_body, _) = forged.split_once('.').unwrap();
+    assert_eq!(
+        check_session_token(SECRET, &format!("{forged_body}.{mac}"), 0),
+        Err("invalid token")
+    );
+
+    let mut flipped = body.to_string().into_bytes();
+    flipped[0] = if flipped[0] == b'A' { b'B' } else { b'A' };
+    let flipped = String::from_utf8(flipped).unwrap();
+    assert_eq!(
+        check_session_token(SECRET, &format!("{flipped}.{mac}"), 0),
+        Err("invalid token")
+    );
+    assert_eq!(check_session_token(SECRET, body, 0), Err("invalid token"));
+}
+
+#[tokio::test]
+async fn token_skips_the_challenge_on_reconnect() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+    let first_id = alice.id.clone().unwrap();
+    let token = alice
+        .expect::<OutNotifSessionToken>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .token;
+    let key = alice.key().clone();
+    alice.close().await;
+
+    let mut again = TestClient::connect(&server.url())
+        .await
+        .unwrap()
+        .with_key(key);
+    assert_eq!(
+        again.request(resume("garbage")).await.unwrap(),
+        "invalid token"
+    );
+    assert_eq!(again.request(resume(&token)).await.unwrap(), "success");
+    again
+        .expect::<OutNotifSessionToken>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(
+        again.request(resume(&token)).await.unwrap(),
+        "already verified"
+    );
+
+    let second_id = again.register("alice").await.unwrap();
+    assert_ne!(second_id, first_id);
+    let list = again
+        .send(TransportMsg::InReqClientList(envelope(InReqClientList {})))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespClientList(list) =
+        again.response(list, DEFAULT_TIMEOUT).await.unwrap()
+    else {
+        panic!("expected a client list");
+    };
+    let me = list
+        .payload
+        .clients
+        .iter()
+        .find(|c| c.id == second_id)
+        .unwrap();
+    assert_eq!(me.key, again.public_key());
+}
@@ -0,0 +1,183 @@
+use base64::{Engine, prelude::BASE64_STANDARD};
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+    tools::{key_identity, player_id, split_key_identity, verify_signature},
+};
+use p256::ecdsa::{Signature, SigningKey, signature::Signer};
+
+// Both keys come from the seed [7; 32] and both signatures are over
+// "challenge".
+const ED25519_KEY: &str = "6kpsY+KcUgq+9VB7Ey7F+ZVHdq6+vnuSQh7qaRRG0iw=";
+const ED25519_SIG: &str =
+    "KeCLdUKSUu0RjOnBEDcbLpzNXn3J4bHwY/juAsXZZGdlTQtK+4Ct8JzuvuekkZtv2+rCd4dXukwEKN/4QPMhAg==";
+const P256_KEY: &str =
+    "BB4YUy/UdUwC8wQdnHXOszuD/9gax85P6ILMscmLxYlupGwxHE4v9A3ZajZT5uRURdMt/khuztdcepDGoYiBwKM=";
+const P256_KEY_COMPRESSED: &str = "Ax4YUy/UdUwC8wQdnHXOszuD/9gax85P6ILMscmLxYlu";
+const P256_SIG: &str =
+    "T4zywaQI+dU6i6ktOvSl2Q0HuaN5e3tWyn0etoVtVGRDuLAIvqeHMYXINsslUZ7aba+9YjK2uV0E9wF9vRToNg==";
+
+#[test]
+fn ed25519_vector() {
+    let alg = SignatureAlgorithm::Ed25519;
+    assert_eq!(
+        verify_signature(alg, "challenge", ED25519_SIG, ED25519_KEY),
+        Ok(true)
+    );
+    assert_eq!(
+        verify_signature(alg, "other", ED25519_SIG, ED25519_KEY),
+        Ok(false)
+    );
+}
+
+#[test]
+fn p256_vector() {
+    let alg = SignatureAlgorithm::EcdsaP256;
+    assert_eq!(
+        verify_signature(alg, "challenge", P256_SIG, P256_KEY),
+        Ok(true)
+    );
+    assert_eq!(
+        verify_signature(alg, "challenge", P256_SIG, P256_KEY_COMPRESSED),
+        Ok(true)
+    );
+    assert_eq!(
+        verify_signature(alg, "other", P256_SIG, P256_KEY),
+        Ok(false)
+    );
+}
+
+#[test]
+fn keys_are_checked_against_their_algorithm() {
+    assert!(
+        verify_signature(SignatureAlgorithm::Ed25519, "challenge", P256_SIG, P256_KEY).is_err()
+    );
+    assert!(
+        verify_signature(
+            SignatureAlgorithm::EcdsaP256,
+            "challenge",
+            ED25519_SIG,
+            ED25519_KEY
+        )
+        .is_err()
+    );
+    assert!(
+        verify_signature(
+            SignatureAlgorithm::Unknown,
+            "challenge",
+            ED25519_SIG,
+            ED25519_KEY
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn malformed_input_is_an_error_not_a_panic() {
+    let truncated_key = &P256_KEY[..20];
+    let short = BASE64_STANDARD.encode([1u8; 5]);
+    for alg in [SignatureAlgorithm::Ed25519, SignatureAlgorithm::EcdsaP256] {
+        assert!(verify_signature(alg, "challenge", ED25519_SIG, "not base64!").is_err());
+        assert!(verify_signature(alg, "challenge", ED25519_SIG, &short).is_err());
+        assert!(verify_signature(alg, "challenge", &short, ED25519_KEY).is_err());
+        assert!(verify_signature(alg, "challenge", P256_SIG, truncated_key).is_err());
+    }
+    // right length, but not a point on the curve
+    let off_curve = BASE64_STANDARD.encode([[4u8].as_slice(), &[0xff; 64]].concat());
+    assert!(
+        verify_signature(
+            SignatureAlgorithm::EcdsaP256,
+            "challenge",
+            P256_SIG,
+            &off_curve
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn player_id_depends_on_the_algorithm() {
+    let ed = key_identity(SignatureAlgorithm::Ed25519, ED25519_KEY);
+    let p256 = key_identity(SignatureAlgorithm::EcdsaP256, ED25519_KEY);
+    // ed25519 ids are the ones handed out before there was a choice
+    assert_eq!(ed, ED25519_KEY);
+    assert_ne!(player_id(&ed), player_id(&p256));
+    assert_eq!(
+        split_key_identity(&p256),
+        (SignatureAlgorithm::EcdsaP256, ED25519_KEY)
+    );
+    assert_eq!(
+        split_key_identity(&ed),
+        (SignatureAlgorithm::Ed25519, ED25519_KEY)
+    );
+}
+
+async fn hello(
+    client: &mut TestClient,
+    algorithm: SignatureAlgorithm,
+    key: String,
+) -> TransportMsg {
+    let correlation_id = client
+        .send(TransportMsg::InReqSendPublicKey(envelope(
+            InReqSendPublicKey {
+                key,
+                algorithm,
+                encoding: Encoding::Json,
+                capabilities: Vec::new(),
+            },
+        )))
+        .await
+        .unwrap();
+    client
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn unknown_algorithm_is_turned_away() {
+    let server = TestServer::start().await;
+    let mut client = TestClient::local(server.game()).await;
+    match hello(&mut client, SignatureAlgorithm::Unknown, P256_KEY.into()).await {
+        TransportMsg::OutRespStatus(env) => assert_eq!(
+            env.payload.status,
+            "unsupported algorithm, expected one of: ed25519, ecdsa-p256"
+        ),
+        other => panic!("expected a status, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn p256_client_completes_the_handshake() {
+    let server = TestServer::start().await;
+    let mut client = TestClient::local(server.game()).await;
+    let key = SigningKey::from_bytes(&[9u8; 32].into()).unwrap();
+    let public = key.verifying_key().to_encoded_point(false);
+
+    let TransportMsg::OutRespSignMessage(challenge) = hello(
+        &mut client,
+        SignatureAlgorithm::EcdsaP256,
+        BASE64_STANDARD.encode(public.as_bytes()),
+    )
+    .await
+    else {
+        panic!("expected a challenge");
+    };
+    let signature: Signature = key.sign(challenge.payload.message.as_bytes());
+    let correlation_id = client
+        .send(TransportMsg::InReqVerifySignature(envelope(
+            InReqVerifySignature {
+                signature: BASE64_STANDARD.encode(signature.to_bytes()),
+            },
+        )))
+        .await
+        .unwrap();
+    assert_eq!(
+        client
+            .status(correlation_id, DEFAULT_TIMEOUT)
+            .await
+            .unwrap(),
+        "success"
+    );
+    client.register("pat").await.unwrap();
+}
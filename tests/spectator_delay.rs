@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    room_actor::{
+        ProvideQuestionResponse, RoomInfoRequest, SendAnswerRequest, StartGameRequest,
+        StopGameRequest,
+    },
+    testkit::{RoomMember, TestRoom, message_types, sample_question},
+};
+use uuid::Uuid;
+
+const DELAY: Duration = Duration::from_secs(2);
+
+async fn room_with_viewer() -> (TestRoom, RoomMember, RoomMember) {
+    let room = TestRoom::start(ServerConfig::default()).await;
+    let alice = room.join("alice", false).await.unwrap();
+    let viewer = room.join("viewer", true).await.unwrap();
+    room.room
+        .ask(StartGameRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            game_settings: GameSettings {
+                round_duration: 30,
+                rounds_count: 2,
+                start_countdown: 0,
+                allow_repeat_words: true,
+                spectator_delay_ms: DELAY.as_millis() as u64,
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+    viewer.sink.take();
+    (room, alice, viewer)
+}
+
+async fn provide_questions(room: &TestRoom, admin: &RoomMember) {
+    for msg in admin.sink.take() {
+        if let TransportMsg::OutReqQuestion(req) = msg {
+            room.room
+                .ask(ProvideQuestionResponse {
+                    requester: admin.session.clone(),
+                    correlation_id: req.correlation_id,
+                    question_info: sample_question(),
+                    question_svg: "<svg/>".into(),
+                })
+                .await
+                .unwrap();
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn spectators_see_questions_and_answers_late() {
+    let (room, alice, viewer) = room_with_viewer().await;
+    provide_questions(&room, &alice).await;
+    assert!(message_types(&alice.sink.take()).contains(&"OUT_NOTIF_question".to_string()));
+
+    room.room
+        .ask(SendAnswerRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            answer: "ひ".into(),
+            question_id: None,
+            game_id: None,
+        })
+        .await
+        .unwrap();
+    // the round's result is not held back
+    assert_eq!(message_types(&viewer.sink.take()), ["OUT_NOTIF_roundEnded"]);
+
+    tokio::time::sleep(DELAY + Duration::from_millis(10)).await;
+    assert_eq!(
+        message_types(&viewer.sink.take()),
+        ["OUT_NOTIF_question", "OUT_NOTIF_clientAnswered"]
+    );
+
+    room.room
+        .ask(RoomInfoRequest {
+            requester: viewer.session.clone(),
+            correlation_id: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+    let Some(TransportMsg::OutRespRoomInfo(info)) = viewer.sink.take().pop() else {
+        panic!("expected room info");
+    };
+    assert_eq!(info.payload.room.spectator_delay_ms, 2000);
+}
+
+#[tokio::test(start_paused = true)]
+async fn spectators_see_notifications_numbered_in_order() {
+    let (room, alice, viewer) = room_with_viewer().await;
+    provide_questions(&room, &alice).await;
+    room.room
+        .ask(SendAnswerRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            answer: "ひ".into(),
+            question_id: None,
+            game_id: None,
+        })
+        .await
+        .unwrap();
+    let mut seen = viewer.sink.take();
+    tokio::time::sleep(DELAY + Duration::from_millis(10)).await;
+    seen.extend(viewer.sink.take());
+
+    // the round's result came first, yet the question isn't numbered below it
+    assert_eq!(
+        message_types(&seen),
+        [
+            "OUT_NOTIF_roundEnded",
+            "OUT_NOTIF_question",
+            "OUT_NOTIF_clientAnswered"
+        ]
+    );
+    let seqs: Vec<u64> = seen
+        .iter()
+        .map(|msg| serde_json::to_value(msg).unwrap()["seq"].as_u64().unwrap())
+        .collect();
+    assert!(seqs.windows(2).all(|w| w[0] < w[1]), "{seqs:?}");
+}
+
+#[tokio::test(start_paused = true)]
+async fn stopping_the_game_drops_what_was_held_back() {
+    let (room, alice, viewer) = room_with_viewer().await;
+    provide_questions(&room, &alice).await;
+    room.room
+        .ask(StopGameRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+
+    tokio::time::sleep(DELAY * 2).await;
+    assert_eq!(
+        message_types(&viewer.sink.take()),
+        ["OUT_NOTIF_gameStopped"]
+    );
+}
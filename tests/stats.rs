@@ -0,0 +1,78 @@
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    game_actor::collect_server_stats,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, operator_key, operator_keys},
+};
+
+async fn server_stats(client: &mut TestClient) -> Result<ServerStats, String> {
+    let correlation_id = client
+        .send(TransportMsg::InReqServerStats(envelope(
+            InReqServerStats {},
+        )))
+        .await?;
+    match client.response(correlation_id, DEFAULT_TIMEOUT).await? {
+        TransportMsg::OutRespServerStats(env) => Ok(env.payload.stats),
+        TransportMsg::OutRespStatus(env) => Err(env.payload.status),
+        other => Err(format!("unexpected response: {other:?}")),
+    }
+}
+
+#[tokio::test]
+async fn stats_count_connections_and_games() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+    let _bob = server.client("bob").await;
+
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: GameSettings {
+            round_duration: 30,
+            rounds_count: 1,
+            start_countdown: 0,
+            ..Default::default()
+        },
+    }));
+    assert_eq!(alice.request(start).await.unwrap(), "success");
+    let stop = TransportMsg::InReqStopGame(envelope(InReqStopGame {}));
+    assert_eq!(alice.request(stop).await.unwrap(), "success");
+
+    let stats = server_stats(&mut alice).await.unwrap();
+    // addresses are for operators, and there are none
+    assert!(stats.connections_per_ip.is_empty());
+    assert_eq!(stats.connections_accepted, 2);
+    assert_eq!(stats.connected_clients, 2);
+    assert_eq!(stats.registrations, 2);
+    assert_eq!(stats.games_started, 1);
+    assert_eq!(stats.games_completed, 0);
+    assert_eq!(stats.rooms[0].players, 2);
+    assert_eq!(stats.rooms[0].games_started, 1);
+
+    assert_eq!(
+        collect_server_stats(server.game())
+            .await
+            .unwrap()
+            .registrations,
+        stats.registrations
+    );
+}
+
+#[tokio::test]
+async fn stats_can_be_limited_to_operators() {
+    let server = TestServer::start_with_config(ServerConfig {
+        operator_keys: operator_keys(),
+        ..Default::default()
+    })
+    .await;
+
+    let mut alice = server.client("alice").await;
+    assert_eq!(server_stats(&mut alice).await.unwrap_err(), "not operator");
+
+    let mut monitor = TestClient::connect(&server.url())
+        .await
+        .unwrap()
+        .with_key(operator_key());
+    monitor.handshake().await.unwrap();
+    let stats = server_stats(&mut monitor).await.unwrap();
+    assert_eq!(stats.registrations, 1);
+    assert_eq!(stats.connections_per_ip.len(), 1);
+}
@@ -0,0 +1,75 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestServer, envelope, sample_question, start_game},
+};
+
+fn start(streak_bonus: Option<u32>) -> TransportMsg {
+    start_game(GameSettings {
+        round_duration: 30,
+        rounds_count: 3,
+        start_countdown: 0,
+        allow_repeat_words: true,
+        streak_bonus,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn streak_is_announced_and_scored() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+    let alice_id = alice.id.clone().unwrap();
+
+    assert_eq!(alice.request(start(Some(2))).await.unwrap(), "success");
+    alice.provide_question(sample_question()).await.unwrap();
+
+    for round in 1..=3 {
+        if round < 3 {
+            alice.provide_question(sample_question()).await.unwrap();
+        }
+        let answer = TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+            answer: "ひ".into(),
+            question_id: None,
+            game_id: None,
+        }));
+        assert_eq!(alice.request(answer).await.unwrap(), "success");
+
+        if round < 3 {
+            let ended = alice
+                .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+                .await
+                .unwrap();
+            assert_eq!(
+                ended.payload.streaks,
+                vec![PlayerStreak {
+                    id: alice_id.clone(),
+                    streak: round,
+                }]
+            );
+        }
+    }
+
+    let streak = alice
+        .expect::<OutNotifStreak>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(streak.payload.id, alice_id);
+    assert_eq!(streak.payload.length, 3);
+
+    let stopped = alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(stopped.payload.final_scores[0].score, 4);
+}
+
+#[tokio::test]
+async fn zero_streak_bonus_is_rejected() {
+    let server = TestServer::start().await;
+    let mut alice = server.client("alice").await;
+
+    assert_eq!(
+        alice.request(start(Some(0))).await.unwrap(),
+        "invalid streak bonus"
+    );
+}
@@ -0,0 +1,153 @@
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    room_actor::{
+        ProvideQuestionResponse, RoomInfoRequest, SendAnswerRequest, SendChatRequest,
+        SetSubscriptionsRequest, StartGameRequest, StopGameRequest,
+    },
+    testkit::{RoomMember, TestRoom, message_types},
+};
+use uuid::Uuid;
+
+async fn subscribe(room: &TestRoom, member: &RoomMember, categories: &[&str]) -> String {
+    room.room
+        .ask(SetSubscriptionsRequest {
+            requester: member.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            categories: categories.iter().map(|c| c.to_string()).collect(),
+        })
+        .await
+        .unwrap();
+    match member.sink.take().pop() {
+        Some(TransportMsg::OutRespStatus(env)) => env.payload.status,
+        other => panic!("expected a status, got {other:?}"),
+    }
+}
+
+async fn subscriptions(room: &TestRoom, member: &RoomMember) -> Vec<Subscription> {
+    room.room
+        .ask(RoomInfoRequest {
+            requester: member.session.clone(),
+            correlation_id: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+    match member.sink.take().pop() {
+        Some(TransportMsg::OutRespRoomInfo(env)) => env.payload.subscriptions,
+        other => panic!("expected room info, got {other:?}"),
+    }
+}
+
+/// Plays one round: alice chats, serves the question and answers it.
+async fn play_round(room: &TestRoom, alice: &RoomMember) {
+    room.room
+        .ask(StartGameRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            game_settings: GameSettings {
+                round_duration: 30,
+                rounds_count: 1,
+                start_countdown: 0,
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+    room.room
+        .ask(SendChatRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            message: "good luck".into(),
+        })
+        .await
+        .unwrap();
+    for msg in alice.sink.take() {
+        if let TransportMsg::OutReqQuestion(req) = msg {
+            room.room
+                .ask(ProvideQuestionResponse {
+                    requester: alice.session.clone(),
+                    correlation_id: req.correlation_id,
+                    question_info: QuestionInfo::default(),
+                    question_svg: "<svg/>".into(),
+                })
+                .await
+                .unwrap();
+        }
+    }
+    room.room
+        .ask(SendAnswerRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+            answer: "ひ".into(),
+            question_id: None,
+            game_id: None,
+        })
+        .await
+        .unwrap();
+    room.room
+        .ask(StopGameRequest {
+            requester: alice.session.clone(),
+            correlation_id: Uuid::new_v4(),
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn everything_is_sent_until_a_client_opts_out() {
+    let room = TestRoom::start(ServerConfig::default()).await;
+    let alice = room.join("alice", false).await.unwrap();
+    let bob = room.join("bob", true).await.unwrap();
+    assert_eq!(subscriptions(&room, &bob).await, Subscription::ALL);
+
+    play_round(&room, &alice).await;
+    let seen = message_types(&bob.sink.take());
+    for expected in [
+        "OUT_NOTIF_chatSent",
+        "OUT_NOTIF_question",
+        "OUT_NOTIF_clientAnswered",
+    ] {
+        assert!(seen.contains(&expected.to_string()), "{expected}: {seen:?}");
+    }
+}
+
+#[tokio::test]
+async fn a_dashboard_skips_questions_and_chat() {
+    let room = TestRoom::start(ServerConfig::default()).await;
+    let alice = room.join("alice", false).await.unwrap();
+    let dashboard = room.join("dashboard", true).await.unwrap();
+    assert_eq!(
+        subscribe(&room, &dashboard, &["answers", "presence"]).await,
+        "success"
+    );
+    assert_eq!(
+        subscriptions(&room, &dashboard).await,
+        [Subscription::Answers, Subscription::Presence]
+    );
+
+    play_round(&room, &alice).await;
+    let seen = message_types(&dashboard.sink.take());
+    assert!(!seen.contains(&"OUT_NOTIF_chatSent".to_string()));
+    assert!(!seen.contains(&"OUT_NOTIF_question".to_string()));
+    assert!(seen.contains(&"OUT_NOTIF_clientAnswered".to_string()));
+    // the game starting and stopping can't be opted out of
+    assert!(seen.contains(&"OUT_NOTIF_gameStarted".to_string()));
+    assert!(seen.contains(&"OUT_NOTIF_gameStopped".to_string()));
+
+    assert_eq!(subscribe(&room, &dashboard, &[]).await, "success");
+    room.join("carol", false).await.unwrap();
+    let seen = message_types(&dashboard.sink.take());
+    assert!(!seen.contains(&"OUT_NOTIF_clientRegistered".to_string()));
+}
+
+#[tokio::test]
+async fn unknown_categories_are_turned_down() {
+    let room = TestRoom::start(ServerConfig::default()).await;
+    let alice = room.join("alice", false).await.unwrap();
+
+    assert_eq!(
+        subscribe(&room, &alice, &["chat", "svg"]).await,
+        "unknown category: svg"
+    );
+    assert_eq!(subscriptions(&room, &alice).await, Subscription::ALL);
+}
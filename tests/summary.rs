@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+
+use kanjilab_server::{
+    data_types::*,
+    summary::{player_summaries, tier_summaries},
+};
+
+fn answer(id: &str, is_correct: bool, answer_time: u64) -> AnswerInfo {
+    AnswerInfo {
+        id: id.into(),
+        answer: String::new(),
+        is_correct,
+        answer_time,
+        kind: AnswerKind::Answered,
+        sequence: None,
+    }
+}
+
+fn timed_out(id: &str) -> AnswerInfo {
+    AnswerInfo {
+        answer_time: 10_000,
+        kind: AnswerKind::TimedOut,
+        ..answer(id, false, 0)
+    }
+}
+
+fn round(answers: Vec<AnswerInfo>) -> RoundRecord {
+    RoundRecord {
+        question: QuestionInfo::default(),
+        answers,
+        aborted: false,
+        question_id: None,
+        reactions: BTreeMap::new(),
+        tier: None,
+    }
+}
+
+#[test]
+fn computes_accuracy_time_and_streak() {
+    let rounds = vec![
+        round(vec![answer("a", true, 1000), answer("b", false, 10_000)]),
+        round(vec![answer("a", true, 3000), answer("b", true, 2000)]),
+        round(vec![answer("a", false, 10_000), answer("b", true, 4000)]),
+        round(vec![answer("a", true, 2000), answer("b", true, 6000)]),
+    ];
+
+    let summaries = player_summaries(&rounds, |_| true);
+    let a = summaries.iter().find(|s| s.id == "a").unwrap();
+    let b = summaries.iter().find(|s| s.id == "b").unwrap();
+
+    assert_eq!(a.rounds_played, 4);
+    assert_eq!(a.correct_answers, 3);
+    assert_eq!(a.accuracy, 75.0);
+    assert_eq!(a.average_correct_time, Some(2000));
+    assert_eq!(a.longest_streak, 2);
+
+    assert_eq!(b.longest_streak, 3);
+    assert_eq!(b.average_correct_time, Some(4000));
+}
+
+#[test]
+fn late_joiner_only_counts_rounds_present() {
+    let rounds = vec![
+        round(vec![answer("a", false, 10_000)]),
+        round(vec![answer("a", true, 1000), answer("late", true, 1000)]),
+    ];
+
+    let summaries = player_summaries(&rounds, |_| true);
+    let late = summaries.iter().find(|s| s.id == "late").unwrap();
+    assert_eq!(late.rounds_played, 1);
+    assert_eq!(late.accuracy, 100.0);
+}
+
+#[test]
+fn timeouts_are_not_attempts() {
+    let rounds = vec![
+        round(vec![answer("a", true, 1000)]),
+        round(vec![timed_out("a")]),
+        round(vec![answer("a", false, 4000)]),
+    ];
+
+    let summaries = player_summaries(&rounds, |_| true);
+    assert_eq!(summaries[0].rounds_played, 3);
+    assert_eq!(summaries[0].accuracy, 50.0);
+}
+
+#[test]
+fn ties_share_rank_and_disconnected_players_stay() {
+    let rounds = vec![round(vec![
+        answer("a", true, 1000),
+        answer("b", true, 2000),
+        answer("c", false, 10_000),
+        answer("gone", false, 10_000),
+    ])];
+
+    let summaries = player_summaries(&rounds, |id| id != "gone");
+    let ranks: Vec<(&str, u64)> = summaries.iter().map(|s| (s.id.as_str(), s.rank)).collect();
+    assert_eq!(ranks, vec![("a", 1), ("b", 1), ("c", 3), ("gone", 3)]);
+
+    let gone = summaries.iter().find(|s| s.id == "gone").unwrap();
+    assert!(gone.disconnected);
+    assert_eq!(gone.average_correct_time, None);
+}
+
+#[test]
+fn no_rounds_means_no_summaries() {
+    assert!(player_summaries(&[], |_| true).is_empty());
+}
+
+#[test]
+fn tiers_total_every_answer_in_their_rounds() {
+    let in_tier = |tier: &str, answers| RoundRecord {
+        tier: Some(tier.into()),
+        ..round(answers)
+    };
+    let rounds = vec![
+        in_tier(
+            "hard",
+            vec![answer("a", true, 1000), answer("b", false, 9000)],
+        ),
+        in_tier("easy", vec![answer("a", true, 1000), timed_out("b")]),
+        round(vec![answer("a", false, 1000)]),
+        in_tier(
+            "hard",
+            vec![answer("a", false, 1000), answer("b", false, 9000)],
+        ),
+    ];
+
+    let tiers = tier_summaries(&rounds);
+    assert_eq!(
+        tiers,
+        vec![
+            TierSummary {
+                tier: "easy".into(),
+                rounds: 1,
+                correct_answers: 1,
+                accuracy: 100.0,
+            },
+            TierSummary {
+                tier: "hard".into(),
+                rounds: 2,
+                correct_answers: 1,
+                accuracy: 25.0,
+            },
+        ]
+    );
+}
@@ -0,0 +1,137 @@
+use kanjilab_server::{
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope, start_game},
+};
+
+fn settings(tier: Option<&str>, rounds_count: u64) -> GameSettings {
+    GameSettings {
+        round_duration: 30,
+        rounds_count,
+        start_countdown: 0,
+        allow_repeat_words: true,
+        tier: tier.map(Into::into),
+        ..Default::default()
+    }
+}
+
+async fn provide(admin: &mut TestClient, frequency: f64) -> OutReqQuestion {
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo {
+                    word_info: WordInfo {
+                        word: "日".into(),
+                        meanings: Vec::new(),
+                        readings: vec![ReadingWithParts {
+                            reading: "ひ".into(),
+                            parts: Vec::new(),
+                        }],
+                    },
+                    frequency: Some(frequency),
+                    ..Default::default()
+                },
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    req.payload
+}
+
+fn answer(answer: &str) -> TransportMsg {
+    TransportMsg::InReqSendAnswer(envelope(InReqSendAnswer {
+        answer: answer.into(),
+        question_id: None,
+        game_id: None,
+    }))
+}
+
+#[tokio::test]
+async fn unknown_tier_is_rejected_with_the_valid_ones() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+
+    assert_eq!(
+        alice
+            .request(start_game(settings(Some("nightmare"), 1)))
+            .await
+            .unwrap(),
+        "unknown tier: easy,medium,hard"
+    );
+}
+
+#[tokio::test]
+async fn tier_replaces_the_frequency_bounds() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+
+    let send = TransportMsg::InReqSendGameSettings(envelope(InReqSendGameSettings {
+        game_settings: GameSettings {
+            min_frequency: 5,
+            ..settings(Some("Medium"), 1)
+        },
+    }));
+    assert_eq!(alice.request(send).await.unwrap(), "success");
+    let changed = alice
+        .expect::<OutNotifGameSettingsChanged>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+        .payload
+        .game_settings;
+    assert_eq!(changed.tier.as_deref(), Some("medium"));
+    assert_eq!(changed.min_frequency, 1_000);
+    assert_eq!(changed.max_frequency, 9_999);
+    assert!(changed.using_max_frequency);
+
+    assert_eq!(
+        alice
+            .request(start_game(settings(Some("easy"), 1)))
+            .await
+            .unwrap(),
+        "success"
+    );
+    let req = provide(&mut alice, 20_000.0).await;
+    assert_eq!(req.min_frequency, 10_000);
+    assert_eq!(req.max_frequency, None);
+}
+
+#[tokio::test]
+async fn game_summary_counts_answers_per_tier() {
+    let server = TestServer::start().await;
+    let mut alice = server.local_client("alice").await;
+
+    assert_eq!(
+        alice.request(start_game(settings(None, 2))).await.unwrap(),
+        "success"
+    );
+    provide(&mut alice, 50.0).await;
+    alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    provide(&mut alice, 20_000.0).await;
+    assert_eq!(alice.request(answer("ひ")).await.unwrap(), "success");
+    alice
+        .expect::<OutNotifRoundEnded>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    assert_eq!(alice.request(answer("つき")).await.unwrap(), "success");
+
+    let stopped = alice
+        .expect::<OutNotifGameStopped>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let tiers: Vec<_> = stopped
+        .payload
+        .tier_summaries
+        .iter()
+        .map(|t| (t.tier.as_str(), t.rounds, t.correct_answers, t.accuracy))
+        .collect();
+    assert_eq!(tiers, vec![("easy", 1, 0, 0.0), ("hard", 1, 1, 100.0)]);
+}
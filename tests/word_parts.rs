@@ -0,0 +1,186 @@
+use std::path::PathBuf;
+
+use kanjilab_server::{
+    ServerConfig,
+    data_types::*,
+    testkit::{DEFAULT_TIMEOUT, TestClient, TestServer, envelope},
+    word_parts::WordPartIndex,
+};
+use uuid::Uuid;
+
+const INDEX: &str = "# part, reading, words\n\
+    日\tにち\t120\n\
+    日\tひ\t80\n\
+    月\tげつ\t60\n\
+    氵\tさんずい\t300\n\
+    木\tき\t90\n";
+
+fn index_file(text: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("kanjilab-parts-{}.tsv", Uuid::new_v4()));
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+async fn start_server() -> TestServer {
+    TestServer::start_with_config(ServerConfig {
+        word_parts_path: Some(index_file(INDEX)),
+        ..Default::default()
+    })
+    .await
+}
+
+fn settings(word_part: &str, word_part_reading: Option<&str>) -> GameSettings {
+    GameSettings {
+        round_duration: 30,
+        rounds_count: 1,
+        start_countdown: 0,
+        word_part: Some(word_part.into()),
+        word_part_reading: word_part_reading.map(Into::into),
+        ..Default::default()
+    }
+}
+
+async fn provide(admin: &mut TestClient, part: &str) -> Uuid {
+    let req = admin
+        .expect::<OutReqQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+    let reading = ReadingWithParts {
+        reading: "にちよう".into(),
+        parts: vec![WordPartInfo {
+            word_part: part.into(),
+            word_part_reading: "にち".into(),
+            examples: Vec::new(),
+        }],
+    };
+    admin
+        .send(TransportMsg::InRespQuestion(TransportEnvelope {
+            correlation_id: req.correlation_id,
+            seq: None,
+            payload: InRespQuestion {
+                question: QuestionInfo {
+                    word_info: WordInfo {
+                        word: "日曜".into(),
+                        meanings: Vec::new(),
+                        readings: vec![reading],
+                    },
+                    ..Default::default()
+                },
+                question_svg: "<svg/>".into(),
+            },
+        }))
+        .await
+        .unwrap();
+    req.correlation_id
+}
+
+#[test]
+fn index_file_is_read_most_used_first() {
+    let index = WordPartIndex::load(&index_file(INDEX)).unwrap();
+    assert_eq!(index.len(), 5);
+    assert_eq!(index.entries()[0].word_part, "氵");
+    assert_eq!(
+        index
+            .readings("日")
+            .map(|e| e.word_part_reading.as_str())
+            .collect::<Vec<_>>(),
+        ["にち", "ひ"]
+    );
+
+    let err = WordPartIndex::load(&index_file("日\tにち\n")).unwrap_err();
+    assert!(err.to_string().starts_with("line 1"), "{err}");
+}
+
+#[test]
+fn unknown_parts_get_suggestions() {
+    let index = WordPartIndex::load(&index_file(INDEX)).unwrap();
+    // a typed reading finds its part
+    assert_eq!(index.suggestions("サンズイ")[0], "氵");
+    assert_eq!(index.suggestions("げつ")[0], "月");
+    assert!(index.check(&settings("日", None)).is_ok());
+    assert!(index.check(&settings("日", Some("ヒ"))).is_ok());
+    assert_eq!(
+        index.check(&settings("日", Some("つき"))).unwrap_err(),
+        "unknown word part reading: にち,ひ"
+    );
+    // nothing is checked without an index
+    assert!(WordPartIndex::default().check(&settings("x", None)).is_ok());
+}
+
+#[tokio::test]
+async fn word_part_list_comes_from_the_index() {
+    let server = start_server().await;
+    let mut alice = server.local_client("alice").await;
+
+    let correlation_id = alice
+        .send(TransportMsg::InReqWordPartList(envelope(
+            InReqWordPartList {},
+        )))
+        .await
+        .unwrap();
+    let TransportMsg::OutRespWordPartList(resp) = alice
+        .response(correlation_id, DEFAULT_TIMEOUT)
+        .await
+        .unwrap()
+    else {
+        panic!("expected the word part list");
+    };
+    let parts = resp.payload.word_parts;
+    assert_eq!(parts.len(), 5);
+    assert_eq!(
+        parts[0],
+        WordPartEntry {
+            word_part: "氵".into(),
+            word_part_reading: "さんずい".into(),
+            count: 300,
+        }
+    );
+}
+
+#[tokio::test]
+async fn settings_with_an_unknown_part_are_rejected() {
+    let server = start_server().await;
+    let mut alice = server.local_client("alice").await;
+
+    let send = |game_settings| {
+        TransportMsg::InReqSendGameSettings(envelope(InReqSendGameSettings { game_settings }))
+    };
+    let status = alice
+        .request(send(settings("さんずい", None)))
+        .await
+        .unwrap();
+    assert!(status.starts_with("unknown word part: 氵"), "{status}");
+    assert_eq!(
+        alice.request(send(settings("氵", None))).await.unwrap(),
+        "success"
+    );
+
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: settings("日", Some("げつ")),
+    }));
+    assert_eq!(
+        alice.request(start).await.unwrap(),
+        "unknown word part reading: にち,ひ"
+    );
+}
+
+#[tokio::test]
+async fn question_without_the_part_is_requested_again() {
+    let server = start_server().await;
+    let mut alice = server.local_client("alice").await;
+    let start = TransportMsg::InReqStartGame(envelope(InReqStartGame {
+        game_settings: settings("日", Some("にち")),
+    }));
+    assert_eq!(alice.request(start).await.unwrap(), "success");
+
+    let rejected = provide(&mut alice, "月").await;
+    assert_eq!(
+        alice.status(rejected, DEFAULT_TIMEOUT).await.unwrap(),
+        "word part missing"
+    );
+    provide(&mut alice, "日").await;
+    alice
+        .expect::<OutNotifQuestion>(DEFAULT_TIMEOUT)
+        .await
+        .unwrap();
+}